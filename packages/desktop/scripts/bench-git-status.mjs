@@ -0,0 +1,196 @@
+#!/usr/bin/env node
+// Measures the work `get_git_status` (src-tauri/src/commands/git.rs, collect_git_status)
+// does per poll on generated repositories with thousands of files. The git invocations and
+// the untracked line counting mirror the Rust code, stage by stage and with the same
+// concurrency, so keep them in sync when collect_git_status changes.
+//
+// Usage: node scripts/bench-git-status.mjs [--files N] [--iterations N] [--budget MS]
+import { execFile, execFileSync } from 'node:child_process';
+import { mkdirSync, mkdtempSync, readFileSync, rmSync, statSync, writeFileSync } from 'node:fs';
+import os from 'node:os';
+import path from 'node:path';
+import { promisify } from 'node:util';
+
+const execFileAsync = promisify(execFile);
+const EMPTY_TREE_HASH = '4b825dc642cb6eb9a060e54bf8d69288fbee4904';
+const OPERATION_MARKERS = [
+  'rebase-merge',
+  'rebase-apply/rebasing',
+  'rebase-apply/applying',
+  'MERGE_HEAD',
+  'CHERRY_PICK_HEAD',
+  'REVERT_HEAD',
+  'BISECT_START',
+];
+
+function option(name, fallback) {
+  const index = process.argv.indexOf(`--${name}`);
+  if (index === -1 || index + 1 >= process.argv.length) {
+    return fallback;
+  }
+  const value = Number(process.argv[index + 1]);
+  return Number.isFinite(value) && value > 0 ? value : fallback;
+}
+
+const FILES = option('files', 5000);
+const ITERATIONS = option('iterations', 15);
+const BUDGET_MS = option('budget', 100);
+
+function gitSync(cwd, args) {
+  execFileSync('git', args, { cwd, stdio: 'ignore' });
+}
+
+async function git(cwd, args) {
+  const { stdout } = await execFileAsync('git', args, { cwd, maxBuffer: 256 * 1024 * 1024 });
+  return stdout;
+}
+
+async function gitAllowingFailure(cwd, args) {
+  try {
+    return await git(cwd, args);
+  } catch {
+    return null;
+  }
+}
+
+function fileContent(index) {
+  return Array.from({ length: 20 }, (_, line) => `file ${index} line ${line}`).join('\n') + '\n';
+}
+
+function createRepo({ modified, untracked }) {
+  const root = mkdtempSync(path.join(os.tmpdir(), 'openchamber-status-bench-'));
+  gitSync(root, ['init', '--quiet', '--initial-branch=main']);
+  gitSync(root, ['config', 'user.name', 'Bench']);
+  gitSync(root, ['config', 'user.email', 'bench@example.com']);
+
+  // Spread files over nested directories like a real source tree
+  for (let i = 0; i < FILES; i++) {
+    const dir = path.join(root, 'src', `pkg${i % 50}`, `mod${i % 7}`);
+    mkdirSync(dir, { recursive: true });
+    writeFileSync(path.join(dir, `file${i}.txt`), fileContent(i));
+  }
+  gitSync(root, ['add', '-A']);
+  gitSync(root, ['commit', '--quiet', '-m', 'initial']);
+
+  for (let i = 0; i < modified; i++) {
+    const file = path.join(root, 'src', `pkg${i % 50}`, `mod${i % 7}`, `file${i}.txt`);
+    writeFileSync(file, fileContent(i) + `changed ${i}\n`);
+  }
+  for (let i = 0; i < untracked; i++) {
+    const dir = path.join(root, 'new', `dir${i % 20}`);
+    mkdirSync(dir, { recursive: true });
+    writeFileSync(path.join(dir, `new${i}.txt`), fileContent(i));
+  }
+  // Warm the index stat cache the way the app's first status call would
+  gitSync(root, ['status', '--porcelain=v2']);
+  return root;
+}
+
+function untrackedOrAddedPaths(statusOutput) {
+  const paths = [];
+  const entries = statusOutput.split('\0');
+  for (let i = 0; i < entries.length; i++) {
+    const entry = entries[i];
+    if (entry.startsWith('? ')) {
+      paths.push(entry.slice(2));
+    } else if (entry.startsWith('1 A')) {
+      paths.push(entry.split(' ').slice(8).join(' '));
+    } else if (entry.startsWith('2 ')) {
+      i += 1;
+    }
+  }
+  return paths;
+}
+
+const indexFlagsCache = new Map();
+
+// Index flags are listed again only when the index file changes
+async function indexFlags(root) {
+  const indexPath = path.resolve(root, (await git(root, ['rev-parse', '--git-path', 'index'])).trim());
+  const { mtimeMs, size } = statSync(indexPath);
+  const key = `${mtimeMs}:${size}`;
+  if (indexFlagsCache.get(indexPath) !== key) {
+    await git(root, ['ls-files', '-v', '-z']);
+    indexFlagsCache.set(indexPath, key);
+  }
+}
+
+async function countNewFiles(root, statusOutput) {
+  for (const file of untrackedOrAddedPaths(statusOutput)) {
+    readFileSync(path.join(root, file)).includes(0);
+  }
+}
+
+async function countUnpublished(root, statusOutput) {
+  if (statusOutput.includes('# branch.upstream ')) {
+    return;
+  }
+  await gitAllowingFailure(root, [
+    'for-each-ref',
+    '--format=%(refname)%00%(symref)',
+    'refs/remotes/origin/HEAD',
+    'refs/remotes/origin/main',
+    'refs/remotes/origin/master',
+    'refs/heads/main',
+    'refs/heads/master',
+  ]);
+  await gitAllowingFailure(root, ['rev-list', '--count', 'main..HEAD']);
+}
+
+async function collectStatus(root) {
+  const [statusOutput, numstatOutput] = await Promise.all([
+    git(root, ['status', '--porcelain=v2', '--branch', '-z', '-uall']),
+    gitAllowingFailure(root, ['diff', 'HEAD', '--numstat', '-z', '--no-renames']),
+    gitAllowingFailure(root, ['config', '--bool', 'core.sparseCheckout']),
+    indexFlags(root),
+    git(root, ['rev-parse', ...OPERATION_MARKERS.flatMap((marker) => ['--git-path', marker])]),
+  ]);
+  if (numstatOutput === null) {
+    await gitAllowingFailure(root, ['diff', EMPTY_TREE_HASH, '--numstat', '-z', '--no-renames']);
+  }
+  await Promise.all([countNewFiles(root, statusOutput), countUnpublished(root, statusOutput)]);
+}
+
+function percentile(sorted, p) {
+  return sorted[Math.min(sorted.length - 1, Math.floor((sorted.length * p) / 100))];
+}
+
+async function measure(name, shape) {
+  const root = createRepo(shape);
+  try {
+    await collectStatus(root);
+    const samples = [];
+    for (let i = 0; i < ITERATIONS; i++) {
+      const start = performance.now();
+      await collectStatus(root);
+      samples.push(performance.now() - start);
+    }
+    samples.sort((a, b) => a - b);
+    const median = percentile(samples, 50);
+    console.log(
+      `${name.padEnd(34)} median ${median.toFixed(1).padStart(6)} ms   p90 ${percentile(samples, 90)
+        .toFixed(1)
+        .padStart(6)} ms`
+    );
+    return median;
+  } finally {
+    rmSync(root, { recursive: true, force: true });
+  }
+}
+
+const scenarios = [
+  [`${FILES} files, clean`, { modified: 0, untracked: 0 }],
+  [`${FILES} files, 500 modified`, { modified: 500, untracked: 0 }],
+  [`${FILES} files, 500 modified + 1000 new`, { modified: 500, untracked: 1000 }],
+];
+
+console.log(`git status benchmark: ${ITERATIONS} iterations per scenario, budget ${BUDGET_MS} ms`);
+let overBudget = false;
+for (const [name, shape] of scenarios) {
+  const median = await measure(name, shape);
+  overBudget ||= median > BUDGET_MS;
+}
+if (overBudget) {
+  console.error(`Median status time exceeded the ${BUDGET_MS} ms budget`);
+  process.exit(1);
+}
@@ -0,0 +1,144 @@
+use anyhow::{anyhow, Result};
+use log::warn;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::DesktopRuntime;
+
+/// OpenAI-compatible chat completions URL used instead of the OpenCode server when set
+pub const AI_ENDPOINT_KEY: &str = "gitAiEndpoint";
+/// Model for git helpers: `provider/model` for OpenCode, a model name for an endpoint.
+/// OpenCode falls back to `defaultModel`, then to its own default.
+pub const AI_MODEL_KEY: &str = "gitAiModel";
+const DEFAULT_MODEL_KEY: &str = "defaultModel";
+
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(120);
+const OPENCODE_READY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Send a single prompt and return the model's text reply. Goes through the managed
+/// OpenCode server, so the user's configured providers, keys and proxy apply, unless
+/// an endpoint is configured in settings.
+pub async fn complete(runtime: &DesktopRuntime, prompt: &str, max_tokens: u32) -> Result<String> {
+    let settings = runtime.settings().load().await.unwrap_or(Value::Null);
+    let setting = |key: &str| {
+        settings
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let client = Client::builder().timeout(COMPLETION_TIMEOUT).build()?;
+
+    if let Some(endpoint) = setting(AI_ENDPOINT_KEY) {
+        let model = setting(AI_MODEL_KEY)
+            .ok_or_else(|| anyhow!("Set {} to use a custom AI endpoint", AI_MODEL_KEY))?;
+        return complete_with_endpoint(&client, &endpoint, &model, prompt, max_tokens).await;
+    }
+
+    let model = setting(AI_MODEL_KEY).or_else(|| setting(DEFAULT_MODEL_KEY));
+    complete_with_opencode(runtime, &client, model.as_deref(), prompt).await
+}
+
+async fn complete_with_endpoint(
+    client: &Client,
+    endpoint: &str,
+    model: &str,
+    prompt: &str,
+    max_tokens: u32,
+) -> Result<String> {
+    let response = client
+        .post(endpoint)
+        .json(&json!({
+            "model": model,
+            "messages": [{ "role": "user", "content": prompt }],
+            "max_tokens": max_tokens,
+            "stream": false,
+        }))
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("API request failed: {}", response.status()));
+    }
+    let body: Value = response.json().await?;
+    Ok(body["choices"][0]["message"]["content"]
+        .as_str()
+        .unwrap_or("")
+        .trim()
+        .to_string())
+}
+
+/// Run the prompt in a throwaway OpenCode session with tools disabled, then delete it so
+/// it never shows up in the session list
+async fn complete_with_opencode(
+    runtime: &DesktopRuntime,
+    client: &Client,
+    model: Option<&str>,
+    prompt: &str,
+) -> Result<String> {
+    let opencode = runtime.opencode_manager();
+    if !opencode.wait_until_ready(OPENCODE_READY_TIMEOUT).await {
+        return Err(anyhow!("OpenCode is not running"));
+    }
+    let port = opencode
+        .current_port()
+        .ok_or_else(|| anyhow!("OpenCode is not running"))?;
+    let base = format!("http://127.0.0.1:{port}{}", opencode.api_prefix());
+
+    let session: Value = client
+        .post(format!("{base}/session"))
+        .json(&json!({ "title": "OpenChamber git assistant" }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let session_id = session["id"]
+        .as_str()
+        .ok_or_else(|| anyhow!("OpenCode did not return a session id"))?
+        .to_string();
+
+    let mut body = json!({
+        "parts": [{ "type": "text", "text": prompt }],
+        // Only the reply text is wanted; never let the agent touch the repository
+        "tools": { "*": false },
+    });
+    if let Some((provider_id, model_id)) = model.and_then(|m| m.split_once('/')) {
+        body["model"] = json!({ "providerID": provider_id, "modelID": model_id });
+    }
+    let result = client
+        .post(format!("{base}/session/{session_id}/message"))
+        .json(&body)
+        .send()
+        .await;
+
+    if let Err(err) = client
+        .delete(format!("{base}/session/{session_id}"))
+        .send()
+        .await
+    {
+        warn!("[ai] Failed to delete session {}: {}", session_id, err);
+    }
+
+    let reply: Value = result?.error_for_status()?.json().await?;
+    if let Some(error) = reply["info"].get("error").filter(|e| !e.is_null()) {
+        let message = error["data"]["message"]
+            .as_str()
+            .or_else(|| error["name"].as_str())
+            .unwrap_or("unknown error");
+        return Err(anyhow!("Model request failed: {}", message));
+    }
+    let text = reply["parts"]
+        .as_array()
+        .map(|parts| {
+            parts
+                .iter()
+                .filter(|part| part["type"] == "text")
+                .filter_map(|part| part["text"].as_str())
+                .collect::<Vec<_>>()
+                .join("")
+        })
+        .unwrap_or_default();
+    Ok(text.trim().to_string())
+}
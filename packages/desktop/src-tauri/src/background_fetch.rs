@@ -0,0 +1,106 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use log::{debug, info, warn};
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+use crate::commands::git::{background_fetch_once, is_read_only_workspace, BranchAheadBehind};
+use crate::DesktopRuntime;
+
+pub const AHEAD_BEHIND_CHANGED_EVENT: &str = "git:ahead-behind-changed";
+const ENABLED_KEY: &str = "backgroundFetchEnabled";
+const INTERVAL_KEY: &str = "backgroundFetchIntervalMinutes";
+const DEFAULT_INTERVAL_MINUTES: u64 = 10;
+/// How often a disabled scheduler re-checks settings so enabling it takes effect
+const DISABLED_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+static SCHEDULERS: Lazy<Mutex<HashMap<PathBuf, tauri::async_runtime::JoinHandle<()>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// `None` while background fetch is turned off in settings
+async fn configured_interval(runtime: &DesktopRuntime) -> Option<Duration> {
+    let settings = runtime.settings().load().await.ok()?;
+    if settings.get(ENABLED_KEY).and_then(Value::as_bool) != Some(true) {
+        return None;
+    }
+    let minutes = settings
+        .get(INTERVAL_KEY)
+        .and_then(Value::as_u64)
+        .filter(|m| *m > 0)
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES);
+    Some(Duration::from_secs(minutes * 60))
+}
+
+async fn run_scheduler(app: AppHandle, runtime: DesktopRuntime, root: PathBuf) {
+    let mut shutdown_rx = runtime.subscribe_shutdown();
+    let mut last: Option<BranchAheadBehind> = None;
+    let directory = root.to_string_lossy().to_string();
+
+    loop {
+        let interval = configured_interval(&runtime).await;
+        let wait = interval.unwrap_or(DISABLED_POLL_INTERVAL);
+        tokio::select! {
+            _ = shutdown_rx.recv() => break,
+            _ = tokio::time::sleep(wait) => {}
+        }
+
+        // Settings may have changed while sleeping
+        if configured_interval(&runtime).await.is_none() {
+            continue;
+        }
+        // Fetching updates refs, which a read-only workspace must not see
+        if is_read_only_workspace(&root, runtime.settings()).await {
+            continue;
+        }
+
+        match background_fetch_once(&root).await {
+            Ok(Some(current)) => {
+                if last.as_ref() != Some(&current) {
+                    debug!(
+                        "[git-fetch] {} {} ahead {} behind {}",
+                        directory, current.branch, current.ahead, current.behind
+                    );
+                    let _ = app.emit(AHEAD_BEHIND_CHANGED_EVENT, &current);
+                    last = Some(current);
+                }
+            }
+            Ok(None) => {}
+            Err(err) => warn!(
+                "[git-fetch] Background fetch failed for {}: {}",
+                directory, err
+            ),
+        }
+    }
+}
+
+/// Schedule periodic `git fetch --prune` for a repository. Fetching only happens while
+/// `backgroundFetchEnabled` is set; the interval is re-read from settings every cycle.
+pub async fn start(app: AppHandle, runtime: DesktopRuntime, root: PathBuf) {
+    let mut schedulers = SCHEDULERS.lock().await;
+    if schedulers
+        .get(&root)
+        .is_some_and(|handle| !handle.inner().is_finished())
+    {
+        return;
+    }
+    info!("[git-fetch] Scheduling background fetch for {:?}", root);
+    let handle = tauri::async_runtime::spawn(run_scheduler(app, runtime, root.clone()));
+    schedulers.insert(root, handle);
+}
+
+pub async fn stop(root: &Path) -> bool {
+    match SCHEDULERS.lock().await.remove(root) {
+        Some(handle) => {
+            handle.abort();
+            info!("[git-fetch] Stopped background fetch for {:?}", root);
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,391 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tauri::State;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+use crate::commands::git::{
+    ensure_openchamber_excluded, is_git_work_tree, sniff_image_mime_type, validate_git_path,
+    validate_writable_git_path,
+};
+use crate::path_utils::expand_tilde_path;
+use crate::{DesktopRuntime, SettingsStore};
+
+const ATTACHMENTS_DIR: &str = ".openchamber/attachments";
+const ATTACHMENTS_INDEX_FILE: &str = "index.json";
+const ATTACHMENTS_QUOTA_KEY: &str = "attachmentsQuotaMb";
+const DEFAULT_ATTACHMENTS_QUOTA_MB: u64 = 500;
+/// Error code prefix returned when storing a file would exceed the configured quota
+pub(crate) const ATTACHMENT_QUOTA_ERROR: &str = "AttachmentQuotaExceeded";
+
+/// Serializes index read/modify/write cycles across concurrent store/gc calls
+static INDEX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentEntry {
+    pub id: String,
+    pub file_name: String,
+    pub stored_name: String,
+    pub mime_type: Option<String>,
+    pub size: u64,
+    pub created_at: String,
+    #[serde(default)]
+    pub sessions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+struct AttachmentIndex {
+    version: u32,
+    attachments: Vec<AttachmentEntry>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredAttachment {
+    pub attachment: AttachmentEntry,
+    pub path: String,
+    pub deduplicated: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentListing {
+    pub directory: String,
+    pub attachments: Vec<AttachmentEntry>,
+    pub total_bytes: u64,
+    pub quota_bytes: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachmentGcResult {
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+    pub remaining: usize,
+    pub dry_run: bool,
+}
+
+fn attachments_dir(root: &Path) -> PathBuf {
+    root.join(ATTACHMENTS_DIR)
+}
+
+async fn load_index(dir: &Path) -> AttachmentIndex {
+    let path = dir.join(ATTACHMENTS_INDEX_FILE);
+    let Ok(bytes) = fs::read(&path).await else {
+        return AttachmentIndex {
+            version: 1,
+            attachments: Vec::new(),
+        };
+    };
+    serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+        warn!(
+            "[attachments] Ignoring unreadable index {:?}: {}",
+            path, err
+        );
+        AttachmentIndex {
+            version: 1,
+            attachments: Vec::new(),
+        }
+    })
+}
+
+async fn save_index(dir: &Path, index: &AttachmentIndex) -> Result<(), String> {
+    let path = dir.join(ATTACHMENTS_INDEX_FILE);
+    let temp_path = dir.join(format!(
+        "{}.tmp-{}",
+        ATTACHMENTS_INDEX_FILE,
+        uuid::Uuid::new_v4()
+    ));
+    let bytes = serde_json::to_vec_pretty(index).map_err(|e| e.to_string())?;
+    fs::write(&temp_path, bytes)
+        .await
+        .map_err(|e| format!("Failed to write attachment index: {}", e))?;
+    if let Err(err) = fs::rename(&temp_path, &path).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(format!("Failed to write attachment index: {}", err));
+    }
+    Ok(())
+}
+
+async fn load_quota_bytes(settings: &SettingsStore) -> u64 {
+    let quota_mb = settings
+        .load()
+        .await
+        .ok()
+        .and_then(|value| value.get(ATTACHMENTS_QUOTA_KEY).and_then(Value::as_u64))
+        .filter(|mb| *mb > 0)
+        .unwrap_or(DEFAULT_ATTACHMENTS_QUOTA_MB);
+    quota_mb * 1024 * 1024
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    let base = Path::new(name.trim())
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| {
+            if c.is_control() || "<>:\"/\\|?*".contains(c) {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    if cleaned.is_empty() {
+        "attachment".to_string()
+    } else {
+        cleaned
+    }
+}
+
+fn stored_name_for(hash: &str, file_name: &str) -> String {
+    let extension = Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .filter(|ext| ext.len() <= 10 && ext.chars().all(|c| c.is_ascii_alphanumeric()));
+    match extension {
+        Some(ext) => format!("{}.{}", hash, ext),
+        None => hash.to_string(),
+    }
+}
+
+/// Store a pasted or dropped file for a chat session. Identical content is kept once
+/// (keyed by SHA-256) and just gains another session reference.
+#[tauri::command]
+pub async fn store_attachment(
+    directory: String,
+    session_id: String,
+    file_name: String,
+    data_base64: Option<String>,
+    source_path: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<StoredAttachment, String> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let session_id = session_id.trim().to_string();
+    if session_id.is_empty() {
+        return Err("Session id is required".to_string());
+    }
+
+    let bytes = match (data_base64, source_path) {
+        (Some(data), _) => {
+            // Accept data URLs as produced by the clipboard/file readers in the webview
+            let encoded = data.split_once(";base64,").map(|(_, d)| d).unwrap_or(&data);
+            BASE64
+                .decode(encoded.trim())
+                .map_err(|e| format!("Invalid attachment data: {}", e))?
+        }
+        (None, Some(path)) => fs::read(expand_tilde_path(&path))
+            .await
+            .map_err(|e| format!("Failed to read {}: {}", path, e))?,
+        (None, None) => return Err("Attachment data or source path is required".to_string()),
+    };
+
+    let hash = format!("{:x}", Sha256::digest(&bytes));
+    let file_name = sanitize_file_name(&file_name);
+    let dir = attachments_dir(&root);
+    let quota = load_quota_bytes(state.settings()).await;
+
+    let _guard = INDEX_LOCK.lock().await;
+    fs::create_dir_all(&dir)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", ATTACHMENTS_DIR, e))?;
+    let mut index = load_index(&dir).await;
+
+    if let Some(entry) = index.attachments.iter_mut().find(|entry| entry.id == hash) {
+        let stored_path = dir.join(&entry.stored_name);
+        if fs::metadata(&stored_path).await.is_ok() {
+            if !entry.sessions.contains(&session_id) {
+                entry.sessions.push(session_id);
+            }
+            let attachment = entry.clone();
+            save_index(&dir, &index).await?;
+            return Ok(StoredAttachment {
+                attachment,
+                path: stored_path.to_string_lossy().to_string(),
+                deduplicated: true,
+            });
+        }
+        // The file vanished from disk; drop the stale entry and store it again
+        index.attachments.retain(|entry| entry.id != hash);
+    }
+
+    let used: u64 = index.attachments.iter().map(|entry| entry.size).sum();
+    let size = bytes.len() as u64;
+    if used + size > quota {
+        return Err(format!(
+            "{}: storing {} bytes would exceed the {} MB attachment quota",
+            ATTACHMENT_QUOTA_ERROR,
+            size,
+            quota / (1024 * 1024)
+        ));
+    }
+
+    let stored_name = stored_name_for(&hash, &file_name);
+    let stored_path = dir.join(&stored_name);
+    fs::write(&stored_path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write attachment: {}", e))?;
+
+    let attachment = AttachmentEntry {
+        id: hash,
+        mime_type: sniff_image_mime_type(&bytes).map(str::to_string),
+        file_name,
+        stored_name,
+        size,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        sessions: vec![session_id],
+    };
+    index.attachments.push(attachment.clone());
+    save_index(&dir, &index).await?;
+
+    if is_git_work_tree(&root).await {
+        if let Err(err) = ensure_openchamber_excluded(&root).await {
+            warn!("[attachments] Failed to update git exclude: {}", err);
+        }
+    }
+
+    Ok(StoredAttachment {
+        attachment,
+        path: stored_path.to_string_lossy().to_string(),
+        deduplicated: false,
+    })
+}
+
+#[tauri::command]
+pub async fn list_attachments(
+    directory: String,
+    session_id: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<AttachmentListing, String> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let dir = attachments_dir(&root);
+    let index = load_index(&dir).await;
+    let total_bytes = index.attachments.iter().map(|entry| entry.size).sum();
+
+    let session_id = session_id
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let attachments = index
+        .attachments
+        .into_iter()
+        .filter(|entry| match &session_id {
+            Some(id) => entry.sessions.contains(id),
+            None => true,
+        })
+        .collect();
+
+    Ok(AttachmentListing {
+        directory: dir.to_string_lossy().to_string(),
+        attachments,
+        total_bytes,
+        quota_bytes: load_quota_bytes(state.settings()).await,
+    })
+}
+
+/// Drop references from sessions that no longer exist, then delete attachments nobody
+/// references and stray files missing from the index.
+#[tauri::command]
+pub async fn gc_attachments(
+    directory: String,
+    active_sessions: Vec<String>,
+    dry_run: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<AttachmentGcResult, String> {
+    let dry_run = dry_run.unwrap_or(false);
+    let root = if dry_run {
+        validate_git_path(&directory, state.settings()).await
+    } else {
+        validate_writable_git_path(&directory, state.settings()).await
+    }
+    .map_err(|e| e.to_string())?;
+
+    let dir = attachments_dir(&root);
+    let active: HashSet<String> = active_sessions.into_iter().collect();
+
+    let _guard = INDEX_LOCK.lock().await;
+    let mut index = load_index(&dir).await;
+    let mut removed = Vec::new();
+    let mut freed_bytes = 0;
+
+    for entry in index.attachments.iter_mut() {
+        entry.sessions.retain(|session| active.contains(session));
+    }
+    let (kept, orphaned): (Vec<_>, Vec<_>) = index
+        .attachments
+        .into_iter()
+        .partition(|entry| !entry.sessions.is_empty());
+    index.attachments = kept;
+
+    for entry in orphaned {
+        freed_bytes += entry.size;
+        removed.push(entry.stored_name);
+    }
+
+    // Files on disk the index does not know about (e.g. left by an interrupted store)
+    let known: HashSet<&str> = index
+        .attachments
+        .iter()
+        .map(|entry| entry.stored_name.as_str())
+        .chain(removed.iter().map(String::as_str))
+        .collect();
+    let mut strays = Vec::new();
+    if let Ok(mut entries) = fs::read_dir(&dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ATTACHMENTS_INDEX_FILE || known.contains(name.as_str()) {
+                continue;
+            }
+            if entry
+                .file_type()
+                .await
+                .map(|t| t.is_file())
+                .unwrap_or(false)
+            {
+                freed_bytes += entry.metadata().await.map(|m| m.len()).unwrap_or(0);
+                strays.push(name);
+            }
+        }
+    }
+    removed.extend(strays);
+
+    if !dry_run {
+        for name in &removed {
+            if let Err(err) = fs::remove_file(dir.join(name)).await {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    warn!("[attachments] Failed to remove {}: {}", name, err);
+                }
+            }
+        }
+        if fs::metadata(&dir).await.is_ok() {
+            save_index(&dir, &index).await?;
+        }
+        info!(
+            "[attachments] Removed {} unreferenced files ({} bytes) from {:?}",
+            removed.len(),
+            freed_bytes,
+            dir
+        );
+    }
+
+    Ok(AttachmentGcResult {
+        removed,
+        freed_bytes,
+        remaining: index.attachments.len(),
+        dry_run,
+    })
+}
@@ -0,0 +1,233 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tokio::fs;
+
+/// Temp files younger than this may belong to a write that is still in flight
+const STALE_TEMP_FILE_AGE: Duration = Duration::from_secs(60 * 60);
+/// Directories without config state: logs, and regenerable caches that are rebuilt when
+/// unreadable and so are neither corruption nor worth quarantining
+const SKIPPED_DIRECTORIES: &[&str] = &["logs", "cache"];
+/// File name fragments that mark a file as holding credentials or identity data
+const SECRET_FILE_MARKERS: &[&str] = &["identities", "credential", "secret", "token", "auth"];
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIntegrityIssue {
+    pub path: String,
+    pub kind: String,
+    pub detail: String,
+    pub repaired: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIntegrityReport {
+    pub config_dir: String,
+    pub checked_files: usize,
+    pub issues: Vec<ConfigIntegrityIssue>,
+}
+
+fn config_dir() -> Result<PathBuf> {
+    let mut path = dirs::home_dir().ok_or_else(|| anyhow!("No home directory"))?;
+    path.push(".config");
+    path.push("openchamber");
+    Ok(path)
+}
+
+fn is_temp_file(name: &str) -> bool {
+    name.ends_with(".tmp")
+        || name.ends_with(".temp")
+        || name.ends_with('~')
+        || name.starts_with(".tmp")
+        || name.contains(".tmp-")
+}
+
+fn is_secret_file(name: &str) -> bool {
+    let lower = name.to_ascii_lowercase();
+    SECRET_FILE_MARKERS
+        .iter()
+        .any(|marker| lower.contains(marker))
+}
+
+async fn collect_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(mut entries) = fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(file_type) = entry.file_type().await else {
+                continue;
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !SKIPPED_DIRECTORIES.contains(&name.as_str()) {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+
+    files
+}
+
+/// Move a corrupt file aside instead of deleting it so the user can still recover it;
+/// every reader falls back to defaults when its file is missing.
+async fn quarantine_file(path: &Path) -> Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Invalid file path"))?
+        .to_string_lossy();
+    let target = path.with_file_name(format!(
+        "{}.corrupt-{}",
+        file_name,
+        chrono::Utc::now().timestamp()
+    ));
+    fs::rename(path, &target).await?;
+    Ok(target)
+}
+
+#[cfg(unix)]
+async fn check_secret_permissions(path: &Path, repair: bool) -> Option<ConfigIntegrityIssue> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata = fs::metadata(path).await.ok()?;
+    let mode = metadata.permissions().mode() & 0o777;
+    if mode & 0o077 == 0 {
+        return None;
+    }
+
+    let repaired = repair
+        && fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .await
+            .is_ok();
+
+    Some(ConfigIntegrityIssue {
+        path: path.to_string_lossy().to_string(),
+        kind: "permissiveMode".to_string(),
+        detail: format!("File mode {:o} is readable by other users", mode),
+        repaired,
+    })
+}
+
+#[cfg(not(unix))]
+async fn check_secret_permissions(_path: &Path, _repair: bool) -> Option<ConfigIntegrityIssue> {
+    None
+}
+
+/// Scan `~/.config/openchamber` for corrupt JSON, loose permissions on secret-bearing
+/// files and leftover temp files. With `repair`, fixes what is safe to fix.
+pub(crate) async fn run_config_integrity_check(repair: bool) -> Result<ConfigIntegrityReport> {
+    let root = config_dir()?;
+    let mut report = ConfigIntegrityReport {
+        config_dir: root.to_string_lossy().to_string(),
+        checked_files: 0,
+        issues: Vec::new(),
+    };
+
+    if fs::metadata(&root).await.is_err() {
+        return Ok(report);
+    }
+
+    for path in collect_files(&root).await {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        report.checked_files += 1;
+
+        if is_temp_file(&name) {
+            let age = fs::metadata(&path)
+                .await
+                .ok()
+                .and_then(|meta| meta.modified().ok())
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .unwrap_or_default();
+            if age < STALE_TEMP_FILE_AGE {
+                continue;
+            }
+            let repaired = repair && fs::remove_file(&path).await.is_ok();
+            report.issues.push(ConfigIntegrityIssue {
+                path: path.to_string_lossy().to_string(),
+                kind: "leftoverTempFile".to_string(),
+                detail: "Temporary file left behind by an interrupted write".to_string(),
+                repaired,
+            });
+            continue;
+        }
+
+        if name.ends_with(".json") {
+            let parsed = match fs::read(&path).await {
+                Ok(bytes) => serde_json::from_slice::<serde_json::Value>(&bytes)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string()),
+                Err(err) => Err(err.to_string()),
+            };
+
+            if let Err(detail) = parsed {
+                let mut issue = ConfigIntegrityIssue {
+                    path: path.to_string_lossy().to_string(),
+                    kind: "corruptJson".to_string(),
+                    detail,
+                    repaired: false,
+                };
+                if repair {
+                    match quarantine_file(&path).await {
+                        Ok(target) => {
+                            issue.repaired = true;
+                            issue.detail =
+                                format!("{} (moved to {})", issue.detail, target.display());
+                        }
+                        Err(err) => warn!("[config] Failed to quarantine {:?}: {}", path, err),
+                    }
+                }
+                report.issues.push(issue);
+                continue;
+            }
+        }
+
+        if is_secret_file(&name) {
+            if let Some(issue) = check_secret_permissions(&path, repair).await {
+                report.issues.push(issue);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Log-only startup pass; runs before settings are loaded so corrupt files are set aside
+pub(crate) async fn check_config_integrity_on_startup() {
+    match run_config_integrity_check(true).await {
+        Ok(report) if report.issues.is_empty() => {
+            info!(
+                "[config] Integrity check passed ({} files)",
+                report.checked_files
+            );
+        }
+        Ok(report) => {
+            for issue in &report.issues {
+                warn!(
+                    "[config] {} {}: {} (repaired: {})",
+                    issue.kind, issue.path, issue.detail, issue.repaired
+                );
+            }
+        }
+        Err(err) => warn!("[config] Integrity check failed: {}", err),
+    }
+}
+
+#[tauri::command]
+pub async fn check_config_integrity(repair: Option<bool>) -> Result<ConfigIntegrityReport, String> {
+    run_config_integrity_check(repair.unwrap_or(false))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,350 @@
+use log::{info, warn};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tauri::State;
+use tokio::process::Command;
+
+use crate::opencode_manager::build_augmented_env;
+use crate::path_utils::expand_tilde_path;
+use crate::DesktopRuntime;
+
+const PREFERRED_EDITOR_KEY: &str = "preferredEditor";
+
+/// How an editor CLI expects a file position to be passed
+#[derive(Clone, Copy)]
+enum GotoStyle {
+    /// `code -g file:line:column`
+    GotoFlag,
+    /// `zed file:line:column`
+    PathSuffix,
+    /// `idea --line N --column M file`
+    JetBrains,
+}
+
+struct EditorSpec {
+    id: &'static str,
+    name: &'static str,
+    commands: &'static [&'static str],
+    mac_paths: &'static [&'static str],
+    goto: GotoStyle,
+}
+
+const EDITORS: &[EditorSpec] = &[
+    EditorSpec {
+        id: "vscode",
+        name: "Visual Studio Code",
+        commands: &["code"],
+        mac_paths: &["/Applications/Visual Studio Code.app/Contents/Resources/app/bin/code"],
+        goto: GotoStyle::GotoFlag,
+    },
+    EditorSpec {
+        id: "vscode-insiders",
+        name: "Visual Studio Code - Insiders",
+        commands: &["code-insiders"],
+        mac_paths: &[
+            "/Applications/Visual Studio Code - Insiders.app/Contents/Resources/app/bin/code",
+        ],
+        goto: GotoStyle::GotoFlag,
+    },
+    EditorSpec {
+        id: "cursor",
+        name: "Cursor",
+        commands: &["cursor"],
+        mac_paths: &["/Applications/Cursor.app/Contents/Resources/app/bin/cursor"],
+        goto: GotoStyle::GotoFlag,
+    },
+    EditorSpec {
+        id: "windsurf",
+        name: "Windsurf",
+        commands: &["windsurf"],
+        mac_paths: &["/Applications/Windsurf.app/Contents/Resources/app/bin/windsurf"],
+        goto: GotoStyle::GotoFlag,
+    },
+    EditorSpec {
+        id: "zed",
+        name: "Zed",
+        commands: &["zed", "zeditor"],
+        mac_paths: &["/Applications/Zed.app/Contents/MacOS/cli"],
+        goto: GotoStyle::PathSuffix,
+    },
+    EditorSpec {
+        id: "sublime",
+        name: "Sublime Text",
+        commands: &["subl"],
+        mac_paths: &["/Applications/Sublime Text.app/Contents/SharedSupport/bin/subl"],
+        goto: GotoStyle::PathSuffix,
+    },
+    EditorSpec {
+        id: "intellij",
+        name: "IntelliJ IDEA",
+        commands: &["idea"],
+        mac_paths: &[
+            "/Applications/IntelliJ IDEA.app/Contents/MacOS/idea",
+            "/Applications/IntelliJ IDEA CE.app/Contents/MacOS/idea",
+        ],
+        goto: GotoStyle::JetBrains,
+    },
+    EditorSpec {
+        id: "webstorm",
+        name: "WebStorm",
+        commands: &["webstorm"],
+        mac_paths: &["/Applications/WebStorm.app/Contents/MacOS/webstorm"],
+        goto: GotoStyle::JetBrains,
+    },
+    EditorSpec {
+        id: "pycharm",
+        name: "PyCharm",
+        commands: &["pycharm", "charm"],
+        mac_paths: &[
+            "/Applications/PyCharm.app/Contents/MacOS/pycharm",
+            "/Applications/PyCharm CE.app/Contents/MacOS/pycharm",
+        ],
+        goto: GotoStyle::JetBrains,
+    },
+    EditorSpec {
+        id: "goland",
+        name: "GoLand",
+        commands: &["goland"],
+        mac_paths: &["/Applications/GoLand.app/Contents/MacOS/goland"],
+        goto: GotoStyle::JetBrains,
+    },
+    EditorSpec {
+        id: "rustrover",
+        name: "RustRover",
+        commands: &["rustrover"],
+        mac_paths: &["/Applications/RustRover.app/Contents/MacOS/rustrover"],
+        goto: GotoStyle::JetBrains,
+    },
+    EditorSpec {
+        id: "clion",
+        name: "CLion",
+        commands: &["clion"],
+        mac_paths: &["/Applications/CLion.app/Contents/MacOS/clion"],
+        goto: GotoStyle::JetBrains,
+    },
+];
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedEditor {
+    pub id: String,
+    pub name: String,
+    pub command: String,
+    pub preferred: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInEditorResult {
+    pub editor: String,
+    pub path: String,
+}
+
+fn find_in_path(command: &str, path_var: &str) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(windows) {
+        &[".cmd", ".exe", ".bat", ""]
+    } else {
+        &[""]
+    };
+
+    for dir in std::env::split_paths(path_var) {
+        for ext in extensions {
+            let candidate = dir.join(format!("{}{}", command, ext));
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+        }
+    }
+    None
+}
+
+fn resolve_editor_command(spec: &EditorSpec, env: &HashMap<String, String>) -> Option<PathBuf> {
+    let path_var = env.get("PATH").map(String::as_str).unwrap_or_default();
+    if let Some(found) = spec
+        .commands
+        .iter()
+        .find_map(|command| find_in_path(command, path_var))
+    {
+        return Some(found);
+    }
+
+    if cfg!(target_os = "macos") {
+        if let Some(found) = spec
+            .mac_paths
+            .iter()
+            .map(PathBuf::from)
+            .find(|candidate| candidate.is_file())
+        {
+            return Some(found);
+        }
+
+        // JetBrains Toolbox installs shell scripts instead of putting launchers on PATH
+        if let Some(home) = dirs::home_dir() {
+            let toolbox = home.join("Library/Application Support/JetBrains/Toolbox/scripts");
+            if let Some(found) = spec
+                .commands
+                .iter()
+                .map(|command| toolbox.join(command))
+                .find(|candidate| candidate.is_file())
+            {
+                return Some(found);
+            }
+        }
+    }
+
+    None
+}
+
+fn detect_installed_editors(env: &HashMap<String, String>) -> Vec<(&'static EditorSpec, PathBuf)> {
+    EDITORS
+        .iter()
+        .filter_map(|spec| resolve_editor_command(spec, env).map(|command| (spec, command)))
+        .collect()
+}
+
+async fn load_preferred_editor(state: &State<'_, DesktopRuntime>) -> Option<String> {
+    let settings = state.settings().load().await.ok()?;
+    settings
+        .get(PREFERRED_EDITOR_KEY)
+        .and_then(Value::as_str)
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn build_editor_args(
+    goto: GotoStyle,
+    path: &Path,
+    line: Option<u32>,
+    column: Option<u32>,
+) -> Vec<String> {
+    let path_str = path.to_string_lossy().to_string();
+    let Some(line) = line.filter(|l| *l > 0 && path.is_file()) else {
+        return vec![path_str];
+    };
+    let column = column.filter(|c| *c > 0);
+
+    match goto {
+        GotoStyle::GotoFlag => {
+            let target = match column {
+                Some(column) => format!("{}:{}:{}", path_str, line, column),
+                None => format!("{}:{}", path_str, line),
+            };
+            vec!["-g".to_string(), target]
+        }
+        GotoStyle::PathSuffix => match column {
+            Some(column) => vec![format!("{}:{}:{}", path_str, line, column)],
+            None => vec![format!("{}:{}", path_str, line)],
+        },
+        GotoStyle::JetBrains => {
+            let mut args = vec!["--line".to_string(), line.to_string()];
+            if let Some(column) = column {
+                args.push("--column".to_string());
+                args.push(column.to_string());
+            }
+            args.push(path_str);
+            args
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn detect_editors(
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<DetectedEditor>, String> {
+    let preferred = load_preferred_editor(&state).await;
+    let detected = tokio::task::spawn_blocking(|| {
+        let env = build_augmented_env();
+        detect_installed_editors(&env)
+            .into_iter()
+            .map(|(spec, command)| (spec.id, spec.name, command))
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(detected
+        .into_iter()
+        .map(|(id, name, command)| DetectedEditor {
+            id: id.to_string(),
+            name: name.to_string(),
+            command: command.to_string_lossy().to_string(),
+            preferred: preferred.as_deref() == Some(id),
+        })
+        .collect())
+}
+
+/// Open a file (optionally at a line) or a whole workspace folder in an external editor.
+/// Falls back to the preferred editor from settings, then to the first detected one.
+#[tauri::command]
+pub async fn open_in_editor(
+    path: String,
+    line: Option<u32>,
+    column: Option<u32>,
+    editor: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<OpenInEditorResult, String> {
+    let target = expand_tilde_path(&path);
+    if !target.is_absolute() {
+        return Err("Path must be absolute".to_string());
+    }
+    if !target.exists() {
+        return Err(format!("Path does not exist: {}", path));
+    }
+
+    let requested = match editor
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+    {
+        Some(id) => Some(id),
+        None => load_preferred_editor(&state).await,
+    };
+
+    let env = tokio::task::spawn_blocking(build_augmented_env)
+        .await
+        .map_err(|e| e.to_string())?;
+    let installed = detect_installed_editors(&env);
+
+    let (spec, command) = match requested.as_deref() {
+        Some(id) => installed
+            .into_iter()
+            .find(|(spec, _)| spec.id == id)
+            .ok_or_else(|| format!("Editor {} is not installed", id))?,
+        None => installed
+            .into_iter()
+            .next()
+            .ok_or_else(|| "No supported editor found".to_string())?,
+    };
+
+    let args = build_editor_args(spec.goto, &target, line, column);
+    let working_dir = if target.is_dir() {
+        target.clone()
+    } else {
+        target
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| target.clone())
+    };
+
+    Command::new(&command)
+        .args(&args)
+        .envs(&env)
+        .current_dir(&working_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            warn!("[editors] Failed to launch {}: {}", spec.name, e);
+            format!("Failed to launch {}: {}", spec.name, e)
+        })?;
+
+    info!("[editors] Opened {:?} in {}", target, spec.name);
+
+    Ok(OpenInEditorResult {
+        editor: spec.id.to_string(),
+        path: target.to_string_lossy().to_string(),
+    })
+}
@@ -1,16 +1,24 @@
 use crate::{DesktopRuntime, SettingsStore};
+use crate::commands::git::{is_read_only_workspace, run_git, READ_ONLY_WORKSPACE_ERROR};
+use crate::commands::trash::remove_path;
+use crate::opencode_manager::build_augmented_env;
 use crate::path_utils::expand_tilde_path;
-use serde::Serialize;
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashSet, VecDeque},
     path::{Path, PathBuf},
+    process::Stdio,
     time::UNIX_EPOCH,
 };
-use tokio::fs;
+use tokio::{fs, process::Command};
 
 const DEFAULT_FILE_SEARCH_LIMIT: usize = 60;
 const MAX_FILE_SEARCH_LIMIT: usize = 400;
 const FILE_SEARCH_MAX_CONCURRENCY: usize = 5;
+const REPLACE_MAX_FILES: usize = 1000;
+const REPLACE_MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+const REPLACE_PREVIEW_MAX_LINES: usize = 100;
 const FILE_SEARCH_EXCLUDED_DIRS: &[&str] = &[
     "node_modules",
     ".git",
@@ -51,6 +59,14 @@ pub struct CreateDirectoryResponse {
     path: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletePathResponse {
+    success: bool,
+    path: String,
+    trashed: bool,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileSearchHit {
@@ -68,6 +84,54 @@ pub struct SearchFilesResponse {
     files: Vec<FileSearchHit>,
 }
 
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceOptions {
+    directory: Option<String>,
+    regex: Option<bool>,
+    case_sensitive: Option<bool>,
+    whole_word: Option<bool>,
+    include: Option<Vec<String>>,
+    apply: Option<bool>,
+    stage: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceLinePreview {
+    line: usize,
+    before: String,
+    after: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceFilePreview {
+    path: String,
+    relative_path: String,
+    matches: usize,
+    lines: Vec<ReplaceLinePreview>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceInWorkspaceResponse {
+    root: String,
+    applied: bool,
+    truncated: bool,
+    total_files: usize,
+    total_matches: usize,
+    files: Vec<ReplaceFilePreview>,
+    staged: bool,
+    stage_error: Option<String>,
+}
+
+struct PendingReplacement {
+    path: PathBuf,
+    original: String,
+    updated: String,
+}
+
 #[derive(Debug)]
 enum FsCommandError {
     NotFound,
@@ -119,6 +183,20 @@ impl FsCommandError {
             FsCommandError::NotFound => "Parent directory not found".to_string(),
         }
     }
+
+    fn to_delete_message(&self) -> String {
+        match self {
+            FsCommandError::NotFound => "Path not found".to_string(),
+            FsCommandError::AccessDenied | FsCommandError::OutsideWorkspace => {
+                "Access to path denied".to_string()
+            }
+            FsCommandError::NotDirectory => "Specified path is not a directory".to_string(),
+            FsCommandError::Other(message) => {
+                let _ = message;
+                "Failed to delete path".to_string()
+            }
+        }
+    }
 }
 
 impl From<std::io::Error> for FsCommandError {
@@ -365,6 +443,411 @@ pub async fn create_directory(
     })
 }
 
+#[tauri::command]
+pub async fn delete_path(
+    path: String,
+    permanent: Option<bool>,
+    state: tauri::State<'_, DesktopRuntime>,
+) -> Result<DeletePathResponse, String> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err("Path is required".to_string());
+    }
+
+    let workspace_root = resolve_workspace_root(state.settings()).await;
+    // Resolve the parent only so a symlink is removed itself rather than its target
+    let candidate = resolve_creatable_path(trimmed, workspace_root.as_ref())
+        .await
+        .map_err(|err| err.to_delete_message())?;
+    let file_name = candidate
+        .file_name()
+        .ok_or_else(|| FsCommandError::AccessDenied.to_delete_message())?;
+    let parent = candidate
+        .parent()
+        .ok_or_else(|| FsCommandError::AccessDenied.to_delete_message())?;
+    let resolved_path = fs::canonicalize(parent)
+        .await
+        .map_err(|err| FsCommandError::from(err).to_delete_message())?
+        .join(file_name);
+
+    // Never allow removing the workspace root itself
+    if workspace_root.as_ref() == Some(&resolved_path) {
+        return Err(FsCommandError::AccessDenied.to_delete_message());
+    }
+
+    let trashed = remove_path(&resolved_path, permanent.unwrap_or(false))
+        .await
+        .map_err(|err| match err.downcast::<std::io::Error>() {
+            Ok(io_err) => FsCommandError::from(io_err).to_delete_message(),
+            Err(other) => other.to_string(),
+        })?;
+
+    Ok(DeletePathResponse {
+        success: true,
+        path: normalize_path(&resolved_path),
+        trashed,
+    })
+}
+
+/// Search-and-replace across the workspace. Returns a preview by default; with
+/// `apply`, every file is rewritten or none is, and the result can be staged.
+#[tauri::command]
+pub async fn replace_in_workspace(
+    query: String,
+    replacement: String,
+    options: Option<ReplaceOptions>,
+    state: tauri::State<'_, DesktopRuntime>,
+) -> Result<ReplaceInWorkspaceResponse, String> {
+    let options = options.unwrap_or_default();
+    if query.is_empty() {
+        return Err("Search query is required".to_string());
+    }
+
+    let workspace_root = resolve_workspace_root(state.settings()).await;
+    let resolved_root = resolve_sandboxed_path(options.directory.clone(), workspace_root.as_ref())
+        .await
+        .map_err(|err| err.to_search_message())?;
+
+    let is_regex = options.regex.unwrap_or(false);
+    let case_sensitive = options.case_sensitive.unwrap_or(true);
+    let whole_word = options.whole_word.unwrap_or(false);
+    let includes: Vec<String> = options
+        .include
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|glob| glob.trim().to_string())
+        .filter(|glob| !glob.is_empty())
+        .collect();
+
+    let pattern = if is_regex {
+        query.clone()
+    } else {
+        regex::escape(&query)
+    };
+    let pattern = if whole_word {
+        format!(r"\b(?:{})\b", pattern)
+    } else {
+        pattern
+    };
+    let matcher = RegexBuilder::new(&pattern)
+        .case_insensitive(!case_sensitive)
+        .multi_line(true)
+        .build()
+        .map_err(|err| format!("Invalid search pattern: {}", err))?;
+
+    let mut candidates = match find_files_with_ripgrep(
+        &resolved_root,
+        &query,
+        is_regex,
+        case_sensitive,
+        whole_word,
+        &includes,
+    )
+    .await
+    {
+        Some(files) => files,
+        None => walk_files_for_replace(&resolved_root, &includes).await,
+    };
+    candidates.sort();
+
+    let truncated = candidates.len() > REPLACE_MAX_FILES;
+    candidates.truncate(REPLACE_MAX_FILES);
+
+    let mut pending = Vec::new();
+    let mut files = Vec::new();
+    let mut total_matches = 0;
+
+    for path in candidates {
+        let Some((replaced, preview)) =
+            build_file_replacement(&resolved_root, &path, &matcher, &replacement, is_regex).await
+        else {
+            continue;
+        };
+        total_matches += preview.matches;
+        files.push(preview);
+        pending.push(replaced);
+    }
+
+    let apply = options.apply.unwrap_or(false);
+    let mut response = ReplaceInWorkspaceResponse {
+        root: normalize_path(&resolved_root),
+        applied: false,
+        truncated,
+        total_files: files.len(),
+        total_matches,
+        files,
+        staged: false,
+        stage_error: None,
+    };
+
+    if !apply || pending.is_empty() {
+        return Ok(response);
+    }
+    if truncated {
+        return Err(format!(
+            "Too many matching files (more than {}); narrow the search before applying",
+            REPLACE_MAX_FILES
+        ));
+    }
+    if is_read_only_workspace(&resolved_root, state.settings()).await {
+        return Err(format!(
+            "{}: {} is opened in read-only mode",
+            READ_ONLY_WORKSPACE_ERROR,
+            resolved_root.display()
+        ));
+    }
+
+    apply_replacements(&pending).await?;
+    response.applied = true;
+
+    if options.stage.unwrap_or(false) {
+        match stage_replaced_files(&resolved_root, &pending).await {
+            Ok(()) => response.staged = true,
+            Err(err) => response.stage_error = Some(err),
+        }
+    }
+
+    Ok(response)
+}
+
+/// List files containing a match using ripgrep, which honours .gitignore. Returns `None`
+/// when ripgrep is not installed so the caller can fall back to walking the tree.
+async fn find_files_with_ripgrep(
+    root: &Path,
+    query: &str,
+    is_regex: bool,
+    case_sensitive: bool,
+    whole_word: bool,
+    includes: &[String],
+) -> Option<Vec<PathBuf>> {
+    let mut command = Command::new("rg");
+    command
+        .args(["--files-with-matches", "--null", "--no-messages"])
+        .current_dir(root)
+        .envs(build_augmented_env())
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+    if !is_regex {
+        command.arg("--fixed-strings");
+    }
+    if !case_sensitive {
+        command.arg("--ignore-case");
+    }
+    if whole_word {
+        command.arg("--word-regexp");
+    }
+    for glob in includes {
+        command.arg("--glob").arg(glob);
+    }
+    command.arg("--regexp").arg(query).arg(".");
+
+    let output = command.output().await.ok()?;
+    // Exit code 1 means no matches; anything else is a real failure
+    match output.status.code() {
+        Some(0) | Some(1) => {}
+        _ => return None,
+    }
+
+    Some(
+        output
+            .stdout
+            .split(|byte| *byte == 0)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let relative = String::from_utf8_lossy(entry);
+                root.join(relative.trim_start_matches("./"))
+            })
+            .collect(),
+    )
+}
+
+async fn walk_files_for_replace(root: &Path, includes: &[String]) -> Vec<PathBuf> {
+    let include_patterns: Vec<Regex> = includes
+        .iter()
+        .filter_map(|glob| {
+            let pattern = format!(
+                "^{}$",
+                glob.split('*')
+                    .map(regex::escape)
+                    .collect::<Vec<_>>()
+                    .join(".*")
+            );
+            Regex::new(&pattern).ok()
+        })
+        .collect();
+
+    let mut files = Vec::new();
+    let mut queue = VecDeque::from([root.to_path_buf()]);
+
+    while let Some(dir) = queue.pop_front() {
+        let Ok(mut entries) = fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(file_type) = entry.file_type().await else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') {
+                continue;
+            }
+            let entry_path = entry.path();
+            if file_type.is_dir() {
+                if !should_skip_directory(&name) {
+                    queue.push_back(entry_path);
+                }
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+
+            let relative = relative_path(root, &entry_path);
+            if include_patterns.is_empty()
+                || include_patterns
+                    .iter()
+                    .any(|re| re.is_match(&relative) || re.is_match(&name))
+            {
+                files.push(entry_path);
+            }
+        }
+    }
+
+    files
+}
+
+async fn build_file_replacement(
+    root: &Path,
+    path: &Path,
+    matcher: &Regex,
+    replacement: &str,
+    expand_captures: bool,
+) -> Option<(PendingReplacement, ReplaceFilePreview)> {
+    let metadata = fs::metadata(path).await.ok()?;
+    if !metadata.is_file() || metadata.len() > REPLACE_MAX_FILE_BYTES {
+        return None;
+    }
+    let bytes = fs::read(path).await.ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    let original = String::from_utf8(bytes).ok()?;
+
+    let mut updated = String::with_capacity(original.len());
+    let mut lines = Vec::new();
+    let mut matches = 0;
+
+    // Replace line by line so the preview shows exactly what gets written
+    for (index, raw_line) in original.split_inclusive('\n').enumerate() {
+        let content = raw_line.trim_end_matches(['\n', '\r']);
+        let ending = &raw_line[content.len()..];
+        let count = matcher.find_iter(content).count();
+        if count == 0 {
+            updated.push_str(raw_line);
+            continue;
+        }
+
+        let replaced = if expand_captures {
+            matcher.replace_all(content, replacement)
+        } else {
+            matcher.replace_all(content, NoExpand(replacement))
+        };
+        matches += count;
+        if lines.len() < REPLACE_PREVIEW_MAX_LINES {
+            lines.push(ReplaceLinePreview {
+                line: index + 1,
+                before: content.to_string(),
+                after: replaced.to_string(),
+            });
+        }
+        updated.push_str(&replaced);
+        updated.push_str(ending);
+    }
+
+    if matches == 0 || updated == original {
+        return None;
+    }
+
+    Some((
+        PendingReplacement {
+            path: path.to_path_buf(),
+            original,
+            updated,
+        },
+        ReplaceFilePreview {
+            path: normalize_path(path),
+            relative_path: relative_path(root, path),
+            matches,
+            lines,
+        },
+    ))
+}
+
+/// Write every file to a sibling temp file first, then rename them into place. If any
+/// step fails, temp files are removed and already-renamed files get their content back.
+async fn apply_replacements(pending: &[PendingReplacement]) -> Result<(), String> {
+    let mut temp_paths = Vec::with_capacity(pending.len());
+
+    for item in pending {
+        let file_name = item
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let temp_path =
+            item.path
+                .with_file_name(format!(".{}.tmp-{}", file_name, uuid::Uuid::new_v4()));
+
+        let written = async {
+            fs::write(&temp_path, &item.updated).await?;
+            let permissions = fs::metadata(&item.path).await?.permissions();
+            fs::set_permissions(&temp_path, permissions).await
+        }
+        .await;
+
+        if let Err(err) = written {
+            let _ = fs::remove_file(&temp_path).await;
+            for temp in &temp_paths {
+                let _ = fs::remove_file(temp).await;
+            }
+            return Err(format!("Failed to write {}: {}", item.path.display(), err));
+        }
+        temp_paths.push(temp_path);
+    }
+
+    for (index, (item, temp_path)) in pending.iter().zip(&temp_paths).enumerate() {
+        if let Err(err) = fs::rename(temp_path, &item.path).await {
+            for restored in &pending[..index] {
+                let _ = fs::write(&restored.path, &restored.original).await;
+            }
+            for temp in &temp_paths[index..] {
+                let _ = fs::remove_file(temp).await;
+            }
+            return Err(format!(
+                "Failed to replace {}: {}",
+                item.path.display(),
+                err
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+async fn stage_replaced_files(root: &Path, pending: &[PendingReplacement]) -> Result<(), String> {
+    let paths: Vec<String> = pending
+        .iter()
+        .map(|item| item.path.to_string_lossy().to_string())
+        .collect();
+    let mut args = vec!["add", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    run_git(&args, root)
+        .await
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
 async fn resolve_sandboxed_path(
     path: Option<String>,
     workspace_root: Option<&PathBuf>,
@@ -1,21 +1,30 @@
 use crate::{DesktopRuntime, SettingsStore};
+use crate::commands::trash::remove_path;
+use crate::ai_completion;
+use crate::background_fetch;
+use crate::git_askpass;
+use crate::git_credentials::{self, GitHostCredential};
+use crate::git_remote::{self, RemoteUrl};
+use crate::git_watcher;
+use crate::identity_store::{self, GitIdentityProfile};
 use crate::path_utils::expand_tilde_path;
 use anyhow::{anyhow, Context, Result};
-use log::{error, info, warn};
+use log::{info, warn};
 use regex::Regex;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Component, Path, PathBuf};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::LazyLock;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tokio::fs;
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
 
-const GIT_IDENTITY_STORAGE_FILE: &str = "git-identities.json";
 const GIT_FILE_DIFF_TIMEOUT_MS: u64 = 15_000;
 const GIT_LS_REMOTE_TIMEOUT_MS: u64 = 5_000;
 const GIT_FILE_TEXT_MAX_BYTES: u64 = 2_000_000;
@@ -23,6 +32,7 @@ const GIT_FILE_IMAGE_MAX_BYTES: u64 = 10_000_000;
 // Tauri invoke payloads can become unstable with very large strings (e.g. huge blobs or base64 data URLs).
 // Keep a conservative upper bound to ensure the diff IPC response always returns.
 const GIT_FILE_IPC_MAX_CHARS: usize = 600_000;
+const GIT_PROGRESS_EVENT: &str = "git:progress";
 
 // --- Structs mirroring TypeScript types ---
 
@@ -32,6 +42,12 @@ pub struct GitStatusFile {
     pub path: String,
     pub index: String,
     pub working_dir: String,
+    /// Git skips checking this file for changes (`update-index --assume-unchanged`)
+    #[serde(default)]
+    pub assume_unchanged: bool,
+    /// Git ignores this file in the worktree (`update-index --skip-worktree`)
+    #[serde(default)]
+    pub skip_worktree: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -44,6 +60,13 @@ pub struct GitStatus {
     pub files: Vec<GitStatusFile>,
     pub is_clean: bool,
     pub diff_stats: Option<HashMap<String, DiffStat>>,
+    /// Operation in progress: merging, rebasing, cherryPicking, reverting,
+    /// applyingPatches, bisecting, or detachedHead when none is but HEAD is detached
+    #[serde(default)]
+    pub operation_state: Option<String>,
+    /// Flagged files whose changes git hides, so they are missing from `files`
+    #[serde(default)]
+    pub hidden_files: Vec<GitIndexFlags>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -62,6 +85,10 @@ pub struct GitBranchDetails {
     pub tracking: Option<String>,
     pub ahead: Option<i32>,
     pub behind: Option<i32>,
+    /// Committer date of the tip, ISO 8601
+    pub last_commit_date: Option<String>,
+    pub last_commit_author: Option<String>,
+    pub last_commit_subject: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -87,9 +114,11 @@ pub struct GitCommitResult {
     pub commit: String,
     pub branch: String,
     pub summary: GitCommitSummary,
+    /// Hooks were bypassed with `--no-verify`
+    pub verification_skipped: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GitPushResult {
     pub success: bool,
@@ -97,6 +126,10 @@ pub struct GitPushResult {
     pub repo: String,
     #[serde(rename = "ref")]
     pub ref_: Option<String>, // "ref" is a keyword in Rust
+    /// One entry per remote pushed to, in push order
+    pub remotes: Vec<GitRemotePushResult>,
+    /// The pre-push hook was bypassed with `--no-verify`
+    pub verification_skipped: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -106,6 +139,45 @@ pub struct GitPushRef {
     pub remote: String,
 }
 
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRemotePushResult {
+    pub remote: String,
+    pub success: bool,
+    pub pushed: Vec<GitPushRef>,
+    pub error: Option<GitError>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFetchRefUpdate {
+    pub kind: String,
+    pub source: String,
+    pub target: String,
+    pub old_hash: Option<String>,
+    pub new_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFetchSummary {
+    pub new_branches: i32,
+    pub new_tags: i32,
+    pub fast_forwards: i32,
+    pub forced_updates: i32,
+    pub deleted: i32,
+    pub rejected: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFetchResult {
+    pub success: bool,
+    pub remote: Option<String>,
+    pub updates: Vec<GitFetchRefUpdate>,
+    pub summary: GitFetchSummary,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GitPullResult {
@@ -180,24 +252,6 @@ async fn collect_changed_files_for_range(root: &Path, range: &str) -> Result<Vec
         .collect())
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct GitIdentityProfile {
-    pub id: String,
-    pub name: String,
-    pub user_name: String,
-    pub user_email: String,
-    pub ssh_key: Option<String>,
-    pub color: Option<String>,
-    pub icon: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct GitIdentityProfilesWrapper {
-    pub profiles: Vec<GitIdentityProfile>,
-}
-
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct GitIdentitySummary {
@@ -221,6 +275,11 @@ pub struct GitLogEntry {
     pub files_changed: i32,
     pub insertions: i32,
     pub deletions: i32,
+    #[serde(default)]
+    pub parents: Vec<String>,
+    /// Note attached under the default notes ref (`refs/notes/commits`)
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -229,14 +288,24 @@ pub struct GitLogResponse {
     pub all: Vec<GitLogEntry>,
     pub latest: Option<GitLogEntry>,
     pub total: i32,
+    /// More commits follow this page
+    #[serde(default)]
+    pub has_more: bool,
+    /// Pass as `before_hash` to `get_git_log` to fetch the next page
+    #[serde(default)]
+    pub next_cursor: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GitWorktreeInfo {
     pub worktree: String,
     pub head: Option<String>,
     pub branch: Option<String>,
+    pub locked: bool,
+    pub lock_reason: Option<String>,
+    pub prunable: bool,
+    pub prunable_reason: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -262,8 +331,26 @@ pub struct GitCommitFilesResponse {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
 pub struct CommitMessageResponse {
     pub message: GeneratedCommitMessage,
+    /// The model could not be used and the message was generated locally
+    #[serde(default)]
+    pub fallback: bool,
+    #[serde(default)]
+    pub fallback_reason: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitProgressEvent {
+    pub operation_id: String,
+    pub operation: String,
+    pub phase: String,
+    pub percent: Option<u32>,
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+    pub message: String,
 }
 
 // --- Constants & Regexes ---
@@ -271,16 +358,119 @@ pub struct CommitMessageResponse {
 static WORKTREE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^worktree (.+)$").unwrap());
 static HEAD_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^HEAD (.+)$").unwrap());
 static BRANCH_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^branch (.+)$").unwrap());
-static FILES_CHANGED_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(\d+)\s+files?\s+changed").unwrap());
-static INSERTIONS_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(\d+)\s+insertions?\(\+\)").unwrap());
-static DELETIONS_REGEX: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(\d+)\s+deletions?\(-\)").unwrap());
+// Progress lines from `--progress` stderr, e.g. "remote: Counting objects:  45% (450/1000)"
+static PROGRESS_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:remote:\s*)?([A-Za-z][A-Za-z ]*?):\s+(\d+)%(?:\s+\((\d+)/(\d+)\))?").unwrap()
+});
+// Ref update lines from `git fetch` stderr, e.g. " + 1a2b3c...4d5e6f  main  -> origin/main  (forced update)"
+static FETCH_REF_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*([ +\-t*=!])\s+(\[[^\]]+\]|\S+)\s+(\S+)\s+->\s+(\S+)(?:\s+\((.+)\))?\s*$")
+        .unwrap()
+});
+
+/// Environment every git runner sets so git never prompts and output stays parseable
+const GIT_ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("GIT_TERMINAL_PROMPT", "0"),
+    ("GCM_INTERACTIVE", "Never"),
+    ("LC_ALL", "C"),
+];
+/// Local (non-network) commands also skip optional lock-taking such as index refreshes
+const GIT_OPTIONAL_LOCKS_OVERRIDE: (&str, &str) = ("GIT_OPTIONAL_LOCKS", "0");
 
 // --- Helpers ---
 
-async fn run_git(args: &[&str], cwd: &Path) -> Result<String> {
+/// One git process a command would start, as reported by explain mode
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitInvocation {
+    pub argv: Vec<String>,
+    pub env: BTreeMap<String, String>,
+    pub cwd: String,
+}
+
+impl GitInvocation {
+    /// A command run through `run_git` and friends
+    fn local<S: AsRef<str>>(args: &[S], cwd: &Path) -> Self {
+        let mut invocation = Self::base(args, cwd);
+        invocation.env.insert(
+            GIT_OPTIONAL_LOCKS_OVERRIDE.0.to_string(),
+            GIT_OPTIONAL_LOCKS_OVERRIDE.1.to_string(),
+        );
+        invocation
+    }
+
+    /// A command run through `run_git_with_progress`
+    fn network<S: AsRef<str>>(args: &[S], cwd: &Path) -> Self {
+        let mut invocation = Self::base(args, cwd);
+        for (key, value) in git_askpass::env() {
+            let value = if key == "OPENCHAMBER_ASKPASS_TOKEN" {
+                "<redacted>".to_string()
+            } else {
+                value
+            };
+            invocation.env.insert(key.to_string(), value);
+        }
+        invocation
+    }
+
+    fn base<S: AsRef<str>>(args: &[S], cwd: &Path) -> Self {
+        Self {
+            argv: std::iter::once("git")
+                .chain(args.iter().map(AsRef::as_ref))
+                .map(str::to_string)
+                .collect(),
+            env: GIT_ENV_OVERRIDES
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            cwd: cwd.to_string_lossy().to_string(),
+        }
+    }
+
+    /// A command whose failing hooks are reported from a `GIT_TRACE2_EVENT` log
+    fn with_hook_trace(mut self) -> Self {
+        self.env.insert(
+            "GIT_TRACE2_EVENT".to_string(),
+            "<temporary trace file>".to_string(),
+        );
+        self
+    }
+
+    fn with_env(mut self, envs: &[(&str, &str)]) -> Self {
+        for (key, value) in envs {
+            self.env.insert(key.to_string(), value.to_string());
+        }
+        self
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommandPlan {
+    /// Always true; lets callers tell a plan from a regular result
+    pub explained: bool,
+    pub steps: Vec<GitInvocation>,
+}
+
+/// Result of a mutating command that accepts `explain`: the regular result, or with
+/// `explain: true` the git invocations it would run, without running anything
+#[derive(Serialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Explainable<T> {
+    Plan(GitCommandPlan),
+    Done(T),
+}
+
+impl<T> Explainable<T> {
+    fn plan(steps: Vec<GitInvocation>) -> Self {
+        Explainable::Plan(GitCommandPlan {
+            explained: true,
+            steps,
+        })
+    }
+}
+
+pub(crate) async fn run_git(args: &[&str], cwd: &Path) -> Result<String> {
     run_git_with_allowed_exit(args, cwd, &[]).await
 }
 
@@ -294,10 +484,8 @@ async fn run_git_with_allowed_exit(
         .current_dir(cwd)
         .stdin(Stdio::null())
         .kill_on_drop(true)
-        .env("GIT_OPTIONAL_LOCKS", "0")
-        .env("GIT_TERMINAL_PROMPT", "0")
-        .env("GCM_INTERACTIVE", "Never")
-        .env("LC_ALL", "C")
+        .env(GIT_OPTIONAL_LOCKS_OVERRIDE.0, GIT_OPTIONAL_LOCKS_OVERRIDE.1)
+        .envs(GIT_ENV_OVERRIDES.iter().copied())
         .output()
         .await
         .context("Failed to execute git command")?;
@@ -309,154 +497,886 @@ async fn run_git_with_allowed_exit(
             }
         }
         let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if let Some(lock) = detect_git_lock(cwd, &stderr).await {
+            return Err(anyhow!("{}", format_lock_error(&lock, &stderr)));
+        }
         return Err(anyhow!("{}", stderr));
     }
 
     Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
-async fn run_git_bytes_with_allowed_exit_timeout(
+/// Run git and return (success, stdout, stderr) without treating failure as an error
+async fn run_git_with_status(args: &[&str], cwd: &Path) -> Result<(bool, String, String)> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GCM_INTERACTIVE", "Never")
+        .env("LC_ALL", "C")
+        .output()
+        .await
+        .context("Failed to execute git command")?;
+
+    Ok((
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).trim().to_string(),
+        String::from_utf8_lossy(&output.stderr).trim().to_string(),
+    ))
+}
+
+/// Run git with extra environment variables, feeding `input` on stdin
+async fn run_git_with_env_input(
     args: &[&str],
     cwd: &Path,
-    allowed_codes: &[i32],
-    timeout_ms: u64,
-) -> Result<Vec<u8>> {
-    let output = tokio::time::timeout(
-        std::time::Duration::from_millis(timeout_ms),
-        Command::new("git")
-            .args(args)
-            .current_dir(cwd)
-            .env("GIT_OPTIONAL_LOCKS", "0")
-            .env("GIT_TERMINAL_PROMPT", "0")
-            .env("GCM_INTERACTIVE", "Never")
-            .env("LC_ALL", "C")
-            .stdin(Stdio::null())
-            .kill_on_drop(true)
-            .output(),
-    )
-    .await
-    .map_err(|_| anyhow!("Git command timed out after {}ms", timeout_ms))?
-    .context("Failed to execute git command")?;
+    envs: &[(&str, &str)],
+    input: &[u8],
+) -> Result<String> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .envs(envs.iter().copied())
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("LC_ALL", "C")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .context("Failed to execute git command")?;
 
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input).await?;
+    }
+    let output = child.wait_with_output().await?;
     if !output.status.success() {
-        if let Some(code) = output.status.code() {
-            if allowed_codes.contains(&code) {
-                return Ok(output.stdout);
-            }
-        }
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        return Err(anyhow!("{}", stderr));
+        return Err(anyhow!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
     }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
 
-    Ok(output.stdout)
+/// What went wrong, classified from git's stderr so the UI can react without matching
+/// on message text
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum GitErrorKind {
+    NotARepository,
+    AuthRequired,
+    Network,
+    MergeConflict,
+    DetachedHead,
+    NonFastForward,
+    LockContention,
+    UncommittedChanges,
+    RefNotFound,
+    AlreadyExists,
+    HookFailed,
+    ReadOnlyWorkspace,
+    Canceled,
+    Unknown,
 }
 
-async fn read_file_bytes_limited(path: &Path, max_bytes: u64) -> Result<(Vec<u8>, bool)> {
-    let file = tokio::fs::File::open(path).await?;
-    let mut buf = Vec::new();
-    let mut limited = file.take(max_bytes);
-    limited.read_to_end(&mut buf).await?;
+/// Substrings of git's output (lowercased) that identify each kind, checked in order
+const GIT_ERROR_PATTERNS: &[(GitErrorKind, &[&str])] = &[
+    (GitErrorKind::ReadOnlyWorkspace, &["readonlyworkspace:"]),
+    (GitErrorKind::Canceled, &["gitcanceled:"]),
+    (
+        GitErrorKind::LockContention,
+        &[
+            "gitlocked:",
+            ".lock': file exists",
+            "another git process",
+            "gc is already running",
+        ],
+    ),
+    (GitErrorKind::NotARepository, &["not a git repository"]),
+    (
+        GitErrorKind::AuthRequired,
+        &[
+            "authentication failed",
+            "could not read username",
+            "could not read password",
+            "terminal prompts disabled",
+            "permission denied (publickey",
+            "invalid username or password",
+            "the requested url returned error: 403",
+            "the requested url returned error: 401",
+        ],
+    ),
+    (
+        GitErrorKind::NonFastForward,
+        &[
+            "non-fast-forward",
+            "updates were rejected",
+            "(fetch first)",
+            "not possible to fast-forward",
+        ],
+    ),
+    (
+        GitErrorKind::MergeConflict,
+        &[
+            "conflict (",
+            "automatic merge failed",
+            "fix conflicts",
+            "unmerged files",
+            "needs merge",
+            "resolve your current index first",
+            "could not apply",
+        ],
+    ),
+    (
+        GitErrorKind::UncommittedChanges,
+        &[
+            "would be overwritten by",
+            "please commit your changes or stash them",
+            "your local changes",
+            "contains modified or untracked files",
+        ],
+    ),
+    (
+        GitErrorKind::DetachedHead,
+        &[
+            "you are not currently on a branch",
+            "head detached",
+            "detached head",
+        ],
+    ),
+    (
+        GitErrorKind::Network,
+        &[
+            "could not resolve host",
+            "could not resolve hostname",
+            "unable to access",
+            "connection timed out",
+            "connection refused",
+            "could not read from remote repository",
+            "network is unreachable",
+        ],
+    ),
+    (
+        GitErrorKind::RefNotFound,
+        &[
+            "unknown revision",
+            "did not match any",
+            "not a valid object name",
+            "couldn't find remote ref",
+            "invalid reference",
+            "not a valid ref",
+            "bad revision",
+        ],
+    ),
+    (GitErrorKind::AlreadyExists, &["already exists"]),
+];
+
+fn classify_git_error(text: &str) -> GitErrorKind {
+    let lower = text.to_lowercase();
+    GIT_ERROR_PATTERNS
+        .iter()
+        .find(|(_, needles)| needles.iter().any(|needle| lower.contains(needle)))
+        .map(|(kind, _)| *kind)
+        .unwrap_or(GitErrorKind::Unknown)
+}
 
-    let truncated = match tokio::fs::metadata(path).await {
-        Ok(meta) => meta.len() > max_bytes,
-        Err(_) => false,
-    };
+/// Error returned by git commands. `message` is the most relevant line (git's `fatal:` or
+/// `error:` line when there is one) and `detail` the full output it was taken from.
+/// For hook failures `hook_output` holds what the hook printed, separated from git's own
+/// `fatal:`/`error:` lines in `git_output`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitError {
+    pub kind: GitErrorKind,
+    pub message: String,
+    pub detail: Option<String>,
+    pub hook: Option<String>,
+    pub hook_output: Option<String>,
+    pub git_output: Option<String>,
+    pub lock: Option<GitLockInfo>,
+}
 
-    Ok((buf, truncated))
+impl From<String> for GitError {
+    fn from(text: String) -> Self {
+        let text = text.trim();
+        let kind = classify_git_error(text);
+        let summary = text
+            .lines()
+            .map(str::trim)
+            .find_map(|line| {
+                line.strip_prefix("fatal:")
+                    .or_else(|| line.strip_prefix("error:"))
+            })
+            .or_else(|| text.lines().map(str::trim).find(|line| !line.is_empty()))
+            .unwrap_or("Git command failed")
+            .trim();
+        // The code prefixes are redundant once the kind is set
+        let message = [
+            READ_ONLY_WORKSPACE_ERROR,
+            GIT_LOCKED_ERROR,
+            GIT_CANCELED_ERROR,
+        ]
+        .iter()
+        .find_map(|code| summary.strip_prefix(code)?.strip_prefix(':'))
+        .unwrap_or(summary)
+        .trim()
+        .to_string();
+        Self {
+            kind,
+            detail: Some(text.to_string()).filter(|detail| *detail != message),
+            message,
+            hook: None,
+            hook_output: None,
+            git_output: None,
+            lock: None,
+        }
+    }
 }
 
-async fn read_file_bytes_limited_with_timeout(
-    path: &Path,
-    max_bytes: u64,
-    timeout_ms: u64,
-) -> Result<(Vec<u8>, bool)> {
-    tokio::time::timeout(
-        std::time::Duration::from_millis(timeout_ms),
-        read_file_bytes_limited(path, max_bytes),
-    )
-    .await
-    .map_err(|_| anyhow!("File read timed out after {}ms", timeout_ms))?
+impl From<&str> for GitError {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_string())
+    }
 }
 
-async fn metadata_with_timeout(path: &Path, timeout_ms: u64) -> Result<std::fs::Metadata> {
-    tokio::time::timeout(
-        std::time::Duration::from_millis(timeout_ms),
-        fs::metadata(path),
-    )
-    .await
-    .map_err(|_| anyhow!("Metadata read timed out after {}ms", timeout_ms))?
-    .map_err(|e| e.into())
+impl From<anyhow::Error> for GitError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::from(err.to_string())
+    }
 }
 
-fn normalize_relative_path(path: &Path) -> PathBuf {
-    let mut result = PathBuf::new();
-    for component in path.components() {
-        match component {
-            Component::ParentDir => {
-                result.pop();
-            }
-            Component::CurDir => {}
-            Component::Normal(part) => result.push(part),
-            _ => {}
-        }
+impl std::fmt::Display for GitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
     }
-    result
 }
 
-async fn resolve_repo_root(root: &Path) -> PathBuf {
-    match run_git(&["rev-parse", "--show-toplevel"], root).await {
-        Ok(output) => {
-            let trimmed = output.trim();
-            if trimmed.is_empty() {
-                root.to_path_buf()
-            } else {
-                PathBuf::from(trimmed)
+/// Name of the hook that exited non-zero, read from a `GIT_TRACE2_EVENT` log
+fn failed_hook_from_trace(trace: &str) -> Option<String> {
+    let mut hooks: HashMap<u64, String> = HashMap::new();
+    for line in trace.lines() {
+        let Ok(event) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let child_id = event.get("child_id").and_then(|v| v.as_u64());
+        match event.get("event").and_then(|v| v.as_str()) {
+            Some("child_start")
+                if event.get("child_class").and_then(|v| v.as_str()) == Some("hook") =>
+            {
+                if let (Some(id), Some(name)) =
+                    (child_id, event.get("hook_name").and_then(|v| v.as_str()))
+                {
+                    hooks.insert(id, name.to_string());
+                }
+            }
+            Some("child_exit") => {
+                let code = event.get("code").and_then(|v| v.as_i64()).unwrap_or(0);
+                if code != 0 {
+                    if let Some(name) = child_id.and_then(|id| hooks.get(&id)) {
+                        return Some(name.clone());
+                    }
+                }
             }
+            _ => {}
         }
-        Err(_) => root.to_path_buf(),
     }
+    None
 }
 
-async fn resolve_git_paths(root: &Path, path_str: &str) -> (PathBuf, PathBuf, String) {
-    let repo_root = resolve_repo_root(root).await;
-    let path_candidate = if path_str.contains(" -> ") {
-        path_str
-            .split(" -> ")
-            .last()
-            .unwrap_or(path_str)
-            .trim()
-            .to_string()
-    } else {
-        path_str.to_string()
-    };
+/// Split stderr into hook output and git's trailing diagnostics
+fn split_hook_output(stderr: &str) -> (String, String) {
+    let lines: Vec<&str> = stderr.lines().collect();
+    let git_start = lines
+        .iter()
+        .rposition(|line| {
+            !(line.starts_with("fatal:")
+                || line.starts_with("error:")
+                || line.starts_with("hint:")
+                || line.trim().is_empty())
+        })
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+
+    (
+        lines[..git_start].join("\n").trim().to_string(),
+        lines[git_start..].join("\n").trim().to_string(),
+    )
+}
 
-    let input_path = Path::new(&path_candidate);
-    let absolute_path = if input_path.is_absolute() {
-        input_path.to_path_buf()
-    } else {
-        let from_root = root.join(input_path);
-        if metadata_with_timeout(&from_root, GIT_FILE_DIFF_TIMEOUT_MS).await.is_ok() {
-            from_root
-        } else {
-            repo_root.join(input_path)
-        }
-    };
+/// Run a git command that may trigger hooks, with extra environment variables; on
+/// failure, report which hook failed and what it printed instead of a bare stderr string.
+async fn run_git_capturing_hooks(
+    args: &[&str],
+    cwd: &Path,
+    envs: &[(&str, &str)],
+) -> std::result::Result<String, GitError> {
+    let trace_path = std::env::temp_dir().join(format!(
+        "openchamber-git-trace-{}.log",
+        uuid::Uuid::new_v4()
+    ));
 
-    let relative_path = absolute_path
-        .strip_prefix(&repo_root)
-        .unwrap_or(input_path)
-        .to_path_buf();
-    let normalized_relative = normalize_relative_path(&relative_path);
-    let mut relative_str = normalized_relative.to_string_lossy().replace('\\', "/");
-    if relative_str.is_empty() || Path::new(&relative_str).is_absolute() {
-        relative_str = path_candidate;
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .env(GIT_OPTIONAL_LOCKS_OVERRIDE.0, GIT_OPTIONAL_LOCKS_OVERRIDE.1)
+        .envs(GIT_ENV_OVERRIDES.iter().copied())
+        .envs(envs.iter().copied())
+        .env("GIT_TRACE2_EVENT", &trace_path)
+        .output()
+        .await
+        .map_err(|e| GitError::from(format!("Failed to execute git command: {}", e)));
+
+    let trace = fs::read_to_string(&trace_path).await.unwrap_or_default();
+    let _ = fs::remove_file(&trace_path).await;
+    let output = output?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        return Ok(stdout);
     }
 
-    (repo_root, absolute_path, relative_str)
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    let Some(hook) = failed_hook_from_trace(&trace) else {
+        let lock = detect_git_lock(cwd, &stderr).await;
+        return Err(GitError {
+            lock,
+            ..GitError::from(stderr)
+        });
+    };
+
+    Err(hook_failed_error(hook, &stdout, stderr))
 }
 
-async fn resolve_path_for_git_show(root: &Path, path_str: &str) -> (PathBuf, PathBuf, String) {
-    // Prefer asking git for the repo-root-relative path when possible.
+fn hook_failed_error(hook: String, stdout: &str, stderr: String) -> GitError {
+    // Hooks write to both streams; git forwards hook stdout to stderr for most hooks
+    let (hook_stderr, git_output) = split_hook_output(&stderr);
+    let hook_output = [stdout, hook_stderr.as_str()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    GitError {
+        kind: GitErrorKind::HookFailed,
+        message: format!("The {} hook failed", hook),
+        detail: Some(stderr).filter(|s| !s.is_empty()),
+        hook: Some(hook),
+        hook_output: Some(hook_output).filter(|s| !s.is_empty()),
+        git_output: Some(git_output).filter(|s| !s.is_empty()),
+        lock: None,
+    }
+}
+
+static REMOTE_HOOK_DECLINED_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\((\S+) hook declined\)").unwrap());
+
+/// A push rejected by a server-side hook (`pre-receive`, `update`), whose output git
+/// relays on `remote:` lines
+fn remote_hook_error(stderr: &str) -> Option<GitError> {
+    let hook = REMOTE_HOOK_DECLINED_REGEX.captures(stderr)?[1].to_string();
+    let hook_output = stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix("remote:"))
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n");
+    // Ref rejections and git's own errors, without object counting chatter
+    let git_lines: Vec<&str> = stderr
+        .lines()
+        .filter(|line| {
+            ["!", "error:", "fatal:", "hint:"]
+                .iter()
+                .any(|p| line.starts_with(p))
+        })
+        .collect();
+
+    Some(GitError {
+        kind: GitErrorKind::HookFailed,
+        message: format!("The remote {} hook rejected the push", hook),
+        detail: Some(stderr.to_string()),
+        hook: Some(hook),
+        hook_output: Some(hook_output.trim().to_string()).filter(|s| !s.is_empty()),
+        git_output: Some(git_lines.join("\n").trim().to_string()).filter(|s| !s.is_empty()),
+        lock: None,
+    })
+}
+
+/// `run_git_with_progress` for commands that run hooks, such as push: a failing local
+/// hook or a rejection by a server-side hook comes back as a `HookFailed` error.
+async fn run_git_with_progress_capturing_hooks(
+    args: &[&str],
+    cwd: &Path,
+    remote_urls: &[String],
+    app: &AppHandle,
+    operation_id: &str,
+    operation: &str,
+) -> std::result::Result<(String, String), GitError> {
+    let trace_path = std::env::temp_dir().join(format!(
+        "openchamber-git-trace-{}.log",
+        uuid::Uuid::new_v4()
+    ));
+    let result = run_git_with_progress_traced(
+        args,
+        cwd,
+        remote_urls,
+        app,
+        operation_id,
+        operation,
+        Some(&trace_path),
+    )
+    .await;
+    let trace = fs::read_to_string(&trace_path).await.unwrap_or_default();
+    let _ = fs::remove_file(&trace_path).await;
+
+    result.map_err(|err| {
+        let stderr = err.to_string();
+        match failed_hook_from_trace(&trace) {
+            Some(hook) => hook_failed_error(hook, "", stderr),
+            None => remote_hook_error(&stderr).unwrap_or_else(|| GitError::from(stderr)),
+        }
+    })
+}
+
+fn parse_progress_line(line: &str) -> Option<(String, u32, Option<u64>, Option<u64>)> {
+    let cap = PROGRESS_REGEX.captures(line.trim())?;
+    let percent = cap[2].parse().ok()?;
+    let current = cap.get(3).and_then(|m| m.as_str().parse().ok());
+    let total = cap.get(4).and_then(|m| m.as_str().parse().ok());
+    Some((cap[1].trim().to_string(), percent, current, total))
+}
+
+/// Run a network git command with `--progress` already in `args`, emitting
+/// `git:progress` events for each phase/percent change. Returns stdout and the
+/// non-progress stderr lines (ref updates, hints, errors). `remote_urls` are the URLs
+/// the command talks to, used to supply stored HTTPS tokens.
+async fn run_git_with_progress(
+    args: &[&str],
+    cwd: &Path,
+    remote_urls: &[String],
+    app: &AppHandle,
+    operation_id: &str,
+    operation: &str,
+) -> Result<(String, String)> {
+    run_git_with_progress_traced(args, cwd, remote_urls, app, operation_id, operation, None).await
+}
+
+/// `run_git_with_progress`, optionally writing a `GIT_TRACE2_EVENT` log to `trace_path`
+async fn run_git_with_progress_traced(
+    args: &[&str],
+    cwd: &Path,
+    remote_urls: &[String],
+    app: &AppHandle,
+    operation_id: &str,
+    operation: &str,
+    trace_path: Option<&Path>,
+) -> Result<(String, String)> {
+    let mut command = Command::new("git");
+    if let Some(trace_path) = trace_path {
+        command.env("GIT_TRACE2_EVENT", trace_path);
+    }
+    // Own process group, so cancelling also reaches ssh and the remote helpers
+    #[cfg(unix)]
+    command.process_group(0);
+    // Lives until git has exited: its per-remote fetches and git-lfs redeem the same grants
+    let credentials = git_credentials::helper_env(remote_urls).await;
+    let operation_handle = register_operation(operation_id);
+    let mut child = command
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .envs(GIT_ENV_OVERRIDES.iter().copied())
+        .envs(git_askpass::env())
+        .envs(credentials.vars().iter().map(|(k, v)| (k, v)))
+        .spawn()
+        .context("Failed to execute git command")?;
+
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| anyhow!("Failed to capture git stdout"))?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .ok_or_else(|| anyhow!("Failed to capture git stderr"))?;
+
+    let stdout_task = tokio::spawn(async move {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf).await;
+        buf
+    });
+
+    let emit_progress = |phase: &str, percent, current, total, message: String| {
+        let _ = app.emit(
+            GIT_PROGRESS_EVENT,
+            GitProgressEvent {
+                operation_id: operation_id.to_string(),
+                operation: operation.to_string(),
+                phase: phase.to_string(),
+                percent,
+                current,
+                total,
+                message,
+            },
+        );
+    };
+    // Tells callers that did not pick an operation id which one to cancel
+    emit_progress("started", None, None, None, String::new());
+
+    let mut messages: Vec<String> = Vec::new();
+    let mut last_progress: Option<(String, u32)> = None;
+    let mut pending: Vec<u8> = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let mut handle_line = |raw: &[u8]| {
+        let line = String::from_utf8_lossy(raw).trim().to_string();
+        if line.is_empty() {
+            return;
+        }
+
+        match parse_progress_line(&line) {
+            Some((phase, percent, current, total)) => {
+                let key = (phase.clone(), percent);
+                if last_progress.as_ref() == Some(&key) {
+                    return;
+                }
+                last_progress = Some(key);
+                emit_progress(&phase, Some(percent), current, total, line);
+            }
+            None => messages.push(line),
+        }
+    };
+
+    let finished = {
+        let output = async {
+            // Progress lines are terminated by '\r' while they update in place
+            loop {
+                let read = stderr.read(&mut chunk).await?;
+                if read == 0 {
+                    break;
+                }
+                for &byte in &chunk[..read] {
+                    if byte == b'\r' || byte == b'\n' {
+                        handle_line(&pending);
+                        pending.clear();
+                    } else {
+                        pending.push(byte);
+                    }
+                }
+            }
+            handle_line(&pending);
+            child.wait().await.context("Failed to wait for git command")
+        };
+        tokio::select! {
+            status = output => Some(status),
+            _ = operation_handle.token.cancelled() => None,
+        }
+    };
+    let Some(status) = finished else {
+        terminate_git_child(&mut child).await;
+        stdout_task.abort();
+        emit_progress("canceled", None, None, None, String::new());
+        info!("[git] Canceled {} operation {}", operation, operation_id);
+        return Err(anyhow!(
+            "{}: {} was canceled",
+            GIT_CANCELED_ERROR,
+            operation
+        ));
+    };
+    let status = status?;
+    let stdout = stdout_task.await.unwrap_or_default();
+    let stderr_text = messages.join("\n");
+
+    if !status.success() {
+        return Err(anyhow!("{}", stderr_text));
+    }
+
+    Ok((
+        String::from_utf8_lossy(&stdout).trim().to_string(),
+        stderr_text,
+    ))
+}
+
+/// URLs git will contact for `remotes`; push URLs when `push` is set
+async fn resolve_remote_urls(root: &Path, remotes: &[&str], push: bool) -> Vec<String> {
+    let mut urls = Vec::new();
+    for remote in remotes {
+        let mut args = vec!["remote", "get-url", "--all"];
+        if push {
+            args.push("--push");
+        }
+        args.push(remote);
+        if let Ok(output) = run_git(&args, root).await {
+            urls.extend(output.lines().map(|line| line.trim().to_string()));
+        }
+    }
+    urls
+}
+
+fn resolve_operation_id(operation_id: Option<String>) -> String {
+    operation_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Error code prefix for operations stopped by `cancel_git_operation`
+pub(crate) const GIT_CANCELED_ERROR: &str = "GitCanceled";
+/// How long git gets to clean up after SIGTERM before it is killed
+const GIT_CANCEL_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Operations currently running, by operation id, with a registration number and the
+/// token that cancels them
+static RUNNING_OPERATIONS: LazyLock<std::sync::Mutex<HashMap<String, (u64, CancellationToken)>>> =
+    LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+static NEXT_OPERATION_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Registration in `RUNNING_OPERATIONS`, removed again when the operation ends
+struct OperationHandle {
+    id: String,
+    seq: u64,
+    token: CancellationToken,
+}
+
+impl Drop for OperationHandle {
+    fn drop(&mut self) {
+        let mut running = RUNNING_OPERATIONS.lock().unwrap_or_else(|e| e.into_inner());
+        // Leave the entry alone if a newer operation reused the id
+        if running
+            .get(&self.id)
+            .is_some_and(|(seq, _)| *seq == self.seq)
+        {
+            running.remove(&self.id);
+        }
+    }
+}
+
+fn register_operation(operation_id: &str) -> OperationHandle {
+    let seq = NEXT_OPERATION_SEQ.fetch_add(1, Ordering::Relaxed);
+    let token = CancellationToken::new();
+    RUNNING_OPERATIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(operation_id.to_string(), (seq, token.clone()));
+    OperationHandle {
+        id: operation_id.to_string(),
+        seq,
+        token,
+    }
+}
+
+/// Stop git with SIGTERM to its process group, which lets it remove its lock files and
+/// takes down the ssh and remote-helper processes it spawned; kill it if it is still
+/// running after a grace period
+async fn terminate_git_child(child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        use nix::{
+            sys::signal::{killpg, Signal},
+            unistd::Pid,
+        };
+        let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGTERM);
+        if tokio::time::timeout(GIT_CANCEL_GRACE, child.wait())
+            .await
+            .is_ok()
+        {
+            return;
+        }
+    }
+    let _ = child.kill().await;
+}
+
+/// Run a read-only git command that `cancel_git_operation` can stop when `operation_id`
+/// is given. Nothing needs cleaning up, so a canceled command is simply killed.
+async fn run_git_cancellable(
+    args: &[&str],
+    cwd: &Path,
+    operation_id: Option<&str>,
+) -> Result<String> {
+    let Some(operation_id) = operation_id else {
+        return run_git(args, cwd).await;
+    };
+    let handle = register_operation(operation_id);
+    tokio::select! {
+        output = run_git(args, cwd) => output,
+        _ = handle.token.cancelled() => {
+            info!("[git] Canceled operation {}", operation_id);
+            Err(anyhow!("{}: git {} was canceled", GIT_CANCELED_ERROR, args[0]))
+        }
+    }
+}
+
+/// Stop a running operation started with `operation_id`: push, pull, fetch, clone,
+/// maintenance, or a log page requested with an operation id. The operation then fails
+/// with a `canceled` error. Returns false when no such operation is running.
+#[tauri::command]
+pub async fn cancel_git_operation(operation_id: String) -> Result<bool, GitError> {
+    let token = RUNNING_OPERATIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(operation_id.trim())
+        .map(|(_, token)| token.clone());
+    match token {
+        Some(token) => {
+            token.cancel();
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+async fn run_git_bytes_with_allowed_exit_timeout(
+    args: &[&str],
+    cwd: &Path,
+    allowed_codes: &[i32],
+    timeout_ms: u64,
+) -> Result<Vec<u8>> {
+    let output = tokio::time::timeout(
+        std::time::Duration::from_millis(timeout_ms),
+        Command::new("git")
+            .args(args)
+            .current_dir(cwd)
+            .env("GIT_OPTIONAL_LOCKS", "0")
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .env("GCM_INTERACTIVE", "Never")
+            .env("LC_ALL", "C")
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| anyhow!("Git command timed out after {}ms", timeout_ms))?
+    .context("Failed to execute git command")?;
+
+    if !output.status.success() {
+        if let Some(code) = output.status.code() {
+            if allowed_codes.contains(&code) {
+                return Ok(output.stdout);
+            }
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(anyhow!("{}", stderr));
+    }
+
+    Ok(output.stdout)
+}
+
+async fn read_file_bytes_limited(path: &Path, max_bytes: u64) -> Result<(Vec<u8>, bool)> {
+    let file = tokio::fs::File::open(path).await?;
+    let mut buf = Vec::new();
+    let mut limited = file.take(max_bytes);
+    limited.read_to_end(&mut buf).await?;
+
+    let truncated = match tokio::fs::metadata(path).await {
+        Ok(meta) => meta.len() > max_bytes,
+        Err(_) => false,
+    };
+
+    Ok((buf, truncated))
+}
+
+async fn read_file_bytes_limited_with_timeout(
+    path: &Path,
+    max_bytes: u64,
+    timeout_ms: u64,
+) -> Result<(Vec<u8>, bool)> {
+    tokio::time::timeout(
+        std::time::Duration::from_millis(timeout_ms),
+        read_file_bytes_limited(path, max_bytes),
+    )
+    .await
+    .map_err(|_| anyhow!("File read timed out after {}ms", timeout_ms))?
+}
+
+async fn metadata_with_timeout(path: &Path, timeout_ms: u64) -> Result<std::fs::Metadata> {
+    tokio::time::timeout(
+        std::time::Duration::from_millis(timeout_ms),
+        fs::metadata(path),
+    )
+    .await
+    .map_err(|_| anyhow!("Metadata read timed out after {}ms", timeout_ms))?
+    .map_err(|e| e.into())
+}
+
+fn normalize_relative_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                result.pop();
+            }
+            Component::CurDir => {}
+            Component::Normal(part) => result.push(part),
+            _ => {}
+        }
+    }
+    result
+}
+
+async fn resolve_repo_root(root: &Path) -> PathBuf {
+    match run_git(&["rev-parse", "--show-toplevel"], root).await {
+        Ok(output) => {
+            let trimmed = output.trim();
+            if trimmed.is_empty() {
+                root.to_path_buf()
+            } else {
+                PathBuf::from(trimmed)
+            }
+        }
+        Err(_) => root.to_path_buf(),
+    }
+}
+
+async fn resolve_git_paths(root: &Path, path_str: &str) -> (PathBuf, PathBuf, String) {
+    let repo_root = resolve_repo_root(root).await;
+    let path_candidate = if path_str.contains(" -> ") {
+        path_str
+            .split(" -> ")
+            .last()
+            .unwrap_or(path_str)
+            .trim()
+            .to_string()
+    } else {
+        path_str.to_string()
+    };
+
+    let input_path = Path::new(&path_candidate);
+    let absolute_path = if input_path.is_absolute() {
+        input_path.to_path_buf()
+    } else {
+        let from_root = root.join(input_path);
+        if metadata_with_timeout(&from_root, GIT_FILE_DIFF_TIMEOUT_MS).await.is_ok() {
+            from_root
+        } else {
+            repo_root.join(input_path)
+        }
+    };
+
+    let relative_path = absolute_path
+        .strip_prefix(&repo_root)
+        .unwrap_or(input_path)
+        .to_path_buf();
+    let normalized_relative = normalize_relative_path(&relative_path);
+    let mut relative_str = normalized_relative.to_string_lossy().replace('\\', "/");
+    if relative_str.is_empty() || Path::new(&relative_str).is_absolute() {
+        relative_str = path_candidate;
+    }
+
+    (repo_root, absolute_path, relative_str)
+}
+
+async fn resolve_path_for_git_show(root: &Path, path_str: &str) -> (PathBuf, PathBuf, String) {
+    // Prefer asking git for the repo-root-relative path when possible.
     // This avoids subtle worktree/subdir path prefix issues.
     let path_candidate = if path_str.contains(" -> ") {
         path_str
@@ -541,7 +1461,7 @@ fn append_git_option_map(args: &mut Vec<String>, map: &serde_json::Map<String, V
 
 // Removed unused resolve_workspace_root function
 
-async fn validate_git_path(path: &str, _settings: &SettingsStore) -> Result<PathBuf> {
+pub(crate) async fn validate_git_path(path: &str, _settings: &SettingsStore) -> Result<PathBuf> {
     let path_buf = expand_tilde_path(path);
     if !path_buf.exists() {
         return Err(anyhow!("Directory does not exist: {}", path));
@@ -554,335 +1474,1034 @@ async fn validate_git_path(path: &str, _settings: &SettingsStore) -> Result<Path
     Ok(path_buf)
 }
 
-// --- Identity Storage ---
-
-async fn get_identity_storage_path() -> Result<PathBuf> {
-    let mut path = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
-    path.push(".config");
-    path.push("openchamber");
-    fs::create_dir_all(&path).await?;
-    path.push(GIT_IDENTITY_STORAGE_FILE);
-    Ok(path)
-}
-
-async fn load_identities() -> Result<Vec<GitIdentityProfile>> {
-    let path = get_identity_storage_path().await?;
-    info!("Loading identities from {:?}", path);
-
-    if !path.exists() {
-        info!("Identities file does not exist at {:?}", path);
-        return Ok(Vec::new());
-    }
-
-    let content = fs::read_to_string(&path).await?;
-    info!("Read {} bytes from identities file", content.len());
+/// Error code prefix for mutations attempted in a read-only workspace
+pub(crate) const READ_ONLY_WORKSPACE_ERROR: &str = "ReadOnlyWorkspace";
+const READ_ONLY_DIRECTORIES_KEY: &str = "readOnlyDirectories";
 
-    let wrapper: serde_json::Value = match serde_json::from_str(&content) {
-        Ok(w) => w,
-        Err(e) => {
-            error!("Failed to parse identities JSON: {}", e);
-            return Err(e.into());
-        }
+pub(crate) async fn is_read_only_workspace(path: &Path, settings: &SettingsStore) -> bool {
+    let Ok(value) = settings.load().await else {
+        return false;
+    };
+    let Some(entries) = value
+        .get(READ_ONLY_DIRECTORIES_KEY)
+        .and_then(|v| v.as_array())
+    else {
+        return false;
     };
 
-    // Handle both array and object wrapper format if needed, but spec says object with profiles array
-    if let Some(profiles) = wrapper.get("profiles") {
-        match serde_json::from_value::<Vec<GitIdentityProfile>>(profiles.clone()) {
-            Ok(p) => {
-                info!("Successfully loaded {} profiles", p.len());
-                Ok(p)
-            }
-            Err(e) => {
-                error!("Failed to deserialize profiles array: {}", e);
-                // Log the failing JSON segment for debugging
-                warn!("Profiles JSON: {}", profiles);
-                Err(e.into())
-            }
+    // Compare resolved paths so symlinks, `..` and trailing slashes can't slip past
+    let path = fs::canonicalize(path)
+        .await
+        .unwrap_or_else(|_| path.to_path_buf());
+    for entry in entries.iter().filter_map(|entry| entry.as_str()) {
+        let read_only = expand_tilde_path(entry);
+        if read_only.as_os_str().is_empty() {
+            continue;
+        }
+        let read_only = fs::canonicalize(&read_only).await.unwrap_or(read_only);
+        if path.starts_with(&read_only) {
+            return true;
         }
-    } else {
-        warn!("No 'profiles' key found in identities JSON");
-        Ok(Vec::new())
     }
+    false
 }
 
-async fn save_identities(profiles: Vec<GitIdentityProfile>) -> Result<()> {
-    let path = get_identity_storage_path().await?;
-    let wrapper = GitIdentityProfilesWrapper { profiles };
-    let content = serde_json::to_string_pretty(&wrapper)?;
-    fs::write(path, content).await?;
-    Ok(())
+/// Like `validate_git_path`, but rejects workspaces the user marked read-only.
+/// Every command that mutates the repository or working tree goes through here.
+pub(crate) async fn validate_writable_git_path(
+    path: &str,
+    settings: &SettingsStore,
+) -> Result<PathBuf> {
+    let root = validate_git_path(path, settings).await?;
+    if is_read_only_workspace(&root, settings).await {
+        return Err(anyhow!(
+            "{}: {} is opened in read-only mode",
+            READ_ONLY_WORKSPACE_ERROR,
+            root.display()
+        ));
+    }
+    Ok(root)
 }
 
-// --- Commands ---
+/// Error code prefix for commands that failed because a git lock file is held
+pub(crate) const GIT_LOCKED_ERROR: &str = "GitLocked";
+/// Locks without a known owner are only considered abandoned after this long
+const STALE_LOCK_MIN_AGE_SECS: u64 = 30;
+/// Lock files git creates at the top of the git directory
+const KNOWN_GIT_LOCKS: &[&str] = &[
+    "index.lock",
+    "shallow.lock",
+    "HEAD.lock",
+    "ORIG_HEAD.lock",
+    "config.lock",
+    "packed-refs.lock",
+    "gc.pid",
+];
+
+static LOCK_FILE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"Unable to create '([^']+\.lock)': File exists").unwrap());
+static GC_RUNNING_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"gc is already running on machine '([^']*)' pid (\d+)").unwrap());
 
-#[tauri::command]
-pub async fn check_is_git_repository(
-    directory: String,
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitLockInfo {
+    pub lock: String,
+    pub path: String,
+    pub kind: String,
+    pub age_seconds: Option<u64>,
+    pub pid: Option<u32>,
+    pub owner_alive: bool,
+    pub stale: bool,
+}
+
+fn lock_kind(name: &str) -> &'static str {
+    match name {
+        "index.lock" => "index",
+        "shallow.lock" => "shallow",
+        "gc.pid" => "gc",
+        "config.lock" => "config",
+        _ if name.ends_with(".lock") => "ref",
+        _ => "other",
+    }
+}
+
+#[cfg(unix)]
+async fn process_alive(pid: u32) -> bool {
+    use nix::{errno::Errno, sys::signal::kill, unistd::Pid};
+    // Signal 0 only checks existence; EPERM means it exists under another user
+    matches!(
+        kill(Pid::from_raw(pid as i32), None),
+        Ok(()) | Err(Errno::EPERM)
+    )
+}
+
+#[cfg(windows)]
+async fn process_alive(pid: u32) -> bool {
+    let filter = format!("PID eq {}", pid);
+    Command::new("tasklist")
+        .args(["/FI", &filter, "/NH"])
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+        .unwrap_or(false)
+}
+
+/// Git keeps `*.lock` files open while it writes them, so `lsof` names the owner
+#[cfg(unix)]
+async fn lock_file_owner(path: &Path) -> Option<u32> {
+    let output = tokio::time::timeout(
+        std::time::Duration::from_secs(2),
+        Command::new("lsof")
+            .arg("-t")
+            .arg("--")
+            .arg(path)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.trim().parse().ok())
+}
+
+#[cfg(windows)]
+async fn lock_file_owner(_path: &Path) -> Option<u32> {
+    None
+}
+
+async fn inspect_git_lock(git_dir: &Path, path: &Path) -> Option<GitLockInfo> {
+    let metadata = fs::metadata(path).await.ok()?;
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let age_seconds = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| std::time::SystemTime::now().duration_since(modified).ok())
+        .map(|age| age.as_secs());
+
+    let (pid, foreign_host) = if name == "gc.pid" {
+        // Format: "<pid> <hostname>"
+        let content = fs::read_to_string(path).await.unwrap_or_default();
+        let mut parts = content.split_whitespace();
+        let pid = parts.next().and_then(|p| p.parse().ok());
+        let foreign_host = match (parts.next(), run_hostname().await) {
+            (Some(host), Ok(local)) => !host.eq_ignore_ascii_case(&local),
+            _ => false,
+        };
+        (pid, foreign_host)
+    } else {
+        (lock_file_owner(path).await, false)
+    };
+
+    // A gc.pid from another machine (shared network checkout) cannot be checked locally
+    let owner_alive = match pid {
+        Some(pid) => foreign_host || process_alive(pid).await,
+        None => false,
+    };
+    let stale =
+        !owner_alive && (pid.is_some() || age_seconds.unwrap_or(0) >= STALE_LOCK_MIN_AGE_SECS);
+
+    Some(GitLockInfo {
+        lock: path
+            .strip_prefix(git_dir)
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| name.clone()),
+        path: path.to_string_lossy().to_string(),
+        kind: lock_kind(&name).to_string(),
+        age_seconds,
+        pid,
+        owner_alive,
+        stale,
+    })
+}
+
+async fn run_hostname() -> Result<String> {
+    let output = Command::new("hostname")
+        .stdin(Stdio::null())
+        .output()
+        .await?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+async fn resolve_git_common_dir(root: &Path) -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+        .current_dir(root)
+        .stdin(Stdio::null())
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(anyhow!("Not a git repository"));
+    }
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// Recognize git's "lock held" failures and describe the lock that caused them
+async fn detect_git_lock(cwd: &Path, stderr: &str) -> Option<GitLockInfo> {
+    if let Some(caps) = LOCK_FILE_REGEX.captures(stderr) {
+        let path = PathBuf::from(&caps[1]);
+        let git_dir = resolve_git_common_dir(cwd).await.ok();
+        let base = git_dir
+            .as_deref()
+            .or_else(|| path.parent())
+            .unwrap_or(Path::new(""));
+        return inspect_git_lock(base, &path).await;
+    }
+    if GC_RUNNING_REGEX.is_match(stderr) {
+        let git_dir = resolve_git_common_dir(cwd).await.ok()?;
+        return inspect_git_lock(&git_dir, &git_dir.join("gc.pid")).await;
+    }
+    None
+}
+
+fn format_lock_error(lock: &GitLockInfo, stderr: &str) -> String {
+    let owner = match (lock.pid, lock.owner_alive) {
+        (Some(pid), true) => format!("held by running process {}", pid),
+        (Some(pid), false) => format!("left by process {} which is no longer running", pid),
+        (None, _) if lock.stale => "no running owner found".to_string(),
+        (None, _) => "owner unknown".to_string(),
+    };
+    let age = lock
+        .age_seconds
+        .map(|age| format!(", {}s old", age))
+        .unwrap_or_default();
+    format!(
+        "{}: {} is locked ({}{}): {}",
+        GIT_LOCKED_ERROR, lock.lock, owner, age, stderr
+    )
+}
+
+/// Lock files currently present in the repository's git directory
+#[tauri::command]
+pub async fn get_git_locks(
+    directory: String,
     state: State<'_, DesktopRuntime>,
-) -> Result<bool, String> {
-    let path = validate_git_path(&directory, state.settings())
+) -> Result<Vec<GitLockInfo>, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let git_dir = resolve_git_common_dir(&root)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut candidates: Vec<PathBuf> = KNOWN_GIT_LOCKS
+        .iter()
+        .map(|name| git_dir.join(name))
+        .collect();
+    // Linked worktrees keep their own index/HEAD under worktrees/<name>/
+    if let Ok(own_dir) = run_git(&["rev-parse", "--absolute-git-dir"], &root).await {
+        let own_dir = PathBuf::from(own_dir.trim());
+        if own_dir != git_dir {
+            candidates.extend(KNOWN_GIT_LOCKS.iter().map(|name| own_dir.join(name)));
+        }
+    }
+
+    let mut pending = vec![git_dir.join("refs")];
+    while let Some(dir) = pending.pop() {
+        let Ok(mut entries) = fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false) {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "lock") {
+                candidates.push(path);
+            }
+        }
+    }
+
+    let mut locks = Vec::new();
+    for path in candidates {
+        if let Some(lock) = inspect_git_lock(&git_dir, &path).await {
+            locks.push(lock);
+        }
+    }
+    Ok(locks)
+}
+
+/// Remove a lock file, but only when no live process owns it
+#[tauri::command]
+pub async fn clear_stale_git_lock(
+    directory: String,
+    lock: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitLockInfo, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let git_dir = resolve_git_common_dir(&root)
         .await
         .map_err(|e| e.to_string())?;
-    match run_git(&["rev-parse", "--is-inside-work-tree"], &path).await {
-        Ok(output) => Ok(output.trim().eq_ignore_ascii_case("true")),
-        Err(_) => Ok(false),
+
+    let requested = PathBuf::from(lock.trim());
+    let path = if requested.is_absolute() {
+        requested
+    } else {
+        git_dir.join(requested)
+    };
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let inside_git_dir = path
+        .components()
+        .all(|c| !matches!(c, Component::ParentDir))
+        && path.starts_with(&git_dir);
+    if !inside_git_dir || !(name.ends_with(".lock") || name == "gc.pid") {
+        return Err(format!("{} is not a git lock file", lock).into());
+    }
+
+    let info = inspect_git_lock(&git_dir, &path)
+        .await
+        .ok_or_else(|| format!("Lock {} no longer exists", lock))?;
+    if info.owner_alive {
+        return Err(format!(
+            "{} is held by running process {}",
+            info.lock,
+            info.pid.unwrap_or_default()
+        )
+        .into());
+    }
+    if !info.stale {
+        return Err(format!(
+            "{} was created moments ago and may still be in use; try again shortly",
+            info.lock
+        )
+        .into());
     }
+
+    fs::remove_file(&path).await.map_err(|e| e.to_string())?;
+    info!("[git] Removed stale lock {:?}", path);
+    Ok(info)
 }
 
+// --- Commands ---
+
 #[tauri::command]
-pub async fn get_git_status(
+pub async fn check_is_git_repository(
     directory: String,
     state: State<'_, DesktopRuntime>,
-) -> Result<GitStatus, String> {
+) -> Result<bool, GitError> {
     let path = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
+    Ok(is_git_work_tree(&path).await)
+}
 
-    // 1. Get porcelain status
-    // Use -uall to show all untracked files individually, not just directories
-    let status_output = run_git(&["status", "--porcelain", "-b", "-z", "-uall"], &path)
+#[tauri::command]
+pub async fn get_workspace_read_only(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<bool, GitError> {
+    let root = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
+    Ok(is_read_only_workspace(&root, state.settings()).await)
+}
 
-    // Parse status output
-    let mut files = Vec::new();
-    let mut current = String::new();
-    let mut tracking = None;
-    let mut ahead = 0;
-    let mut behind = 0;
+#[tauri::command]
+pub async fn set_workspace_read_only(
+    directory: String,
+    read_only: bool,
+    state: State<'_, DesktopRuntime>,
+) -> Result<bool, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let root_str = root.to_string_lossy().to_string();
+
+    let mut settings = state.settings().load().await.map_err(|e| e.to_string())?;
+    let mut entries: Vec<String> = settings
+        .get(READ_ONLY_DIRECTORIES_KEY)
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
 
-    let entries: Vec<&str> = status_output.split('\0').collect();
+    entries.retain(|entry| expand_tilde_path(entry) != root);
+    if read_only {
+        entries.push(root_str);
+    }
+
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert(
+            READ_ONLY_DIRECTORIES_KEY.to_string(),
+            serde_json::json!(entries),
+        );
+    }
+    state
+        .settings()
+        .save(settings)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(is_read_only_workspace(&root, state.settings()).await)
+}
+
+/// The well-known id of git's empty tree, used to diff before the first commit
+const EMPTY_TREE_HASH: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+#[derive(Default)]
+struct PorcelainV2Status {
+    head: String,
+    upstream: Option<String>,
+    ahead: i32,
+    behind: i32,
+    initial: bool,
+    files: Vec<GitStatusFile>,
+}
+
+/// Parse `git status --porcelain=v2 --branch -z`. File codes are mapped to the v1
+/// letters (`.` becomes empty, untracked is `?`/`?`) so callers see the same shape.
+fn parse_porcelain_v2_status(output: &str) -> PorcelainV2Status {
+    let mut status = PorcelainV2Status::default();
+    let entries: Vec<&str> = output.split('\0').collect();
     let mut i = 0usize;
 
+    let code = |c: char| {
+        if c == '.' {
+            String::new()
+        } else {
+            c.to_string()
+        }
+    };
+
     while i < entries.len() {
         let entry = entries[i];
         i += 1;
-
         if entry.is_empty() {
             continue;
         }
 
-        if entry.starts_with("## ") {
-            // Branch info: ## main...origin/main [ahead 1, behind 2]
-            let branch_line = &entry[3..];
-            if let Some((local, remote_part)) = branch_line.split_once("...") {
-                current = local.to_string();
-                // Parse remote part for ahead/behind
-                // Format: origin/main [ahead 1, behind 2] or origin/main
-                if let Some(bracket_start) = remote_part.find('[') {
-                    tracking = Some(remote_part[..bracket_start].trim().to_string());
-                    let stats = &remote_part[bracket_start + 1..remote_part.len() - 1]; // inside brackets
-                    for part in stats.split(", ") {
-                        if let Some(val) = part.strip_prefix("ahead ") {
-                            ahead = val.parse().unwrap_or(0);
-                        } else if let Some(val) = part.strip_prefix("behind ") {
-                            behind = val.parse().unwrap_or(0);
-                        }
-                    }
+        if let Some(header) = entry.strip_prefix("# ") {
+            if let Some(oid) = header.strip_prefix("branch.oid ") {
+                status.initial = oid == "(initial)";
+            } else if let Some(head) = header.strip_prefix("branch.head ") {
+                status.head = if head == "(detached)" {
+                    "HEAD (no branch)".to_string()
                 } else {
-                    tracking = Some(remote_part.trim().to_string());
+                    head.to_string()
+                };
+            } else if let Some(upstream) = header.strip_prefix("branch.upstream ") {
+                status.upstream = Some(upstream.to_string());
+            } else if let Some(ab) = header.strip_prefix("branch.ab ") {
+                for part in ab.split_whitespace() {
+                    if let Some(value) = part.strip_prefix('+') {
+                        status.ahead = value.parse().unwrap_or(0);
+                    } else if let Some(value) = part.strip_prefix('-') {
+                        status.behind = value.parse().unwrap_or(0);
+                    }
                 }
-            } else {
-                // No remote or initial commit
-                current = branch_line.to_string();
             }
             continue;
         }
 
-        // File entries (porcelain v1, -z):
-        // - Normal: XY<space>path
-        // - Rename/Copy: XY<space>old_path<null>new_path
-        if entry.len() >= 4 {
-            let index_status = &entry[0..1];
-            let working_status = &entry[1..2];
-            let mut file_path = &entry[3..];
-
-            // Handle rename/copy by consuming the next NUL-terminated token as the new path.
-            let is_rename_or_copy = index_status == "R"
-                || working_status == "R"
-                || index_status == "C"
-                || working_status == "C";
-            if is_rename_or_copy && i < entries.len() {
-                let next_path = entries[i];
-                if !next_path.is_empty() {
-                    file_path = next_path;
-                    i += 1;
-                }
+        // Ordinary (1), rename/copy (2) and unmerged (u) entries carry a fixed number of
+        // space-separated fields before the path; renames are followed by the old path
+        let (fields, rest) = match entry.as_bytes()[0] {
+            b'1' => (8, entry),
+            b'2' => (9, entry),
+            b'u' => (10, entry),
+            b'?' => {
+                status.files.push(GitStatusFile {
+                    path: entry[2..].to_string(),
+                    index: "?".to_string(),
+                    working_dir: "?".to_string(),
+                    assume_unchanged: false,
+                    skip_worktree: false,
+                });
+                continue;
             }
+            _ => continue,
+        };
 
-            // Simple-git parsing logic approximation
-            files.push(GitStatusFile {
-                path: file_path.to_string(),
-                index: index_status.trim().to_string(),
-                working_dir: working_status.trim().to_string(),
-            });
+        let mut parts = rest.splitn(fields + 1, ' ');
+        let _kind = parts.next();
+        let xy: Vec<char> = parts.next().unwrap_or("..").chars().collect();
+        let Some(path) = parts.nth(fields - 2) else {
+            continue;
+        };
+        if entry.starts_with('2') {
+            // Skip the original path of the rename
+            i += 1;
         }
+
+        status.files.push(GitStatusFile {
+            path: path.to_string(),
+            index: xy.first().copied().map(code).unwrap_or_default(),
+            working_dir: xy.get(1).copied().map(code).unwrap_or_default(),
+            assume_unchanged: false,
+            skip_worktree: false,
+        });
     }
 
-    // 2. Get diff stats (staged and unstaged)
-    let mut diff_stats = HashMap::new();
+    status
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitIndexFlags {
+    pub path: String,
+    pub assume_unchanged: bool,
+    pub skip_worktree: bool,
+}
 
-    let collect_stats = |output: String| {
-        let mut stats = HashMap::new();
-        for line in output.lines() {
-            let parts: Vec<&str> = line.split('\t').collect();
-            if parts.len() >= 3 {
-                let insertions = if parts[0] == "-" {
-                    0
-                } else {
-                    parts[0].parse().unwrap_or(0)
-                };
-                let deletions = if parts[1] == "-" {
-                    0
-                } else {
-                    parts[1].parse().unwrap_or(0)
-                };
-                let path = parts[2].to_string();
-                stats.insert(
-                    path,
-                    DiffStat {
-                        insertions,
-                        deletions,
-                    },
-                );
-            }
+/// Files with the assume-unchanged or skip-worktree bit from `git ls-files -v -z`, whose
+/// tag is lowercase for assume-unchanged and `S`/`s` for skip-worktree
+fn parse_index_flags(output: &str) -> Vec<GitIndexFlags> {
+    output
+        .split('\0')
+        .filter_map(|entry| {
+            let (tag, path) = entry.split_once(' ')?;
+            let tag = tag.chars().next()?;
+            let assume_unchanged = tag.is_ascii_lowercase();
+            let skip_worktree = tag.eq_ignore_ascii_case(&'s');
+            (assume_unchanged || skip_worktree).then(|| GitIndexFlags {
+                path: path.to_string(),
+                assume_unchanged,
+                skip_worktree,
+            })
+        })
+        .collect()
+}
+
+fn parse_numstat_z(output: &str) -> HashMap<String, DiffStat> {
+    let mut stats = HashMap::new();
+    for entry in output.split('\0') {
+        let mut parts = entry.splitn(3, '\t');
+        let (Some(insertions), Some(deletions), Some(path)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        if path.is_empty() {
+            continue;
         }
-        stats
-    };
+        // Binary files report `-` for both counts
+        stats.insert(
+            path.to_string(),
+            DiffStat {
+                insertions: insertions.parse().unwrap_or(0),
+                deletions: deletions.parse().unwrap_or(0),
+            },
+        );
+    }
+    stats
+}
+
+/// Count commits not yet on the default branch, for branches without an upstream.
+/// A single `for-each-ref` resolves which of the candidate bases exist.
+async fn count_unpublished_commits(path: &Path) -> Option<i32> {
+    const CANDIDATES: &[&str] = &[
+        "refs/remotes/origin/HEAD",
+        "refs/remotes/origin/main",
+        "refs/remotes/origin/master",
+        "refs/heads/main",
+        "refs/heads/master",
+    ];
 
-    let staged_stats_raw = run_git(&["diff", "--cached", "--numstat"], &path)
+    let mut args = vec!["for-each-ref", "--format=%(refname)%00%(symref)"];
+    args.extend_from_slice(CANDIDATES);
+    let output = run_git(&args, path).await.ok()?;
+
+    let existing: HashMap<&str, &str> = output
+        .lines()
+        .filter_map(|line| line.split_once('\0'))
+        .collect();
+    let base_ref = CANDIDATES.iter().find_map(|candidate| {
+        let symref = existing.get(candidate)?;
+        let target = if symref.is_empty() { candidate } else { symref };
+        Some(
+            target
+                .trim_start_matches("refs/remotes/")
+                .trim_start_matches("refs/heads/")
+                .to_string(),
+        )
+    })?;
+
+    let range = format!("{}..HEAD", base_ref);
+    run_git(&["rev-list", "--count", &range], path)
         .await
-        .unwrap_or_default();
-    let working_stats_raw = run_git(&["diff", "--numstat"], &path)
+        .ok()
+        .and_then(|raw| raw.trim().parse::<i32>().ok())
+}
+
+#[tauri::command]
+pub async fn get_git_status(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitStatus, GitError> {
+    let path = validate_git_path(&directory, state.settings())
         .await
-        .unwrap_or_default();
+        .map_err(|e| e.to_string())?;
+    collect_git_status(&path).await
+}
 
-    let staged_stats = collect_stats(staged_stats_raw);
-    let working_stats = collect_stats(working_stats_raw);
+/// Repositories whose status `get_git_status_batch` reads at the same time
+const GIT_STATUS_BATCH_CONCURRENCY: usize = 4;
 
-    // Merge stats
-    let mut all_paths: HashSet<String> = staged_stats.keys().cloned().collect();
-    all_paths.extend(working_stats.keys().cloned());
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatusBatchEntry {
+    pub directory: String,
+    pub status: Option<GitStatus>,
+    pub error: Option<GitError>,
+}
 
-    for p in all_paths {
-        let s = staged_stats.get(&p).unwrap_or(&DiffStat {
-            insertions: 0,
-            deletions: 0,
-        });
-        let w = working_stats.get(&p).unwrap_or(&DiffStat {
-            insertions: 0,
-            deletions: 0,
-        });
-        diff_stats.insert(
-            p,
-            DiffStat {
-                insertions: s.insertions + w.insertions,
-                deletions: s.deletions + w.deletions,
-            },
-        );
-    }
+/// Status of several repositories in one call, e.g. for dirty indicators in a project
+/// picker. Results keep the order of `directories`; a failing entry carries its error.
+#[tauri::command]
+pub async fn get_git_status_batch(
+    directories: Vec<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<GitStatusBatchEntry>, GitError> {
+    use futures_util::StreamExt;
+
+    let settings = state.settings();
+    let entries = futures_util::stream::iter(directories)
+        .map(|directory| async move {
+            let result = match validate_git_path(&directory, settings).await {
+                Ok(path) => collect_git_status(&path).await,
+                Err(err) => Err(GitError::from(err)),
+            };
+            let (status, error) = match result {
+                Ok(status) => (Some(status), None),
+                Err(err) => (None, Some(err)),
+            };
+            GitStatusBatchEntry {
+                directory,
+                status,
+                error,
+            }
+        })
+        .buffered(GIT_STATUS_BATCH_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+    Ok(entries)
+}
 
-    // 3. Handle new/untracked files (manual calculation if needed, or skip if complex)
-    // Node implementation does manual read. For now, let's assume files with '??' or 'A'
-    // might need stats if they aren't in numstat.
-    // NOTE: untracked files don't show up in `git diff --numstat`.
-    // We can try `wc -l` logic but Rust fs read is safer.
+/// Untracked files larger than this get no line count in status
+const UNTRACKED_STAT_MAX_BYTES: u64 = 4 * 1024 * 1024;
 
-    for file in &files {
-        if (file.working_dir == "?" || file.index == "A") && !diff_stats.contains_key(&file.path) {
-            let full_path = path.join(&file.path);
-            if let Ok(metadata) = fs::metadata(&full_path).await {
-                if metadata.is_file() {
-                    if let Ok(content) = fs::read_to_string(&full_path).await {
-                        let lines = content.lines().count() as i32;
-                        diff_stats.insert(
-                            file.path.clone(),
-                            DiffStat {
-                                insertions: lines,
-                                deletions: 0,
-                            },
-                        );
-                    }
-                }
+/// Line count of an untracked file, read in chunks. `None` for binaries, non-files and
+/// files over `UNTRACKED_STAT_MAX_BYTES`, matching `--numstat` which has no count for them.
+async fn count_untracked_lines(path: &Path) -> Option<i32> {
+    let metadata = fs::metadata(path).await.ok()?;
+    if !metadata.is_file() || metadata.len() > UNTRACKED_STAT_MAX_BYTES {
+        return None;
+    }
+
+    let mut file = fs::File::open(path).await.ok()?;
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut lines = 0;
+    let mut read_total = 0;
+    let mut last_byte = b'\n';
+    loop {
+        let read = file.read(&mut buf).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        let chunk = &buf[..read];
+        if read_total < BINARY_SNIFF_BYTES {
+            let sniff = (BINARY_SNIFF_BYTES - read_total).min(read);
+            if chunk[..sniff].contains(&0) {
+                return None;
             }
         }
+        lines += chunk.iter().filter(|&&b| b == b'\n').count() as i32;
+        last_byte = chunk[read - 1];
+        read_total += read;
+    }
+    // A final line without a trailing newline still counts
+    if last_byte != b'\n' {
+        lines += 1;
     }
+    Some(lines)
+}
 
-    // When there's no upstream yet (e.g. a freshly-created local worktree branch),
-    // git status doesn't report ahead/behind. We still want to surface unpublished commits.
-    if tracking.is_none() && !current.trim().is_empty() {
-        let mut base_candidates: Vec<String> = Vec::new();
+/// Marker files under the git directory, in the order they are checked. A rebase also
+/// detaches HEAD and bisect can run alongside the others, so they go first and last.
+const OPERATION_MARKERS: &[(&str, &str)] = &[
+    ("rebase-merge", "rebasing"),
+    ("rebase-apply/rebasing", "rebasing"),
+    ("rebase-apply/applying", "applyingPatches"),
+    ("MERGE_HEAD", "merging"),
+    ("CHERRY_PICK_HEAD", "cherryPicking"),
+    ("REVERT_HEAD", "reverting"),
+    ("BISECT_START", "bisecting"),
+];
+
+async fn detect_operation_state(root: &Path) -> Option<String> {
+    let mut args = vec!["rev-parse"];
+    for (marker, _) in OPERATION_MARKERS {
+        args.extend(["--git-path", marker]);
+    }
+    let paths = run_git(&args, root).await.ok()?;
+    for ((_, state), path) in OPERATION_MARKERS.iter().zip(paths.lines()) {
+        let path = PathBuf::from(path.trim());
+        let path = if path.is_absolute() {
+            path
+        } else {
+            root.join(path)
+        };
+        if fs::metadata(&path).await.is_ok() {
+            return Some(state.to_string());
+        }
+    }
+    None
+}
 
-        let origin_head = run_git_with_allowed_exit(
-            &["symbolic-ref", "-q", "refs/remotes/origin/HEAD"],
-            &path,
-            &[1],
-        )
+/// Flagged files per index file, valid while the index keeps its mtime and size
+static INDEX_FLAGS_CACHE: LazyLock<
+    std::sync::Mutex<HashMap<PathBuf, (std::time::SystemTime, u64, Vec<GitIndexFlags>)>>,
+> = LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Every flagged file in the index. Listing the whole index is only worth it when it
+/// changes, so the result is cached against the index file's metadata.
+async fn cached_index_flags(root: &Path) -> Vec<GitIndexFlags> {
+    let Ok(index_path) = run_git(&["rev-parse", "--git-path", "index"], root).await else {
+        return Vec::new();
+    };
+    let index_path = PathBuf::from(index_path.trim());
+    let index_path = if index_path.is_absolute() {
+        index_path
+    } else {
+        root.join(index_path)
+    };
+    let Some((modified, len)) = fs::metadata(&index_path)
         .await
-        .unwrap_or_default();
+        .ok()
+        .and_then(|meta| Some((meta.modified().ok()?, meta.len())))
+    else {
+        return Vec::new();
+    };
 
-        if !origin_head.trim().is_empty() {
-            base_candidates.push(origin_head.trim().replace("refs/remotes/", ""));
+    if let Some((cached_modified, cached_len, flags)) =
+        INDEX_FLAGS_CACHE.lock().unwrap().get(&index_path)
+    {
+        if *cached_modified == modified && *cached_len == len {
+            return flags.clone();
         }
+    }
 
-        base_candidates.push("origin/main".to_string());
-        base_candidates.push("origin/master".to_string());
-        base_candidates.push("main".to_string());
-        base_candidates.push("master".to_string());
+    let output = run_git(&["ls-files", "-v", "-z"], root)
+        .await
+        .unwrap_or_default();
+    let flags = parse_index_flags(&output);
+    INDEX_FLAGS_CACHE
+        .lock()
+        .unwrap()
+        .insert(index_path, (modified, len, flags.clone()));
+    flags
+}
 
-        let mut selected_base: Option<String> = None;
-        for candidate in base_candidates {
-            let verified = run_git_with_allowed_exit(
-                &["rev-parse", "--verify", &candidate],
-                &path,
-                &[1],
-            )
-            .await
-            .unwrap_or_default();
+async fn collect_git_status(path: &Path) -> Result<GitStatus, GitError> {
+    // Status and a combined HEAD-to-worktree numstat run concurrently with the lookups
+    // that don't depend on them; -uall lists untracked files individually. Before the
+    // first commit, diff against the empty tree. See scripts/bench-git-status.mjs.
+    let status_args = ["status", "--porcelain=v2", "--branch", "-z", "-uall"];
+    let numstat_args = |base: &'static str| ["diff", base, "--numstat", "-z", "--no-renames"];
+    let head_numstat_args = numstat_args("HEAD");
+    let (status_output, numstat_output, sparse_output, index_flags, marker_state) = tokio::join!(
+        run_git(&status_args, path),
+        run_git(&head_numstat_args, path),
+        run_git_with_allowed_exit(&["config", "--bool", "core.sparseCheckout"], path, &[1]),
+        cached_index_flags(path),
+        detect_operation_state(path)
+    );
+    let status = parse_porcelain_v2_status(&status_output.map_err(|e| e.to_string())?);
 
-            if !verified.trim().is_empty() {
-                selected_base = Some(candidate);
-                break;
+    let numstat_output = match numstat_output {
+        Ok(output) => output,
+        Err(_) if status.initial => run_git(&numstat_args(EMPTY_TREE_HASH), path)
+            .await
+            .unwrap_or_default(),
+        Err(_) => String::new(),
+    };
+    let mut diff_stats = parse_numstat_z(&numstat_output);
+
+    let PorcelainV2Status {
+        head: current,
+        upstream: tracking,
+        mut ahead,
+        mut behind,
+        mut files,
+        ..
+    } = status;
+    let is_clean = files.is_empty();
+
+    // A flag hides further worktree changes: mark the listed files that carry one and
+    // report the rest as hidden. With sparse checkout, skip-worktree marks every file
+    // outside the checkout and is expected.
+    let sparse = sparse_output.is_ok_and(|value| value.trim() == "true");
+    let tracked: HashMap<String, usize> = files
+        .iter()
+        .enumerate()
+        .filter(|(_, file)| file.working_dir != "?")
+        .map(|(i, file)| (file.path.clone(), i))
+        .collect();
+    let mut hidden_files = Vec::new();
+    for flags in index_flags {
+        if !flags.assume_unchanged && sparse {
+            continue;
+        }
+        match tracked.get(&flags.path) {
+            Some(&i) => {
+                files[i].assume_unchanged = flags.assume_unchanged;
+                files[i].skip_worktree = flags.skip_worktree;
             }
+            None => hidden_files.push(flags),
         }
+    }
 
-        if let Some(base_ref) = selected_base {
-            let range = format!("{}..HEAD", base_ref);
-            if let Ok(raw) = run_git(&["rev-list", "--count", &range], &path).await {
-                if let Ok(count) = raw.trim().parse::<i32>() {
-                    ahead = count;
-                    behind = 0;
+    // Untracked files don't show up in `git diff --numstat`
+    let count_new_files = async {
+        let mut counts = Vec::new();
+        for file in &files {
+            let is_new = file.working_dir == "?" || file.index == "A";
+            if is_new && !diff_stats.contains_key(&file.path) {
+                if let Some(lines) = count_untracked_lines(&path.join(&file.path)).await {
+                    counts.push((file.path.clone(), lines));
                 }
             }
         }
+        counts
+    };
+    // When there's no upstream yet (e.g. a freshly-created local worktree branch),
+    // git status doesn't report ahead/behind. We still want to surface unpublished commits.
+    let unpublished = async {
+        if tracking.is_none() && !current.trim().is_empty() {
+            count_unpublished_commits(path).await
+        } else {
+            None
+        }
+    };
+    let (new_file_counts, unpublished) = tokio::join!(count_new_files, unpublished);
+    for (file, lines) in new_file_counts {
+        diff_stats.insert(
+            file,
+            DiffStat {
+                insertions: lines,
+                deletions: 0,
+            },
+        );
     }
+    if let Some(count) = unpublished {
+        ahead = count;
+        behind = 0;
+    }
+
+    let detached = current == "HEAD (no branch)";
+    let operation_state = marker_state.or_else(|| detached.then(|| "detachedHead".to_string()));
 
     Ok(GitStatus {
         current,
         tracking,
         ahead,
         behind,
-        is_clean: files.is_empty(),
+        is_clean,
         files,
         diff_stats: Some(diff_stats),
+        operation_state,
+        hidden_files,
     })
 }
 
+/// Emit debounced `git:status-changed` events for `directory` instead of polling status
 #[tauri::command]
-pub async fn get_git_diff(
+pub async fn watch_git_status(
     directory: String,
-    path_str: String,
-    staged: Option<bool>,
-    context_lines: Option<u32>,
+    app: AppHandle,
     state: State<'_, DesktopRuntime>,
-) -> Result<String, String> {
+) -> Result<(), GitError> {
     let root = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
+    let root = fs::canonicalize(&root).await.unwrap_or(root);
+    git_watcher::watch_directory(app, root)
+        .await
+        .map_err(GitError::from)
+}
+
+/// Stop the watch started by `watch_git_status` for `directory`, unless the UI has
+/// already moved it elsewhere
+#[tauri::command]
+pub async fn unwatch_git_status(directory: String) -> Result<(), GitError> {
+    let root = expand_tilde_path(&directory);
+    let root = fs::canonicalize(&root).await.unwrap_or(root);
+    git_watcher::unwatch(&root).await;
+    Ok(())
+}
+
+/// Files whose changes git hides because of the assume-unchanged or skip-worktree bit
+#[tauri::command]
+pub async fn get_index_flags(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<GitIndexFlags>, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let output = run_git(&["ls-files", "-v", "-z"], &root).await?;
+    Ok(parse_index_flags(&output))
+}
+
+/// Set or clear the assume-unchanged and/or skip-worktree bit on tracked `paths`; a flag
+/// left as `None` is not touched. Returns every flagged file afterwards.
+#[tauri::command]
+pub async fn set_index_flags(
+    directory: String,
+    paths: Vec<String>,
+    assume_unchanged: Option<bool>,
+    skip_worktree: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<GitIndexFlags>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let input: String = paths
+        .iter()
+        .map(|path| path.trim())
+        .filter(|path| !path.is_empty())
+        .map(|path| format!("{}\0", path))
+        .collect();
+    if input.is_empty() {
+        return Err("No files given".into());
+    }
+
+    // update-index applies only one of these options per run
+    let options = [
+        (
+            assume_unchanged,
+            "--assume-unchanged",
+            "--no-assume-unchanged",
+        ),
+        (skip_worktree, "--skip-worktree", "--no-skip-worktree"),
+    ];
+    for (value, set, clear) in options {
+        let Some(value) = value else {
+            continue;
+        };
+        let flag = if value { set } else { clear };
+        run_git_with_env_input(
+            &["update-index", flag, "-z", "--stdin"],
+            &root,
+            &[],
+            input.as_bytes(),
+        )
+        .await?;
+    }
+
+    let output = run_git(&["ls-files", "-v", "-z"], &root).await?;
+    Ok(parse_index_flags(&output))
+}
+
+const DIFF_CONTEXT_LINES_KEY: &str = "diffContextLines";
+const DIFF_RENAME_SIMILARITY_KEY: &str = "diffRenameSimilarity";
+const DIFF_WHITESPACE_KEY: &str = "diffWhitespace";
+
+/// User-wide diff defaults from settings, shared by every diff-producing command
+struct DiffPreferences {
+    context_lines: u32,
+    rename_similarity: u32,
+    whitespace: Option<&'static str>,
+}
+
+impl DiffPreferences {
+    /// Git's own defaults: 3 context lines, 50% rename similarity, whitespace shown
+    async fn load(settings: &SettingsStore) -> Self {
+        let value = settings.load().await.unwrap_or(Value::Null);
+        let number = |key: &str| value.get(key).and_then(Value::as_u64).map(|n| n as u32);
+        let whitespace = match value.get(DIFF_WHITESPACE_KEY).and_then(Value::as_str) {
+            Some("ignore-all") => Some("--ignore-all-space"),
+            Some("ignore-change") => Some("--ignore-space-change"),
+            Some("ignore-eol") => Some("--ignore-space-at-eol"),
+            _ => None,
+        };
+        Self {
+            context_lines: number(DIFF_CONTEXT_LINES_KEY).unwrap_or(3).min(100),
+            rename_similarity: number(DIFF_RENAME_SIMILARITY_KEY)
+                .unwrap_or(50)
+                .clamp(1, 100),
+            whitespace,
+        }
+    }
+
+    fn rename_arg(&self) -> String {
+        format!("--find-renames={}%", self.rename_similarity)
+    }
+}
+
+#[tauri::command]
+pub async fn get_git_diff(
+    directory: String,
+    path_str: String,
+    staged: Option<bool>,
+    context_lines: Option<u32>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<String, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let preferences = DiffPreferences::load(state.settings()).await;
 
     let mut args = vec!["diff", "--no-color"];
-    let context = format!("-U{}", context_lines.unwrap_or(3));
+    let context = format!("-U{}", context_lines.unwrap_or(preferences.context_lines));
     args.push(&context);
+    if let Some(flag) = preferences.whitespace {
+        args.push(flag);
+    }
 
     if staged.unwrap_or(false) {
         args.push("--cached");
@@ -898,18 +2517,12 @@ pub async fn get_git_diff(
         // git diff --no-index -- /dev/null path
         let full_path = root.join(&path_str);
         if full_path.exists() {
-            let args_no_index = vec![
-                "diff",
-                "--no-color",
-                &context,
-                "--no-index",
-                "--",
-                "/dev/null",
-                &path_str,
-            ];
+            let mut args_no_index = vec!["diff", "--no-color", &context, "--no-index"];
+            args_no_index.extend(preferences.whitespace);
+            args_no_index.extend(["--", "/dev/null", &path_str]);
             return run_git_with_allowed_exit(&args_no_index, &root, &[1])
                 .await
-                .map_err(|e| e.to_string());
+                .map_err(GitError::from);
         }
     }
 
@@ -917,6 +2530,8 @@ pub async fn get_git_diff(
 }
 
 const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "svg", "webp", "ico", "bmp", "avif"];
+/// Git's own heuristic: a NUL byte in the first 8000 bytes means binary
+const BINARY_SNIFF_BYTES: usize = 8000;
 
 fn is_image_file(path: &str) -> bool {
     if let Some(ext) = path.rsplit('.').next() {
@@ -941,6 +2556,129 @@ fn get_image_mime_type(path: &str) -> &'static str {
     }
 }
 
+/// Identify raster images from their magic bytes, regardless of file extension
+pub(crate) fn sniff_image_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if looks_like_bmp(bytes) {
+        Some("image/bmp")
+    } else if bytes.starts_with(&[0x00, 0x00, 0x01, 0x00]) {
+        Some("image/x-icon")
+    } else if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" && &bytes[8..12] == b"avif" {
+        Some("image/avif")
+    } else {
+        None
+    }
+}
+
+/// "BM" alone starts plenty of text files, so also require the header to agree with
+/// the data: file size matching the buffer, zero reserved bytes and a known DIB header
+fn looks_like_bmp(bytes: &[u8]) -> bool {
+    const DIB_HEADER_SIZES: [u32; 4] = [12, 40, 108, 124];
+    if bytes.len() < 18 || !bytes.starts_with(b"BM") {
+        return false;
+    }
+    let read_u32 =
+        |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+    read_u32(2) as usize == bytes.len()
+        && bytes[6..10].iter().all(|&b| b == 0)
+        && DIB_HEADER_SIZES.contains(&read_u32(14))
+}
+
+/// Content sniffing for blobs git attributes did not classify: NUL bytes or
+/// invalid UTF-8 (ignoring a sequence cut off by truncation) mean binary
+fn looks_binary(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(BINARY_SNIFF_BYTES)];
+    if head.contains(&0) {
+        return true;
+    }
+    match std::str::from_utf8(bytes) {
+        Ok(_) => false,
+        Err(err) => err.error_len().is_some(),
+    }
+}
+
+/// Ask git whether `.gitattributes` marks the path as binary (`binary`, `-diff` or `-text`)
+async fn git_attr_marks_binary(repo_root: &Path, relative_path: &str) -> bool {
+    let output = run_git(
+        &["check-attr", "binary", "diff", "text", "--", relative_path],
+        repo_root,
+    )
+    .await
+    .unwrap_or_default();
+
+    output.lines().any(|line| {
+        let mut parts = line.rsplitn(3, ": ");
+        let value = parts.next().unwrap_or("").trim();
+        let attr = parts.next().unwrap_or("").trim();
+        matches!(
+            (attr, value),
+            ("binary", "set") | ("diff", "unset") | ("text", "unset")
+        )
+    })
+}
+
+enum FileDiffSide {
+    Head,
+    WorkingTree,
+}
+
+/// Render one side of a file diff: a data URL for images, a short stub for binary
+/// content (so the diff still shows whether it changed), or text otherwise.
+async fn render_file_diff_side(
+    side: FileDiffSide,
+    bytes: Vec<u8>,
+    total_len: u64,
+    repo_root: &Path,
+    relative_path: &str,
+    attr_binary: bool,
+) -> String {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+    if bytes.is_empty() {
+        return String::new();
+    }
+
+    let mime_type = sniff_image_mime_type(&bytes)
+        .or_else(|| is_image_file(relative_path).then(|| get_image_mime_type(relative_path)));
+    if let Some(mime_type) = mime_type {
+        if total_len > GIT_FILE_IMAGE_MAX_BYTES || (bytes.len() as u64) < total_len {
+            return String::new();
+        }
+        return format!("data:{};base64,{}", mime_type, BASE64.encode(&bytes));
+    }
+
+    let text_len = (GIT_FILE_TEXT_MAX_BYTES as usize).min(bytes.len());
+    if attr_binary || looks_binary(&bytes[..text_len]) {
+        let object_id = match side {
+            FileDiffSide::Head => {
+                let spec = format!("HEAD:{}", relative_path);
+                run_git(&["rev-parse", &spec], repo_root).await
+            }
+            FileDiffSide::WorkingTree => {
+                run_git(&["hash-object", "--", relative_path], repo_root).await
+            }
+        }
+        .unwrap_or_default();
+        return format!(
+            "Binary file {}\nSize: {} bytes\nObject: {}\n",
+            relative_path, total_len, object_id
+        );
+    }
+
+    let mut text = String::from_utf8_lossy(&bytes[..text_len]).to_string();
+    if total_len > text_len as u64 {
+        text.push_str("\n…(truncated)\n");
+    }
+    text
+}
+
 fn truncate_string_to_char_boundary(mut value: String, max_chars: usize, suffix: &str) -> String {
     if value.len() <= max_chars {
         return value;
@@ -973,120 +2711,180 @@ fn cap_ipc_payload(value: String) -> String {
     truncate_string_to_char_boundary(value, GIT_FILE_IPC_MAX_CHARS, "\n…(truncated for desktop)\n")
 }
 
-async fn run_git_binary(args: &[&str], cwd: &Path) -> Result<Vec<u8>> {
-    run_git_bytes_with_allowed_exit_timeout(args, cwd, &[0, 128], GIT_FILE_DIFF_TIMEOUT_MS).await
-}
-
 #[tauri::command]
 pub async fn get_git_file_diff(
     directory: String,
     path_str: String,
     state: State<'_, DesktopRuntime>,
-) -> Result<(String, String), String> {
-    use tokio::fs;
-    use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-
+) -> Result<(String, String), GitError> {
     let root = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
 
     let (repo_root, full_path, relative_path) = resolve_path_for_git_show(&root, &path_str).await;
-    let is_image = is_image_file(&relative_path);
-    let mime_type = if is_image { get_image_mime_type(&relative_path) } else { "" };
+    let attr_binary = git_attr_marks_binary(&repo_root, &relative_path).await;
 
     // Original from HEAD
-    let original = if is_image {
-        // For images, get binary content and convert to data URL
-        let original_spec = format!("HEAD:{}", relative_path);
-        match run_git_binary(&["show", &original_spec], &repo_root).await {
-            Ok(bytes) if !bytes.is_empty() => {
-                if bytes.len() as u64 > GIT_FILE_IMAGE_MAX_BYTES {
-                    String::new()
-                } else {
-                    format!("data:{};base64,{}", mime_type, BASE64.encode(&bytes))
+    let original_spec = format!("HEAD:{}", relative_path);
+    let original_bytes = run_git_bytes_with_allowed_exit_timeout(
+        &["show", original_spec.as_str()],
+        &repo_root,
+        &[0, 128],
+        GIT_FILE_DIFF_TIMEOUT_MS,
+    )
+    .await
+    .unwrap_or_default();
+    let original_len = original_bytes.len() as u64;
+    let original = render_file_diff_side(
+        FileDiffSide::Head,
+        original_bytes,
+        original_len,
+        &repo_root,
+        &relative_path,
+        attr_binary,
+    )
+    .await;
+
+    // Modified from working tree (if file exists)
+    let modified = match metadata_with_timeout(&full_path, GIT_FILE_DIFF_TIMEOUT_MS).await {
+        Ok(metadata) if metadata.is_file() => {
+            match read_file_bytes_limited_with_timeout(
+                &full_path,
+                GIT_FILE_IMAGE_MAX_BYTES,
+                GIT_FILE_DIFF_TIMEOUT_MS,
+            )
+            .await
+            {
+                Ok((bytes, _)) => {
+                    render_file_diff_side(
+                        FileDiffSide::WorkingTree,
+                        bytes,
+                        metadata.len(),
+                        &repo_root,
+                        &relative_path,
+                        attr_binary,
+                    )
+                    .await
                 }
+                Err(_) => String::new(),
             }
-            _ => String::new(),
         }
-    } else {
-        let original_spec = format!("HEAD:{}", relative_path);
-        match run_git_bytes_with_allowed_exit_timeout(
-            &["show", original_spec.as_str()],
+        _ => String::new(),
+    };
+
+    Ok((cap_ipc_payload(original), cap_ipc_payload(modified)))
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileAtRevision {
+    pub path: String,
+    #[serde(rename = "ref")]
+    pub ref_: String,
+    pub object: String,
+    pub size: u64,
+    pub binary: bool,
+    /// `utf8` for text, `base64` for binary content
+    pub encoding: String,
+    pub content: String,
+    pub mime_type: Option<String>,
+    /// Content was cut to the size caps, or left out for binaries too large to send
+    pub truncated: bool,
+}
+
+/// Contents of `path` as of `git_ref`. Text is capped like the diff viewer's; binary
+/// content is base64 when it fits in an IPC payload and omitted otherwise.
+#[tauri::command]
+pub async fn get_file_at_revision(
+    directory: String,
+    git_ref: String,
+    path: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<FileAtRevision, GitError> {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let git_ref = git_ref.trim().to_string();
+    if git_ref.is_empty() || git_ref.starts_with('-') {
+        return Err("A ref is required".into());
+    }
+    let (repo_root, _, relative_path) = resolve_path_for_git_show(&root, &path).await;
+
+    let spec = format!("{}:{}", git_ref, relative_path);
+    let object = run_git(&["rev-parse", "--verify", "--quiet", &spec], &repo_root)
+        .await
+        .map_err(|_| format!("{} does not exist at {}", relative_path, git_ref))?
+        .trim()
+        .to_string();
+    let object_type = run_git(&["cat-file", "-t", &object], &repo_root).await?;
+    if object_type.trim() != "blob" {
+        return Err(format!("{} is not a file at {}", relative_path, git_ref).into());
+    }
+    let size: u64 = run_git(&["cat-file", "-s", &object], &repo_root)
+        .await?
+        .trim()
+        .parse()
+        .unwrap_or(0);
+
+    let bytes = if size <= GIT_FILE_IMAGE_MAX_BYTES {
+        run_git_bytes_with_allowed_exit_timeout(
+            &["cat-file", "blob", &object],
             &repo_root,
-            &[0, 128],
+            &[0],
             GIT_FILE_DIFF_TIMEOUT_MS,
         )
-        .await
-        {
-            Ok(bytes) if !bytes.is_empty() => {
-                if bytes.len() as u64 > GIT_FILE_TEXT_MAX_BYTES {
-                    let mut text = String::from_utf8_lossy(
-                        &bytes[..(GIT_FILE_TEXT_MAX_BYTES as usize).min(bytes.len())],
-                    )
-                    .to_string();
-                    text.push_str("\n…(truncated)\n");
-                    text
-                } else {
-                    String::from_utf8_lossy(&bytes).to_string()
-                }
-            }
-            _ => String::new(),
-        }
+        .await?
+    } else {
+        Vec::new()
     };
 
-    // Modified from working tree (if file exists)
-    let modified = if let Ok(metadata) = metadata_with_timeout(&full_path, GIT_FILE_DIFF_TIMEOUT_MS).await {
-        if metadata.is_file() {
-            if is_image {
-                // For images, read as binary and convert to data URL
-                if metadata.len() > GIT_FILE_IMAGE_MAX_BYTES {
-                    String::new()
-                } else {
-                    match tokio::time::timeout(
-                        std::time::Duration::from_millis(GIT_FILE_DIFF_TIMEOUT_MS),
-                        fs::read(&full_path),
-                    )
-                    .await
-                    {
-                        Ok(Ok(bytes)) => format!("data:{};base64,{}", mime_type, BASE64.encode(&bytes)),
-                        _ => String::new(),
-                    }
-                }
-            } else {
-                match read_file_bytes_limited_with_timeout(
-                    &full_path,
-                    GIT_FILE_TEXT_MAX_BYTES,
-                    GIT_FILE_DIFF_TIMEOUT_MS,
-                )
-                .await
-                {
-                    Ok((bytes, truncated)) => {
-                        let mut text = String::from_utf8_lossy(&bytes).to_string();
-                        if truncated {
-                            text.push_str("\n…(truncated)\n");
-                        }
-                        text
-                    }
-                    Err(_) => String::new(),
-                }
-            }
+    let mime_type = sniff_image_mime_type(&bytes)
+        .or_else(|| is_image_file(&relative_path).then(|| get_image_mime_type(&relative_path)));
+    let text_len = (GIT_FILE_TEXT_MAX_BYTES as usize).min(bytes.len());
+    let binary = mime_type.is_some()
+        || git_attr_marks_binary(&repo_root, &relative_path).await
+        || looks_binary(&bytes[..text_len]);
+
+    let loaded = bytes.len() as u64 == size;
+    let (encoding, content, truncated) = if binary {
+        // base64 grows content by a third
+        let fits = loaded && bytes.len().div_ceil(3) * 4 <= GIT_FILE_IPC_MAX_CHARS;
+        let content = if fits {
+            BASE64.encode(&bytes)
         } else {
             String::new()
-        }
+        };
+        ("base64", content, !fits)
     } else {
-        String::new()
+        let text = String::from_utf8_lossy(&bytes[..text_len]).to_string();
+        let cut = !loaded || (text_len as u64) < size || text.len() > GIT_FILE_IPC_MAX_CHARS;
+        let text = truncate_string_to_char_boundary(text, GIT_FILE_IPC_MAX_CHARS, "");
+        ("utf8", text, cut)
     };
 
-    Ok((cap_ipc_payload(original), cap_ipc_payload(modified)))
+    Ok(FileAtRevision {
+        path: relative_path,
+        ref_: git_ref,
+        object,
+        size,
+        binary,
+        encoding: encoding.to_string(),
+        content,
+        mime_type: mime_type.map(str::to_string),
+        truncated,
+    })
 }
 
 #[tauri::command]
 pub async fn revert_git_file(
     directory: String,
     file_path: String,
+    permanent: Option<bool>,
     state: State<'_, DesktopRuntime>,
-) -> Result<(), String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<(), GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
 
@@ -1096,16 +2894,17 @@ pub async fn revert_git_file(
         .is_ok();
 
     if !is_tracked {
-        // Clean untracked
-        let _ = run_git(&["clean", "-f", "-d", "--", &file_path], &root).await;
-        // Fallback fs remove if git clean failed (e.g. ignored files)
         let full_path = root.join(&file_path);
-        if full_path.exists() {
-            if full_path.is_dir() {
-                let _ = fs::remove_dir_all(full_path).await;
-            } else {
-                let _ = fs::remove_file(full_path).await;
-            }
+        if permanent.unwrap_or(false) {
+            // Clean untracked
+            let _ = run_git(&["clean", "-f", "-d", "--", &file_path], &root).await;
+        }
+        // Untracked files go to the OS trash by default so they can be restored;
+        // also covers files git clean skipped (e.g. ignored files)
+        if fs::symlink_metadata(&full_path).await.is_ok() {
+            remove_path(&full_path, permanent.unwrap_or(false))
+                .await
+                .map_err(|e| e.to_string())?;
         }
     } else {
         // Restore staged
@@ -1117,79 +2916,654 @@ pub async fn revert_git_file(
     Ok(())
 }
 
+const SAFETY_SNAPSHOT_REF_PREFIX: &str = "refs/openchamber/snapshots/";
+/// Older snapshots beyond this are pruned when a new one is taken
+const SAFETY_SNAPSHOT_LIMIT: usize = 50;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscardAllOptions {
+    /// Also delete untracked files (ignored files are always kept)
+    pub include_untracked: Option<bool>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SafetySnapshot {
+    pub id: String,
+    pub ref_name: String,
+    pub commit: String,
+    /// Commit HEAD pointed at when the snapshot was taken
+    pub base: String,
+    pub created_at: String,
+    pub message: String,
+    pub includes_untracked: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscardAllResult {
+    /// `None` when there was nothing to discard
+    pub snapshot: Option<SafetySnapshot>,
+    pub untracked_removed: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreSnapshotResult {
+    pub snapshot: SafetySnapshot,
+    /// The staged state was restored too, not only the working tree
+    pub index_restored: bool,
+    pub conflicts: Vec<String>,
+}
+
+/// Commit of the untracked (not ignored) files, built in a throwaway index
+async fn commit_untracked_files(root: &Path, files: &str) -> Result<String> {
+    let git_dir = run_git(&["rev-parse", "--path-format=absolute", "--git-dir"], root).await?;
+    let temp_index = PathBuf::from(git_dir.trim()).join(format!(
+        "index.openchamber-snapshot-{}",
+        uuid::Uuid::new_v4()
+    ));
+    let index_env = temp_index.to_string_lossy().to_string();
+    let envs = [("GIT_INDEX_FILE", index_env.as_str())];
+    let result = async {
+        run_git_with_env_input(
+            &["update-index", "--add", "-z", "--stdin"],
+            root,
+            &envs,
+            files.as_bytes(),
+        )
+        .await?;
+        let tree = run_git_with_env_input(&["write-tree"], root, &envs, b"").await?;
+        run_git(&["commit-tree", tree.trim(), "-m", "untracked files"], root).await
+    }
+    .await;
+    let _ = fs::remove_file(&temp_index).await;
+    Ok(result?.trim().to_string())
+}
+
+/// Record the index, tracked changes and optionally untracked files as a stash-shaped
+/// commit (parents: HEAD, index[, untracked]) under `refs/openchamber/snapshots/`,
+/// without touching the working tree. `None` when there is nothing to record.
+async fn create_safety_snapshot(
+    root: &Path,
+    reason: &str,
+    include_untracked: bool,
+) -> Result<Option<SafetySnapshot>> {
+    let head = get_head_hash(root)
+        .await
+        .map_err(|_| anyhow!("Cannot snapshot a branch without commits"))?;
+    let branch = get_current_branch_name(root)
+        .await
+        .unwrap_or_else(|_| "HEAD".to_string());
+    let message = format!("openchamber snapshot on {}: {}", branch, reason);
+
+    let stash = run_git(&["stash", "create", &message], root).await?;
+    let stash = stash.trim();
+    let untracked = if include_untracked {
+        run_git(&["ls-files", "-z", "--others", "--exclude-standard"], root).await?
+    } else {
+        String::new()
+    };
+    if stash.is_empty() && untracked.is_empty() {
+        return Ok(None);
+    }
+
+    let (tree, index_commit) = if stash.is_empty() {
+        let index_message = format!("index on {}", branch);
+        let head_tree = format!("{}^{{tree}}", head);
+        let index_commit = run_git(
+            &["commit-tree", &head_tree, "-p", &head, "-m", &index_message],
+            root,
+        )
+        .await?;
+        (head_tree, index_commit.trim().to_string())
+    } else {
+        let index_commit = run_git(&["rev-parse", &format!("{}^2", stash)], root).await?;
+        (
+            format!("{}^{{tree}}", stash),
+            index_commit.trim().to_string(),
+        )
+    };
+
+    let mut args = vec!["commit-tree", &tree, "-p", &head, "-p", &index_commit];
+    let untracked_commit = if untracked.is_empty() {
+        None
+    } else {
+        Some(commit_untracked_files(root, &untracked).await?)
+    };
+    if let Some(untracked_commit) = &untracked_commit {
+        args.extend(["-p", untracked_commit]);
+    }
+    args.extend(["-m", &message]);
+    let commit = run_git(&args, root).await?.trim().to_string();
+
+    let id = chrono::Utc::now().format("%Y%m%dT%H%M%S%3fZ").to_string();
+    let ref_name = format!("{}{}", SAFETY_SNAPSHOT_REF_PREFIX, id);
+    run_git(&["update-ref", &ref_name, &commit], root).await?;
+
+    let snapshots = read_safety_snapshots(root).await.unwrap_or_default();
+    for stale in snapshots.iter().skip(SAFETY_SNAPSHOT_LIMIT) {
+        let _ = run_git(&["update-ref", "-d", &stale.ref_name], root).await;
+    }
+
+    Ok(Some(SafetySnapshot {
+        id,
+        ref_name,
+        commit,
+        base: head,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        message,
+        includes_untracked: untracked_commit.is_some(),
+    }))
+}
+
+/// Snapshots, newest first
+async fn read_safety_snapshots(root: &Path) -> Result<Vec<SafetySnapshot>> {
+    let output = run_git(
+        &[
+            "for-each-ref",
+            "--sort=-refname",
+            "--format=%(refname)%00%(objectname)%00%(parent)%00%(creatordate:iso-strict)%00%(contents:subject)",
+            SAFETY_SNAPSHOT_REF_PREFIX,
+        ],
+        root,
+    )
+    .await?;
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split('\0').collect();
+            if parts.len() < 5 {
+                return None;
+            }
+            let parents: Vec<&str> = parts[2].split_whitespace().collect();
+            Some(SafetySnapshot {
+                id: parts[0]
+                    .strip_prefix(SAFETY_SNAPSHOT_REF_PREFIX)?
+                    .to_string(),
+                ref_name: parts[0].to_string(),
+                commit: parts[1].to_string(),
+                base: parents.first()?.to_string(),
+                created_at: parts[3].to_string(),
+                message: parts[4].to_string(),
+                includes_untracked: parents.len() > 2,
+            })
+        })
+        .collect())
+}
+
+/// Discard every staged and unstaged change (and untracked files with
+/// `include_untracked`), after saving them as a safety snapshot that
+/// `restore_safety_snapshot` can bring back
 #[tauri::command]
-pub async fn is_linked_worktree(
+pub async fn discard_all_changes(
     directory: String,
+    options: Option<DiscardAllOptions>,
     state: State<'_, DesktopRuntime>,
-) -> Result<bool, String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<DiscardAllResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-    let git_dir = run_git(&["rev-parse", "--git-dir"], &root)
-        .await
-        .unwrap_or_default();
-    let common_dir = run_git(&["rev-parse", "--git-common-dir"], &root)
+    let include_untracked = options
+        .unwrap_or_default()
+        .include_untracked
+        .unwrap_or(false);
+
+    let snapshot = create_safety_snapshot(&root, "discard all changes", include_untracked)
         .await
-        .unwrap_or_default();
-    Ok(git_dir.trim() != common_dir.trim())
+        .map_err(|e| format!("Could not snapshot changes, nothing was discarded: {}", e))?;
+    let Some(snapshot) = snapshot else {
+        return Ok(DiscardAllResult {
+            snapshot: None,
+            untracked_removed: false,
+        });
+    };
+
+    run_git(&["reset", "--hard", "--quiet", "HEAD"], &root).await?;
+    if snapshot.includes_untracked {
+        run_git(&["clean", "-f", "-d", "--quiet"], &root).await?;
+    }
+    info!(
+        "[git] Discarded all changes in {:?} (snapshot {})",
+        root, snapshot.id
+    );
+
+    Ok(DiscardAllResult {
+        untracked_removed: snapshot.includes_untracked,
+        snapshot: Some(snapshot),
+    })
 }
 
 #[tauri::command]
-pub async fn get_git_branches(
+pub async fn list_safety_snapshots(
     directory: String,
     state: State<'_, DesktopRuntime>,
-) -> Result<GitBranch, String> {
+) -> Result<Vec<SafetySnapshot>, GitError> {
     let root = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
+    read_safety_snapshots(&root).await.map_err(GitError::from)
+}
 
-    // Discover actual remote heads so we can drop stale remote-tracking refs
-    let allowed_remote_heads: Option<HashSet<String>> = match run_git_bytes_with_allowed_exit_timeout(
-        &["ls-remote", "--heads", "origin"],
+/// Re-apply a snapshot's changes on top of the current HEAD, like `git stash apply`.
+/// Staged changes are restored as staged when possible. With `delete`, the snapshot ref
+/// is removed once it applied cleanly.
+#[tauri::command]
+pub async fn restore_safety_snapshot(
+    directory: String,
+    id: String,
+    delete: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RestoreSnapshotResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let id = id.trim();
+    let snapshot = read_safety_snapshots(&root)
+        .await?
+        .into_iter()
+        .find(|s| s.id == id || s.ref_name == id)
+        .ok_or_else(|| format!("Snapshot {} not found", id))?;
+
+    let (index_restored, success, stdout, stderr) = match run_git(
+        &["stash", "apply", "--index", "--quiet", &snapshot.commit],
         &root,
-        &[0],
-        GIT_LS_REMOTE_TIMEOUT_MS,
     )
     .await
     {
-        Ok(bytes) => {
-            let ls_remote = String::from_utf8_lossy(&bytes);
-            let mut set = HashSet::new();
-            for line in ls_remote.lines() {
-                if let Some((_, ref_name)) = line.split_once('\t') {
-                    if let Some(stripped) = ref_name.trim().strip_prefix("refs/heads/") {
-                        set.insert(stripped.to_string());
-                    }
-                }
-            }
-            Some(set)
-        }
-        Err(err) => {
-            warn!("Failed to list remote heads: {}", err);
-            None
+        Ok(_) => (true, true, String::new(), String::new()),
+        Err(_) => {
+            let (success, stdout, stderr) =
+                run_git_with_status(&["stash", "apply", &snapshot.commit], &root).await?;
+            (false, success, stdout, stderr)
         }
     };
+    let conflicts = list_conflicted_files(&root).await;
+    if !success && conflicts.is_empty() {
+        return Err(GitError::from(if stderr.is_empty() {
+            stdout
+        } else {
+            stderr
+        }));
+    }
 
-    // Structured for-each-ref output so we can mark remotes consistently with the web runtime
-    let output = run_git(
-        &[
-            "for-each-ref",
-            "--format=%(refname)|%(refname:short)|%(objectname)|%(upstream:short)|%(HEAD)|%(upstream:track)",
-            "refs/heads",
-            "refs/remotes",
-        ],
-        &root,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
+    if success && delete.unwrap_or(false) {
+        run_git(&["update-ref", "-d", &snapshot.ref_name], &root).await?;
+    }
+    Ok(RestoreSnapshotResult {
+        snapshot,
+        index_restored,
+        conflicts,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCleanEntry {
+    pub path: String,
+    pub is_directory: bool,
+    pub ignored: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCleanResult {
+    pub removed: Vec<String>,
+    pub failed: Vec<String>,
+    pub trashed: bool,
+}
+
+async fn collect_clean_candidates(
+    root: &Path,
+    include_ignored: bool,
+    paths: &[String],
+) -> Result<Vec<GitCleanEntry>> {
+    let run_dry = |flag: &'static str| {
+        let mut args: Vec<&str> = vec!["-c", "core.quotePath=false", "clean", flag];
+        if !paths.is_empty() {
+            args.push("--");
+            args.extend(paths.iter().map(|p| p.as_str()));
+        }
+        async move { run_git(&args, root).await }
+    };
+
+    let parse = |output: &str, ignored: bool| -> Vec<GitCleanEntry> {
+        output
+            .lines()
+            .filter_map(|line| line.strip_prefix("Would remove "))
+            .map(|path| GitCleanEntry {
+                is_directory: path.ends_with('/'),
+                path: path.trim_end_matches('/').to_string(),
+                ignored,
+            })
+            .collect()
+    };
+
+    let mut entries = parse(&run_dry("-nd").await?, false);
+    if include_ignored {
+        // -X lists only ignored files, so the two runs never overlap
+        entries.extend(parse(&run_dry("-ndX").await?, true));
+    }
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(entries)
+}
+
+/// Dry-run `git clean` to list untracked (and optionally ignored) files that would be removed
+#[tauri::command]
+pub async fn git_clean_preview(
+    directory: String,
+    include_ignored: Option<bool>,
+    paths: Option<Vec<String>>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<GitCleanEntry>, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    collect_clean_candidates(
+        &root,
+        include_ignored.unwrap_or(false),
+        &paths.unwrap_or_default(),
+    )
+    .await
+    .map_err(GitError::from)
+}
+
+/// Remove selected untracked paths. Only paths that `git clean` itself would remove are
+/// touched, so tracked files are never deleted. Items go to the OS trash unless `permanent`.
+#[tauri::command]
+pub async fn git_clean(
+    directory: String,
+    paths: Vec<String>,
+    include_ignored: Option<bool>,
+    permanent: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitCleanResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let paths: Vec<String> = paths
+        .into_iter()
+        .map(|p| p.trim().trim_end_matches('/').to_string())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if paths.is_empty() {
+        return Err("No paths selected".into());
+    }
+
+    let candidates = collect_clean_candidates(&root, include_ignored.unwrap_or(false), &paths)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let permanent = permanent.unwrap_or(false);
+    let mut removed = Vec::new();
+    let mut failed = Vec::new();
+    for entry in candidates {
+        match remove_path(&root.join(&entry.path), permanent).await {
+            Ok(_) => removed.push(entry.path),
+            Err(err) => {
+                warn!("[git] Failed to clean {}: {}", entry.path, err);
+                failed.push(entry.path);
+            }
+        }
+    }
+
+    info!("[git] Cleaned {} paths in {:?}", removed.len(), root);
+
+    Ok(GitCleanResult {
+        removed,
+        failed,
+        trashed: !permanent,
+    })
+}
+
+#[tauri::command]
+pub async fn is_linked_worktree(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<bool, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let git_dir = run_git(&["rev-parse", "--git-dir"], &root)
+        .await
+        .unwrap_or_default();
+    let common_dir = run_git(&["rev-parse", "--git-common-dir"], &root)
+        .await
+        .unwrap_or_default();
+    Ok(git_dir.trim() != common_dir.trim())
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SparseCheckoutState {
+    pub enabled: bool,
+    /// Cone mode: patterns are directories rather than gitignore-style patterns
+    pub cone: bool,
+    pub patterns: Vec<String>,
+}
+
+async fn read_sparse_checkout(root: &Path) -> Result<SparseCheckoutState> {
+    let config_bool = |key: &'static str| async move {
+        run_git(&["config", "--get", "--bool", key], root)
+            .await
+            .map(|v| v.trim() == "true")
+            .ok()
+    };
+    let enabled = config_bool("core.sparseCheckout").await.unwrap_or(false);
+    if !enabled {
+        return Ok(SparseCheckoutState {
+            enabled,
+            cone: false,
+            patterns: Vec::new(),
+        });
+    }
+    let cone = config_bool("core.sparseCheckoutCone")
+        .await
+        .unwrap_or(false);
+    let patterns = run_git(&["sparse-checkout", "list"], root)
+        .await?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+    Ok(SparseCheckoutState {
+        enabled,
+        cone,
+        patterns,
+    })
+}
+
+/// Patterns are sent on stdin so none can be mistaken for an option. Cone mode takes
+/// directories, so surrounding slashes are dropped.
+fn sparse_checkout_input(patterns: &[String], cone: bool) -> Result<String, String> {
+    let patterns: Vec<&str> = patterns
+        .iter()
+        .map(|p| p.trim())
+        .map(|p| if cone { p.trim_matches('/') } else { p })
+        .filter(|p| !p.is_empty())
+        .collect();
+    if patterns.is_empty() {
+        return Err("At least one pattern is required".to_string());
+    }
+    if patterns.iter().any(|p| p.contains('\n')) {
+        return Err("Patterns must be single lines".to_string());
+    }
+    Ok(patterns.join("\n") + "\n")
+}
+
+#[tauri::command]
+pub async fn get_sparse_checkout(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<SparseCheckoutState, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    read_sparse_checkout(&root).await.map_err(GitError::from)
+}
+
+/// Enable sparse-checkout. Only top-level files stay checked out until patterns are set.
+#[tauri::command]
+pub async fn init_sparse_checkout(
+    directory: String,
+    cone: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<SparseCheckoutState, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let mode = if cone.unwrap_or(true) {
+        "--cone"
+    } else {
+        "--no-cone"
+    };
+    run_git(&["sparse-checkout", "init", mode], &root).await?;
+    read_sparse_checkout(&root).await.map_err(GitError::from)
+}
+
+/// Replace the sparse-checkout patterns, enabling sparse-checkout if needed. `cone`
+/// defaults to the current mode, or cone mode when sparse-checkout is off.
+#[tauri::command]
+pub async fn set_sparse_checkout(
+    directory: String,
+    patterns: Vec<String>,
+    cone: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<SparseCheckoutState, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let current = read_sparse_checkout(&root).await?;
+    let cone = cone.unwrap_or(!current.enabled || current.cone);
+    let input = sparse_checkout_input(&patterns, cone)?;
+    let mode = if cone { "--cone" } else { "--no-cone" };
+    run_git_with_env_input(
+        &["sparse-checkout", "set", mode, "--stdin"],
+        &root,
+        &[],
+        input.as_bytes(),
+    )
+    .await?;
+    info!(
+        "[git] Sparse-checkout set to {} pattern(s) in {:?}",
+        patterns.len(),
+        root
+    );
+    read_sparse_checkout(&root).await.map_err(GitError::from)
+}
+
+/// Add patterns to an existing sparse-checkout
+#[tauri::command]
+pub async fn add_sparse_checkout_patterns(
+    directory: String,
+    patterns: Vec<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<SparseCheckoutState, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let current = read_sparse_checkout(&root).await?;
+    if !current.enabled {
+        return Err("Sparse-checkout is not enabled for this repository".into());
+    }
+    let input = sparse_checkout_input(&patterns, current.cone)?;
+    run_git_with_env_input(
+        &["sparse-checkout", "add", "--stdin"],
+        &root,
+        &[],
+        input.as_bytes(),
+    )
+    .await?;
+    read_sparse_checkout(&root).await.map_err(GitError::from)
+}
+
+/// Turn sparse-checkout off and restore the full worktree
+#[tauri::command]
+pub async fn disable_sparse_checkout(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<SparseCheckoutState, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    run_git(&["sparse-checkout", "disable"], &root).await?;
+    read_sparse_checkout(&root).await.map_err(GitError::from)
+}
+
+/// List local and remote-tracking branches with their tip commit. `sort` is `name`
+/// (default) or `recent` for the most recently committed first; `include_remotes: false`
+/// lists local branches only and skips contacting the remote.
+#[tauri::command]
+pub async fn get_git_branches(
+    directory: String,
+    sort: Option<String>,
+    include_remotes: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBranch, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let sort_arg = match sort.as_deref().map(str::trim) {
+        None | Some("") | Some("name") => "--sort=refname",
+        Some("recent") => "--sort=-committerdate",
+        Some(other) => return Err(format!("Unsupported branch sort: {}", other).into()),
+    };
+    let include_remotes = include_remotes.unwrap_or(true);
+
+    // Discover actual remote heads so we can drop stale remote-tracking refs
+    let allowed_remote_heads: Option<HashSet<String>> = if !include_remotes {
+        None
+    } else {
+        match run_git_bytes_with_allowed_exit_timeout(
+            &["ls-remote", "--heads", "origin"],
+            &root,
+            &[0],
+            GIT_LS_REMOTE_TIMEOUT_MS,
+        )
+        .await
+        {
+            Ok(bytes) => {
+                let ls_remote = String::from_utf8_lossy(&bytes);
+                let mut set = HashSet::new();
+                for line in ls_remote.lines() {
+                    if let Some((_, ref_name)) = line.split_once('\t') {
+                        if let Some(stripped) = ref_name.trim().strip_prefix("refs/heads/") {
+                            set.insert(stripped.to_string());
+                        }
+                    }
+                }
+                Some(set)
+            }
+            Err(err) => {
+                warn!("Failed to list remote heads: {}", err);
+                None
+            }
+        }
+    };
+
+    // Structured for-each-ref output so we can mark remotes consistently with the web runtime
+    let mut args = vec![
+        "for-each-ref",
+        sort_arg,
+        "--format=%(refname)%00%(refname:short)%00%(objectname)%00%(upstream:short)%00%(HEAD)%00%(upstream:track)%00%(committerdate:iso-strict)%00%(authorname)%00%(contents:subject)",
+        "refs/heads",
+    ];
+    if include_remotes {
+        args.push("refs/remotes");
+    }
+    let output = run_git(&args, &root).await.map_err(|e| e.to_string())?;
 
     let mut all = Vec::new();
     let mut current_branch = String::new();
     let mut branches = HashMap::new();
 
     for line in output.lines() {
-        let parts: Vec<&str> = line.split('|').collect();
-        if parts.len() < 6 {
+        let parts: Vec<&str> = line.split('\0').collect();
+        if parts.len() < 9 {
             continue;
         }
 
@@ -1199,6 +3573,7 @@ pub async fn get_git_branches(
         let upstream = parts[3].trim();
         let is_current = parts[4] == "*";
         let track_info = parts[5];
+        let non_empty = |value: &str| Some(value.trim().to_string()).filter(|v| !v.is_empty());
 
         let is_remote = full_ref.starts_with("refs/remotes/");
 
@@ -1259,6 +3634,9 @@ pub async fn get_git_branches(
                 tracking,
                 ahead,
                 behind,
+                last_commit_date: non_empty(parts[6]),
+                last_commit_author: non_empty(parts[7]),
+                last_commit_subject: non_empty(parts[8]),
             },
         );
     }
@@ -1275,16 +3653,19 @@ pub async fn delete_git_branch(
     directory: String,
     branch: String,
     force: Option<bool>,
+    explain: Option<bool>,
     state: State<'_, DesktopRuntime>,
-) -> Result<(), String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<Explainable<()>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
     let flag = if force.unwrap_or(false) { "-D" } else { "-d" };
-    run_git(&["branch", flag, &branch], &root)
-        .await
-        .map_err(|e| e.to_string())?;
-    Ok(())
+    let args = ["branch", flag, &branch];
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(vec![GitInvocation::local(&args, &root)]));
+    }
+    run_git(&args, &root).await.map_err(|e| e.to_string())?;
+    Ok(Explainable::Done(()))
 }
 
 #[tauri::command]
@@ -1292,9 +3673,10 @@ pub async fn delete_remote_branch(
     directory: String,
     branch: String,
     remote: Option<String>,
+    explain: Option<bool>,
     state: State<'_, DesktopRuntime>,
-) -> Result<(), String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<Explainable<()>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
     let remote_name = remote.unwrap_or_else(|| "origin".to_string());
@@ -1302,17 +3684,224 @@ pub async fn delete_remote_branch(
     // branch might be refs/heads/foo or just foo
     let clean_branch = branch.trim_start_matches("refs/heads/");
 
-    run_git(&["push", &remote_name, "--delete", clean_branch], &root)
+    let args = ["push", &remote_name, "--delete", clean_branch];
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(vec![GitInvocation::local(&args, &root)]));
+    }
+    run_git(&args, &root).await.map_err(|e| e.to_string())?;
+    Ok(Explainable::Done(()))
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleBranch {
+    pub name: String,
+    pub commit: String,
+    /// Every commit is already in the base branch
+    pub merged: bool,
+    /// The upstream branch was deleted on the remote
+    pub upstream_gone: bool,
+    pub upstream: Option<String>,
+    pub last_commit_date: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StaleBranchReport {
+    pub base: String,
+    pub branches: Vec<StaleBranch>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchDeleteResult {
+    pub branch: String,
+    pub success: bool,
+    /// Tip of the deleted branch, so it can be recreated
+    pub commit: Option<String>,
+    pub error: Option<GitError>,
+}
+
+/// Branch that cleanup compares against: `base` when given, else the default remote's
+/// default branch, else a local `main` or `master`
+async fn resolve_cleanup_base(root: &Path, base: Option<&str>) -> Result<String, String> {
+    if let Some(base) = base.map(str::trim).filter(|b| !b.is_empty()) {
+        if base.starts_with('-') {
+            return Err(format!("Invalid base branch: {}", base));
+        }
+        return Ok(base.to_string());
+    }
+    if let Ok(remote) = resolve_default_remote(root).await {
+        if let Some(branch) = resolve_remote_default_branch(root, &remote).await {
+            return Ok(format!("{}/{}", remote, branch));
+        }
+    }
+    for candidate in ["main", "master"] {
+        let local_ref = format!("refs/heads/{}", candidate);
+        if run_git(&["show-ref", "--verify", "--quiet", &local_ref], root)
+            .await
+            .is_ok()
+        {
+            return Ok(candidate.to_string());
+        }
+    }
+    Err("Could not determine the default branch".to_string())
+}
+
+/// Local branches that are safe to clean up: fully merged into the default branch (or
+/// `base_branch`), or tracking an upstream that no longer exists. The current branch,
+/// the base itself and branches checked out in other worktrees are never listed.
+#[tauri::command]
+pub async fn get_stale_branches(
+    directory: String,
+    base_branch: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<StaleBranchReport, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let base = resolve_cleanup_base(&root, base_branch.as_deref()).await?;
+
+    let merged: HashSet<String> = run_git(
+        &["branch", "--format=%(refname:short)", "--merged", &base],
+        &root,
+    )
+    .await?
+    .lines()
+    .map(|line| line.trim().to_string())
+    .collect();
+    let checked_out: HashSet<String> = run_git(&["worktree", "list", "--porcelain"], &root)
+        .await
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.strip_prefix("branch refs/heads/"))
+        .map(str::to_string)
+        .collect();
+    // `origin/main` also protects the local `main`
+    let remotes = run_git(&["remote"], &root).await.unwrap_or_default();
+    let base_name = match base.split_once('/') {
+        Some((remote, branch)) if remotes.lines().any(|r| r.trim() == remote) => branch,
+        _ => base.as_str(),
+    };
+
+    let output = run_git(
+        &[
+            "for-each-ref",
+            "--sort=committerdate",
+            "--format=%(refname:short)%00%(objectname)%00%(upstream:short)%00%(upstream:track)%00%(committerdate:iso-strict)",
+            "refs/heads",
+        ],
+        &root,
+    )
+    .await?;
+
+    let mut branches = Vec::new();
+    for line in output.lines() {
+        let parts: Vec<&str> = line.split('\0').collect();
+        if parts.len() < 5 {
+            continue;
+        }
+        let name = parts[0];
+        if name == base || name == base_name || checked_out.contains(name) {
+            continue;
+        }
+        let is_merged = merged.contains(name);
+        let upstream_gone = parts[3].trim() == "[gone]";
+        if !is_merged && !upstream_gone {
+            continue;
+        }
+        branches.push(StaleBranch {
+            name: name.to_string(),
+            commit: parts[1].to_string(),
+            merged: is_merged,
+            upstream_gone,
+            upstream: Some(parts[2].to_string()).filter(|u| !u.is_empty()),
+            last_commit_date: Some(parts[4].to_string()).filter(|d| !d.is_empty()),
+        });
+    }
+
+    Ok(StaleBranchReport { base, branches })
+}
+
+/// Delete several local branches, continuing past failures. Without `force`, a branch
+/// is deleted only when its commits are in `base_branch` (the default branch when
+/// omitted) or git's own `branch -d` check passes.
+#[tauri::command]
+pub async fn delete_branches(
+    directory: String,
+    branches: Vec<String>,
+    force: Option<bool>,
+    base_branch: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<BranchDeleteResult>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-    Ok(())
+    let force = force.unwrap_or(false);
+    let base = if force {
+        None
+    } else {
+        resolve_cleanup_base(&root, base_branch.as_deref())
+            .await
+            .ok()
+    };
+
+    let mut results = Vec::with_capacity(branches.len());
+    for branch in branches {
+        let branch = branch.trim().trim_start_matches("refs/heads/").to_string();
+        if branch.is_empty() {
+            continue;
+        }
+        let branch_ref = format!("refs/heads/{}", branch);
+        let Ok(commit) = run_git(&["rev-parse", "--verify", "--quiet", &branch_ref], &root).await
+        else {
+            results.push(BranchDeleteResult {
+                error: Some(format!("Branch {} not found", branch).into()),
+                branch,
+                success: false,
+                commit: None,
+            });
+            continue;
+        };
+        let commit = commit.trim().to_string();
+
+        let merged_into_base = match &base {
+            Some(base) => run_git(&["merge-base", "--is-ancestor", &commit, base], &root)
+                .await
+                .is_ok(),
+            None => false,
+        };
+        let flag = if force || merged_into_base {
+            "-D"
+        } else {
+            "-d"
+        };
+        match run_git(&["branch", flag, &branch], &root).await {
+            Ok(_) => {
+                info!("[git] Deleted branch {} at {}", branch, commit);
+                results.push(BranchDeleteResult {
+                    branch,
+                    success: true,
+                    commit: Some(commit),
+                    error: None,
+                });
+            }
+            Err(err) => results.push(BranchDeleteResult {
+                branch,
+                success: false,
+                commit: Some(commit),
+                error: Some(err.into()),
+            }),
+        }
+    }
+    Ok(results)
 }
 
 #[tauri::command]
 pub async fn list_git_worktrees(
     directory: String,
     state: State<'_, DesktopRuntime>,
-) -> Result<Vec<GitWorktreeInfo>, String> {
+) -> Result<Vec<GitWorktreeInfo>, GitError> {
     let root = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
@@ -1321,35 +3910,27 @@ pub async fn list_git_worktrees(
         .map_err(|e| e.to_string())?;
 
     let mut worktrees = Vec::new();
-    let mut current = GitWorktreeInfo {
-        worktree: String::new(),
-        head: None,
-        branch: None,
-    };
+    let mut current = GitWorktreeInfo::default();
 
     for line in output.lines() {
         if let Some(cap) = WORKTREE_REGEX.captures(line) {
             if !current.worktree.is_empty() {
-                worktrees.push(current.clone());
-                current = GitWorktreeInfo {
-                    worktree: String::new(),
-                    head: None,
-                    branch: None,
-                };
+                worktrees.push(std::mem::take(&mut current));
             }
             current.worktree = cap[1].to_string();
         } else if let Some(cap) = HEAD_REGEX.captures(line) {
             current.head = Some(cap[1].to_string());
         } else if let Some(cap) = BRANCH_REGEX.captures(line) {
             current.branch = Some(cap[1].trim_start_matches("refs/heads/").to_string());
+        } else if line == "locked" || line.starts_with("locked ") {
+            current.locked = true;
+            current.lock_reason = line.strip_prefix("locked ").map(str::to_string);
+        } else if line == "prunable" || line.starts_with("prunable ") {
+            current.prunable = true;
+            current.prunable_reason = line.strip_prefix("prunable ").map(str::to_string);
         } else if line.is_empty() {
             if !current.worktree.is_empty() {
-                worktrees.push(current.clone());
-                current = GitWorktreeInfo {
-                    worktree: String::new(),
-                    head: None,
-                    branch: None,
-                };
+                worktrees.push(std::mem::take(&mut current));
             }
         }
     }
@@ -1367,9 +3948,10 @@ pub async fn add_git_worktree(
     branch: String,
     create_branch: Option<bool>,
     start_point: Option<String>,
+    explain: Option<bool>,
     state: State<'_, DesktopRuntime>,
-) -> Result<(), String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<Explainable<()>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
 
@@ -1389,8 +3971,17 @@ pub async fn add_git_worktree(
         }
     }
 
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(vec![GitInvocation::local(&args, &root)]));
+    }
     run_git(&args, &root).await.map_err(|e| e.to_string())?;
-    Ok(())
+
+    let worktree = root.join(expand_tilde_path(&path_str));
+    let worktree = fs::canonicalize(&worktree).await.unwrap_or(worktree);
+    if let Err(err) = apply_mapped_identity(&worktree, state.settings()).await {
+        warn!("[git] Applying mapped identity to {:?}: {}", worktree, err);
+    }
+    Ok(Explainable::Done(()))
 }
 
 #[tauri::command]
@@ -1398,708 +3989,7778 @@ pub async fn remove_git_worktree(
     directory: String,
     path_str: String,
     force: Option<bool>,
+    explain: Option<bool>,
     state: State<'_, DesktopRuntime>,
-) -> Result<(), String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<Explainable<()>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
     let mut args = vec!["worktree", "remove", &path_str];
     if force.unwrap_or(false) {
         args.push("--force");
     }
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(vec![GitInvocation::local(&args, &root)]));
+    }
     run_git(&args, &root).await.map_err(|e| e.to_string())?;
-    Ok(())
+    Ok(Explainable::Done(()))
 }
 
+/// Remove administrative data for worktrees whose directories are gone. Returns git's
+/// description of each pruned (or, with `dry_run`, prunable) entry.
 #[tauri::command]
-pub async fn ensure_openchamber_ignored(
+pub async fn prune_git_worktrees(
     directory: String,
+    dry_run: Option<bool>,
+    expire: Option<String>,
     state: State<'_, DesktopRuntime>,
-) -> Result<(), String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<Vec<String>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-    let exclude_path = root.join(".git/info/exclude");
-
-    if let Some(parent) = exclude_path.parent() {
-        fs::create_dir_all(parent)
-            .await
-            .map_err(|e| e.to_string())?;
+    let mut args = vec![
+        "worktree".to_string(),
+        "prune".to_string(),
+        "--verbose".to_string(),
+    ];
+    if dry_run.unwrap_or(false) {
+        args.push("--dry-run".to_string());
     }
+    if let Some(expire) = expire
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+    {
+        args.push(format!("--expire={}", expire));
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
 
-    let entry = "/.openchamber/\n";
-    let mut content = fs::read_to_string(&exclude_path).await.unwrap_or_default();
-
-    if !content.contains("/.openchamber/") {
-        if !content.ends_with('\n') && !content.is_empty() {
-            content.push('\n');
-        }
-        content.push_str(entry);
-        fs::write(&exclude_path, content)
-            .await
-            .map_err(|e| e.to_string())?;
+    // `prune --verbose` reports on stderr
+    let (success, _stdout, stderr) = run_git_with_status(&args, &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !success {
+        return Err(stderr.into());
     }
-    Ok(())
+    Ok(stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
 }
 
 #[tauri::command]
-pub async fn create_git_commit(
+pub async fn lock_git_worktree(
     directory: String,
-    message: String,
-    add_all: Option<bool>,
-    files: Option<Vec<String>>,
+    path_str: String,
+    reason: Option<String>,
     state: State<'_, DesktopRuntime>,
-) -> Result<GitCommitResult, String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<(), GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-
-    if add_all.unwrap_or(false) {
-        run_git(&["add", "."], &root)
-            .await
-            .map_err(|e| e.to_string())?;
-    } else if let Some(file_list) = files {
-        if !file_list.is_empty() {
-            let mut args = vec!["add"];
-            args.extend(file_list.iter().map(|s| s.as_str()));
-            run_git(&args, &root).await.map_err(|e| e.to_string())?;
-        }
+    let mut args = vec!["worktree", "lock"];
+    let reason = reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if let Some(reason) = reason {
+        args.push("--reason");
+        args.push(reason);
     }
+    args.push(&path_str);
+    run_git(&args, &root).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
 
-    run_git(&["commit", "-m", &message], &root)
+#[tauri::command]
+pub async fn unlock_git_worktree(
+    directory: String,
+    path_str: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<(), GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-
-    let commit_hash = get_head_hash(&root).await.map_err(|e| e.to_string())?;
-    let branch_name = get_current_branch_name(&root)
-        .await
-        .unwrap_or_else(|_| "HEAD".to_string());
-
-    let stat_output = run_git(&["log", "-1", "--pretty=", "--shortstat"], &root)
+    run_git(&["worktree", "unlock", &path_str], &root)
         .await
-        .unwrap_or_default();
-    let summary = parse_shortstat(&stat_output);
-
-    Ok(GitCommitResult {
-        success: true,
-        commit: commit_hash,
-        branch: branch_name,
-        summary,
-    })
+        .map_err(|e| e.to_string())?;
+    Ok(())
 }
 
+/// Move a linked worktree. `force` also moves a locked worktree.
 #[tauri::command]
-pub async fn git_push(
+pub async fn move_git_worktree(
     directory: String,
-    remote: Option<String>,
-    branch: Option<String>,
-    options: Option<Value>,
+    path_str: String,
+    new_path: String,
+    force: Option<bool>,
     state: State<'_, DesktopRuntime>,
-) -> Result<GitPushResult, String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<(), GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-    let remote_name = remote.unwrap_or_else(|| "origin".to_string());
-    let explicit_branch = branch
-        .as_deref()
-        .map(|value| !value.trim().is_empty())
-        .unwrap_or(false);
-    let mut branch_name = branch.unwrap_or_default();
-
-    let mut args = vec!["push".to_string(), remote_name.clone()];
-    if branch_name.is_empty() {
-        branch_name = get_current_branch_name(&root).await.unwrap_or_default();
-    }
-
-    if !branch_name.is_empty() {
-        // If caller didn't specify a branch and there's no upstream configured yet,
-        // publish on first push so future pushes/pulls work without extra prompts.
-        if !explicit_branch {
-            let remote_key = format!("branch.{}.remote", branch_name);
-            let merge_key = format!("branch.{}.merge", branch_name);
-
-            let upstream_remote = run_git_with_allowed_exit(
-                &["config", "--get", &remote_key],
-                &root,
-                &[1],
-            )
-            .await
-            .unwrap_or_default();
-
-            let upstream_merge = run_git_with_allowed_exit(
-                &["config", "--get", &merge_key],
-                &root,
-                &[1],
-            )
-            .await
-            .unwrap_or_default();
-
-            if upstream_remote.trim().is_empty() || upstream_merge.trim().is_empty() {
-                args.push("--set-upstream".to_string());
-            }
-        }
-
-        args.push(branch_name.clone());
+    let new_path = new_path.trim();
+    if new_path.is_empty() {
+        return Err("New worktree path is required".into());
     }
-
-    if let Some(extra) = options.as_ref() {
-        append_git_option(&mut args, extra);
+    let mut args = vec!["worktree", "move"];
+    if force.unwrap_or(false) {
+        // Git needs the flag twice to move a locked worktree
+        args.extend(["--force", "--force"]);
     }
-
-    let arg_refs: Vec<&str> = args.iter().map(|value| value.as_str()).collect();
-
-    // TODO: Streaming? Frontend types.ts defines GitPushResult, but doesn't mention streaming response for this call,
-    // but Stage 2 plan says "streaming progress events for long operations".
-    // Implementing simple await for now as `simple-git` wrapper does in `git-service.js`.
-
-    run_git(&arg_refs, &root).await.map_err(|e| e.to_string())?;
-
-    Ok(GitPushResult {
-        success: true,
-        pushed: if branch_name.is_empty() {
-            vec![]
-        } else {
-            vec![GitPushRef {
-                local: branch_name.clone(),
-                remote: format!("{}/{}", remote_name, branch_name),
-            }]
-        },
-        repo: remote_name,
-        ref_: if branch_name.is_empty() {
-            None
-        } else {
-            Some(branch_name)
-        },
-    })
+    args.push(&path_str);
+    args.push(new_path);
+    run_git(&args, &root).await.map_err(|e| e.to_string())?;
+    Ok(())
 }
 
+/// Fix the links between the main repository and its worktrees, e.g. after a worktree
+/// or the repository itself was moved by hand. Returns git's repair messages.
 #[tauri::command]
-pub async fn git_pull(
+pub async fn repair_git_worktrees(
     directory: String,
-    remote: Option<String>,
-    branch: Option<String>,
+    paths: Option<Vec<String>>,
     state: State<'_, DesktopRuntime>,
-) -> Result<GitPullResult, String> {
-    let root = validate_git_path(&directory, state.settings())
+) -> Result<Vec<String>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-    let r = remote.unwrap_or_else(|| "origin".to_string());
-    let b = branch.unwrap_or_default();
+    let paths = paths.unwrap_or_default();
+    let mut args = vec!["worktree", "repair"];
+    args.extend(paths.iter().map(|p| p.trim()).filter(|p| !p.is_empty()));
 
-    let mut args = vec!["pull", &r];
-    if !b.is_empty() {
-        args.push(&b);
+    let (success, _stdout, stderr) = run_git_with_status(&args, &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !success {
+        return Err(stderr.into());
     }
+    Ok(stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
 
-    let previous_head = get_head_hash(&root).await.ok();
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorktreeFileDiff {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub status: String,
+    pub insertions: Option<u32>,
+    pub deletions: Option<u32>,
+}
 
-    run_git(&args, &root).await.map_err(|e| e.to_string())?;
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitWorktreeComparison {
+    pub worktree_a: String,
+    pub worktree_b: String,
+    pub files: Vec<WorktreeFileDiff>,
+    pub insertions: u32,
+    pub deletions: u32,
+}
 
-    let (summary, files) = if let Some(previous) = previous_head {
-        let new_head = get_head_hash(&root).await.unwrap_or(previous.clone());
-        if new_head != previous {
-            let range = format!("{previous}..{new_head}");
-            let summary = collect_shortstat_for_range(&root, &range)
-                .await
-                .unwrap_or_else(|_| GitCommitSummary {
-                    changes: 0,
-                    insertions: 0,
-                    deletions: 0,
-                });
-            let files = collect_changed_files_for_range(&root, &range)
+/// Capture a worktree's current files (uncommitted and untracked, minus ignored) as a
+/// tree object, using a throwaway copy of its index so the real one is left untouched
+async fn snapshot_worktree_tree(worktree: &Path) -> Result<String> {
+    let index_path = run_git(&["rev-parse", "--git-path", "index"], worktree).await?;
+    let index_path = worktree.join(index_path.trim());
+    let temp_index = index_path.with_file_name(format!(
+        "index.openchamber-compare-{}",
+        uuid::Uuid::new_v4()
+    ));
+    if fs::metadata(&index_path).await.is_ok() {
+        fs::copy(&index_path, &temp_index).await?;
+    }
+
+    let run_with_index = |args: &'static [&'static str]| {
+        let temp_index = temp_index.clone();
+        async move {
+            let output = Command::new("git")
+                .args(args)
+                .current_dir(worktree)
+                .env("GIT_INDEX_FILE", &temp_index)
+                .env("GIT_OPTIONAL_LOCKS", "0")
+                .stdin(Stdio::null())
+                .output()
                 .await
-                .unwrap_or_default();
-            (summary, files)
-        } else {
-            (
-                GitCommitSummary {
-                    changes: 0,
-                    insertions: 0,
-                    deletions: 0,
-                },
-                vec![],
-            )
+                .context("Failed to execute git command")?;
+            if !output.status.success() {
+                return Err(anyhow!(
+                    "{}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
         }
+    };
+
+    let result = match run_with_index(&["add", "-A"]).await {
+        Ok(_) => run_with_index(&["write-tree"]).await,
+        Err(err) => Err(err),
+    };
+    let _ = fs::remove_file(&temp_index).await;
+    result
+}
+
+async fn resolve_listed_worktree(root: &Path, listed: &[PathBuf], input: &str) -> Result<PathBuf> {
+    let candidate = expand_tilde_path(input.trim());
+    let candidate = if candidate.is_absolute() {
+        candidate
     } else {
-        (
-            GitCommitSummary {
-                changes: 0,
-                insertions: 0,
-                deletions: 0,
-            },
-            vec![],
-        )
+        root.join(candidate)
     };
+    let canonical = fs::canonicalize(&candidate)
+        .await
+        .map_err(|_| anyhow!("Worktree not found: {}", input))?;
+    listed
+        .iter()
+        .find(|path| **path == canonical)
+        .cloned()
+        .ok_or_else(|| anyhow!("{} is not a worktree of this repository", input))
+}
 
-    Ok(GitPullResult {
-        success: true,
-        summary: summary.clone(),
-        files,
-        insertions: summary.insertions,
-        deletions: summary.deletions,
-    })
+/// Combine `--name-status` and `--numstat` listings of the same diff into per-file
+/// entries; binary files have no line counts
+fn pair_file_diffs(name_status: &str, numstat: &str) -> Vec<WorktreeFileDiff> {
+    let mut files = Vec::new();
+    // Both listings come out in the same order, one line per file
+    for (status_line, stat_line) in name_status
+        .lines()
+        .filter(|l| !l.is_empty())
+        .zip(numstat.lines().filter(|l| !l.is_empty()))
+    {
+        let mut parts = status_line.split('\t');
+        let status = parts.next().unwrap_or_default();
+        let (old_path, path) = match (parts.next(), parts.next()) {
+            (Some(old), Some(new)) => (Some(old.to_string()), new.to_string()),
+            (Some(path), None) => (None, path.to_string()),
+            _ => continue,
+        };
+
+        let mut stats = stat_line.split('\t');
+        files.push(WorktreeFileDiff {
+            path,
+            old_path,
+            status: status.chars().next().unwrap_or('M').to_string(),
+            insertions: stats.next().and_then(|v| v.parse::<u32>().ok()),
+            deletions: stats.next().and_then(|v| v.parse::<u32>().ok()),
+        });
+    }
+    files
 }
 
+/// File-level diff between the working trees of two worktrees of the same repository,
+/// including changes that are not committed yet
 #[tauri::command]
-pub async fn git_fetch(
+pub async fn compare_worktrees(
     directory: String,
-    remote: Option<String>,
+    worktree_a: String,
+    worktree_b: String,
     state: State<'_, DesktopRuntime>,
-) -> Result<(), String> {
+) -> Result<GitWorktreeComparison, GitError> {
     let root = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-    let r = remote.unwrap_or_else(|| "origin".to_string());
-    run_git(&["fetch", &r], &root)
+
+    let output = run_git(&["worktree", "list", "--porcelain"], &root)
         .await
         .map_err(|e| e.to_string())?;
-    Ok(())
-}
+    let mut listed = Vec::new();
+    for line in output.lines() {
+        if let Some(cap) = WORKTREE_REGEX.captures(line) {
+            if let Ok(path) = fs::canonicalize(&cap[1]).await {
+                listed.push(path);
+            }
+        }
+    }
 
-#[tauri::command]
-pub async fn checkout_branch(
-    directory: String,
-    branch: String,
-    state: State<'_, DesktopRuntime>,
-) -> Result<(), String> {
-    let root = validate_git_path(&directory, state.settings())
+    let path_a = resolve_listed_worktree(&root, &listed, &worktree_a)
         .await
         .map_err(|e| e.to_string())?;
-    run_git(&["checkout", &branch], &root)
+    let path_b = resolve_listed_worktree(&root, &listed, &worktree_b)
         .await
         .map_err(|e| e.to_string())?;
-    Ok(())
-}
 
-#[tauri::command]
-pub async fn create_branch(
-    directory: String,
-    name: String,
-    start_point: Option<String>,
-    state: State<'_, DesktopRuntime>,
-) -> Result<(), String> {
-    let root = validate_git_path(&directory, state.settings())
+    let tree_a = snapshot_worktree_tree(&path_a)
+        .await
+        .map_err(|e| format!("Failed to snapshot {}: {}", worktree_a, e))?;
+    let tree_b = snapshot_worktree_tree(&path_b)
+        .await
+        .map_err(|e| format!("Failed to snapshot {}: {}", worktree_b, e))?;
+
+    let rename_arg = DiffPreferences::load(state.settings()).await.rename_arg();
+    let diff_args = |mode: &'static str| {
+        [
+            "-c",
+            "core.quotePath=false",
+            "diff-tree",
+            "-r",
+            rename_arg.as_str(),
+            mode,
+            tree_a.as_str(),
+            tree_b.as_str(),
+        ]
+    };
+    let name_status = run_git(&diff_args("--name-status"), &root)
         .await
         .map_err(|e| e.to_string())?;
-    let start = start_point.unwrap_or_else(|| "HEAD".to_string());
-    run_git(&["checkout", "-b", &name, &start], &root)
+    let numstat = run_git(&diff_args("--numstat"), &root)
         .await
         .map_err(|e| e.to_string())?;
-    Ok(())
+
+    let files = pair_file_diffs(&name_status, &numstat);
+    let insertions = files.iter().filter_map(|f| f.insertions).sum();
+    let deletions = files.iter().filter_map(|f| f.deletions).sum();
+
+    Ok(GitWorktreeComparison {
+        worktree_a: path_a.to_string_lossy().to_string(),
+        worktree_b: path_b.to_string_lossy().to_string(),
+        files,
+        insertions,
+        deletions,
+    })
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitRangeDiffstat {
+    pub from_ref: String,
+    pub to_ref: String,
+    pub files: Vec<WorktreeFileDiff>,
+    pub summary: GitCommitSummary,
 }
 
+/// Per-file insertions and deletions between two refs. With `merge_base`, `from_ref` is
+/// replaced by its merge-base with `to_ref`, giving just what `to_ref` adds, e.g. the
+/// contents of a branch.
 #[tauri::command]
-pub async fn get_git_log(
+pub async fn get_range_diffstat(
     directory: String,
-    max_count: Option<i32>,
-    from: Option<String>,
-    to: Option<String>,
-    file: Option<String>,
+    from_ref: String,
+    to_ref: Option<String>,
+    merge_base: Option<bool>,
     state: State<'_, DesktopRuntime>,
-) -> Result<GitLogResponse, String> {
+) -> Result<GitRangeDiffstat, GitError> {
     let root = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-
-    let max = max_count.unwrap_or(50).to_string();
-    let mut args = vec![
-        "log",
-        "--max-count",
-        &max,
-        "--date=iso",
-        "--pretty=format:%H%x1f%an%x1f%ae%x1f%ad%x1f%s%x1e",
-        "--shortstat",
-    ];
-
-    let range;
-    if let (Some(f), Some(t)) = (&from, &to) {
-        range = format!("{}..{}", f, t);
-        args.push(&range);
-    } else if let Some(f) = &from {
-        range = format!("{}..HEAD", f);
-        args.push(&range);
-    } else if let Some(t) = &to {
-        args.push(t);
-    }
-
-    if let Some(f) = &file {
-        args.push("--");
-        args.push(f);
+    let from_ref = from_ref.trim().to_string();
+    let to_ref = to_ref
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+    for rev in [&from_ref, &to_ref] {
+        if rev.is_empty() || rev.starts_with('-') || rev.contains("..") {
+            return Err(format!("Invalid ref: {}", rev).into());
+        }
     }
 
-    let output = run_git(&args, &root).await.map_err(|e| e.to_string())?;
-
-    let mut entries = Vec::new();
-    let entries_raw: Vec<&str> = output.split('\x1e').collect();
-
-    let mut current_header = if !entries_raw.is_empty() {
-        entries_raw[0].trim()
+    let separator = if merge_base.unwrap_or(false) {
+        "..."
     } else {
-        ""
+        ".."
     };
+    let range = format!("{}{}{}", from_ref, separator, to_ref);
+    let rename_arg = DiffPreferences::load(state.settings()).await.rename_arg();
+    let diff_args = |mode: &'static str| {
+        [
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            rename_arg.as_str(),
+            mode,
+            range.as_str(),
+            "--",
+        ]
+    };
+    let name_status = run_git(&diff_args("--name-status"), &root).await?;
+    let numstat = run_git(&diff_args("--numstat"), &root).await?;
+    let summary = collect_shortstat_for_range(&root, &range).await?;
+
+    Ok(GitRangeDiffstat {
+        from_ref,
+        to_ref,
+        files: pair_file_diffs(&name_status, &numstat),
+        summary,
+    })
+}
 
-    for i in 1..entries_raw.len() {
-        let chunk = entries_raw[i];
+/// Write the `.openchamber/` exclusion to the exclude file git reads for `root`. In
+/// linked worktrees and submodules `.git` is a file, and `info/` lives in the common
+/// git dir, so the path comes from `rev-parse --git-path` instead of `<root>/.git`.
+pub(crate) async fn ensure_openchamber_excluded(root: &Path) -> Result<()> {
+    let exclude_path = run_git(
+        &[
+            "rev-parse",
+            "--path-format=absolute",
+            "--git-path",
+            "info/exclude",
+        ],
+        root,
+    )
+    .await
+    .map(|path| PathBuf::from(path.trim()))
+    .context("Failed to resolve the git exclude file")?;
 
-        if current_header.is_empty() {
-            break;
-        }
+    if let Some(parent) = exclude_path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
 
-        let header_parts: Vec<&str> = current_header.split('\x1f').collect();
-        if header_parts.len() < 5 {
-            // Try to recover next header anyway before skipping
-            // But current_header is invalid, so we can't push an entry.
-            // We still need to update current_header for the next loop.
-        } else {
-            let hash = header_parts[0];
-            let name = header_parts[1];
-            let email = header_parts[2];
-            let date = header_parts[3];
-            let subject = header_parts[4];
-
-            let mut files_changed = 0;
-            let mut insertions = 0;
-            let mut deletions = 0;
-
-            if let Some(cap) = FILES_CHANGED_REGEX.captures(chunk) {
-                files_changed = cap[1].parse().unwrap_or(0);
-            }
-            if let Some(cap) = INSERTIONS_REGEX.captures(chunk) {
-                insertions = cap[1].parse().unwrap_or(0);
-            }
-            if let Some(cap) = DELETIONS_REGEX.captures(chunk) {
-                deletions = cap[1].parse().unwrap_or(0);
-            }
-
-            entries.push(GitLogEntry {
-                hash: hash.to_string(),
-                author_name: name.to_string(),
-                author_email: email.to_string(),
-                date: date.to_string(),
-                message: subject.to_string(),
-                body: String::new(),
-                refs: String::new(),
-                files_changed,
-                insertions,
-                deletions,
-            });
-        }
+    let entry = "/.openchamber/\n";
+    let mut content = fs::read_to_string(&exclude_path).await.unwrap_or_default();
 
-        // Find next header by looking for the line containing \x1f (separator used in format)
-        // The chunk contains stats then the next header.
-        current_header = "";
-        for line in chunk.lines().rev() {
-            let trimmed = line.trim();
-            if !trimmed.is_empty() && trimmed.contains('\x1f') {
-                current_header = trimmed;
-                break;
-            }
+    if !content.contains("/.openchamber/") {
+        if !content.ends_with('\n') && !content.is_empty() {
+            content.push('\n');
         }
+        content.push_str(entry);
+        fs::write(&exclude_path, content).await?;
     }
+    Ok(())
+}
 
-    if entries.is_empty() && !output.is_empty() {
-        for line in output.lines() {
-            let parts: Vec<&str> = line.split('\x1f').collect();
-            if parts.len() >= 5 {
-                entries.push(GitLogEntry {
-                    hash: parts[0].to_string(),
-                    author_name: parts[1].to_string(),
-                    author_email: parts[2].to_string(),
-                    date: parts[3].to_string(),
-                    message: parts[4].to_string(),
-                    body: "".to_string(),
-                    refs: "".to_string(),
-                    files_changed: 0,
-                    insertions: 0,
-                    deletions: 0,
-                });
-            }
-        }
+pub(crate) async fn is_git_work_tree(root: &Path) -> bool {
+    match run_git(&["rev-parse", "--is-inside-work-tree"], root).await {
+        Ok(output) => output.trim().eq_ignore_ascii_case("true"),
+        Err(_) => false,
     }
+}
 
-    Ok(GitLogResponse {
-        all: entries.clone(),
-        latest: entries.first().cloned(),
-        total: entries.len() as i32,
-    })
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitInitResult {
+    pub root: String,
+    pub branch: String,
+    pub gitignore_template: Option<String>,
+    pub initial_commit: Option<String>,
 }
 
+const GITIGNORE_TEMPLATES: &[(&str, &str)] = &[
+    (
+        "node",
+        "node_modules/\ndist/\nbuild/\ncoverage/\n.env\n.env.local\nnpm-debug.log*\nyarn-debug.log*\nyarn-error.log*\n.DS_Store\n",
+    ),
+    ("rust", "target/\n**/*.rs.bk\n*.pdb\n.DS_Store\n"),
+    (
+        "python",
+        "__pycache__/\n*.py[cod]\n.venv/\nvenv/\n.env\n*.egg-info/\ndist/\nbuild/\n.pytest_cache/\n.mypy_cache/\n.DS_Store\n",
+    ),
+    ("go", "bin/\n*.exe\n*.test\n*.out\nvendor/\n.env\n.DS_Store\n"),
+    (
+        "java",
+        "target/\nbuild/\n.gradle/\n*.class\n*.jar\n.idea/\n*.iml\n.DS_Store\n",
+    ),
+    ("macos", ".DS_Store\n.AppleDouble\n.LSOverride\n._*\n"),
+];
+
 #[tauri::command]
-pub async fn get_commit_files(
+pub async fn get_gitignore_templates() -> Result<Vec<String>, GitError> {
+    Ok(GITIGNORE_TEMPLATES
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect())
+}
+
+/// Turn a plain folder into a git repository, optionally seeding `.gitignore`
+/// and recording an initial commit
+#[tauri::command]
+pub async fn init_git_repository(
     directory: String,
-    hash: String,
+    default_branch: Option<String>,
+    gitignore_template: Option<String>,
+    initial_commit: Option<bool>,
+    commit_message: Option<String>,
     state: State<'_, DesktopRuntime>,
-) -> Result<GitCommitFilesResponse, String> {
-    let root = validate_git_path(&directory, state.settings())
-        .await
-        .map_err(|e| e.to_string())?;
-
-    // Get numstat for insertions/deletions per file
-    let numstat_output = run_git(&["show", "--numstat", "--format=", &hash], &root)
+) -> Result<GitInitResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
 
-    let mut files = Vec::new();
-
-    for line in numstat_output.lines() {
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() < 3 {
-            continue;
-        }
-
-        let insertions_raw = parts[0];
-        let deletions_raw = parts[1];
-        let file_path = parts[2..].join("\t");
-
-        if file_path.is_empty() {
-            continue;
-        }
-
-        // Binary files show '-' for stats
-        let is_binary = insertions_raw == "-" && deletions_raw == "-";
-        let insertions = if insertions_raw == "-" {
-            0
-        } else {
-            insertions_raw.parse().unwrap_or(0)
-        };
-        let deletions = if deletions_raw == "-" {
-            0
-        } else {
-            deletions_raw.parse().unwrap_or(0)
-        };
-
-        files.push(CommitFileEntry {
-            path: file_path,
-            insertions,
-            deletions,
-            is_binary,
-            change_type: "M".to_string(), // Default, will update below
-        });
+    if root.join(".git").exists() {
+        return Err(format!("{} is already a git repository", directory).into());
     }
 
-    // Get accurate change types using --name-status
-    let name_status_output = run_git(&["show", "--name-status", "--format=", &hash], &root)
+    let template = match gitignore_template
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        Some(name) => Some(
+            GITIGNORE_TEMPLATES
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("Unknown .gitignore template: {}", name))?,
+        ),
+        None => None,
+    };
+
+    let branch = default_branch
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+        .unwrap_or("main")
+        .to_string();
+    run_git(&["check-ref-format", "--branch", &branch], &root)
         .await
-        .unwrap_or_default();
+        .map_err(|_| format!("Invalid branch name: {}", branch))?;
 
-    let mut status_map: HashMap<String, String> = HashMap::new();
-    for line in name_status_output.lines() {
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() >= 2 {
-            let status = parts[0].chars().next().unwrap_or('M').to_string();
-            let path = parts.last().unwrap_or(&"").to_string();
-            status_map.insert(path, status);
-        }
-    }
+    run_git(&["init"], &root).await.map_err(|e| e.to_string())?;
+    // `git init -b` needs git 2.28+, pointing HEAD works everywhere
+    let head_ref = format!("refs/heads/{}", branch);
+    run_git(&["symbolic-ref", "HEAD", &head_ref], &root)
+        .await
+        .map_err(|e| e.to_string())?;
 
-    // Update change types
-    for file in &mut files {
-        let base_path = if file.path.contains(" => ") {
-            file.path
-                .split(" => ")
-                .last()
-                .unwrap_or(&file.path)
-                .replace(['{', '}'], "")
+    if let Some((_, content)) = template {
+        let gitignore_path = root.join(".gitignore");
+        if gitignore_path.exists() {
+            let mut existing = fs::read_to_string(&gitignore_path)
+                .await
+                .map_err(|e| e.to_string())?;
+            if !existing.is_empty() && !existing.ends_with('\n') {
+                existing.push('\n');
+            }
+            existing.push_str(content);
+            fs::write(&gitignore_path, existing)
+                .await
+                .map_err(|e| e.to_string())?;
         } else {
-            file.path.clone()
-        };
-
-        if let Some(status) = status_map.get(&base_path).or_else(|| status_map.get(&file.path)) {
-            file.change_type = status.clone();
+            fs::write(&gitignore_path, content)
+                .await
+                .map_err(|e| e.to_string())?;
         }
     }
 
-    Ok(GitCommitFilesResponse { files })
-}
-
-#[tauri::command]
-pub async fn get_git_identities() -> Result<Vec<GitIdentityProfile>, String> {
-    load_identities().await.map_err(|e| e.to_string())
-}
-
-#[tauri::command]
-pub async fn create_git_identity(
-    profile: GitIdentityProfile,
-) -> Result<GitIdentityProfile, String> {
-    let mut profiles = load_identities().await.map_err(|e| e.to_string())?;
-    if profiles.iter().any(|p| p.id == profile.id) {
-        return Err(format!("Profile with ID {} already exists", profile.id));
-    }
-    profiles.push(profile.clone());
-    save_identities(profiles).await.map_err(|e| e.to_string())?;
-    Ok(profile)
-}
-
-#[tauri::command]
-pub async fn update_git_identity(
-    id: String,
-    updates: GitIdentityProfile,
-) -> Result<GitIdentityProfile, String> {
-    let mut profiles = load_identities().await.map_err(|e| e.to_string())?;
-    if let Some(idx) = profiles.iter().position(|p| p.id == id) {
-        profiles[idx] = updates.clone();
-        save_identities(profiles).await.map_err(|e| e.to_string())?;
-        Ok(updates)
+    let initial_commit = if initial_commit.unwrap_or(false) {
+        let message = commit_message
+            .as_deref()
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .unwrap_or("Initial commit")
+            .to_string();
+        run_git(&["add", "-A"], &root)
+            .await
+            .map_err(|e| e.to_string())?;
+        run_git(&["commit", "--allow-empty", "-m", &message], &root)
+            .await
+            .map_err(|e| e.to_string())?;
+        Some(get_head_hash(&root).await.map_err(|e| e.to_string())?)
     } else {
-        Err(format!("Profile with ID {} not found", id))
-    }
-}
+        None
+    };
 
-#[tauri::command]
-pub async fn delete_git_identity(id: String) -> Result<(), String> {
-    let mut profiles = load_identities().await.map_err(|e| e.to_string())?;
-    let len = profiles.len();
-    profiles.retain(|p| p.id != id);
-    if profiles.len() == len {
-        return Err(format!("Profile with ID {} not found", id));
-    }
-    save_identities(profiles).await.map_err(|e| e.to_string())?;
-    Ok(())
+    info!("[git] Initialized repository in {:?} on {}", root, branch);
+
+    Ok(GitInitResult {
+        root: root.to_string_lossy().to_string(),
+        branch,
+        gitignore_template: template.map(|(name, _)| name.to_string()),
+        initial_commit,
+    })
 }
 
 #[tauri::command]
-pub async fn get_current_git_identity(
+pub async fn ensure_openchamber_ignored(
     directory: String,
     state: State<'_, DesktopRuntime>,
-) -> Result<GitIdentitySummary, String> {
+) -> Result<(), GitError> {
     let root = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
+    ensure_openchamber_excluded(&root)
+        .await
+        .map_err(GitError::from)
+}
 
-    let user_name = run_git(&["config", "user.name"], &root).await.ok();
-    let user_email = run_git(&["config", "user.email"], &root).await.ok();
-    let ssh_command = run_git(&["config", "core.sshCommand"], &root).await.ok();
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCoAuthor {
+    pub name: String,
+    pub email: String,
+}
 
-    Ok(GitIdentitySummary {
-        user_name: user_name.filter(|s| !s.is_empty()),
-        user_email: user_email.filter(|s| !s.is_empty()),
-        ssh_command: ssh_command.filter(|s| !s.is_empty()),
-    })
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCollaborator {
+    pub name: String,
+    pub email: String,
+    pub commits: u32,
+}
+
+/// Append `Co-authored-by:` trailers, skipping people already credited in the message
+fn append_co_author_trailers(message: &str, co_authors: &[GitCoAuthor]) -> String {
+    let mut result = message.trim_end().to_string();
+    let lower = result.to_lowercase();
+    let trailers: Vec<String> = co_authors
+        .iter()
+        .filter(|c| !c.name.trim().is_empty() && !c.email.trim().is_empty())
+        .filter(|c| {
+            let marker = format!("<{}>", c.email.trim().to_lowercase());
+            !lower.contains(&marker)
+        })
+        .map(|c| format!("Co-authored-by: {} <{}>", c.name.trim(), c.email.trim()))
+        .collect();
+
+    if trailers.is_empty() {
+        return result;
+    }
+
+    // Trailers must be separated from the body by a blank line, unless the last
+    // paragraph already is a trailer block
+    let last_paragraph = result.rsplit("\n\n").next().unwrap_or("");
+    let ends_with_trailers = result.contains("\n\n")
+        && last_paragraph.lines().all(|line| {
+            line.split_once(": ")
+                .is_some_and(|(key, _)| !key.contains(' '))
+        });
+    result.push_str(if ends_with_trailers { "\n" } else { "\n\n" });
+    result.push_str(&trailers.join("\n"));
+    result
 }
 
+/// People who authored or co-authored recent commits, most active first
 #[tauri::command]
-pub async fn set_git_identity(
+pub async fn get_recent_collaborators(
     directory: String,
-    profile_id: String,
+    limit: Option<u32>,
     state: State<'_, DesktopRuntime>,
-) -> Result<GitIdentityProfile, String> {
+) -> Result<Vec<GitCollaborator>, GitError> {
     let root = validate_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-    let profiles = load_identities().await.map_err(|e| e.to_string())?;
-
-    let profile = profiles
-        .into_iter()
-        .find(|p| p.id == profile_id)
-        .ok_or_else(|| format!("Profile {} not found", profile_id))?;
 
-    run_git(
-        &["config", "--local", "user.name", &profile.user_name],
-        &root,
-    )
-    .await
-    .map_err(|e| e.to_string())?;
-    run_git(
-        &["config", "--local", "user.email", &profile.user_email],
+    let output = run_git_with_allowed_exit(
+        &[
+            "log",
+            "--max-count=500",
+            "--format=%x1e%an%x00%ae%x00%(trailers:key=Co-authored-by,valueonly,separator=%x1f)",
+        ],
         &root,
+        &[128],
     )
     .await
     .map_err(|e| e.to_string())?;
 
-    if let Some(key) = &profile.ssh_key {
-        let cmd = format!("ssh -i {}", key);
-        run_git(&["config", "--local", "core.sshCommand", &cmd], &root)
-            .await
-            .map_err(|e| e.to_string())?;
-    } else {
-        let _ = run_git(&["config", "--local", "--unset", "core.sshCommand"], &root).await;
+    let own_email = run_git(&["config", "user.email"], &root)
+        .await
+        .unwrap_or_default()
+        .to_lowercase();
+
+    let mut collaborators: HashMap<String, GitCollaborator> = HashMap::new();
+    let mut record = |name: &str, email: &str| {
+        let name = name.trim();
+        let email = email.trim();
+        if name.is_empty() || email.is_empty() || email.to_lowercase() == own_email {
+            return;
+        }
+        collaborators
+            .entry(email.to_lowercase())
+            .or_insert_with(|| GitCollaborator {
+                name: name.to_string(),
+                email: email.to_string(),
+                commits: 0,
+            })
+            .commits += 1;
+    };
+
+    for entry in output.split('\x1e') {
+        let parts: Vec<&str> = entry.split('\0').collect();
+        if parts.len() < 2 {
+            continue;
+        }
+        record(parts[0], parts[1]);
+        for trailer in parts.get(2).unwrap_or(&"").split('\x1f') {
+            if let Some((name, email)) = trailer.trim().trim_end_matches('>').split_once(" <") {
+                record(name, email);
+            }
+        }
     }
 
-    Ok(profile)
+    let mut result: Vec<GitCollaborator> = collaborators.into_values().collect();
+    result.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+    result.truncate(limit.unwrap_or(20).clamp(1, 200) as usize);
+    Ok(result)
+}
+
+/// Repository config key holding the per-repo sign-off default
+const SIGNOFF_CONFIG_KEY: &str = "openchamber.signoff";
+
+/// Whether commits get a `Signed-off-by` trailer when the caller doesn't say: the repo's
+/// `openchamber.signoff` setting, else the identity profile matching `user.email`
+async fn resolve_signoff_default(root: &Path) -> bool {
+    if let Ok(value) = run_git(&["config", "--get", "--bool", SIGNOFF_CONFIG_KEY], root).await {
+        return value.trim() == "true";
+    }
+    let Ok(email) = run_git(&["config", "user.email"], root).await else {
+        return false;
+    };
+    let email = email.trim();
+    identity_store::load_profiles()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .find(|p| !email.is_empty() && p.user_email.trim().eq_ignore_ascii_case(email))
+        .is_some_and(|p| p.signoff)
 }
 
+/// Set the repository's sign-off default; `None` removes it so the identity's applies
 #[tauri::command]
-pub async fn generate_commit_message(
+pub async fn set_commit_signoff_default(
     directory: String,
-    files: Vec<String>,
+    enabled: Option<bool>,
     state: State<'_, DesktopRuntime>,
-) -> Result<CommitMessageResponse, String> {
-    let _root = validate_git_path(&directory, state.settings())
+) -> Result<bool, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
-
-    // 1. Collect diffs
-    let mut diff_summaries = String::new();
-    for file in files {
-        if let Ok(diff) =
-            get_git_diff(directory.clone(), file.clone(), None, None, state.clone()).await
-        {
-            let trimmed = if diff.len() > 4000 {
-                format!("{}\n...", &diff[..4000])
-            } else {
-                diff
-            };
-            diff_summaries.push_str(&format!("FILE: {}\n{}\n\n", file, trimmed));
+    match enabled {
+        Some(enabled) => {
+            let value = if enabled { "true" } else { "false" };
+            run_git(&["config", "--local", SIGNOFF_CONFIG_KEY, value], &root).await?;
+        }
+        None => {
+            run_git_with_allowed_exit(
+                &["config", "--local", "--unset", SIGNOFF_CONFIG_KEY],
+                &root,
+                &[5],
+            )
+            .await?;
         }
     }
+    Ok(resolve_signoff_default(&root).await)
+}
 
-    if diff_summaries.is_empty() {
-        return Err("No diffs available for selected files".to_string());
-    }
-
-    // 2. Construct prompt (matching server/index.js)
-    let prompt = format!(
-        r#"You are drafting git commit notes for this codebase. Respond in JSON of the shape {{"subject": string, "highlights": string[]}} (ONLY the JSON in response, no markdown wrappers or anything except JSON) with these rules:
-- subject follows our convention: type[optional-scope]: summary (examples: "feat: add diff virtualization", "fix(chat): restore enter key handling")
-- allowed types: feat, fix, chore, style, refactor, perf, docs, test, build, ci (choose the best match or fallback to chore)
-- summary must be imperative, concise, <= 70 characters, no trailing punctuation
-- scope is optional; include only when obvious from filenames/folders; do not invent scopes
-- focus on the most impactful user-facing change; if multiple capabilities ship together, align the subject with the dominant theme and use highlights to cover the other major outcomes
-- highlights array should contain 2-3 plain sentences (<= 90 chars each) that describe distinct features or UI changes users will notice (e.g. "Add per-file revert action in Changes list"). Avoid subjective benefit statements, marketing tone, repeating the subject, or referencing helper function names. Highlight additions such as new controls/buttons, new actions (e.g. revert), or stored state changes explicitly. Skip highlights if fewer than two meaningful points exist.
-- text must be plain (no markdown bullets); each highlight should start with an uppercase verb
-
-Diff summary:
-{}"#,
-        diff_summaries
-    );
-
-    // 3. Call API
-    let client = Client::new();
-    let res = client
-        .post("https://opencode.ai/zen/v1/chat/completions")
-        .json(&serde_json::json!({
-            "model": "big-pickle",
-            "messages": [{ "role": "user", "content": prompt }],
-            "max_tokens": 3000,
-            "stream": false,
-            "reasoning": {
-                "effort": "low"
-            }
-        }))
-        .send()
+/// Commit staged changes, or `files`/everything first when given. `signoff` adds a
+/// `Signed-off-by` trailer (`git commit -s`) and defaults per repo or identity;
+/// `no_verify` skips the pre-commit and commit-msg hooks. `allow_empty` commits even
+/// with nothing staged, e.g. to trigger CI. `author_date`/`commit_date` override the
+/// timestamps and take any date format git accepts.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_git_commit(
+    directory: String,
+    message: String,
+    add_all: Option<bool>,
+    files: Option<Vec<String>>,
+    co_authors: Option<Vec<GitCoAuthor>>,
+    signoff: Option<bool>,
+    no_verify: Option<bool>,
+    allow_empty: Option<bool>,
+    author_date: Option<String>,
+    commit_date: Option<String>,
+    explain: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Explainable<GitCommitResult>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
         .await
         .map_err(|e| e.to_string())?;
 
-    if !res.status().is_success() {
-        return Err(format!("API request failed: {}", res.status()));
-    }
-
-    let body: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
-    let raw_content = body["choices"][0]["message"]["content"]
-        .as_str()
-        .unwrap_or("")
-        .trim();
+    let add_args: Option<Vec<&str>> = if add_all.unwrap_or(false) {
+        Some(vec!["add", "."])
+    } else {
+        files.as_ref().filter(|list| !list.is_empty()).map(|list| {
+            let mut args = vec!["add"];
+            args.extend(list.iter().map(|s| s.as_str()));
+            args
+        })
+    };
 
-    // 4. Parse JSON
-    // Strip markdown code blocks if present
-    let cleaned = raw_content
+    let message = match co_authors.as_deref() {
+        Some(co_authors) if !co_authors.is_empty() => {
+            append_co_author_trailers(&message, co_authors)
+        }
+        _ => message,
+    };
+    let signoff = match signoff {
+        Some(signoff) => signoff,
+        None => resolve_signoff_default(&root).await,
+    };
+    let mut commit_args = vec!["commit", "-m", &message];
+    if signoff {
+        commit_args.push("--signoff");
+    }
+    let no_verify = no_verify.unwrap_or(false);
+    if no_verify {
+        commit_args.push("--no-verify");
+    }
+    if allow_empty.unwrap_or(false) {
+        commit_args.push("--allow-empty");
+    }
+    let date = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let author_date_arg = date(&author_date).map(|d| format!("--date={}", d));
+    if let Some(arg) = &author_date_arg {
+        commit_args.push(arg);
+    }
+    let commit_date = date(&commit_date);
+    let envs: Vec<(&str, &str)> = commit_date
+        .iter()
+        .map(|d| ("GIT_COMMITTER_DATE", d.as_str()))
+        .collect();
+
+    if explain.unwrap_or(false) {
+        let mut steps: Vec<GitInvocation> = add_args
+            .iter()
+            .map(|args| GitInvocation::local(args, &root))
+            .collect();
+        steps.push(
+            GitInvocation::local(&commit_args, &root)
+                .with_env(&envs)
+                .with_hook_trace(),
+        );
+        return Ok(Explainable::plan(steps));
+    }
+
+    if let Some(args) = &add_args {
+        run_git(args, &root).await.map_err(|e| e.to_string())?;
+    }
+    run_git_capturing_hooks(&commit_args, &root, &envs).await?;
+
+    let commit_hash = get_head_hash(&root).await.map_err(|e| e.to_string())?;
+    let branch_name = get_current_branch_name(&root)
+        .await
+        .unwrap_or_else(|_| "HEAD".to_string());
+
+    let stat_output = run_git(&["log", "-1", "--pretty=", "--shortstat"], &root)
+        .await
+        .unwrap_or_default();
+    let summary = parse_shortstat(&stat_output);
+
+    Ok(Explainable::Done(GitCommitResult {
+        success: true,
+        commit: commit_hash,
+        branch: branch_name,
+        summary,
+        verification_skipped: no_verify,
+    }))
+}
+
+/// Push the current (or given) branch. `remotes` pushes to several remotes in order, and
+/// `push_to_all` to every configured remote with `remote` (or `origin`) first; only that
+/// first remote becomes the upstream. With more than one remote a failure doesn't stop
+/// the rest: each outcome is reported in `remotes`, and an error is returned only when
+/// every push failed. `no_verify` skips the pre-push hook.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn git_push(
+    directory: String,
+    remote: Option<String>,
+    branch: Option<String>,
+    options: Option<Value>,
+    remotes: Option<Vec<String>>,
+    push_to_all: Option<bool>,
+    no_verify: Option<bool>,
+    operation_id: Option<String>,
+    explain: Option<bool>,
+    app: AppHandle,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Explainable<GitPushResult>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let remote_name = remote.unwrap_or_else(|| "origin".to_string());
+    let mut targets: Vec<String> = if push_to_all.unwrap_or(false) {
+        let configured = run_git(&["remote"], &root).await.unwrap_or_default();
+        let mut all: Vec<String> = configured
+            .lines()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(str::to_string)
+            .collect();
+        all.sort_by_key(|r| *r != remote_name);
+        all
+    } else {
+        remotes
+            .unwrap_or_default()
+            .iter()
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty())
+            .collect()
+    };
+    let mut seen = HashSet::new();
+    targets.retain(|r| seen.insert(r.clone()));
+    if targets.is_empty() {
+        targets.push(remote_name);
+    }
+    if let Some(invalid) = targets.iter().find(|r| r.starts_with('-')) {
+        return Err(format!("Invalid remote: {}", invalid).into());
+    }
+
+    let explicit_branch = branch
+        .as_deref()
+        .map(|value| !value.trim().is_empty())
+        .unwrap_or(false);
+    let mut branch_name = branch.unwrap_or_default();
+    if branch_name.is_empty() {
+        branch_name = get_current_branch_name(&root).await.unwrap_or_default();
+    }
+
+    let mut set_upstream = false;
+    if !branch_name.is_empty() && !explicit_branch {
+        // If caller didn't specify a branch and there's no upstream configured yet,
+        // publish on first push so future pushes/pulls work without extra prompts.
+        let remote_key = format!("branch.{}.remote", branch_name);
+        let merge_key = format!("branch.{}.merge", branch_name);
+
+        let upstream_remote =
+            run_git_with_allowed_exit(&["config", "--get", &remote_key], &root, &[1])
+                .await
+                .unwrap_or_default();
+
+        let upstream_merge =
+            run_git_with_allowed_exit(&["config", "--get", &merge_key], &root, &[1])
+                .await
+                .unwrap_or_default();
+
+        set_upstream = upstream_remote.trim().is_empty() || upstream_merge.trim().is_empty();
+    }
+
+    let no_verify = no_verify.unwrap_or(false);
+    let push_args: Vec<Vec<String>> = targets
+        .iter()
+        .enumerate()
+        .map(|(index, target)| {
+            let mut args = vec!["push".to_string(), "--progress".to_string()];
+            if no_verify {
+                args.push("--no-verify".to_string());
+            }
+            args.push(target.clone());
+            if !branch_name.is_empty() {
+                if set_upstream && index == 0 {
+                    args.push("--set-upstream".to_string());
+                }
+                args.push(branch_name.clone());
+            }
+            if let Some(extra) = options.as_ref() {
+                append_git_option(&mut args, extra);
+            }
+            args
+        })
+        .collect();
+
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(
+            push_args
+                .iter()
+                .map(|args| GitInvocation::network(args, &root).with_hook_trace())
+                .collect(),
+        ));
+    }
+
+    let operation_id = resolve_operation_id(operation_id);
+    let mut results = Vec::with_capacity(targets.len());
+    for (target, args) in targets.iter().zip(&push_args) {
+        let arg_refs: Vec<&str> = args.iter().map(|value| value.as_str()).collect();
+        let urls = resolve_remote_urls(&root, &[target], true).await;
+        let outcome = run_git_with_progress_capturing_hooks(
+            &arg_refs,
+            &root,
+            &urls,
+            &app,
+            &operation_id,
+            "push",
+        )
+        .await;
+        if let Err(err) = outcome {
+            if targets.len() == 1 {
+                return Err(err);
+            }
+            warn!("[git] Push to {} failed: {}", target, err);
+            results.push(GitRemotePushResult {
+                remote: target.clone(),
+                success: false,
+                pushed: vec![],
+                error: Some(err),
+            });
+            continue;
+        }
+        results.push(GitRemotePushResult {
+            remote: target.clone(),
+            success: true,
+            pushed: if branch_name.is_empty() {
+                vec![]
+            } else {
+                vec![GitPushRef {
+                    local: branch_name.clone(),
+                    remote: format!("{}/{}", target, branch_name),
+                }]
+            },
+            error: None,
+        });
+    }
+
+    if results.iter().all(|r| !r.success) {
+        if let Some(error) = results.into_iter().find_map(|r| r.error) {
+            return Err(error);
+        }
+        return Err("Push failed".into());
+    }
+
+    Ok(Explainable::Done(GitPushResult {
+        success: results.iter().all(|r| r.success),
+        pushed: results.iter().flat_map(|r| r.pushed.clone()).collect(),
+        repo: targets[0].clone(),
+        ref_: if branch_name.is_empty() {
+            None
+        } else {
+            Some(branch_name)
+        },
+        remotes: results,
+        verification_skipped: no_verify,
+    }))
+}
+
+#[tauri::command]
+pub async fn git_pull(
+    directory: String,
+    remote: Option<String>,
+    branch: Option<String>,
+    operation_id: Option<String>,
+    explain: Option<bool>,
+    app: AppHandle,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Explainable<GitPullResult>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let r = remote.unwrap_or_else(|| "origin".to_string());
+    let b = branch.unwrap_or_default();
+
+    let mut args = vec!["pull", "--progress", &r];
+    if !b.is_empty() {
+        args.push(&b);
+    }
+
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(vec![GitInvocation::network(
+            &args, &root,
+        )]));
+    }
+
+    let previous_head = get_head_hash(&root).await.ok();
+    let operation_id = resolve_operation_id(operation_id);
+
+    let urls = resolve_remote_urls(&root, &[&r], false).await;
+    run_git_with_progress(&args, &root, &urls, &app, &operation_id, "pull")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (summary, files) = if let Some(previous) = previous_head {
+        let new_head = get_head_hash(&root).await.unwrap_or(previous.clone());
+        if new_head != previous {
+            let range = format!("{previous}..{new_head}");
+            let summary = collect_shortstat_for_range(&root, &range)
+                .await
+                .unwrap_or_else(|_| GitCommitSummary {
+                    changes: 0,
+                    insertions: 0,
+                    deletions: 0,
+                });
+            let files = collect_changed_files_for_range(&root, &range)
+                .await
+                .unwrap_or_default();
+            (summary, files)
+        } else {
+            (
+                GitCommitSummary {
+                    changes: 0,
+                    insertions: 0,
+                    deletions: 0,
+                },
+                vec![],
+            )
+        }
+    } else {
+        (
+            GitCommitSummary {
+                changes: 0,
+                insertions: 0,
+                deletions: 0,
+            },
+            vec![],
+        )
+    };
+
+    Ok(Explainable::Done(GitPullResult {
+        success: true,
+        summary: summary.clone(),
+        files,
+        insertions: summary.insertions,
+        deletions: summary.deletions,
+    }))
+}
+
+fn parse_fetch_output(output: &str) -> (Vec<GitFetchRefUpdate>, GitFetchSummary) {
+    let mut updates = Vec::new();
+    let mut summary = GitFetchSummary::default();
+
+    for line in output.lines() {
+        let Some(cap) = FETCH_REF_REGEX.captures(line) else {
+            continue;
+        };
+
+        let flag = &cap[1];
+        let detail = &cap[2];
+        let note = cap.get(5).map(|m| m.as_str()).unwrap_or("");
+
+        let (old_hash, new_hash) =
+            match detail.split_once("...").or_else(|| detail.split_once("..")) {
+                Some((old, new)) => (Some(old.to_string()), Some(new.to_string())),
+                None => (None, None),
+            };
+
+        let kind = match flag {
+            "*" if detail.contains("tag") => {
+                summary.new_tags += 1;
+                "newTag"
+            }
+            "*" => {
+                summary.new_branches += 1;
+                "newBranch"
+            }
+            "+" => {
+                summary.forced_updates += 1;
+                "forcedUpdate"
+            }
+            "-" => {
+                summary.deleted += 1;
+                "deleted"
+            }
+            "t" => "tagUpdate",
+            "=" => "upToDate",
+            "!" => {
+                summary.rejected += 1;
+                "rejected"
+            }
+            _ if note.contains("forced") => {
+                summary.forced_updates += 1;
+                "forcedUpdate"
+            }
+            _ => {
+                summary.fast_forwards += 1;
+                "fastForward"
+            }
+        };
+
+        updates.push(GitFetchRefUpdate {
+            kind: kind.to_string(),
+            source: cap[3].to_string(),
+            target: cap[4].to_string(),
+            old_hash,
+            new_hash,
+        });
+    }
+
+    (updates, summary)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn git_fetch(
+    directory: String,
+    remote: Option<String>,
+    all: Option<bool>,
+    prune: Option<bool>,
+    tags: Option<bool>,
+    operation_id: Option<String>,
+    explain: Option<bool>,
+    app: AppHandle,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Explainable<GitFetchResult>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let fetch_all = all.unwrap_or(false);
+    let r = remote.unwrap_or_else(|| "origin".to_string());
+
+    let mut args = vec!["fetch", "--progress"];
+    if fetch_all {
+        args.push("--all");
+    }
+    if prune.unwrap_or(false) {
+        args.push("--prune");
+    }
+    if tags.unwrap_or(false) {
+        args.push("--tags");
+    }
+    if !fetch_all {
+        args.push(&r);
+    }
+
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(vec![GitInvocation::network(
+            &args, &root,
+        )]));
+    }
+
+    let operation_id = resolve_operation_id(operation_id);
+
+    let remotes = if fetch_all {
+        run_git(&["remote"], &root).await.unwrap_or_default()
+    } else {
+        r.clone()
+    };
+    let remotes: Vec<&str> = remotes.lines().map(str::trim).collect();
+    let urls = resolve_remote_urls(&root, &remotes, false).await;
+
+    // Ref updates are reported on stderr alongside progress
+    let (_, stderr) = run_git_with_progress(&args, &root, &urls, &app, &operation_id, "fetch")
+        .await
+        .map_err(|e| e.to_string())?;
+    let (updates, summary) = parse_fetch_output(&stderr);
+
+    Ok(Explainable::Done(GitFetchResult {
+        success: true,
+        remote: if fetch_all { None } else { Some(r) },
+        updates,
+        summary,
+    }))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchAheadBehind {
+    pub directory: String,
+    pub branch: String,
+    pub upstream: String,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+/// One background cycle: quietly fetch the current branch's remote with `--prune`, then
+/// report ahead/behind against the upstream. `None` when the branch has no upstream.
+pub(crate) async fn background_fetch_once(root: &Path) -> Result<Option<BranchAheadBehind>> {
+    let branch = get_current_branch_name(root).await?;
+    if branch == "HEAD" {
+        return Ok(None);
+    }
+    let Ok(upstream) = run_git(
+        &[
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{upstream}",
+        ],
+        root,
+    )
+    .await
+    else {
+        return Ok(None);
+    };
+
+    let remote_key = format!("branch.{}.remote", branch);
+    let remote = run_git(&["config", "--get", &remote_key], root)
+        .await
+        .unwrap_or_else(|_| "origin".to_string());
+    run_git(&["fetch", "--prune", "--quiet", remote.trim()], root).await?;
+
+    let counts = run_git(
+        &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+        root,
+    )
+    .await?;
+    let mut parts = counts.split_whitespace();
+    Ok(Some(BranchAheadBehind {
+        directory: root.to_string_lossy().to_string(),
+        branch,
+        upstream: upstream.trim().to_string(),
+        ahead: parts.next().and_then(|v| v.parse().ok()).unwrap_or(0),
+        behind: parts.next().and_then(|v| v.parse().ok()).unwrap_or(0),
+    }))
+}
+
+#[tauri::command]
+pub async fn start_background_fetch(
+    directory: String,
+    app: AppHandle,
+    state: State<'_, DesktopRuntime>,
+) -> Result<(), GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    if !is_git_work_tree(&root).await {
+        return Err(format!("{} is not a git repository", directory).into());
+    }
+    background_fetch::start(app, state.inner().clone(), root).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_background_fetch(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<bool, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(background_fetch::stop(&root).await)
+}
+
+#[tauri::command]
+pub async fn git_clone(
+    url: String,
+    directory: String,
+    branch: Option<String>,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, DesktopRuntime>,
+) -> Result<String, GitError> {
+    let target = expand_tilde_path(&directory);
+    if !target.is_absolute() {
+        return Err("Path must be absolute".into());
+    }
+    if target.exists() {
+        return Err(format!("Destination already exists: {}", directory).into());
+    }
+    let (Some(parent), Some(name)) = (target.parent(), target.file_name()) else {
+        return Err("Invalid destination path".into());
+    };
+    // The clone lands in an existing directory that passes the same checks as any repository
+    let parent = validate_writable_git_path(&parent.to_string_lossy(), state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let parent = parent.as_path();
+    let target = parent.join(name);
+
+    let target_str = target.to_string_lossy().to_string();
+    let mut args = vec!["clone", "--progress"];
+    if let Some(branch) = branch.as_deref().map(str::trim).filter(|b| !b.is_empty()) {
+        args.push("--branch");
+        args.push(branch);
+    }
+    args.push("--");
+    args.push(url.trim());
+    args.push(&target_str);
+
+    let operation_id = resolve_operation_id(operation_id);
+    let urls = [url.trim().to_string()];
+    run_git_with_progress(&args, parent, &urls, &app, &operation_id, "clone")
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(target_str)
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteUrlInfo {
+    #[serde(flatten)]
+    pub remote: RemoteUrl,
+    pub commit_url: Option<String>,
+    pub branch_url: Option<String>,
+    pub file_url: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFilePermalink {
+    pub url: String,
+    pub remote: String,
+    pub commit: String,
+    pub path: String,
+}
+
+fn normalize_line_range(line_start: Option<u32>, line_end: Option<u32>) -> Option<(u32, u32)> {
+    match (line_start.filter(|l| *l > 0), line_end.filter(|l| *l > 0)) {
+        (Some(start), Some(end)) => Some((start, end)),
+        (Some(start), None) => Some((start, start)),
+        (None, Some(end)) => Some((end, end)),
+        (None, None) => None,
+    }
+}
+
+/// Pick the remote a branch tracks, falling back to `origin` and then the first remote
+async fn resolve_default_remote(root: &Path) -> Result<String> {
+    if let Ok(upstream) = run_git(
+        &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"],
+        root,
+    )
+    .await
+    {
+        if let Some((remote, _)) = upstream.trim().split_once('/') {
+            if !remote.is_empty() {
+                return Ok(remote.to_string());
+            }
+        }
+    }
+
+    let remotes = run_git(&["remote"], root).await?;
+    let remotes: Vec<&str> = remotes
+        .lines()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .collect();
+    if remotes.contains(&"origin") {
+        return Ok("origin".to_string());
+    }
+    remotes
+        .first()
+        .map(|r| r.to_string())
+        .ok_or_else(|| anyhow!("Repository has no remotes"))
+}
+
+#[tauri::command]
+pub async fn parse_remote_url(
+    url: String,
+    commit: Option<String>,
+    branch: Option<String>,
+    path: Option<String>,
+    line_start: Option<u32>,
+    line_end: Option<u32>,
+) -> Result<RemoteUrlInfo, GitError> {
+    let remote = git_remote::parse_remote_url(&url).map_err(|e| e.to_string())?;
+    let commit = commit.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    let branch = branch.as_deref().map(str::trim).filter(|b| !b.is_empty());
+
+    let commit_url = commit.map(|c| remote.commit_url(c));
+    let branch_url = branch.map(|b| remote.branch_url(b));
+    let file_url = path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .and_then(|p| {
+            let lines = normalize_line_range(line_start, line_end);
+            commit
+                .or(branch)
+                .map(|reference| remote.file_url(reference, p, lines))
+        });
+
+    Ok(RemoteUrlInfo {
+        remote,
+        commit_url,
+        branch_url,
+        file_url,
+    })
+}
+
+/// Build a web permalink for a file pinned to the current HEAD commit
+#[tauri::command]
+pub async fn get_file_permalink(
+    directory: String,
+    path: String,
+    line_start: Option<u32>,
+    line_end: Option<u32>,
+    remote: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitFilePermalink, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let remote_name = match remote.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+        Some(name) => name.to_string(),
+        None => resolve_default_remote(&root)
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+    let remote_url = run_git(&["remote", "get-url", &remote_name], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    let parsed = git_remote::parse_remote_url(&remote_url).map_err(|e| e.to_string())?;
+
+    let commit = get_head_hash(&root).await.map_err(|e| e.to_string())?;
+    let (_, _, relative_path) = resolve_path_for_git_show(&root, &path).await;
+    let url = parsed.file_url(
+        &commit,
+        &relative_path,
+        normalize_line_range(line_start, line_end),
+    );
+
+    Ok(GitFilePermalink {
+        url,
+        remote: remote_name,
+        commit,
+        path: relative_path,
+    })
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteWebUrlTarget {
+    pub path: Option<String>,
+    pub line: Option<u32>,
+    pub line_end: Option<u32>,
+    pub hash: Option<String>,
+    pub branch: Option<String>,
+    /// Base of a compare view; the remote's default branch when omitted
+    pub base: Option<String>,
+    pub remote: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteWebUrl {
+    pub url: String,
+    pub kind: String,
+    pub remote: String,
+    #[serde(flatten)]
+    pub parsed: RemoteUrl,
+}
+
+/// The remote's default branch from `<remote>/HEAD`, falling back to main or master
+async fn resolve_remote_default_branch(root: &Path, remote: &str) -> Option<String> {
+    let head_ref = format!("refs/remotes/{}/HEAD", remote);
+    if let Ok(target) = run_git(&["symbolic-ref", "--short", &head_ref], root).await {
+        let prefix = format!("{}/", remote);
+        if let Some(branch) = target.trim().strip_prefix(&prefix) {
+            return Some(branch.to_string());
+        }
+    }
+    for candidate in ["main", "master"] {
+        let remote_ref = format!("refs/remotes/{}/{}", remote, candidate);
+        if run_git(&["show-ref", "--verify", "--quiet", &remote_ref], root)
+            .await
+            .is_ok()
+        {
+            return Some(candidate.to_string());
+        }
+    }
+    None
+}
+
+/// Canonical web URL on the hosting provider for the repository (`repo`), a file or
+/// line range (`file`/`line`), a commit, a branch, or a compare view of `branch`
+/// (current branch by default) against `base`
+#[tauri::command]
+pub async fn resolve_remote_web_url(
+    directory: String,
+    kind: String,
+    target: Option<RemoteWebUrlTarget>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RemoteWebUrl, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let target = target.unwrap_or_default();
+    let non_empty = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let hash = non_empty(&target.hash);
+    let branch = non_empty(&target.branch);
+
+    let remote_name = match non_empty(&target.remote) {
+        Some(name) => name,
+        None => resolve_default_remote(&root)
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+    let remote_url = run_git(&["remote", "get-url", &remote_name], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    let parsed = git_remote::parse_remote_url(&remote_url).map_err(|e| e.to_string())?;
+
+    let current_branch = || async {
+        match get_current_branch_name(&root).await {
+            Ok(name) if name != "HEAD" => Ok(name),
+            _ => Err("HEAD is detached; specify a branch".to_string()),
+        }
+    };
+
+    let kind = kind.trim().to_ascii_lowercase();
+    let url = match kind.as_str() {
+        "repo" => parsed.web_url.clone(),
+        "commit" => {
+            let spec = format!("{}^{{commit}}", hash.as_deref().unwrap_or("HEAD"));
+            let commit = run_git(&["rev-parse", "--verify", &spec], &root)
+                .await
+                .map_err(|e| e.to_string())?;
+            parsed.commit_url(commit.trim())
+        }
+        "branch" => {
+            let branch = match branch {
+                Some(branch) => branch,
+                None => current_branch().await?,
+            };
+            parsed.branch_url(&branch)
+        }
+        "file" | "line" => {
+            let path = non_empty(&target.path).ok_or("A file path is required")?;
+            if kind == "line" && target.line.is_none() {
+                return Err("A line number is required".into());
+            }
+            // Pin to a commit unless a branch was asked for explicitly
+            let reference = match (hash, branch) {
+                (Some(hash), _) => hash,
+                (None, Some(branch)) => branch,
+                (None, None) => get_head_hash(&root).await.map_err(|e| e.to_string())?,
+            };
+            let (_, _, relative_path) = resolve_path_for_git_show(&root, &path).await;
+            let lines = normalize_line_range(target.line, target.line_end.or(target.line));
+            parsed.file_url(&reference, &relative_path, lines)
+        }
+        "compare" => {
+            let head = match branch {
+                Some(branch) => branch,
+                None => current_branch().await?,
+            };
+            let base = match non_empty(&target.base) {
+                Some(base) => base,
+                None => resolve_remote_default_branch(&root, &remote_name)
+                    .await
+                    .ok_or("Could not determine the default branch; specify a base")?,
+            };
+            parsed.compare_url(&base, &head)
+        }
+        other => return Err(format!("Unknown URL kind: {}", other).into()),
+    };
+
+    Ok(RemoteWebUrl {
+        url,
+        kind,
+        remote: remote_name,
+        parsed,
+    })
+}
+
+const CHECKS_REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+const CHECKS_USER_AGENT: &str = "OpenChamber-Desktop";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitCheck {
+    pub name: String,
+    /// Normalized outcome: pass, fail, pending, skipped or neutral
+    pub status: String,
+    /// Status or conclusion exactly as reported by the provider
+    pub raw_status: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitChecks {
+    pub commit: String,
+    pub remote: String,
+    pub provider: String,
+    /// Whether the commit exists on the remote; unpushed commits have no checks
+    pub pushed: bool,
+    /// Overall state: fail if any check failed, pending if any is still running,
+    /// pass if at least one passed, none when there are no checks
+    pub state: String,
+    pub checks: Vec<GitCommitCheck>,
+}
+
+/// Token for a hosting provider's API: the usual environment variables first, then
+/// whatever git's credential helpers hold for the host
+async fn resolve_forge_token(root: &Path, remote: &RemoteUrl) -> Option<String> {
+    let env_vars: &[&str] = match remote.provider {
+        git_remote::RemoteProvider::Github => &["GH_TOKEN", "GITHUB_TOKEN"],
+        git_remote::RemoteProvider::Gitlab => &["GITLAB_TOKEN", "GL_TOKEN"],
+        _ => &[],
+    };
+    for var in env_vars {
+        if let Some(token) = std::env::var(var).ok().filter(|t| !t.trim().is_empty()) {
+            return Some(token.trim().to_string());
+        }
+    }
+
+    let input = format!("protocol=https\nhost={}\n\n", remote.host);
+    let output = run_git_with_env_input(
+        &["credential", "fill"],
+        root,
+        &[
+            ("GCM_INTERACTIVE", "Never"),
+            ("GIT_ASKPASS", ""),
+            ("SSH_ASKPASS", ""),
+        ],
+        input.as_bytes(),
+    )
+    .await
+    .ok()?;
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("password="))
+        .map(str::to_string)
+        .filter(|token| !token.is_empty())
+}
+
+fn json_text(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn normalize_github_check(status: &str, conclusion: Option<&str>) -> &'static str {
+    if status != "completed" {
+        return "pending";
+    }
+    match conclusion.unwrap_or_default() {
+        "success" => "pass",
+        "skipped" => "skipped",
+        "neutral" => "neutral",
+        "" | "stale" => "pending",
+        _ => "fail",
+    }
+}
+
+async fn fetch_github_checks(
+    client: &Client,
+    remote: &RemoteUrl,
+    commit: &str,
+    token: Option<&str>,
+) -> Result<Vec<GitCommitCheck>> {
+    let api_base = if remote.host.eq_ignore_ascii_case("github.com") {
+        "https://api.github.com".to_string()
+    } else {
+        format!("https://{}/api/v3", remote.host)
+    };
+    let repo_url = format!("{}/repos/{}/{}", api_base, remote.owner, remote.repo);
+    let get = |url: String| {
+        let mut request = client
+            .get(url)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        async move {
+            let response = request.send().await?;
+            let status = response.status();
+            if !status.is_success() {
+                return Err(anyhow!("GitHub API returned {}", status));
+            }
+            Ok(response.json::<Value>().await?)
+        }
+    };
+
+    let (check_runs, statuses) = tokio::join!(
+        get(format!(
+            "{}/commits/{}/check-runs?per_page=100",
+            repo_url, commit
+        )),
+        get(format!(
+            "{}/commits/{}/status?per_page=100",
+            repo_url, commit
+        )),
+    );
+    let check_runs = check_runs?;
+
+    let mut checks = Vec::new();
+    for run in check_runs
+        .get("check_runs")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let status = run
+            .get("status")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let conclusion = run.get("conclusion").and_then(Value::as_str);
+        checks.push(GitCommitCheck {
+            name: json_text(run, "name").unwrap_or_default(),
+            status: normalize_github_check(status, conclusion).to_string(),
+            raw_status: conclusion.unwrap_or(status).to_string(),
+            description: run
+                .get("output")
+                .and_then(|output| output.get("title"))
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            url: json_text(run, "html_url"),
+            started_at: json_text(run, "started_at"),
+            completed_at: json_text(run, "completed_at"),
+        });
+    }
+
+    // Commit statuses are the older API some integrations still use
+    match statuses {
+        Ok(statuses) => {
+            for status in statuses
+                .get("statuses")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let state = status
+                    .get("state")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                checks.push(GitCommitCheck {
+                    name: json_text(status, "context").unwrap_or_default(),
+                    status: match state {
+                        "success" => "pass",
+                        "pending" => "pending",
+                        _ => "fail",
+                    }
+                    .to_string(),
+                    raw_status: state.to_string(),
+                    description: json_text(status, "description"),
+                    url: json_text(status, "target_url"),
+                    started_at: json_text(status, "created_at"),
+                    completed_at: json_text(status, "updated_at"),
+                });
+            }
+        }
+        Err(err) => warn!("[git] Failed to read GitHub commit statuses: {}", err),
+    }
+    Ok(checks)
+}
+
+async fn fetch_gitlab_checks(
+    client: &Client,
+    remote: &RemoteUrl,
+    commit: &str,
+    token: Option<&str>,
+) -> Result<Vec<GitCommitCheck>> {
+    let project = urlencoding::encode(&format!("{}/{}", remote.owner, remote.repo)).into_owned();
+    let url = format!(
+        "https://{}/api/v4/projects/{}/repository/commits/{}/statuses?per_page=100",
+        remote.host, project, commit
+    );
+    let mut request = client.get(url);
+    if let Some(token) = token {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow!("GitLab API returned {}", status));
+    }
+    let statuses = response.json::<Value>().await?;
+
+    Ok(statuses
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|job| {
+            let state = job
+                .get("status")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            let allow_failure = job.get("allow_failure").and_then(Value::as_bool) == Some(true);
+            let normalized = match state {
+                "success" => "pass",
+                "skipped" | "manual" => "skipped",
+                "failed" if allow_failure => "neutral",
+                "failed" | "canceled" => "fail",
+                _ => "pending",
+            };
+            GitCommitCheck {
+                name: json_text(job, "name").unwrap_or_default(),
+                status: normalized.to_string(),
+                raw_status: state.to_string(),
+                description: json_text(job, "description"),
+                url: json_text(job, "target_url"),
+                started_at: json_text(job, "started_at"),
+                completed_at: json_text(job, "finished_at"),
+            }
+        })
+        .collect())
+}
+
+/// CI checks and commit statuses the hosting provider reports for a commit (HEAD by
+/// default). Supports GitHub (including Enterprise) and GitLab.
+#[tauri::command]
+pub async fn get_commit_checks(
+    directory: String,
+    hash: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitCommitChecks, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let reference = hash
+        .as_deref()
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .unwrap_or("HEAD");
+    let spec = format!("{}^{{commit}}", reference);
+    let commit = run_git(&["rev-parse", "--verify", &spec], &root)
+        .await
+        .map_err(|e| e.to_string())?
+        .trim()
+        .to_string();
+
+    let remote_name = resolve_default_remote(&root)
+        .await
+        .map_err(|e| e.to_string())?;
+    let remote_url = run_git(&["remote", "get-url", &remote_name], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    let remote = git_remote::parse_remote_url(&remote_url).map_err(|e| e.to_string())?;
+    let provider = serde_json::to_value(&remote.provider)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let remote_prefix = format!("refs/remotes/{}/", remote_name);
+    let containing = run_git(
+        &[
+            "for-each-ref",
+            "--format=%(refname)",
+            "--contains",
+            &commit,
+            &remote_prefix,
+        ],
+        &root,
+    )
+    .await
+    .unwrap_or_default();
+    let pushed = containing.lines().any(|line| !line.trim().is_empty());
+
+    let mut result = GitCommitChecks {
+        commit: commit.clone(),
+        remote: remote_name,
+        provider,
+        pushed,
+        state: "none".to_string(),
+        checks: Vec::new(),
+    };
+    if !pushed {
+        return Ok(result);
+    }
+
+    let client = Client::builder()
+        .timeout(CHECKS_REQUEST_TIMEOUT)
+        .user_agent(CHECKS_USER_AGENT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let token = resolve_forge_token(&root, &remote).await;
+    let checks = match remote.provider {
+        git_remote::RemoteProvider::Github => {
+            fetch_github_checks(&client, &remote, &commit, token.as_deref()).await
+        }
+        git_remote::RemoteProvider::Gitlab => {
+            fetch_gitlab_checks(&client, &remote, &commit, token.as_deref()).await
+        }
+        _ => Err(anyhow!("Checks are not supported for {}", remote.host)),
+    }
+    .map_err(|e| e.to_string())?;
+
+    let has = |status: &str| checks.iter().any(|check| check.status == status);
+    result.state = if has("fail") {
+        "fail"
+    } else if has("pending") {
+        "pending"
+    } else if has("pass") {
+        "pass"
+    } else {
+        "none"
+    }
+    .to_string();
+    result.checks = checks;
+    Ok(result)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranchRelationship {
+    pub a: String,
+    pub b: String,
+    pub merge_base: Option<String>,
+    pub merge_base_date: Option<String>,
+    pub a_contains_b: bool,
+    pub b_contains_a: bool,
+    /// Commits reachable from `a` but not from `b`
+    pub ahead: u32,
+    /// Commits reachable from `b` but not from `a`
+    pub behind: u32,
+    /// How `a` relates to `b`: same, ancestor, descendant, diverged or unrelated
+    pub relationship: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchTrackingRepair {
+    pub branch: String,
+    /// `unset` when no upstream is configured, `gone` when it points at a deleted ref
+    pub issue: String,
+    pub previous_upstream: Option<String>,
+    pub proposed_upstream: Option<String>,
+    pub applied: bool,
+}
+
+/// Find local branches with a missing or deleted upstream and point them at the remote
+/// branch of the same name. With `dry_run`, only reports the proposed fixes.
+#[tauri::command]
+pub async fn repair_branch_tracking(
+    directory: String,
+    branch: Option<String>,
+    dry_run: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<BranchTrackingRepair>, GitError> {
+    let dry_run = dry_run.unwrap_or(false);
+    let root = if dry_run {
+        validate_git_path(&directory, state.settings()).await
+    } else {
+        validate_writable_git_path(&directory, state.settings()).await
+    }
+    .map_err(|e| e.to_string())?;
+
+    let branches = run_git(
+        &[
+            "for-each-ref",
+            "--format=%(refname:short)%00%(upstream:short)%00%(upstream:track)%00%(upstream:remotename)",
+            "refs/heads",
+        ],
+        &root,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let remote_branches: HashSet<String> = run_git(
+        &["for-each-ref", "--format=%(refname:short)", "refs/remotes"],
+        &root,
+    )
+    .await
+    .map_err(|e| e.to_string())?
+    .lines()
+    .map(|line| line.trim().to_string())
+    .filter(|line| !line.is_empty() && !line.ends_with("/HEAD"))
+    .collect();
+    let remotes: Vec<String> = run_git(&["remote"], &root)
+        .await
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let only = branch.as_deref().map(str::trim).filter(|b| !b.is_empty());
+    let mut repairs = Vec::new();
+
+    for line in branches.lines() {
+        let parts: Vec<&str> = line.split('\0').collect();
+        if parts.len() < 4 {
+            continue;
+        }
+        let (name, upstream, track, configured_remote) = (parts[0], parts[1], parts[2], parts[3]);
+        if only.is_some_and(|b| b != name) {
+            continue;
+        }
+
+        let issue = if upstream.is_empty() {
+            "unset"
+        } else if track.contains("gone") {
+            "gone"
+        } else {
+            continue;
+        };
+
+        // Prefer the remote the branch was configured with, then origin, then any remote
+        let mut candidate_remotes: Vec<&str> = Vec::new();
+        if !configured_remote.is_empty() {
+            candidate_remotes.push(configured_remote);
+        }
+        candidate_remotes.push("origin");
+        candidate_remotes.extend(remotes.iter().map(|r| r.as_str()));
+        let proposed_upstream = candidate_remotes
+            .iter()
+            .map(|remote| format!("{}/{}", remote, name))
+            .find(|candidate| candidate != upstream && remote_branches.contains(candidate));
+
+        let mut applied = false;
+        if let Some(target) = proposed_upstream.as_deref().filter(|_| !dry_run) {
+            let flag = format!("--set-upstream-to={}", target);
+            match run_git(&["branch", &flag, name], &root).await {
+                Ok(_) => {
+                    applied = true;
+                    info!("[git] Repaired upstream of {} -> {}", name, target);
+                }
+                Err(err) => warn!("[git] Failed to set upstream of {}: {}", name, err),
+            }
+        }
+
+        repairs.push(BranchTrackingRepair {
+            branch: name.to_string(),
+            issue: issue.to_string(),
+            previous_upstream: Some(upstream.to_string()).filter(|u| !u.is_empty()),
+            proposed_upstream,
+            applied,
+        });
+    }
+
+    Ok(repairs)
+}
+
+#[tauri::command]
+pub async fn get_branch_relationship(
+    directory: String,
+    a: String,
+    b: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBranchRelationship, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let resolve = |rev: String| {
+        let root = root.clone();
+        async move {
+            let spec = format!("{}^{{commit}}", rev.trim());
+            run_git(&["rev-parse", "--verify", "--quiet", &spec], &root)
+                .await
+                .map_err(|_| format!("Unknown revision: {}", rev.trim()))
+        }
+    };
+    let a_hash = resolve(a.clone()).await?;
+    let b_hash = resolve(b.clone()).await?;
+
+    // merge-base exits 1 when the histories share no commit
+    let merge_base = run_git_with_allowed_exit(&["merge-base", &a_hash, &b_hash], &root, &[1])
+        .await
+        .map_err(|e| e.to_string())?;
+    let merge_base = Some(merge_base.trim().to_string()).filter(|m| !m.is_empty());
+
+    let merge_base_date = match merge_base.as_deref() {
+        Some(hash) => run_git(&["show", "-s", "--format=%cI", hash], &root)
+            .await
+            .ok(),
+        None => None,
+    };
+
+    let counts_range = format!("{}...{}", a_hash, b_hash);
+    let counts = run_git(
+        &["rev-list", "--left-right", "--count", &counts_range],
+        &root,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut parts = counts.split_whitespace();
+    let ahead: u32 = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+    let behind: u32 = parts.next().and_then(|v| v.parse().ok()).unwrap_or(0);
+
+    let a_contains_b = merge_base.as_deref() == Some(b_hash.as_str());
+    let b_contains_a = merge_base.as_deref() == Some(a_hash.as_str());
+    let relationship = if a_hash == b_hash {
+        "same"
+    } else if merge_base.is_none() {
+        "unrelated"
+    } else if b_contains_a {
+        "ancestor"
+    } else if a_contains_b {
+        "descendant"
+    } else {
+        "diverged"
+    };
+
+    Ok(GitBranchRelationship {
+        a,
+        b,
+        merge_base,
+        merge_base_date,
+        a_contains_b,
+        b_contains_a,
+        ahead,
+        behind,
+        relationship: relationship.to_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitFastForwardCheck {
+    pub from: String,
+    pub to: String,
+    pub can_fast_forward: bool,
+    pub up_to_date: bool,
+    pub commits: u32,
+}
+
+/// Whether updating `from` to `to` is a clean fast-forward (`from` is an ancestor of `to`)
+/// or would need a merge commit
+#[tauri::command]
+pub async fn can_fast_forward(
+    directory: String,
+    from: String,
+    to: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitFastForwardCheck, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut hashes = Vec::with_capacity(2);
+    for rev in [&from, &to] {
+        let spec = format!("{}^{{commit}}", rev.trim());
+        let hash = run_git(&["rev-parse", "--verify", "--quiet", &spec], &root)
+            .await
+            .map_err(|_| format!("Unknown revision: {}", rev.trim()))?;
+        hashes.push(hash.trim().to_string());
+    }
+    let (from_hash, to_hash) = (&hashes[0], &hashes[1]);
+
+    let is_ancestor = |ancestor: String, descendant: String| {
+        let root = root.clone();
+        async move {
+            run_git_with_status(
+                &["merge-base", "--is-ancestor", &ancestor, &descendant],
+                &root,
+            )
+            .await
+            .map(|(success, _, _)| success)
+            .map_err(|e| e.to_string())
+        }
+    };
+
+    let up_to_date = is_ancestor(to_hash.clone(), from_hash.clone()).await?;
+    let can_fast_forward = !up_to_date && is_ancestor(from_hash.clone(), to_hash.clone()).await?;
+
+    let commits = if can_fast_forward {
+        let range = format!("{}..{}", from_hash, to_hash);
+        run_git(&["rev-list", "--count", &range], &root)
+            .await
+            .ok()
+            .and_then(|count| count.trim().parse().ok())
+            .unwrap_or(0)
+    } else {
+        0
+    };
+
+    Ok(GitFastForwardCheck {
+        from,
+        to,
+        can_fast_forward,
+        up_to_date,
+        commits,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranchDivergence {
+    pub branch: String,
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+/// Max concurrent `rev-list` processes when git is too old for the `ahead-behind` atom
+const DIVERGENCE_FALLBACK_CONCURRENCY: usize = 8;
+
+/// Ahead/behind counts of every local branch relative to `base_ref`. Uses the batched
+/// `%(ahead-behind:<base>)` format atom (git 2.41+), falling back to one `rev-list` per branch.
+#[tauri::command]
+pub async fn get_branch_divergence(
+    directory: String,
+    base_ref: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<GitBranchDivergence>, GitError> {
+    use futures_util::StreamExt;
+
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let base_ref = base_ref.trim().to_string();
+    let spec = format!("{}^{{commit}}", base_ref);
+    run_git(&["rev-parse", "--verify", "--quiet", &spec], &root)
+        .await
+        .map_err(|_| format!("Unknown revision: {}", base_ref))?;
+
+    let format = format!("--format=%(refname:short)%00%(ahead-behind:{})", base_ref);
+    if let Ok(output) = run_git(&["for-each-ref", &format, "refs/heads"], &root).await {
+        return Ok(output
+            .lines()
+            .filter_map(|line| {
+                let (branch, counts) = line.split_once('\0')?;
+                let (ahead, behind) = counts.split_once(' ')?;
+                Some(GitBranchDivergence {
+                    branch: branch.to_string(),
+                    ahead: ahead.trim().parse().ok()?,
+                    behind: behind.trim().parse().ok()?,
+                })
+            })
+            .collect());
+    }
+
+    let branches = run_git(
+        &["for-each-ref", "--format=%(refname:short)", "refs/heads"],
+        &root,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+    let branches: Vec<String> = branches
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect();
+
+    let results = futures_util::stream::iter(branches)
+        .map(|branch| {
+            let root = root.clone();
+            let range = format!("{}...{}", branch, base_ref);
+            async move {
+                let counts = run_git(&["rev-list", "--left-right", "--count", &range], &root)
+                    .await
+                    .ok()?;
+                let mut parts = counts.split_whitespace();
+                Some(GitBranchDivergence {
+                    ahead: parts.next()?.parse().ok()?,
+                    behind: parts.next()?.parse().ok()?,
+                    branch,
+                })
+            }
+        })
+        .buffered(DIVERGENCE_FALLBACK_CONCURRENCY)
+        .filter_map(|entry| async move { entry })
+        .collect::<Vec<_>>()
+        .await;
+
+    Ok(results)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitTag {
+    pub name: String,
+    pub target: String,
+    pub annotated: bool,
+    pub signed: bool,
+    pub tagger: Option<String>,
+    pub date: Option<String>,
+    pub subject: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitTagVerification {
+    pub tag: String,
+    pub signed: bool,
+    pub valid: bool,
+    pub signature_type: Option<String>,
+    pub signer: Option<String>,
+    pub key: Option<String>,
+    pub output: String,
+}
+
+static SSH_GOOD_SIGNATURE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"Good "git" signature for (.+?) with (\S+) key (\S+)"#).unwrap());
+
+fn signature_type_of(object: &str) -> Option<&'static str> {
+    if object.contains("-----BEGIN PGP SIGNATURE-----") {
+        Some("gpg")
+    } else if object.contains("-----BEGIN SSH SIGNATURE-----") {
+        Some("ssh")
+    } else if object.contains("-----BEGIN SIGNED MESSAGE-----") {
+        Some("x509")
+    } else {
+        None
+    }
+}
+
+#[tauri::command]
+pub async fn list_git_tags(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<GitTag>, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let output = run_git(
+        &[
+            "for-each-ref",
+            "--sort=-creatordate",
+            "--format=%(refname:short)%00%(objecttype)%00%(*objectname)%00%(objectname)%00%(taggername)%00%(creatordate:iso-strict)%00%(contents:subject)%00%(contents:signature)%00",
+            "refs/tags",
+        ],
+        &root,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    // Every field ends in NUL, so records can't be split on newlines: signatures span
+    // several lines. Each record after the first starts with the newline git adds.
+    let fields: Vec<&str> = output.split('\0').collect();
+    let mut tags = Vec::new();
+    for parts in fields.chunks_exact(8) {
+        let annotated = parts[1] == "tag";
+        tags.push(GitTag {
+            name: parts[0].trim_start_matches('\n').to_string(),
+            target: if annotated { parts[2] } else { parts[3] }.to_string(),
+            annotated,
+            signed: annotated && !parts[7].trim().is_empty(),
+            tagger: Some(parts[4].to_string()).filter(|s| !s.is_empty()),
+            date: Some(parts[5].to_string()).filter(|s| !s.is_empty()),
+            subject: Some(parts[6].to_string()).filter(|s| annotated && !s.is_empty()),
+        });
+    }
+
+    Ok(tags)
+}
+
+/// First public key loaded in ssh-agent, as printed by `ssh-add -L`
+async fn first_agent_public_key() -> Option<String> {
+    let output = Command::new("ssh-add")
+        .arg("-L")
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// Create an annotated tag. With `sign`, the tag is signed using the repository's
+/// signing configuration, or the SSH key of `profile_id` when an identity is given.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn create_git_tag(
+    directory: String,
+    name: String,
+    message: Option<String>,
+    target: Option<String>,
+    sign: Option<bool>,
+    profile_id: Option<String>,
+    explain: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Explainable<GitTag>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let name = name.trim().to_string();
+    let tag_ref = format!("refs/tags/{}", name);
+    run_git(&["check-ref-format", &tag_ref], &root)
+        .await
+        .map_err(|_| format!("Invalid tag name: {}", name))?;
+
+    let message = message
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| name.clone());
+    let target = target
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+
+    let mut args: Vec<String> = Vec::new();
+    let sign = sign.unwrap_or(false);
+    if sign {
+        if let Some(id) = profile_id.as_deref().filter(|id| !id.trim().is_empty()) {
+            let profile = identity_store::find_profile(id)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Profile {} not found", id))?;
+            let signing_key = match profile.ssh_key.as_deref().filter(|k| !k.trim().is_empty()) {
+                Some(key) => expand_tilde_path(key).display().to_string(),
+                // git signs with an agent key given literally as `key::<public key>`
+                None if profile.ssh_agent => first_agent_public_key()
+                    .await
+                    .map(|key| format!("key::{}", key))
+                    .ok_or_else(|| "ssh-agent has no keys loaded".to_string())?,
+                None => {
+                    return Err(
+                        format!("Identity {} has no SSH key to sign with", profile.name).into(),
+                    )
+                }
+            };
+            args.extend([
+                "-c".to_string(),
+                "gpg.format=ssh".to_string(),
+                "-c".to_string(),
+                format!("user.signingkey={}", signing_key),
+                "-c".to_string(),
+                format!("user.name={}", profile.user_name),
+                "-c".to_string(),
+                format!("user.email={}", profile.user_email),
+            ]);
+        }
+    }
+
+    args.push("tag".to_string());
+    args.push(if sign { "-s" } else { "-a" }.to_string());
+    args.push(name.clone());
+    args.push("-m".to_string());
+    args.push(message);
+    args.push(target);
+
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(vec![GitInvocation::local(&args, &root)]));
+    }
+
+    let arg_refs: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    run_git(&arg_refs, &root).await.map_err(|e| e.to_string())?;
+
+    let tags = list_git_tags(directory, state).await?;
+    tags.into_iter()
+        .find(|tag| tag.name == name)
+        .map(Explainable::Done)
+        .ok_or_else(|| format!("Tag {} was not created", name).into())
+}
+
+#[tauri::command]
+pub async fn verify_git_tag(
+    directory: String,
+    tag: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitTagVerification, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let tag = tag.trim().to_string();
+    let tag_ref = format!("refs/tags/{}", tag);
+    let object_type = run_git(&["cat-file", "-t", &tag_ref], &root)
+        .await
+        .map_err(|_| format!("Tag {} not found", tag))?;
+
+    let unsigned = |output: &str| GitTagVerification {
+        tag: tag.clone(),
+        signed: false,
+        valid: false,
+        signature_type: None,
+        signer: None,
+        key: None,
+        output: output.to_string(),
+    };
+
+    if object_type.trim() != "tag" {
+        return Ok(unsigned("Lightweight tags cannot be signed"));
+    }
+
+    let object = run_git(&["cat-file", "tag", &tag_ref], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    let Some(signature_type) = signature_type_of(&object) else {
+        return Ok(unsigned("Tag is not signed"));
+    };
+
+    let (valid, stdout, stderr) = run_git_with_status(&["verify-tag", "--raw", &tag_ref], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    let output = [stdout.as_str(), stderr.as_str()]
+        .iter()
+        .filter(|s| !s.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut signer = None;
+    let mut key = None;
+    for line in output.lines() {
+        let line = line.trim();
+        if let Some(rest) = line
+            .strip_prefix("[GNUPG:] GOODSIG ")
+            .or_else(|| line.strip_prefix("[GNUPG:] BADSIG "))
+            .or_else(|| line.strip_prefix("[GNUPG:] EXPKEYSIG "))
+        {
+            if let Some((key_id, uid)) = rest.split_once(' ') {
+                key.get_or_insert_with(|| key_id.to_string());
+                signer = Some(uid.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("[GNUPG:] VALIDSIG ") {
+            key = rest.split_whitespace().next().map(|fpr| fpr.to_string());
+        } else if let Some(rest) = line.strip_prefix("[GNUPG:] ERRSIG ") {
+            key.get_or_insert_with(|| rest.split_whitespace().next().unwrap_or("").to_string());
+        } else if let Some(caps) = SSH_GOOD_SIGNATURE_REGEX.captures(line) {
+            signer = Some(caps[1].to_string());
+            key = Some(format!("{} {}", &caps[2], &caps[3]));
+        }
+    }
+
+    Ok(GitTagVerification {
+        tag,
+        signed: true,
+        valid,
+        signature_type: Some(signature_type.to_string()),
+        signer,
+        key: key.filter(|k| !k.is_empty()),
+        output,
+    })
+}
+
+/// Commits listed in a generated changelog section
+const RELEASE_CHANGELOG_MAX_COMMITS: usize = 500;
+/// Changelog headings for conventional commit types; anything else lands under "Other"
+const RELEASE_CHANGELOG_SECTIONS: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+];
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseOptions {
+    /// Annotated tag carrying the notes as its message (default); `false` for lightweight
+    pub annotated_tag: Option<bool>,
+    /// Generate a changelog section (default `true`)
+    pub changelog: Option<bool>,
+    /// Commits to summarize, e.g. `v1.2.0..HEAD`; defaults to commits since the previous tag
+    pub changelog_range: Option<String>,
+    /// Text placed above the generated changelog
+    pub notes: Option<String>,
+    pub target: Option<String>,
+    /// Push the tag (default `true`)
+    pub push: Option<bool>,
+    pub remote: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseResult {
+    pub tag: String,
+    pub commit: String,
+    pub annotated: bool,
+    pub previous_tag: Option<String>,
+    pub range: Option<String>,
+    pub commits: usize,
+    pub notes: String,
+    pub pushed: bool,
+    pub remote: Option<String>,
+    /// Set when the tag was created but pushing it failed
+    pub push_error: Option<GitError>,
+}
+
+/// Markdown changelog for the commits in `range`, grouped by conventional commit type
+async fn compose_release_changelog(root: &Path, range: &str) -> Result<(String, usize)> {
+    let max_count = format!("--max-count={}", RELEASE_CHANGELOG_MAX_COMMITS);
+    let output = run_git(
+        &[
+            "log",
+            "--no-merges",
+            &max_count,
+            "--format=%h%x00%s",
+            range,
+            "--",
+        ],
+        root,
+    )
+    .await?;
+
+    let mut sections: Vec<(&str, Vec<String>)> = RELEASE_CHANGELOG_SECTIONS
+        .iter()
+        .map(|(_, title)| (*title, Vec::new()))
+        .chain(std::iter::once(("Other changes", Vec::new())))
+        .collect();
+    let mut count = 0;
+    for line in output.lines() {
+        let Some((hash, subject)) = line.split_once('\0') else {
+            continue;
+        };
+        count += 1;
+        let (index, text) = match COMMIT_HEADER_REGEX.captures(subject) {
+            Some(caps) => {
+                let index = RELEASE_CHANGELOG_SECTIONS
+                    .iter()
+                    .position(|(kind, _)| caps[1].eq_ignore_ascii_case(kind));
+                let text = match caps.get(2).map(|m| m.as_str()).filter(|s| !s.is_empty()) {
+                    Some(scope) => format!("**{}:** {}", scope, &caps[4]),
+                    None => caps[4].to_string(),
+                };
+                (index, text)
+            }
+            None => (None, subject.to_string()),
+        };
+        let index = index.unwrap_or(RELEASE_CHANGELOG_SECTIONS.len());
+        sections[index].1.push(format!("- {} ({})", text, hash));
+    }
+
+    let changelog = sections
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .map(|(title, entries)| format!("### {}\n\n{}", title, entries.join("\n")))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    Ok((changelog, count))
+}
+
+/// Tag a release: compose notes (optional text plus a changelog of commits since the
+/// previous tag), create the tag with them as its message and push it. The notes are
+/// returned so they can be pasted into a hosting provider's release form.
+#[tauri::command]
+pub async fn create_release(
+    directory: String,
+    version: String,
+    options: Option<ReleaseOptions>,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, DesktopRuntime>,
+) -> Result<ReleaseResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let options = options.unwrap_or_default();
+
+    let tag = version.trim().to_string();
+    let tag_ref = format!("refs/tags/{}", tag);
+    if tag.is_empty()
+        || tag.starts_with('-')
+        || run_git(&["check-ref-format", &tag_ref], &root)
+            .await
+            .is_err()
+    {
+        return Err(format!("Invalid tag name: {}", tag).into());
+    }
+    if run_git(&["rev-parse", "--verify", "--quiet", &tag_ref], &root)
+        .await
+        .is_ok()
+    {
+        return Err(format!("Tag {} already exists", tag).into());
+    }
+    let target = options
+        .target
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or("HEAD");
+    let commit = run_git(
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", target),
+        ],
+        &root,
+    )
+    .await
+    .map_err(|_| format!("Unknown revision: {}", target))?
+    .trim()
+    .to_string();
+
+    let previous_tag = run_git(&["describe", "--tags", "--abbrev=0", &commit], &root)
+        .await
+        .ok()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    let range = if options.changelog.unwrap_or(true) {
+        match options.changelog_range.as_deref().map(str::trim) {
+            Some(range) if range.starts_with('-') => {
+                return Err(format!("Invalid range: {}", range).into())
+            }
+            Some(range) if !range.is_empty() => Some(range.to_string()),
+            _ => Some(match &previous_tag {
+                Some(previous) => format!("{}..{}", previous, commit),
+                None => commit.clone(),
+            }),
+        }
+    } else {
+        None
+    };
+    let (changelog, commits) = match &range {
+        Some(range) => compose_release_changelog(&root, range).await?,
+        None => (String::new(), 0),
+    };
+
+    let date = chrono::Local::now().format("%Y-%m-%d");
+    let mut notes = format!("## {} ({})", tag, date);
+    if let Some(text) = options
+        .notes
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        notes.push_str("\n\n");
+        notes.push_str(text);
+    }
+    if !changelog.is_empty() {
+        notes.push_str("\n\n");
+        notes.push_str(&changelog);
+    }
+    notes.push('\n');
+
+    let annotated = options.annotated_tag.unwrap_or(true);
+    if annotated {
+        // Markdown headings start with `#`, which the default cleanup would strip
+        let args = ["tag", "-a", "--cleanup=verbatim", &tag, "-F", "-", &commit];
+        run_git_with_env_input(&args, &root, &[], notes.as_bytes()).await?;
+    } else {
+        run_git(&["tag", &tag, &commit], &root).await?;
+    }
+    info!("[git] Created release tag {} at {}", tag, commit);
+
+    let mut pushed = false;
+    let mut push_error = None;
+    let mut remote = None;
+    if options.push.unwrap_or(true) {
+        let name = match options
+            .remote
+            .as_deref()
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+        {
+            Some(name) => name.to_string(),
+            None => resolve_default_remote(&root).await?,
+        };
+        let urls = resolve_remote_urls(&root, &[&name], true).await;
+        let operation_id = resolve_operation_id(operation_id);
+        let args = ["push", "--progress", &name, &tag_ref];
+        match run_git_with_progress(&args, &root, &urls, &app, &operation_id, "push").await {
+            Ok(_) => pushed = true,
+            Err(err) => {
+                warn!(
+                    "[git] Pushing release tag {} to {} failed: {}",
+                    tag, name, err
+                );
+                push_error = Some(err.into());
+            }
+        }
+        remote = Some(name);
+    }
+
+    Ok(ReleaseResult {
+        tag,
+        commit,
+        annotated,
+        previous_tag,
+        range,
+        commits,
+        notes,
+        pushed,
+        remote,
+        push_error,
+    })
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCheckoutResult {
+    /// Local branch now checked out
+    pub branch: String,
+    /// A local branch was created to track a remote branch
+    pub created: bool,
+    pub tracking: Option<String>,
+}
+
+/// Local branch name and remote-tracking ref for `remotes/<remote>/<name>`, or a bare
+/// `<remote>/<name>` when no local branch has that name
+async fn split_remote_branch(root: &Path, branch: &str) -> Option<(String, String)> {
+    let explicit = branch
+        .strip_prefix("refs/remotes/")
+        .or_else(|| branch.strip_prefix("remotes/"));
+    let remote_branch = match explicit {
+        Some(rest) => rest,
+        None => {
+            let local_ref = format!("refs/heads/{}", branch);
+            if run_git(&["show-ref", "--verify", "--quiet", &local_ref], root)
+                .await
+                .is_ok()
+            {
+                return None;
+            }
+            branch
+        }
+    };
+    let remote_ref = format!("refs/remotes/{}", remote_branch);
+    run_git(&["show-ref", "--verify", "--quiet", &remote_ref], root)
+        .await
+        .ok()?;
+    let remotes = run_git(&["remote"], root).await.ok()?;
+    remotes
+        .lines()
+        .map(str::trim)
+        .filter(|remote| !remote.is_empty())
+        .find_map(|remote| remote_branch.strip_prefix(remote)?.strip_prefix('/'))
+        .filter(|name| !name.is_empty() && *name != "HEAD")
+        .map(|name| (name.to_string(), remote_branch.to_string()))
+}
+
+/// Switch branches. A remote branch (`remotes/origin/foo`) checks out a local `foo`
+/// tracking it, created when missing, rather than a detached HEAD.
+#[tauri::command]
+pub async fn checkout_branch(
+    directory: String,
+    branch: String,
+    explain: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Explainable<GitCheckoutResult>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let branch = branch.trim().to_string();
+    if branch.is_empty() || branch.starts_with('-') {
+        return Err(format!("Invalid branch: {}", branch).into());
+    }
+
+    let (local, args, created) = match split_remote_branch(&root, &branch).await {
+        Some((local, remote_branch)) => {
+            let local_ref = format!("refs/heads/{}", local);
+            if run_git(&["show-ref", "--verify", "--quiet", &local_ref], &root)
+                .await
+                .is_ok()
+            {
+                // Already tracked locally: switch to it rather than reset it
+                (local.clone(), vec!["checkout".to_string(), local], false)
+            } else {
+                let args = ["switch", "-c", &local, "--track", &remote_branch]
+                    .map(str::to_string)
+                    .to_vec();
+                (local, args, true)
+            }
+        }
+        None => (branch.clone(), vec!["checkout".to_string(), branch], false),
+    };
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(vec![GitInvocation::local(&args, &root)]));
+    }
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    run_git(&arg_refs, &root).await.map_err(|e| e.to_string())?;
+
+    let upstream = format!("{}@{{upstream}}", local);
+    let tracking = run_git(&["rev-parse", "--abbrev-ref", &upstream], &root)
+        .await
+        .ok()
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    Ok(Explainable::Done(GitCheckoutResult {
+        branch: local,
+        created,
+        tracking,
+    }))
+}
+
+#[tauri::command]
+pub async fn create_branch(
+    directory: String,
+    name: String,
+    start_point: Option<String>,
+    explain: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Explainable<()>, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let start = start_point.unwrap_or_else(|| "HEAD".to_string());
+    let args = ["checkout", "-b", &name, &start];
+    if explain.unwrap_or(false) {
+        return Ok(Explainable::plan(vec![GitInvocation::local(&args, &root)]));
+    }
+    run_git(&args, &root).await.map_err(|e| e.to_string())?;
+    Ok(Explainable::Done(()))
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBranchUpstream {
+    pub branch: String,
+    pub upstream: Option<String>,
+}
+
+async fn resolve_local_branch(root: &Path, branch: Option<String>) -> Result<String> {
+    let branch = match branch
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+    {
+        Some(branch) => branch,
+        None => get_current_branch_name(root).await?,
+    };
+    if branch == "HEAD" {
+        return Err(anyhow!("HEAD is detached; specify a branch"));
+    }
+    run_git(
+        &[
+            "show-ref",
+            "--verify",
+            "--quiet",
+            &format!("refs/heads/{}", branch),
+        ],
+        root,
+    )
+    .await
+    .map_err(|_| anyhow!("Local branch {} not found", branch))?;
+    Ok(branch)
+}
+
+async fn read_branch_upstream(root: &Path, branch: &str) -> Option<String> {
+    let spec = format!("{}@{{upstream}}", branch);
+    run_git(
+        &["rev-parse", "--abbrev-ref", "--symbolic-full-name", &spec],
+        root,
+    )
+    .await
+    .ok()
+    .map(|s| s.trim().to_string())
+    .filter(|s| !s.is_empty())
+}
+
+/// Point `branch` (current branch by default) at `upstream`, e.g. `origin/main`
+#[tauri::command]
+pub async fn set_branch_upstream(
+    directory: String,
+    branch: Option<String>,
+    upstream: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBranchUpstream, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let branch = resolve_local_branch(&root, branch)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let upstream = upstream.trim();
+    if upstream.is_empty() {
+        return Err("Upstream is required".into());
+    }
+
+    let flag = format!("--set-upstream-to={}", upstream);
+    run_git(&["branch", &flag, &branch], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(GitBranchUpstream {
+        upstream: read_branch_upstream(&root, &branch).await,
+        branch,
+    })
+}
+
+#[tauri::command]
+pub async fn unset_branch_upstream(
+    directory: String,
+    branch: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBranchUpstream, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let branch = resolve_local_branch(&root, branch)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if read_branch_upstream(&root, &branch).await.is_some()
+        || run_git(
+            &["config", "--get", &format!("branch.{}.merge", branch)],
+            &root,
+        )
+        .await
+        .is_ok()
+    {
+        run_git(&["branch", "--unset-upstream", &branch], &root)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(GitBranchUpstream {
+        branch,
+        upstream: None,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitSquashMergeResult {
+    pub branch: String,
+    pub success: bool,
+    pub message: String,
+    pub commits: Vec<String>,
+    pub staged_files: Vec<String>,
+    pub conflicts: Vec<String>,
+    /// Conflicts rerere resolved by replaying a recorded resolution
+    pub reused_resolutions: Vec<String>,
+}
+
+fn build_squash_message(branch: &str, into: &str, subjects: &[String]) -> String {
+    let mut message = match subjects {
+        [only] => only.clone(),
+        _ => format!("Squash merge branch '{}' into {}", branch, into),
+    };
+    if subjects.len() > 1 {
+        message.push_str("\n\n");
+        for subject in subjects {
+            message.push_str(&format!("* {}\n", subject));
+        }
+        message = message.trim_end().to_string();
+    }
+    message
+}
+
+/// `git merge --squash`: the combined changes are left staged (nothing is committed)
+/// and SQUASH_MSG is replaced with a summary of the squashed commits.
+#[tauri::command]
+pub async fn git_squash_merge(
+    directory: String,
+    branch: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitSquashMergeResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let branch = branch.trim().to_string();
+    if branch.is_empty() {
+        return Err("Branch is required".into());
+    }
+    run_git(
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", branch),
+        ],
+        &root,
+    )
+    .await
+    .map_err(|_| format!("Branch {} not found", branch))?;
+
+    let dirty = run_git(&["diff", "--cached", "--name-only"], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !dirty.trim().is_empty() {
+        return Err("Commit or unstage your staged changes before a squash merge".into());
+    }
+
+    let into = get_current_branch_name(&root)
+        .await
+        .unwrap_or_else(|_| "HEAD".to_string());
+    let range = format!("HEAD..{}", branch);
+    let subjects: Vec<String> = run_git(&["log", "--reverse", "--format=%s", &range], &root)
+        .await
+        .map_err(|e| e.to_string())?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.to_string())
+        .collect();
+    if subjects.is_empty() {
+        return Err(format!("{} has no commits that are not already in {}", branch, into).into());
+    }
+
+    let (success, stdout, stderr) = run_git_with_status(&["merge", "--squash", &branch], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let conflicts = list_conflicted_files(&root).await;
+    if !success && conflicts.is_empty() {
+        return Err(GitError::from(if stderr.is_empty() {
+            stdout
+        } else {
+            stderr
+        }));
+    }
+    let reused_resolutions = parse_rerere_reused(&format!("{}\n{}", stdout, stderr));
+
+    let staged_files: Vec<String> = run_git(&["diff", "--cached", "--name-only"], &root)
+        .await
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect();
+
+    let message = build_squash_message(&branch, &into, &subjects);
+    if let Ok(squash_msg) = run_git(&["rev-parse", "--git-path", "SQUASH_MSG"], &root).await {
+        let squash_msg_path = root.join(squash_msg.trim());
+        if let Err(err) = fs::write(&squash_msg_path, format!("{}\n", message)).await {
+            warn!("Failed to write {:?}: {}", squash_msg_path, err);
+        }
+    }
+
+    Ok(GitSquashMergeResult {
+        branch,
+        success: conflicts.is_empty(),
+        message,
+        commits: subjects,
+        staged_files,
+        conflicts,
+        reused_resolutions,
+    })
+}
+
+/// Paths with unresolved merge conflicts
+async fn list_conflicted_files(root: &Path) -> Vec<String> {
+    run_git(&["diff", "--name-only", "--diff-filter=U"], root)
+        .await
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
+static RERERE_REUSED_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^(?:Resolved|Staged) '(.+)' using previous resolution\.").unwrap()
+});
+
+/// Paths rerere resolved by replaying a recorded resolution, from merge/am output
+fn parse_rerere_reused(output: &str) -> Vec<String> {
+    RERERE_REUSED_REGEX
+        .captures_iter(output)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RerereResolution {
+    /// Conflict hash naming the `rr-cache` entry
+    pub id: String,
+    /// A resolution was recorded; otherwise only the conflict was seen so far
+    pub resolved: bool,
+    pub recorded_at: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RerereStatus {
+    pub enabled: bool,
+    /// Replayed resolutions are staged automatically (`rerere.autoUpdate`)
+    pub auto_update: bool,
+    pub resolutions: Vec<RerereResolution>,
+}
+
+async fn read_rerere_status(root: &Path) -> Result<RerereStatus> {
+    let config_bool = |key: &'static str| async move {
+        run_git(&["config", "--get", "--bool", key], root)
+            .await
+            .ok()
+            .map(|v| v.trim() == "true")
+    };
+    let cache_dir = run_git(
+        &[
+            "rev-parse",
+            "--path-format=absolute",
+            "--git-path",
+            "rr-cache",
+        ],
+        root,
+    )
+    .await
+    .map(|path| PathBuf::from(path.trim()))?;
+
+    let mut resolutions = Vec::new();
+    if let Ok(mut entries) = fs::read_dir(&cache_dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let recorded = fs::metadata(path.join("postimage")).await.ok();
+            let modified = match &recorded {
+                Some(metadata) => metadata.modified().ok(),
+                None => entry.metadata().await.ok().and_then(|m| m.modified().ok()),
+            };
+            resolutions.push(RerereResolution {
+                id: entry.file_name().to_string_lossy().to_string(),
+                resolved: recorded.is_some(),
+                recorded_at: modified
+                    .map(|time| chrono::DateTime::<chrono::Utc>::from(time).to_rfc3339()),
+            });
+        }
+    }
+    resolutions.sort_by(|a, b| b.recorded_at.cmp(&a.recorded_at));
+
+    // Unset `rerere.enabled` means enabled once an rr-cache directory exists
+    let enabled = match config_bool("rerere.enabled").await {
+        Some(enabled) => enabled,
+        None => cache_dir.is_dir(),
+    };
+    Ok(RerereStatus {
+        enabled,
+        auto_update: config_bool("rerere.autoUpdate").await.unwrap_or(false),
+        resolutions,
+    })
+}
+
+/// Whether rerere (reuse recorded resolution) is on, and the resolutions it has recorded
+#[tauri::command]
+pub async fn get_rerere_status(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RerereStatus, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    read_rerere_status(&root).await.map_err(GitError::from)
+}
+
+/// Turn rerere on or off for the repository; `auto_update` also stages replayed
+/// resolutions. Recorded resolutions are kept when disabling.
+#[tauri::command]
+pub async fn set_rerere_enabled(
+    directory: String,
+    enabled: bool,
+    auto_update: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RerereStatus, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let value = if enabled { "true" } else { "false" };
+    run_git(&["config", "--local", "rerere.enabled", value], &root).await?;
+    if let Some(auto_update) = auto_update {
+        let value = if auto_update { "true" } else { "false" };
+        run_git(&["config", "--local", "rerere.autoUpdate", value], &root).await?;
+    }
+    read_rerere_status(&root).await.map_err(GitError::from)
+}
+
+/// Drop the recorded resolution for a conflicted path (`git rerere forget`), e.g. after
+/// a wrong resolution was recorded. Only works while `path` has a conflict.
+#[tauri::command]
+pub async fn forget_rerere_resolution(
+    directory: String,
+    path: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RerereStatus, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("A path is required".into());
+    }
+    run_git(&["rerere", "forget", "--", path], &root).await?;
+    read_rerere_status(&root).await.map_err(GitError::from)
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitPatchExport {
+    /// Written patch files, when an output directory was given
+    pub files: Vec<String>,
+    /// Patch contents, when no output directory was given
+    pub patches: Vec<String>,
+}
+
+/// `git format-patch` for `range` (`base..tip`, or a single commit). Patches are written
+/// to `output_dir` when given, otherwise returned as strings.
+#[tauri::command]
+pub async fn export_patches(
+    directory: String,
+    range: String,
+    output_dir: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitPatchExport, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let range = range.trim();
+    if range.is_empty() || range.starts_with('-') {
+        return Err("A commit range is required".into());
+    }
+
+    let output_dir = output_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(expand_tilde_path);
+    let keep_files = output_dir.is_some();
+    let target = match output_dir {
+        Some(dir) if !dir.is_absolute() => return Err("Output directory must be absolute".into()),
+        Some(dir) => dir,
+        None => std::env::temp_dir().join(format!("openchamber-patches-{}", uuid::Uuid::new_v4())),
+    };
+    fs::create_dir_all(&target)
+        .await
+        .map_err(|e| e.to_string())?;
+    let target_str = target.to_string_lossy().to_string();
+
+    let mut args = vec!["format-patch", "--output-directory", &target_str];
+    // A lone commit means "this commit", not format-patch's "everything since"
+    if !range.contains("..") {
+        args.push("-1");
+    }
+    args.push(range);
+    let output = run_git(&args, &root).await;
+
+    let files: Vec<String> = output
+        .as_deref()
+        .unwrap_or_default()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let path = PathBuf::from(line.trim());
+            if path.is_absolute() {
+                path
+            } else {
+                root.join(path)
+            }
+            .to_string_lossy()
+            .to_string()
+        })
+        .collect();
+    if keep_files {
+        output.map_err(|e| e.to_string())?;
+        return Ok(GitPatchExport {
+            files,
+            patches: Vec::new(),
+        });
+    }
+
+    let mut patches = Vec::new();
+    for file in &files {
+        if let Ok(patch) = fs::read_to_string(file).await {
+            patches.push(patch);
+        }
+    }
+    let _ = fs::remove_dir_all(&target).await;
+    output.map_err(|e| e.to_string())?;
+    Ok(GitPatchExport {
+        files: Vec::new(),
+        patches,
+    })
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitApplyPatchResult {
+    pub success: bool,
+    /// Commits created by `git am`
+    pub commits: Vec<String>,
+    pub conflicts: Vec<String>,
+    /// A `git am` session was left open to resolve conflicts (`git am --continue`/`--abort`)
+    pub in_progress: bool,
+    pub message: String,
+    /// Conflicts rerere resolved by replaying a recorded resolution
+    pub reused_resolutions: Vec<String>,
+}
+
+/// Apply a patch given as text. With `use_am` mailbox patches (from `export_patches`)
+/// become commits; otherwise the diff is applied to the working tree and index. Both use
+/// a three-way merge so conflicts are left as markers instead of failing outright.
+#[tauri::command]
+pub async fn apply_patch(
+    directory: String,
+    patch: String,
+    use_am: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitApplyPatchResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    if patch.trim().is_empty() {
+        return Err("Patch is empty".into());
+    }
+
+    let patch_path =
+        std::env::temp_dir().join(format!("openchamber-apply-{}.patch", uuid::Uuid::new_v4()));
+    fs::write(&patch_path, &patch)
+        .await
+        .map_err(|e| e.to_string())?;
+    let patch_str = patch_path.to_string_lossy().to_string();
+
+    let use_am = use_am.unwrap_or(false);
+    let previous_head = get_head_hash(&root).await.ok();
+    let args = if use_am {
+        vec!["am", "--3way", "--", &patch_str]
+    } else {
+        vec!["apply", "--3way", "--", &patch_str]
+    };
+    let result = run_git_with_status(&args, &root).await;
+    let _ = fs::remove_file(&patch_path).await;
+    let (success, stdout, stderr) = result.map_err(|e| e.to_string())?;
+
+    let conflicts = list_conflicted_files(&root).await;
+    let in_progress = use_am
+        && match run_git(&["rev-parse", "--git-path", "rebase-apply/applying"], &root).await {
+            Ok(path) => root.join(path.trim()).exists(),
+            Err(_) => false,
+        };
+    if !success && conflicts.is_empty() && !in_progress {
+        return Err(GitError::from(if stderr.is_empty() {
+            stdout
+        } else {
+            stderr
+        }));
+    }
+    let reused_resolutions = parse_rerere_reused(&format!("{}\n{}", stdout, stderr));
+
+    let commits = if use_am {
+        let range = previous_head
+            .map(|previous| format!("{}..HEAD", previous))
+            .unwrap_or_else(|| "HEAD".to_string());
+        run_git(&["log", "--reverse", "--format=%H", &range], &root)
+            .await
+            .unwrap_or_default()
+            .lines()
+            .map(|line| line.to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(GitApplyPatchResult {
+        success,
+        commits,
+        conflicts,
+        in_progress,
+        message: if success { stdout } else { stderr },
+        reused_resolutions,
+    })
+}
+
+/// `git log` format read by `parse_git_log`; pair with `--numstat`
+const GIT_LOG_FORMAT: &str =
+    "--format=%x1e%H%x00%P%x00%an%x00%ae%x00%ad%x00%D%x00%s%x00%b%x00%N%x1f";
+
+/// Parse `git log --numstat` output produced with `GIT_LOG_FORMAT`
+fn parse_git_log(output: &str) -> Vec<GitLogEntry> {
+    let mut entries = Vec::new();
+
+    for record in output.split('\x1e') {
+        let Some((header, stats)) = record.split_once('\x1f') else {
+            continue;
+        };
+        let parts: Vec<&str> = header.split('\0').collect();
+        if parts.len() < 8 || parts[0].trim().is_empty() {
+            continue;
+        }
+
+        let mut files_changed = 0;
+        let mut insertions = 0;
+        let mut deletions = 0;
+        for line in stats.lines() {
+            let mut cols = line.splitn(3, '\t');
+            let (Some(added), Some(removed), Some(_)) = (cols.next(), cols.next(), cols.next())
+            else {
+                continue;
+            };
+            // Binary files report "-" for both counts
+            files_changed += 1;
+            insertions += added.parse::<i32>().unwrap_or(0);
+            deletions += removed.parse::<i32>().unwrap_or(0);
+        }
+
+        entries.push(GitLogEntry {
+            hash: parts[0].trim().to_string(),
+            parents: parts[1].split_whitespace().map(str::to_string).collect(),
+            author_name: parts[2].to_string(),
+            author_email: parts[3].to_string(),
+            date: parts[4].to_string(),
+            refs: parts[5].to_string(),
+            message: parts[6].to_string(),
+            body: parts[7].trim().to_string(),
+            note: parts
+                .get(8)
+                .map(|note| note.trim().to_string())
+                .filter(|note| !note.is_empty()),
+            files_changed,
+            insertions,
+            deletions,
+        });
+    }
+
+    entries
+}
+
+/// Run `git log` with `GIT_LOG_FORMAT` plus `args`, fetching one extra commit to tell
+/// whether another page follows
+async fn run_git_log_page(
+    root: &Path,
+    args: &[String],
+    max: usize,
+    operation_id: Option<&str>,
+) -> Result<GitLogResponse> {
+    let mut full_args: Vec<String> = vec![
+        "log".into(),
+        format!("--max-count={}", max + 1),
+        "--date=iso".into(),
+        GIT_LOG_FORMAT.into(),
+        "--numstat".into(),
+    ];
+    full_args.extend(args.iter().cloned());
+    let arg_refs: Vec<&str> = full_args.iter().map(|a| a.as_str()).collect();
+    let output = run_git_cancellable(&arg_refs, root, operation_id).await?;
+
+    let mut entries = parse_git_log(&output);
+    let has_more = entries.len() > max;
+    entries.truncate(max);
+
+    Ok(GitLogResponse {
+        latest: entries.first().cloned(),
+        total: entries.len() as i32,
+        next_cursor: None,
+        has_more,
+        all: entries,
+    })
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GitLogFilters {
+    /// Matched against author name and email, ignoring case
+    pub author: Option<String>,
+    /// Any date git understands, e.g. `2024-01-31` or `2 weeks ago`
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Matched against the commit message, ignoring case
+    pub grep: Option<String>,
+    /// Only commits touching any of these paths
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+impl GitLogFilters {
+    /// `git log` options for the filters, without the paths
+    fn to_args(&self) -> Vec<String> {
+        let non_empty = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let mut args = Vec::new();
+        let author = non_empty(&self.author);
+        let grep = non_empty(&self.grep);
+        if author.is_some() || grep.is_some() {
+            args.push("--regexp-ignore-case".to_string());
+        }
+        if let Some(author) = author {
+            args.push(format!("--author={}", author));
+        }
+        if let Some(grep) = grep {
+            args.push(format!("--grep={}", grep));
+        }
+        if let Some(since) = non_empty(&self.since) {
+            args.push(format!("--since={}", since));
+        }
+        if let Some(until) = non_empty(&self.until) {
+            args.push(format!("--until={}", until));
+        }
+        args
+    }
+}
+
+/// Paginate with `skip`, or with `before_hash` (the previous page's `next_cursor`), which
+/// continues the same walk where that page ended as long as its tip hasn't moved. With
+/// `operation_id`, a slow page can be stopped with `cancel_git_operation`. `filters`
+/// narrow the history in git itself; `file` is kept as a single-path shorthand.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_git_log(
+    directory: String,
+    max_count: Option<i32>,
+    from: Option<String>,
+    to: Option<String>,
+    file: Option<String>,
+    skip: Option<i32>,
+    before_hash: Option<String>,
+    operation_id: Option<String>,
+    filters: Option<GitLogFilters>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitLogResponse, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let max = max_count.filter(|c| *c > 0).unwrap_or(50) as usize;
+    let cursor = before_hash
+        .as_deref()
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .map(|cursor| parse_log_cursor(cursor).ok_or("Invalid cursor"))
+        .transpose()?;
+    if let Some(to) = to.as_deref().filter(|t| t.starts_with('-')) {
+        return Err(format!("Invalid ref: {}", to).into());
+    }
+
+    let filters = filters.unwrap_or_default();
+    let mut range = filters.to_args();
+    match (&from, &to) {
+        (Some(f), Some(t)) => range.push(format!("{}..{}", f, t)),
+        (Some(f), None) => range.push(format!("{}..HEAD", f)),
+        (None, Some(t)) => range.push(t.clone()),
+        (None, None) => {}
+    }
+    let paths: Vec<String> = file
+        .into_iter()
+        .chain(filters.paths)
+        .map(|path| path.trim().to_string())
+        .filter(|path| !path.is_empty())
+        .collect();
+    if !paths.is_empty() {
+        range.push("--".into());
+        range.extend(paths);
+    }
+
+    // The walk always starts from the tip so merge parents keep their place; a cursor
+    // records that tip and how far the previous pages got
+    let tip_rev = format!("{}^{{commit}}", to.as_deref().unwrap_or("HEAD"));
+    let tip = run_git(&["rev-parse", "--verify", "--quiet", &tip_rev], &root)
+        .await
+        .ok();
+    let skip = match cursor {
+        Some((cursor_tip, offset)) if tip.as_deref() == Some(cursor_tip) => offset,
+        Some(_) => return Err("History changed since this page was loaded".into()),
+        None => skip.filter(|s| *s > 0).unwrap_or(0) as usize,
+    };
+
+    let mut args = Vec::with_capacity(range.len() + 1);
+    if skip > 0 {
+        args.push(format!("--skip={}", skip));
+    }
+    args.extend(range);
+
+    let mut page = run_git_log_page(&root, &args, max, operation_id.as_deref()).await?;
+    if page.has_more {
+        page.next_cursor = tip.map(|tip| format!("{}:{}", tip, skip + page.all.len()));
+    }
+    Ok(page)
+}
+
+/// `<tip>:<offset>` as produced for `GitLogResponse::next_cursor`
+fn parse_log_cursor(cursor: &str) -> Option<(&str, usize)> {
+    let (tip, offset) = cursor.split_once(':')?;
+    if tip.is_empty() || !tip.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some((tip, offset.parse().ok()?))
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitSearchOptions {
+    /// Matched against author name and email
+    pub author: Option<String>,
+    /// Only commits touching this path
+    pub path: Option<String>,
+    /// Search diffs instead of messages: `string` (`-S`) or `regex` (`-G`)
+    pub pickaxe: Option<String>,
+    pub max_count: Option<i32>,
+    pub skip: Option<i32>,
+}
+
+/// Search history by commit message (case-insensitive, literal text) or, with `pickaxe`,
+/// by content added or removed. An empty query with an author or path filter lists their
+/// commits.
+#[tauri::command]
+pub async fn search_git_commits(
+    directory: String,
+    query: String,
+    options: Option<GitCommitSearchOptions>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitLogResponse, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let options = options.unwrap_or_default();
+    let non_empty = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let author = non_empty(&options.author);
+    let path = non_empty(&options.path);
+    let query = query.trim();
+
+    // Queries are typed text, so `fix(ui)` or `[WIP]` must not be parsed as a regex;
+    // this covers `--grep` and `--author`, while `-G` keeps its own regex
+    let mut args: Vec<String> = vec!["--regexp-ignore-case".into(), "--fixed-strings".into()];
+    match options.pickaxe.as_deref().map(str::trim) {
+        None | Some("") => {
+            if !query.is_empty() {
+                args.push(format!("--grep={}", query));
+            }
+        }
+        Some(mode) => {
+            if query.is_empty() {
+                return Err("A query is required for content search".into());
+            }
+            match mode {
+                "string" => args.push(format!("-S{}", query)),
+                "regex" => args.push(format!("-G{}", query)),
+                other => return Err(format!("Unsupported pickaxe mode: {}", other).into()),
+            }
+        }
+    }
+    if query.is_empty() && author.is_none() && path.is_none() {
+        return Err("A query, author or path is required".into());
+    }
+    if let Some(author) = author {
+        args.push(format!("--author={}", author));
+    }
+    if let Some(skip) = options.skip.filter(|s| *s > 0) {
+        args.push(format!("--skip={}", skip));
+    }
+    args.push("--".into());
+    if let Some(path) = path {
+        args.push(path);
+    }
+
+    let max = options.max_count.filter(|c| *c > 0).unwrap_or(50) as usize;
+    run_git_log_page(&root, &args, max, None)
+        .await
+        .map_err(GitError::from)
+}
+
+const COMMIT_GRAPH_DEFAULT_COUNT: usize = 200;
+const COMMIT_GRAPH_MAX_COUNT: usize = 2000;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphCommit {
+    pub hash: String,
+    pub parents: Vec<String>,
+    /// Decorations such as `HEAD -> main`, `origin/main` or `tag: v1.0`
+    pub refs: Vec<String>,
+    pub author_name: String,
+    pub date: String,
+    pub subject: String,
+    /// Column of this commit's node
+    pub lane: usize,
+    /// Column each parent continues in on the following rows, in `parents` order
+    pub parent_lanes: Vec<usize>,
+    /// Other columns whose line ends at this node (branches merging into it)
+    pub merged_lanes: Vec<usize>,
+    /// Columns whose line passes this row without touching the node
+    pub pass_through_lanes: Vec<usize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitGraph {
+    pub commits: Vec<GraphCommit>,
+    /// Widest row, for sizing the graph column
+    pub lane_count: usize,
+    pub has_more: bool,
+}
+
+/// First unused column, reusing gaps left by finished branches
+fn claim_lane(lanes: &mut Vec<Option<String>>, hash: &str) -> usize {
+    match lanes.iter().position(Option::is_none) {
+        Some(index) => {
+            lanes[index] = Some(hash.to_string());
+            index
+        }
+        None => {
+            lanes.push(Some(hash.to_string()));
+            lanes.len() - 1
+        }
+    }
+}
+
+/// Assign columns in topological order. Each open lane waits for the next commit on its
+/// line; a commit takes the leftmost lane waiting for it, and its first parent continues
+/// in that lane while further parents join an existing lane or open a new one.
+fn assign_graph_lanes(commits: &mut [GraphCommit]) -> usize {
+    let mut lanes: Vec<Option<String>> = Vec::new();
+    let mut lane_count = 0;
+
+    for commit in commits.iter_mut() {
+        let waiting: Vec<usize> = lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, expected)| expected.as_deref() == Some(commit.hash.as_str()))
+            .map(|(index, _)| index)
+            .collect();
+        let lane = match waiting.first() {
+            Some(&lane) => lane,
+            None => claim_lane(&mut lanes, &commit.hash),
+        };
+        commit.lane = lane;
+        commit.merged_lanes = waiting.iter().skip(1).copied().collect();
+        for &merged in &commit.merged_lanes {
+            lanes[merged] = None;
+        }
+        commit.pass_through_lanes = lanes
+            .iter()
+            .enumerate()
+            .filter(|(index, expected)| *index != lane && expected.is_some())
+            .map(|(index, _)| index)
+            .collect();
+
+        lanes[lane] = None;
+        commit.parent_lanes = Vec::with_capacity(commit.parents.len());
+        for (position, parent) in commit.parents.iter().enumerate() {
+            let existing = lanes
+                .iter()
+                .position(|expected| expected.as_deref() == Some(parent));
+            let parent_lane = match existing {
+                Some(existing) => existing,
+                None if position == 0 => {
+                    lanes[lane] = Some(parent.clone());
+                    lane
+                }
+                None => claim_lane(&mut lanes, parent),
+            };
+            commit.parent_lanes.push(parent_lane);
+        }
+
+        lane_count = lane_count.max(lanes.len());
+        while lanes.last().is_some_and(Option::is_none) {
+            lanes.pop();
+        }
+    }
+    lane_count
+}
+
+/// Commits reachable from `refs` (all branches, remote branches, tags and HEAD by
+/// default) in topological order, with columns assigned for drawing a branch graph
+#[tauri::command]
+pub async fn get_commit_graph(
+    directory: String,
+    max_count: Option<usize>,
+    refs: Option<Vec<String>>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<CommitGraph, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let max = max_count
+        .unwrap_or(COMMIT_GRAPH_DEFAULT_COUNT)
+        .clamp(1, COMMIT_GRAPH_MAX_COUNT);
+
+    let refs: Vec<String> = refs
+        .unwrap_or_default()
+        .iter()
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .collect();
+    if let Some(invalid) = refs.iter().find(|r| r.starts_with('-')) {
+        return Err(format!("Invalid ref: {}", invalid).into());
+    }
+
+    let max_arg = format!("--max-count={}", max + 1);
+    let mut args = vec![
+        "log",
+        "--topo-order",
+        "--date=iso-strict",
+        &max_arg,
+        "--format=%H%x00%P%x00%D%x00%an%x00%ad%x00%s",
+    ];
+    if refs.is_empty() {
+        args.extend(["--branches", "--remotes", "--tags", "HEAD"]);
+    } else {
+        args.extend(refs.iter().map(String::as_str));
+    }
+    args.push("--");
+    // An unborn branch has nothing to draw
+    let output = match run_git(&args, &root).await {
+        Ok(output) => output,
+        Err(_) if get_head_hash(&root).await.is_err() => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut commits: Vec<GraphCommit> = output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.splitn(6, '\0').collect();
+            if parts.len() < 6 {
+                return None;
+            }
+            Some(GraphCommit {
+                hash: parts[0].to_string(),
+                parents: parts[1].split_whitespace().map(str::to_string).collect(),
+                refs: parts[2]
+                    .split(", ")
+                    .map(str::trim)
+                    .filter(|r| !r.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+                author_name: parts[3].to_string(),
+                date: parts[4].to_string(),
+                subject: parts[5].to_string(),
+                lane: 0,
+                parent_lanes: Vec::new(),
+                merged_lanes: Vec::new(),
+                pass_through_lanes: Vec::new(),
+            })
+        })
+        .collect();
+    let has_more = commits.len() > max;
+    commits.truncate(max);
+    let lane_count = assign_graph_lanes(&mut commits);
+
+    Ok(CommitGraph {
+        commits,
+        lane_count,
+        has_more,
+    })
+}
+
+/// Files reported by `get_repo_statistics`, highest churn first
+const REPO_STATS_CHURN_LIMIT: usize = 50;
+/// Activity is bucketed per day up to this span, per month beyond it
+const REPO_STATS_DAILY_SPAN_DAYS: i64 = 92;
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorStatistics {
+    pub name: String,
+    pub email: String,
+    pub commits: u32,
+    pub insertions: u64,
+    pub deletions: u64,
+    pub first_commit: String,
+    pub last_commit: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChurn {
+    pub path: String,
+    pub commits: u32,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityBucket {
+    /// `YYYY-MM-DD` for daily buckets, `YYYY-MM` for monthly ones
+    pub period: String,
+    pub commits: u32,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoStatistics {
+    pub since: Option<String>,
+    pub total_commits: u32,
+    pub authors: Vec<AuthorStatistics>,
+    pub churn: Vec<FileChurn>,
+    /// `day` or `month`
+    pub granularity: String,
+    pub activity: Vec<ActivityBucket>,
+}
+
+/// Contributor, churn and activity statistics for the current branch, from one
+/// `git log --numstat` pass. `since` is anything `git log --since` accepts, e.g.
+/// `3 months ago` or `2024-01-01`. Authors are grouped by mailmapped email.
+#[tauri::command]
+pub async fn get_repo_statistics(
+    directory: String,
+    since: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RepoStatistics, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let since = since
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string);
+
+    let mut args = vec![
+        "log".to_string(),
+        "--numstat".into(),
+        "--no-renames".into(),
+        "--date=short".into(),
+        "--format=%x1e%aN%x00%aE%x00%ad".into(),
+    ];
+    if let Some(since) = &since {
+        args.push(format!("--since={}", since));
+    }
+    args.push("HEAD".into());
+    args.push("--".into());
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    // An unborn branch has no history yet
+    let output = match run_git(&args, &root).await {
+        Ok(output) => output,
+        Err(_) if get_head_hash(&root).await.is_err() => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut total_commits = 0;
+    let mut authors: HashMap<String, AuthorStatistics> = HashMap::new();
+    let mut files: HashMap<String, FileChurn> = HashMap::new();
+    let mut days: BTreeMap<String, ActivityBucket> = BTreeMap::new();
+
+    for record in output.split('\x1e').filter(|r| !r.trim().is_empty()) {
+        let (header, numstat) = record.split_once('\n').unwrap_or((record, ""));
+        let mut fields = header.split('\0');
+        let (Some(name), Some(email), Some(date)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        total_commits += 1;
+
+        let (mut insertions, mut deletions) = (0, 0);
+        for line in numstat.lines() {
+            let mut parts = line.splitn(3, '\t');
+            let (Some(added), Some(removed), Some(path)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            // Binary files report `-` for both counts
+            let added: u64 = added.parse().unwrap_or(0);
+            let removed: u64 = removed.parse().unwrap_or(0);
+            insertions += added;
+            deletions += removed;
+            let file = files.entry(path.to_string()).or_insert_with(|| FileChurn {
+                path: path.to_string(),
+                ..Default::default()
+            });
+            file.commits += 1;
+            file.insertions += added;
+            file.deletions += removed;
+        }
+
+        let author = authors
+            .entry(email.to_lowercase())
+            .or_insert_with(|| AuthorStatistics {
+                name: name.to_string(),
+                email: email.to_string(),
+                first_commit: date.to_string(),
+                last_commit: date.to_string(),
+                ..Default::default()
+            });
+        author.commits += 1;
+        author.insertions += insertions;
+        author.deletions += deletions;
+        // Log order is newest first
+        author.first_commit = date.to_string();
+
+        let day = days
+            .entry(date.to_string())
+            .or_insert_with(|| ActivityBucket {
+                period: date.to_string(),
+                ..Default::default()
+            });
+        day.commits += 1;
+        day.insertions += insertions;
+        day.deletions += deletions;
+    }
+
+    let span_days = match (days.keys().next(), days.keys().next_back()) {
+        (Some(first), Some(last)) => {
+            let parse = |d: &str| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok();
+            match (parse(first), parse(last)) {
+                (Some(first), Some(last)) => (last - first).num_days(),
+                _ => 0,
+            }
+        }
+        _ => 0,
+    };
+    let (granularity, activity) = if span_days <= REPO_STATS_DAILY_SPAN_DAYS {
+        ("day", days.into_values().collect())
+    } else {
+        let mut months: BTreeMap<String, ActivityBucket> = BTreeMap::new();
+        for (date, bucket) in days {
+            let period = date.get(..7).unwrap_or(&date).to_string();
+            let month = months
+                .entry(period.clone())
+                .or_insert_with(|| ActivityBucket {
+                    period,
+                    ..Default::default()
+                });
+            month.commits += bucket.commits;
+            month.insertions += bucket.insertions;
+            month.deletions += bucket.deletions;
+        }
+        ("month", months.into_values().collect())
+    };
+
+    let mut authors: Vec<AuthorStatistics> = authors.into_values().collect();
+    authors.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+    let mut churn: Vec<FileChurn> = files.into_values().collect();
+    churn.sort_by(|a, b| {
+        (b.insertions + b.deletions)
+            .cmp(&(a.insertions + a.deletions))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    churn.truncate(REPO_STATS_CHURN_LIMIT);
+
+    Ok(RepoStatistics {
+        since,
+        total_commits,
+        authors,
+        churn,
+        granularity: granularity.to_string(),
+        activity,
+    })
+}
+
+/// Notes read by `get_commit_notes` when no commits are given
+const COMMIT_NOTES_LIST_LIMIT: usize = 200;
+const COMMIT_NOTES_CONCURRENCY: usize = 8;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitCommitNote {
+    pub commit: String,
+    pub note: String,
+}
+
+/// Resolve a notes ref name such as `commits` or `refs/notes/review`; `commits` by default
+fn resolve_notes_ref(notes_ref: Option<&str>) -> Result<String, String> {
+    let name = notes_ref
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .unwrap_or("commits");
+    if name.starts_with('-') || name.contains(char::is_whitespace) || name.contains("..") {
+        return Err(format!("Invalid notes ref: {}", name));
+    }
+    Ok(if name.starts_with("refs/") {
+        name.to_string()
+    } else {
+        format!("refs/notes/{}", name)
+    })
+}
+
+/// Notes attached to `hashes`, or to any commit when `hashes` is omitted. Commits without
+/// a note are left out.
+#[tauri::command]
+pub async fn get_commit_notes(
+    directory: String,
+    hashes: Option<Vec<String>>,
+    notes_ref: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<GitCommitNote>, GitError> {
+    use futures_util::StreamExt;
+
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let notes_ref = resolve_notes_ref(notes_ref.as_deref())?;
+
+    // `notes list` prints "<note blob> <commit>" and fails only on a bad ref
+    let listing = run_git(&["notes", "--ref", &notes_ref, "list"], &root)
+        .await
+        .unwrap_or_default();
+    let mut noted: Vec<(String, String)> = listing
+        .lines()
+        .filter_map(|line| {
+            let (blob, commit) = line.split_once(' ')?;
+            Some((commit.trim().to_string(), blob.trim().to_string()))
+        })
+        .collect();
+
+    match hashes {
+        Some(hashes) => {
+            let mut wanted = Vec::new();
+            for hash in hashes.iter().map(|h| h.trim()).filter(|h| !h.is_empty()) {
+                let spec = format!("{}^{{commit}}", hash);
+                let full = run_git(&["rev-parse", "--verify", "--quiet", &spec], &root)
+                    .await
+                    .map_err(|_| format!("Unknown revision: {}", hash))?;
+                wanted.push(full.trim().to_string());
+            }
+            noted.retain(|(commit, _)| wanted.contains(commit));
+            noted.sort_by_key(|(commit, _)| wanted.iter().position(|w| w == commit));
+        }
+        None => noted.truncate(COMMIT_NOTES_LIST_LIMIT),
+    }
+
+    let notes = futures_util::stream::iter(noted)
+        .map(|(commit, blob)| {
+            let root = root.clone();
+            async move {
+                let note = run_git(&["cat-file", "blob", &blob], &root).await.ok()?;
+                Some(GitCommitNote { commit, note })
+            }
+        })
+        .buffered(COMMIT_NOTES_CONCURRENCY)
+        .filter_map(|entry| async move { entry })
+        .collect::<Vec<_>>()
+        .await;
+    Ok(notes)
+}
+
+/// Attach a note to a commit, replacing an existing one unless `append` is set
+#[tauri::command]
+pub async fn add_commit_note(
+    directory: String,
+    hash: String,
+    note: String,
+    append: Option<bool>,
+    notes_ref: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitCommitNote, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let notes_ref = resolve_notes_ref(notes_ref.as_deref())?;
+    if note.trim().is_empty() {
+        return Err("Note is required".into());
+    }
+    let spec = format!("{}^{{commit}}", hash.trim());
+    let commit = run_git(&["rev-parse", "--verify", "--quiet", &spec], &root)
+        .await
+        .map_err(|_| format!("Unknown revision: {}", hash))?
+        .trim()
+        .to_string();
+
+    let mut args = vec!["notes", "--ref", &notes_ref];
+    if append.unwrap_or(false) {
+        args.push("append");
+    } else {
+        args.extend(["add", "--force"]);
+    }
+    args.extend(["-m", &note, &commit]);
+    run_git(&args, &root).await.map_err(|e| e.to_string())?;
+
+    let note = run_git(&["notes", "--ref", &notes_ref, "show", &commit], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(GitCommitNote { commit, note })
+}
+
+/// Publish a notes ref; git does not push notes along with branches
+#[tauri::command]
+pub async fn push_notes(
+    directory: String,
+    remote: Option<String>,
+    notes_ref: Option<String>,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, DesktopRuntime>,
+) -> Result<String, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let notes_ref = resolve_notes_ref(notes_ref.as_deref())?;
+    let remote = match remote.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+        Some(remote) => remote.to_string(),
+        None => resolve_default_remote(&root)
+            .await
+            .map_err(|e| e.to_string())?,
+    };
+    if remote.starts_with('-') {
+        return Err(format!("Invalid remote: {}", remote).into());
+    }
+    run_git(&["rev-parse", "--verify", "--quiet", &notes_ref], &root)
+        .await
+        .map_err(|_| format!("No notes under {}", notes_ref))?;
+
+    let refspec = format!("{}:{}", notes_ref, notes_ref);
+    let args = ["push", "--progress", &remote, &refspec];
+    let urls = resolve_remote_urls(&root, &[&remote], true).await;
+    let operation_id = resolve_operation_id(operation_id);
+    run_git_with_progress(&args, &root, &urls, &app, &operation_id, "push")
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(notes_ref)
+}
+
+/// Matches returned by `git_grep` before the result is marked truncated
+const GIT_GREP_DEFAULT_LIMIT: usize = 500;
+const GIT_GREP_SNIPPET_CHARS: usize = 400;
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GitGrepOptions {
+    /// Revision to search; `HEAD` when omitted
+    #[serde(rename = "ref")]
+    pub ref_: Option<String>,
+    /// Glob such as `src/**/*.rs` restricting which files are searched
+    pub path_glob: Option<String>,
+    pub case_insensitive: Option<bool>,
+    /// Treat the pattern as a literal string rather than a regex
+    pub fixed_strings: Option<bool>,
+    pub max_results: Option<usize>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitGrepMatch {
+    pub path: String,
+    pub line: u32,
+    pub column: u32,
+    pub snippet: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitGrepResult {
+    #[serde(rename = "ref")]
+    pub ref_: String,
+    pub commit: String,
+    pub matches: Vec<GitGrepMatch>,
+    pub truncated: bool,
+}
+
+/// Search file contents as committed at a revision, unlike `search_files` which reads
+/// the working tree. Binary files are skipped.
+#[tauri::command]
+pub async fn git_grep(
+    directory: String,
+    pattern: String,
+    options: Option<GitGrepOptions>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitGrepResult, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    if pattern.is_empty() {
+        return Err("Pattern is required".into());
+    }
+    let options = options.unwrap_or_default();
+    let rev = options
+        .ref_
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .unwrap_or("HEAD")
+        .to_string();
+    if rev.starts_with('-') {
+        return Err(format!("Invalid revision: {}", rev).into());
+    }
+    let commit = run_git(
+        &["rev-parse", "--verify", &format!("{}^{{commit}}", rev)],
+        &root,
+    )
+    .await
+    .map_err(|_| format!("Unknown revision: {}", rev))?
+    .trim()
+    .to_string();
+
+    let mut args: Vec<String> = vec![
+        "-c".into(),
+        "core.quotePath=false".into(),
+        "grep".into(),
+        "-n".into(),
+        "--column".into(),
+        "-I".into(),
+        "--null".into(),
+    ];
+    if options.case_insensitive.unwrap_or(false) {
+        args.push("-i".into());
+    }
+    if options.fixed_strings.unwrap_or(false) {
+        args.push("-F".into());
+    } else {
+        args.push("-E".into());
+    }
+    args.push("-e".into());
+    args.push(pattern);
+    args.push(commit.clone());
+    args.push("--".into());
+    if let Some(glob) = options
+        .path_glob
+        .as_deref()
+        .map(str::trim)
+        .filter(|g| !g.is_empty())
+    {
+        args.push(format!(":(glob){}", glob));
+    }
+
+    let arg_refs: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    // Exit code 1 means no matches
+    let output = run_git_with_allowed_exit(&arg_refs, &root, &[1])
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let limit = options
+        .max_results
+        .filter(|m| *m > 0)
+        .unwrap_or(GIT_GREP_DEFAULT_LIMIT);
+    let prefix = format!("{}:", commit);
+    let mut matches = Vec::new();
+    let mut truncated = false;
+    for line in output.lines() {
+        let mut fields = line.splitn(4, '\0');
+        let (Some(path), Some(line_no), Some(column), Some(text)) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        if matches.len() >= limit {
+            truncated = true;
+            break;
+        }
+        matches.push(GitGrepMatch {
+            path: path.strip_prefix(&prefix).unwrap_or(path).to_string(),
+            line: line_no.parse().unwrap_or(0),
+            column: column.parse().unwrap_or(0),
+            snippet: text
+                .trim_end()
+                .chars()
+                .take(GIT_GREP_SNIPPET_CHARS)
+                .collect(),
+        });
+    }
+
+    Ok(GitGrepResult {
+        ref_: rev,
+        commit,
+        matches,
+        truncated,
+    })
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportArchiveResult {
+    pub path: String,
+    pub format: String,
+    pub commit: String,
+    pub size: u64,
+}
+
+/// Write a snapshot of `git_ref` without the `.git` directory using `git archive`.
+/// `format` is `zip` or `tar.gz`; `subpath` limits the archive to one directory or file.
+#[tauri::command]
+pub async fn export_git_archive(
+    directory: String,
+    git_ref: String,
+    format: String,
+    output_path: String,
+    subpath: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<ExportArchiveResult, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let format = match format.trim().to_lowercase().as_str() {
+        "zip" => "zip".to_string(),
+        "tar.gz" | "tgz" => "tar.gz".to_string(),
+        other => return Err(format!("Unsupported archive format: {}", other).into()),
+    };
+    let git_ref = git_ref.trim();
+    if git_ref.is_empty() || git_ref.starts_with('-') {
+        return Err("A ref is required".into());
+    }
+    let spec = format!("{}^{{commit}}", git_ref);
+    let commit = run_git(&["rev-parse", "--verify", "--quiet", &spec], &root)
+        .await
+        .map_err(|_| format!("Unknown revision: {}", git_ref))?
+        .trim()
+        .to_string();
+
+    let output_path = expand_tilde_path(&output_path);
+    if !output_path.is_absolute() {
+        return Err("Archive path must be absolute".into());
+    }
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    let output_str = output_path.to_string_lossy().to_string();
+    let format_arg = format!("--format={}", format);
+
+    let mut args = vec!["archive", &format_arg, "--output", &output_str, &commit];
+    let subpath = subpath
+        .as_deref()
+        .map(|p| p.trim().trim_matches('/'))
+        .filter(|p| !p.is_empty());
+    if let Some(subpath) = subpath {
+        args.extend(["--", subpath]);
+    }
+    if let Err(err) = run_git(&args, &root).await {
+        let _ = fs::remove_file(&output_path).await;
+        return Err(err.into());
+    }
+
+    let size = fs::metadata(&output_path)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+    info!("[git] Archived {} to {:?}", commit, output_path);
+
+    Ok(ExportArchiveResult {
+        path: output_str,
+        format,
+        commit,
+        size,
+    })
+}
+
+/// Tasks accepted by `run_git_maintenance`, in the order git would run them
+const MAINTENANCE_TASKS: &[&str] = &[
+    "prefetch",
+    "loose-objects",
+    "incremental-repack",
+    "gc",
+    "commit-graph",
+];
+const LARGEST_BLOBS_DEFAULT: usize = 20;
+const LARGEST_BLOBS_MAX: usize = 200;
+
+/// `git count-objects -v`, with sizes converted from KiB to bytes
+#[derive(Serialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoObjectCounts {
+    pub loose_objects: u64,
+    pub loose_size_bytes: u64,
+    pub packed_objects: u64,
+    pub packs: u64,
+    pub pack_size_bytes: u64,
+    pub prune_packable: u64,
+    pub garbage: u64,
+    pub garbage_size_bytes: u64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoLargeBlob {
+    pub hash: String,
+    pub size: u64,
+    /// A path the blob was reachable at; `None` for unreachable objects
+    pub path: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoSizeStats {
+    #[serde(flatten)]
+    pub counts: RepoObjectCounts,
+    pub largest_blobs: Vec<RepoLargeBlob>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitMaintenanceResult {
+    pub tasks: Vec<String>,
+    pub before: RepoObjectCounts,
+    pub after: RepoObjectCounts,
+    pub duration_ms: u64,
+}
+
+async fn count_repo_objects(root: &Path) -> Result<RepoObjectCounts> {
+    let output = run_git(&["count-objects", "-v"], root).await?;
+    let mut counts = RepoObjectCounts::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value: u64 = value.trim().parse().unwrap_or(0);
+        match key.trim() {
+            "count" => counts.loose_objects = value,
+            "size" => counts.loose_size_bytes = value * 1024,
+            "in-pack" => counts.packed_objects = value,
+            "packs" => counts.packs = value,
+            "size-pack" => counts.pack_size_bytes = value * 1024,
+            "prune-packable" => counts.prune_packable = value,
+            "garbage" => counts.garbage = value,
+            "size-garbage" => counts.garbage_size_bytes = value * 1024,
+            _ => {}
+        }
+    }
+    Ok(counts)
+}
+
+/// Run `git maintenance run` for the given tasks (`gc`, `commit-graph`, `prefetch`,
+/// `loose-objects`, `incremental-repack`). Prefetch only updates `refs/prefetch/`, so
+/// remote-tracking branches are left alone.
+#[tauri::command]
+pub async fn run_git_maintenance(
+    directory: String,
+    tasks: Vec<String>,
+    operation_id: Option<String>,
+    app: AppHandle,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitMaintenanceResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut selected = Vec::new();
+    for task in &tasks {
+        let task = task.trim();
+        if !MAINTENANCE_TASKS.contains(&task) {
+            return Err(format!("Unsupported maintenance task: {}", task).into());
+        }
+        selected.push(task);
+    }
+    if selected.is_empty() {
+        return Err("At least one maintenance task is required".into());
+    }
+    selected.sort_by_key(|task| MAINTENANCE_TASKS.iter().position(|t| t == task));
+    selected.dedup();
+
+    let task_args: Vec<String> = selected.iter().map(|t| format!("--task={}", t)).collect();
+    let mut args = vec!["maintenance", "run"];
+    args.extend(task_args.iter().map(String::as_str));
+
+    let urls = if selected.contains(&"prefetch") {
+        let remotes = run_git(&["remote"], &root).await.unwrap_or_default();
+        let remotes: Vec<&str> = remotes.lines().map(str::trim).collect();
+        resolve_remote_urls(&root, &remotes, false).await
+    } else {
+        Vec::new()
+    };
+
+    let before = count_repo_objects(&root).await?;
+    let started = std::time::Instant::now();
+    let operation_id = resolve_operation_id(operation_id);
+    run_git_with_progress(&args, &root, &urls, &app, &operation_id, "maintenance").await?;
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let after = count_repo_objects(&root).await?;
+    info!(
+        "[git] Maintenance {:?} finished in {}ms",
+        selected, duration_ms
+    );
+
+    Ok(GitMaintenanceResult {
+        tasks: selected.into_iter().map(str::to_string).collect(),
+        before,
+        after,
+        duration_ms,
+    })
+}
+
+/// Object counts and pack size, plus the largest blobs anywhere in the object database
+/// so users can find what is bloating a slow repository.
+#[tauri::command]
+pub async fn get_repo_size_stats(
+    directory: String,
+    limit: Option<usize>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RepoSizeStats, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let limit = limit
+        .unwrap_or(LARGEST_BLOBS_DEFAULT)
+        .clamp(1, LARGEST_BLOBS_MAX);
+
+    let counts = count_repo_objects(&root).await?;
+
+    let objects = run_git(
+        &[
+            "cat-file",
+            "--batch-all-objects",
+            "--unordered",
+            "--batch-check=%(objecttype) %(objectname) %(objectsize)",
+        ],
+        &root,
+    )
+    .await?;
+    let mut blobs: Vec<(u64, &str)> = objects
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split(' ');
+            if parts.next()? != "blob" {
+                return None;
+            }
+            let hash = parts.next()?;
+            Some((parts.next()?.parse().ok()?, hash))
+        })
+        .collect();
+    blobs.sort_unstable_by_key(|(size, _)| std::cmp::Reverse(*size));
+    blobs.truncate(limit);
+
+    // Map the top blobs back to a path; `rev-list --objects` prints `<hash> <path>`
+    let mut paths: HashMap<&str, Option<String>> =
+        blobs.iter().map(|(_, hash)| (*hash, None)).collect();
+    if !blobs.is_empty() {
+        let listing = run_git(&["rev-list", "--objects", "--all"], &root)
+            .await
+            .unwrap_or_default();
+        for line in listing.lines() {
+            let Some((hash, path)) = line.split_once(' ') else {
+                continue;
+            };
+            if let Some(slot @ None) = paths.get_mut(hash) {
+                *slot = Some(path.to_string());
+            }
+        }
+    }
+
+    let largest_blobs = blobs
+        .iter()
+        .map(|(size, hash)| RepoLargeBlob {
+            hash: hash.to_string(),
+            size: *size,
+            path: paths.get(hash).cloned().flatten(),
+        })
+        .collect();
+
+    Ok(RepoSizeStats {
+        counts,
+        largest_blobs,
+    })
+}
+
+const HISTORY_EXPORT_FIELDS: &[&str] = &[
+    "hash",
+    "authorName",
+    "authorEmail",
+    "date",
+    "subject",
+    "body",
+    "filesChanged",
+    "insertions",
+    "deletions",
+    "files",
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportHistoryResult {
+    pub path: String,
+    pub format: String,
+    pub commits: usize,
+    pub fields: Vec<String>,
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parse `git log --numstat` output produced with the export format into JSON rows
+fn parse_history_export(output: &str) -> Vec<serde_json::Map<String, Value>> {
+    let mut rows = Vec::new();
+
+    for record in output.split('\x1e') {
+        let Some((header, stats)) = record.split_once('\x1f') else {
+            continue;
+        };
+        let parts: Vec<&str> = header.split('\0').collect();
+        if parts.len() < 6 || parts[0].trim().is_empty() {
+            continue;
+        }
+
+        let mut insertions = 0u64;
+        let mut deletions = 0u64;
+        let mut files = Vec::new();
+        for line in stats.lines() {
+            let mut cols = line.splitn(3, '\t');
+            let (Some(added), Some(removed), Some(path)) = (cols.next(), cols.next(), cols.next())
+            else {
+                continue;
+            };
+            // Binary files report "-" for both counts
+            insertions += added.parse::<u64>().unwrap_or(0);
+            deletions += removed.parse::<u64>().unwrap_or(0);
+            files.push(Value::String(path.to_string()));
+        }
+
+        let mut row = serde_json::Map::new();
+        row.insert("hash".into(), Value::String(parts[0].trim().to_string()));
+        row.insert("authorName".into(), Value::String(parts[1].to_string()));
+        row.insert("authorEmail".into(), Value::String(parts[2].to_string()));
+        row.insert("date".into(), Value::String(parts[3].to_string()));
+        row.insert("subject".into(), Value::String(parts[4].to_string()));
+        row.insert("body".into(), Value::String(parts[5].trim().to_string()));
+        row.insert("filesChanged".into(), Value::from(files.len()));
+        row.insert("insertions".into(), Value::from(insertions));
+        row.insert("deletions".into(), Value::from(deletions));
+        row.insert("files".into(), Value::Array(files));
+        rows.push(row);
+    }
+
+    rows
+}
+
+/// Write commit metadata for a range to a CSV or JSON Lines file
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn export_git_history(
+    directory: String,
+    range: Option<String>,
+    format: String,
+    path: String,
+    fields: Option<Vec<String>>,
+    max_count: Option<u32>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<ExportHistoryResult, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let format = format.trim().to_lowercase();
+    if format != "csv" && format != "jsonl" {
+        return Err(format!("Unsupported export format: {}", format).into());
+    }
+
+    let range = range.as_deref().map(str::trim).filter(|r| !r.is_empty());
+    if let Some(range) = range.filter(|r| r.starts_with('-')) {
+        return Err(format!("Invalid range: {}", range).into());
+    }
+
+    let output_path = expand_tilde_path(&path);
+    if !output_path.is_absolute() {
+        return Err("Export path must be absolute".into());
+    }
+
+    let fields: Vec<String> = match fields {
+        Some(requested) if !requested.is_empty() => {
+            if let Some(unknown) = requested
+                .iter()
+                .find(|f| !HISTORY_EXPORT_FIELDS.contains(&f.as_str()))
+            {
+                return Err(format!("Unknown export field: {}", unknown).into());
+            }
+            requested
+        }
+        _ => HISTORY_EXPORT_FIELDS
+            .iter()
+            .map(|f| f.to_string())
+            .collect(),
+    };
+
+    let mut args: Vec<String> = vec![
+        "-c".into(),
+        "core.quotePath=false".into(),
+        "log".into(),
+        "--no-renames".into(),
+        "--numstat".into(),
+        "--format=%x1e%H%x00%an%x00%ae%x00%aI%x00%s%x00%b%x1f".into(),
+    ];
+    if let Some(count) = max_count.filter(|c| *c > 0) {
+        args.push(format!("--max-count={}", count));
+    }
+    if let Some(range) = range {
+        args.push(range.to_string());
+    }
+    args.push("--".into());
+
+    let arg_refs: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    let output = run_git(&arg_refs, &root).await.map_err(|e| e.to_string())?;
+    let rows = parse_history_export(&output);
+
+    let mut content = String::new();
+    if format == "csv" {
+        content.push_str(&fields.join(","));
+        content.push('\n');
+        for row in &rows {
+            let line: Vec<String> = fields
+                .iter()
+                .map(|field| match row.get(field) {
+                    Some(Value::String(s)) => csv_escape(s),
+                    Some(Value::Array(items)) => csv_escape(
+                        &items
+                            .iter()
+                            .filter_map(|v| v.as_str())
+                            .collect::<Vec<_>>()
+                            .join(";"),
+                    ),
+                    Some(other) => other.to_string(),
+                    None => String::new(),
+                })
+                .collect();
+            content.push_str(&line.join(","));
+            content.push('\n');
+        }
+    } else {
+        for row in &rows {
+            let selected: serde_json::Map<String, Value> = fields
+                .iter()
+                .filter_map(|field| row.get(field).map(|v| (field.clone(), v.clone())))
+                .collect();
+            content.push_str(&Value::Object(selected).to_string());
+            content.push('\n');
+        }
+    }
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    fs::write(&output_path, content)
+        .await
+        .map_err(|e| format!("Failed to write export: {}", e))?;
+
+    info!("[git] Exported {} commits to {:?}", rows.len(), output_path);
+
+    Ok(ExportHistoryResult {
+        path: output_path.to_string_lossy().to_string(),
+        format,
+        commits: rows.len(),
+        fields,
+    })
+}
+
+static CONVENTIONAL_SUBJECT_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([a-zA-Z]+)(?:\(([^)]+)\))?!?:\s").unwrap());
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitConventionCount {
+    pub name: String,
+    pub count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentCommitSubjects {
+    pub subjects: Vec<String>,
+    pub types: Vec<CommitConventionCount>,
+    pub scopes: Vec<CommitConventionCount>,
+}
+
+fn sorted_counts(counts: HashMap<String, u32>) -> Vec<CommitConventionCount> {
+    let mut items: Vec<CommitConventionCount> = counts
+        .into_iter()
+        .map(|(name, count)| CommitConventionCount { name, count })
+        .collect();
+    items.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    items
+}
+
+/// Distinct recent subjects plus conventional-commit types/scopes, for commit autocomplete
+#[tauri::command]
+pub async fn get_recent_commit_subjects(
+    directory: String,
+    limit: Option<u32>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RecentCommitSubjects, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let limit = limit.unwrap_or(50).clamp(1, 500) as usize;
+    // Scan further back than `limit` so duplicates don't starve the list
+    let scan_count = format!("--max-count={}", limit * 4);
+    let output = run_git_with_allowed_exit(
+        &["log", "--no-merges", &scan_count, "--format=%s"],
+        &root,
+        &[128],
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut seen = HashSet::new();
+    let mut subjects = Vec::new();
+    let mut types: HashMap<String, u32> = HashMap::new();
+    let mut scopes: HashMap<String, u32> = HashMap::new();
+
+    for subject in output.lines().map(str::trim).filter(|s| !s.is_empty()) {
+        if let Some(caps) = CONVENTIONAL_SUBJECT_REGEX.captures(subject) {
+            *types.entry(caps[1].to_lowercase()).or_insert(0) += 1;
+            if let Some(scope) = caps.get(2) {
+                *scopes.entry(scope.as_str().trim().to_string()).or_insert(0) += 1;
+            }
+        }
+        if subjects.len() < limit && seen.insert(subject.to_string()) {
+            subjects.push(subject.to_string());
+        }
+    }
+
+    Ok(RecentCommitSubjects {
+        subjects,
+        types: sorted_counts(types),
+        scopes: sorted_counts(scopes),
+    })
+}
+
+#[tauri::command]
+pub async fn get_commit_files(
+    directory: String,
+    hash: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitCommitFilesResponse, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let preferences = DiffPreferences::load(state.settings()).await;
+    let rename_arg = preferences.rename_arg();
+    let show_args = |mode: &'static str| {
+        let mut args = vec!["show", mode, "--format=", rename_arg.as_str()];
+        args.extend(preferences.whitespace);
+        args.push(hash.as_str());
+        args
+    };
+
+    // Get numstat for insertions/deletions per file
+    let numstat_output = run_git(&show_args("--numstat"), &root)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut files = Vec::new();
+
+    for line in numstat_output.lines() {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() < 3 {
+            continue;
+        }
+
+        let insertions_raw = parts[0];
+        let deletions_raw = parts[1];
+        let file_path = parts[2..].join("\t");
+
+        if file_path.is_empty() {
+            continue;
+        }
+
+        // Binary files show '-' for stats
+        let is_binary = insertions_raw == "-" && deletions_raw == "-";
+        let insertions = if insertions_raw == "-" {
+            0
+        } else {
+            insertions_raw.parse().unwrap_or(0)
+        };
+        let deletions = if deletions_raw == "-" {
+            0
+        } else {
+            deletions_raw.parse().unwrap_or(0)
+        };
+
+        files.push(CommitFileEntry {
+            path: file_path,
+            insertions,
+            deletions,
+            is_binary,
+            change_type: "M".to_string(), // Default, will update below
+        });
+    }
+
+    // Get accurate change types using --name-status
+    let name_status_output = run_git(&show_args("--name-status"), &root)
+        .await
+        .unwrap_or_default();
+
+    let mut status_map: HashMap<String, String> = HashMap::new();
+    for line in name_status_output.lines() {
+        let parts: Vec<&str> = line.split('\t').collect();
+        if parts.len() >= 2 {
+            let status = parts[0].chars().next().unwrap_or('M').to_string();
+            let path = parts.last().unwrap_or(&"").to_string();
+            status_map.insert(path, status);
+        }
+    }
+
+    // Update change types
+    for file in &mut files {
+        let base_path = if file.path.contains(" => ") {
+            file.path
+                .split(" => ")
+                .last()
+                .unwrap_or(&file.path)
+                .replace(['{', '}'], "")
+        } else {
+            file.path.clone()
+        };
+
+        if let Some(status) = status_map.get(&base_path).or_else(|| status_map.get(&file.path)) {
+            file.change_type = status.clone();
+        }
+    }
+
+    Ok(GitCommitFilesResponse { files })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFileEntry {
+    pub path: String,
+    pub modified_at: Option<i64>,
+    pub status: Option<String>,
+    pub committed: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentCommitEntry {
+    pub hash: String,
+    pub subject: String,
+    pub author_name: String,
+    pub timestamp: i64,
+    pub files: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentActivitySummary {
+    pub files_changed: usize,
+    pub uncommitted_files: usize,
+    pub commits: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentFileActivity {
+    pub since: i64,
+    pub hours: u32,
+    pub files: Vec<RecentFileEntry>,
+    pub commits: Vec<RecentCommitEntry>,
+    pub summary: RecentActivitySummary,
+}
+
+/// Parse `git status --porcelain -z` file entries, ignoring the branch header
+fn parse_porcelain_files(output: &str) -> Vec<GitStatusFile> {
+    let entries: Vec<&str> = output.split('\0').collect();
+    let mut files = Vec::new();
+    let mut i = 0usize;
+
+    while i < entries.len() {
+        let entry = entries[i];
+        i += 1;
+        if entry.len() < 4 || entry.starts_with("## ") {
+            continue;
+        }
+
+        let index_status = &entry[0..1];
+        let working_status = &entry[1..2];
+        let mut file_path = &entry[3..];
+        let is_rename_or_copy =
+            matches!(index_status, "R" | "C") || matches!(working_status, "R" | "C");
+        if is_rename_or_copy && i < entries.len() && !entries[i].is_empty() {
+            file_path = entries[i];
+            i += 1;
+        }
+
+        files.push(GitStatusFile {
+            path: file_path.to_string(),
+            index: index_status.trim().to_string(),
+            working_dir: working_status.trim().to_string(),
+            assume_unchanged: false,
+            skip_worktree: false,
+        });
+    }
+
+    files
+}
+
+/// Summarize what changed in the last `hours`: uncommitted files touched since then
+/// (by mtime) plus recent commits.
+#[tauri::command]
+pub async fn get_recent_file_activity(
+    directory: String,
+    hours: Option<u32>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RecentFileActivity, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let repo_root = resolve_repo_root(&root).await;
+
+    let hours = hours.unwrap_or(24).clamp(1, 24 * 30);
+    let since = chrono::Utc::now().timestamp() - i64::from(hours) * 3600;
+
+    let since_arg = format!("--since=@{}", since);
+    let log_output = run_git(
+        &[
+            "-c",
+            "core.quotePath=false",
+            "log",
+            &since_arg,
+            "--format=%x1e%H%x00%s%x00%an%x00%ct",
+            "--name-only",
+        ],
+        &repo_root,
+    )
+    .await
+    .unwrap_or_default();
+
+    let mut commits = Vec::new();
+    for record in log_output.split('\x1e') {
+        let mut lines = record.lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+        let parts: Vec<&str> = header.split('\0').collect();
+        if parts.len() < 4 || parts[0].trim().is_empty() {
+            continue;
+        }
+
+        let files = lines
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|l| l.to_string())
+            .collect();
+
+        commits.push(RecentCommitEntry {
+            hash: parts[0].trim().to_string(),
+            subject: parts[1].to_string(),
+            author_name: parts[2].to_string(),
+            timestamp: parts[3].trim().parse().unwrap_or(0),
+            files,
+        });
+    }
+
+    let mut files: HashMap<String, RecentFileEntry> = HashMap::new();
+    // Newest commit wins so the reported time reflects the last change
+    for commit in commits.iter().rev() {
+        for path in &commit.files {
+            files.insert(
+                path.clone(),
+                RecentFileEntry {
+                    path: path.clone(),
+                    modified_at: Some(commit.timestamp),
+                    status: None,
+                    committed: true,
+                },
+            );
+        }
+    }
+
+    let status_output = run_git(&["status", "--porcelain", "-z", "-uall"], &repo_root)
+        .await
+        .unwrap_or_default();
+    let mut uncommitted_files = 0usize;
+    for entry in parse_porcelain_files(&status_output) {
+        let status = format!("{}{}", entry.index, entry.working_dir);
+        let modified_at = fs::metadata(repo_root.join(&entry.path))
+            .await
+            .ok()
+            .and_then(|meta| meta.modified().ok())
+            .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp());
+
+        // Deleted files have no mtime; keep them since the deletion is still pending
+        if matches!(modified_at, Some(ts) if ts < since) {
+            continue;
+        }
+
+        uncommitted_files += 1;
+        files.insert(
+            entry.path.clone(),
+            RecentFileEntry {
+                path: entry.path,
+                modified_at,
+                status: Some(status),
+                committed: false,
+            },
+        );
+    }
+
+    let mut files: Vec<RecentFileEntry> = files.into_values().collect();
+    files.sort_by(|a, b| {
+        b.modified_at
+            .unwrap_or(i64::MAX)
+            .cmp(&a.modified_at.unwrap_or(i64::MAX))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let summary = RecentActivitySummary {
+        files_changed: files.len(),
+        uncommitted_files,
+        commits: commits.len(),
+    };
+
+    Ok(RecentFileActivity {
+        since,
+        hours,
+        files,
+        commits,
+        summary,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitReflogEntry {
+    pub hash: String,
+    pub selector: String,
+    pub action: String,
+    pub message: String,
+    pub subject: String,
+    pub date: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreFromReflogResult {
+    pub hash: String,
+    pub mode: String,
+    pub branch: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_git_reflog(
+    directory: String,
+    max_count: Option<u32>,
+    reference: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<GitReflogEntry>, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let reference = reference
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty())
+        .unwrap_or_else(|| "HEAD".to_string());
+    let max_count = max_count.unwrap_or(100).clamp(1, 1000).to_string();
+
+    let output = run_git(
+        &[
+            "reflog",
+            "show",
+            "--date=iso-strict",
+            "--format=%H%x00%gd%x00%gs%x00%s",
+            "-n",
+            &max_count,
+            &reference,
+            "--",
+        ],
+        &root,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let entries = output
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let mut parts = line.split('\0');
+            let hash = parts.next()?.trim().to_string();
+            let dated_selector = parts.next().unwrap_or("");
+            let message = parts.next().unwrap_or("").to_string();
+            let subject = parts.next().unwrap_or("").to_string();
+            if hash.is_empty() {
+                return None;
+            }
+
+            // `%gd` renders as ref@{date} with --date, the index form is what git accepts back
+            let date = dated_selector
+                .split_once("@{")
+                .map(|(_, rest)| rest.trim_end_matches('}').to_string())
+                .unwrap_or_default();
+            let action = message
+                .split_once(':')
+                .map(|(action, _)| action.trim().to_string())
+                .unwrap_or_else(|| message.clone());
+
+            Some(GitReflogEntry {
+                hash,
+                selector: format!("{}@{{{}}}", reference, index),
+                action,
+                message,
+                subject,
+                date,
+            })
+        })
+        .collect();
+
+    Ok(entries)
+}
+
+/// Recover a commit from the reflog by creating a branch at it (default) or resetting
+/// the current branch to it.
+#[tauri::command]
+pub async fn restore_from_reflog(
+    directory: String,
+    hash: String,
+    mode: Option<String>,
+    branch_name: Option<String>,
+    reset_mode: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RestoreFromReflogResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let commit_ref = format!("{}^{{commit}}", hash.trim());
+    let resolved = run_git(&["rev-parse", "--verify", "--quiet", &commit_ref], &root)
+        .await
+        .map_err(|_| format!("Commit {} not found", hash.trim()))?;
+
+    let mode = mode.unwrap_or_else(|| "branch".to_string());
+    match mode.as_str() {
+        "branch" => {
+            let short = &resolved[..resolved.len().min(7)];
+            let name = branch_name
+                .map(|b| b.trim().to_string())
+                .filter(|b| !b.is_empty())
+                .unwrap_or_else(|| format!("recovered-{}", short));
+            run_git(&["check-ref-format", "--branch", &name], &root)
+                .await
+                .map_err(|_| format!("Invalid branch name: {}", name))?;
+            run_git(&["branch", &name, &resolved], &root)
+                .await
+                .map_err(|e| e.to_string())?;
+            info!("[git] Recovered {} as branch {}", resolved, name);
+
+            Ok(RestoreFromReflogResult {
+                hash: resolved,
+                mode,
+                branch: Some(name),
+            })
+        }
+        "reset" => {
+            let reset_flag = match reset_mode.as_deref().unwrap_or("mixed") {
+                "soft" => "--soft",
+                "mixed" => "--mixed",
+                "hard" => "--hard",
+                "keep" => "--keep",
+                other => return Err(format!("Unsupported reset mode: {}", other).into()),
+            };
+            run_git(&["reset", reset_flag, &resolved], &root)
+                .await
+                .map_err(|e| e.to_string())?;
+            let branch = get_current_branch_name(&root).await.ok();
+            info!("[git] Reset {:?} to {} ({})", branch, resolved, reset_flag);
+
+            Ok(RestoreFromReflogResult {
+                hash: resolved,
+                mode,
+                branch,
+            })
+        }
+        other => Err(format!("Unsupported restore mode: {}", other).into()),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBisectCommit {
+    pub hash: String,
+    pub subject: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitBisectStatus {
+    pub active: bool,
+    pub current: Option<GitBisectCommit>,
+    pub bad: Option<String>,
+    pub good: Vec<String>,
+    pub skipped: Vec<String>,
+    pub remaining_revisions: Option<u32>,
+    pub remaining_steps: Option<u32>,
+    pub first_bad_commit: Option<GitBisectCommit>,
+    pub message: Option<String>,
+}
+
+static BISECT_FIRST_BAD_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^# first bad commit: \[([0-9a-f]+)\] ?(.*)$").unwrap());
+
+async fn is_bisecting(root: &Path) -> bool {
+    let Ok(path) = run_git(&["rev-parse", "--git-path", "BISECT_START"], root).await else {
+        return false;
+    };
+    let path = PathBuf::from(path.trim());
+    let path = if path.is_absolute() {
+        path
+    } else {
+        root.join(path)
+    };
+    fs::metadata(path).await.is_ok()
+}
+
+async fn collect_bisect_status(root: &Path, message: Option<String>) -> Result<GitBisectStatus> {
+    if !is_bisecting(root).await {
+        return Ok(GitBisectStatus {
+            active: false,
+            current: None,
+            bad: None,
+            good: Vec::new(),
+            skipped: Vec::new(),
+            remaining_revisions: None,
+            remaining_steps: None,
+            first_bad_commit: None,
+            message,
+        });
+    }
+
+    let refs = run_git(
+        &[
+            "for-each-ref",
+            "--format=%(objectname) %(refname)",
+            "refs/bisect",
+        ],
+        root,
+    )
+    .await?;
+    let mut bad = None;
+    let mut good = Vec::new();
+    let mut skipped = Vec::new();
+    for line in refs.lines() {
+        let Some((hash, refname)) = line.trim().split_once(' ') else {
+            continue;
+        };
+        if refname == "refs/bisect/bad" {
+            bad = Some(hash.to_string());
+        } else if refname.starts_with("refs/bisect/good-") {
+            good.push(hash.to_string());
+        } else if refname.starts_with("refs/bisect/skip-") {
+            skipped.push(hash.to_string());
+        }
+    }
+
+    let mut remaining_revisions = None;
+    let mut remaining_steps = None;
+    if let Some(bad_hash) = bad.as_deref().filter(|_| !good.is_empty()) {
+        let mut args = vec!["rev-list", "--bisect-vars", bad_hash, "--not"];
+        args.extend(good.iter().map(|h| h.as_str()));
+        let vars = run_git(&args, root).await.unwrap_or_default();
+        for line in vars.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim_matches('\'').parse::<u32>().ok();
+                match key {
+                    "bisect_nr" => remaining_revisions = value,
+                    "bisect_steps" => remaining_steps = value,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    let log = run_git(&["bisect", "log"], root).await.unwrap_or_default();
+    let first_bad_commit = log
+        .lines()
+        .rev()
+        .find_map(|line| BISECT_FIRST_BAD_REGEX.captures(line.trim()))
+        .map(|caps| GitBisectCommit {
+            hash: caps[1].to_string(),
+            subject: caps[2].to_string(),
+        });
+
+    let current = run_git(&["log", "-1", "--format=%H%x00%s"], root)
+        .await
+        .ok()
+        .and_then(|output| {
+            output
+                .split_once('\0')
+                .map(|(hash, subject)| GitBisectCommit {
+                    hash: hash.to_string(),
+                    subject: subject.to_string(),
+                })
+        });
+
+    Ok(GitBisectStatus {
+        active: true,
+        current,
+        bad,
+        good,
+        skipped,
+        remaining_revisions: if first_bad_commit.is_some() {
+            Some(0)
+        } else {
+            remaining_revisions
+        },
+        remaining_steps: if first_bad_commit.is_some() {
+            Some(0)
+        } else {
+            remaining_steps
+        },
+        first_bad_commit,
+        message,
+    })
+}
+
+async fn run_bisect_step(
+    directory: &str,
+    step: &str,
+    revision: Option<String>,
+    state: &State<'_, DesktopRuntime>,
+) -> Result<GitBisectStatus, GitError> {
+    let root = validate_writable_git_path(directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    if !is_bisecting(&root).await {
+        return Err("No bisect session in progress".into());
+    }
+
+    let mut args = vec!["bisect", step];
+    if let Some(rev) = revision.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+        args.push(rev);
+    }
+    let output = run_git(&args, &root).await.map_err(|e| e.to_string())?;
+    let message = output.lines().next().map(|line| line.trim().to_string());
+
+    collect_bisect_status(&root, message)
+        .await
+        .map_err(GitError::from)
+}
+
+#[tauri::command]
+pub async fn git_bisect_start(
+    directory: String,
+    bad: Option<String>,
+    good: Option<Vec<String>>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBisectStatus, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    if is_bisecting(&root).await {
+        return Err("A bisect session is already in progress".into());
+    }
+
+    let good: Vec<String> = good
+        .unwrap_or_default()
+        .into_iter()
+        .map(|rev| rev.trim().to_string())
+        .filter(|rev| !rev.is_empty())
+        .collect();
+    let bad = bad
+        .map(|rev| rev.trim().to_string())
+        .filter(|rev| !rev.is_empty());
+    if bad.is_none() && !good.is_empty() {
+        return Err("A bad revision is required when good revisions are given".into());
+    }
+
+    let mut args = vec!["bisect", "start"];
+    if let Some(bad) = bad.as_deref() {
+        args.push(bad);
+    }
+    args.extend(good.iter().map(|rev| rev.as_str()));
+    args.push("--");
+
+    let output = run_git(&args, &root).await.map_err(|e| e.to_string())?;
+    let message = output
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty());
+
+    collect_bisect_status(&root, message)
+        .await
+        .map_err(GitError::from)
+}
+
+#[tauri::command]
+pub async fn git_bisect_good(
+    directory: String,
+    revision: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBisectStatus, GitError> {
+    run_bisect_step(&directory, "good", revision, &state).await
+}
+
+#[tauri::command]
+pub async fn git_bisect_bad(
+    directory: String,
+    revision: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBisectStatus, GitError> {
+    run_bisect_step(&directory, "bad", revision, &state).await
+}
+
+#[tauri::command]
+pub async fn git_bisect_skip(
+    directory: String,
+    revision: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBisectStatus, GitError> {
+    run_bisect_step(&directory, "skip", revision, &state).await
+}
+
+#[tauri::command]
+pub async fn git_bisect_reset(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBisectStatus, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    run_git(&["bisect", "reset"], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    collect_bisect_status(&root, None)
+        .await
+        .map_err(GitError::from)
+}
+
+#[tauri::command]
+pub async fn git_bisect_status(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitBisectStatus, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    collect_bisect_status(&root, None)
+        .await
+        .map_err(GitError::from)
+}
+
+#[tauri::command]
+pub async fn get_git_identities() -> Result<Vec<GitIdentityProfile>, GitError> {
+    identity_store::load_profiles()
+        .await
+        .map_err(GitError::from)
+}
+
+#[tauri::command]
+pub async fn create_git_identity(
+    profile: GitIdentityProfile,
+) -> Result<GitIdentityProfile, GitError> {
+    identity_store::update_profiles(|profiles| {
+        if profiles.iter().any(|p| p.id == profile.id) {
+            return Err(anyhow!("Profile with ID {} already exists", profile.id));
+        }
+        profiles.push(profile.clone());
+        Ok(profile)
+    })
+    .await
+    .map_err(GitError::from)
+}
+
+#[tauri::command]
+pub async fn update_git_identity(
+    id: String,
+    updates: GitIdentityProfile,
+) -> Result<GitIdentityProfile, GitError> {
+    identity_store::update_profiles(|profiles| {
+        let idx = profiles
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| anyhow!("Profile with ID {} not found", id))?;
+        profiles[idx] = updates.clone();
+        Ok(updates)
+    })
+    .await
+    .map_err(GitError::from)
+}
+
+#[tauri::command]
+pub async fn delete_git_identity(id: String) -> Result<(), GitError> {
+    identity_store::update_profiles(|profiles| {
+        let len = profiles.len();
+        profiles.retain(|p| p.id != id);
+        if profiles.len() == len {
+            return Err(anyhow!("Profile with ID {} not found", id));
+        }
+        Ok(())
+    })
+    .await?;
+    identity_store::update_directory_rules(|rules| {
+        rules.retain(|rule| rule.profile_id != id);
+        Ok(())
+    })
+    .await
+    .map_err(GitError::from)
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedSshKey {
+    pub profile: GitIdentityProfile,
+    pub private_key_path: String,
+    pub public_key: String,
+    pub fingerprint: Option<String>,
+}
+
+/// Create a new key pair at `~/.ssh/openchamber-<id>` with `ssh-keygen`, store its
+/// path on the identity and return the public key for adding to the hosting provider.
+/// Existing key files are never overwritten.
+#[tauri::command]
+pub async fn generate_ssh_key_for_identity(
+    id: String,
+    key_type: Option<String>,
+) -> Result<GeneratedSshKey, GitError> {
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("Identity id {} cannot be used in a file name", id).into());
+    }
+    let profile = identity_store::find_profile(&id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Profile {} not found", id))?;
+
+    let key_type = key_type
+        .map(|t| t.trim().to_ascii_lowercase())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| "ed25519".to_string());
+    let type_args: &[&str] = match key_type.as_str() {
+        "ed25519" => &["-t", "ed25519"],
+        "rsa" => &["-t", "rsa", "-b", "4096"],
+        "ecdsa" => &["-t", "ecdsa", "-b", "521"],
+        other => return Err(format!("Unsupported key type: {}", other).into()),
+    };
+
+    let ssh_dir = dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?
+        .join(".ssh");
+    fs::create_dir_all(&ssh_dir)
+        .await
+        .map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&ssh_dir, std::fs::Permissions::from_mode(0o700)).await;
+    }
+
+    let key_path = ssh_dir.join(format!("openchamber-{}", id));
+    let public_path = key_path.with_file_name(format!("openchamber-{}.pub", id));
+    if fs::metadata(&key_path).await.is_ok() || fs::metadata(&public_path).await.is_ok() {
+        return Err(format!("{} already exists", key_path.display()).into());
+    }
+
+    let comment = if profile.user_email.trim().is_empty() {
+        format!("openchamber-{}", id)
+    } else {
+        profile.user_email.clone()
+    };
+    let output = Command::new("ssh-keygen")
+        .args(type_args)
+        .arg("-f")
+        .arg(&key_path)
+        .args(["-N", "", "-q", "-C", &comment])
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run ssh-keygen: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "ssh-keygen failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    let public_key = fs::read_to_string(&public_path)
+        .await
+        .map_err(|e| e.to_string())?
+        .trim()
+        .to_string();
+    let fingerprint = Command::new("ssh-keygen")
+        .arg("-lf")
+        .arg(&public_path)
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .nth(1)
+                .map(str::to_string)
+        });
+
+    let private_key_path = key_path.to_string_lossy().to_string();
+    let stored_path = private_key_path.clone();
+    let profile = identity_store::update_profiles(move |profiles| {
+        let profile = profiles
+            .iter_mut()
+            .find(|p| p.id == id)
+            .ok_or_else(|| anyhow!("Profile with ID {} not found", id))?;
+        profile.ssh_key = Some(stored_path);
+        Ok(profile.clone())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    info!(
+        "[git] Generated {} key for identity {}",
+        key_type, profile.name
+    );
+
+    Ok(GeneratedSshKey {
+        profile,
+        private_key_path,
+        public_key,
+        fingerprint,
+    })
+}
+
+static SSH_GREETING_USER_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^Hi |Welcome to GitLab, @|logged in as |authenticated as )([^\s!,.]+)").unwrap()
+});
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitIdentityTestResult {
+    pub host: String,
+    /// authenticated, wrong_key, key_locked, host_key_failed, unreachable or unknown
+    pub status: String,
+    pub authenticated: bool,
+    /// Account the server says the key belongs to, when it says so
+    pub username: Option<String>,
+    pub output: String,
+}
+
+fn classify_ssh_test(output: &str) -> &'static str {
+    let lower = output.to_ascii_lowercase();
+    if lower.contains("successfully authenticated")
+        || lower.contains("welcome to gitlab")
+        || lower.contains("authenticated via ssh key")
+        || lower.contains("logged in as")
+    {
+        "authenticated"
+    } else if lower.contains("host key verification failed")
+        || lower.contains("remote host identification has changed")
+    {
+        "host_key_failed"
+    } else if lower.contains("could not resolve hostname")
+        || lower.contains("connection timed out")
+        || lower.contains("connection refused")
+        || lower.contains("network is unreachable")
+        || lower.contains("no route to host")
+        || lower.contains("operation timed out")
+    {
+        "unreachable"
+    } else if lower.contains("incorrect passphrase") || lower.contains("passphrase") {
+        "key_locked"
+    } else if lower.contains("permission denied") {
+        "wrong_key"
+    } else {
+        "unknown"
+    }
+}
+
+/// Check that an identity's key is accepted by a git host by running `ssh -T` in batch
+/// mode (no prompts). `host` may be `github.com`, `git@github.com` or `host:port`.
+#[tauri::command]
+pub async fn test_git_identity(
+    id: String,
+    host: String,
+) -> Result<GitIdentityTestResult, GitError> {
+    let profile = identity_store::find_profile(&id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Profile {} not found", id))?;
+
+    let host = host
+        .trim()
+        .trim_start_matches("ssh://")
+        .trim_end_matches('/');
+    if host.is_empty() || host.starts_with('-') || host.contains(char::is_whitespace) {
+        return Err("A valid host is required".into());
+    }
+    let (destination, port) = match host.rsplit_once(':') {
+        Some((destination, port)) if port.parse::<u16>().is_ok() => (destination, Some(port)),
+        _ => (host, None),
+    };
+    let destination = if destination.contains('@') {
+        destination.to_string()
+    } else {
+        format!("git@{}", destination)
+    };
+
+    let mut command = Command::new("ssh");
+    command.args([
+        "-T",
+        "-o",
+        "BatchMode=yes",
+        "-o",
+        "ConnectTimeout=10",
+        "-o",
+        "StrictHostKeyChecking=accept-new",
+    ]);
+    let key = profile
+        .ssh_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty());
+    if let Some(key) = key {
+        command
+            .args(["-o", "IdentitiesOnly=yes", "-i"])
+            .arg(expand_tilde_path(key));
+    } else if !profile.ssh_agent {
+        return Err(format!(
+            "Identity {} has no SSH key or agent configured",
+            profile.name
+        )
+        .into());
+    }
+    if let Some(port) = port {
+        command.args(["-p", port]);
+    }
+    command.arg(&destination);
+
+    let output = tokio::time::timeout(
+        std::time::Duration::from_secs(20),
+        command
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .env("LC_ALL", "C")
+            .output(),
+    )
+    .await
+    .map_err(|_| format!("Timed out connecting to {}", destination))?
+    .map_err(|e| format!("Failed to run ssh: {}", e))?;
+
+    // Hosts print their greeting on stderr and usually exit 1 since there is no shell
+    let text = [
+        String::from_utf8_lossy(&output.stdout).trim(),
+        String::from_utf8_lossy(&output.stderr).trim(),
+    ]
+    .iter()
+    .filter(|part| !part.is_empty())
+    .cloned()
+    .collect::<Vec<_>>()
+    .join("\n");
+    let status = classify_ssh_test(&text);
+    let username = SSH_GREETING_USER_REGEX
+        .captures(&text)
+        .map(|caps| caps[1].to_string());
+
+    Ok(GitIdentityTestResult {
+        host: destination,
+        status: status.to_string(),
+        authenticated: status == "authenticated",
+        username,
+        output: text,
+    })
+}
+
+/// Answer a `git:askpass-request` prompt; `None` cancels it
+#[tauri::command]
+pub async fn respond_git_askpass(id: String, response: Option<String>) -> Result<bool, GitError> {
+    Ok(git_askpass::respond(&id, response).await)
+}
+
+/// Called by the UI when it starts or stops handling `git:askpass-request`
+#[tauri::command]
+pub async fn set_git_askpass_listener(active: bool) -> Result<(), GitError> {
+    git_askpass::set_listener_active(active);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_git_credentials() -> Result<Vec<GitHostCredential>, GitError> {
+    git_credentials::list().await.map_err(GitError::from)
+}
+
+/// Store an HTTPS personal access token for `host` in the OS keychain. It is supplied to
+/// push, pull, fetch and clone for HTTPS remotes on that host.
+#[tauri::command]
+pub async fn save_git_credential(
+    host: String,
+    username: Option<String>,
+    token: String,
+) -> Result<GitHostCredential, GitError> {
+    git_credentials::save(&host, username.as_deref().unwrap_or(""), &token)
+        .await
+        .map_err(GitError::from)
+}
+
+#[tauri::command]
+pub async fn remove_git_credential(host: String) -> Result<bool, GitError> {
+    git_credentials::remove(&host).await.map_err(GitError::from)
+}
+
+#[tauri::command]
+pub async fn get_current_git_identity(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitIdentitySummary, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let user_name = run_git(&["config", "user.name"], &root).await.ok();
+    let user_email = run_git(&["config", "user.email"], &root).await.ok();
+    let ssh_command = run_git(&["config", "core.sshCommand"], &root).await.ok();
+
+    Ok(GitIdentitySummary {
+        user_name: user_name.filter(|s| !s.is_empty()),
+        user_email: user_email.filter(|s| !s.is_empty()),
+        ssh_command: ssh_command.filter(|s| !s.is_empty()),
+    })
+}
+
+async fn apply_identity_to_repo(root: &Path, profile: &GitIdentityProfile) -> Result<()> {
+    run_git(
+        &["config", "--local", "user.name", &profile.user_name],
+        root,
+    )
+    .await?;
+    run_git(
+        &["config", "--local", "user.email", &profile.user_email],
+        root,
+    )
+    .await?;
+
+    let key = profile
+        .ssh_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty());
+    match (key, profile.ssh_agent) {
+        // With IdentitiesOnly, `-i` may name just the public key; ssh then asks the
+        // agent for the matching private key
+        (Some(key), true) => {
+            let cmd = format!("ssh -o IdentitiesOnly=yes -i {}", key);
+            run_git(&["config", "--local", "core.sshCommand", &cmd], root).await?;
+        }
+        (Some(key), false) => {
+            let cmd = format!("ssh -i {}", key);
+            run_git(&["config", "--local", "core.sshCommand", &cmd], root).await?;
+        }
+        // Agent without a specific key: plain ssh offers every agent key
+        (None, _) => {
+            let _ = run_git(&["config", "--local", "--unset", "core.sshCommand"], root).await;
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_git_identity(
+    directory: String,
+    profile_id: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitIdentityProfile, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let profiles = identity_store::load_profiles()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let profile = profiles
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Profile {} not found", profile_id))?;
+
+    apply_identity_to_repo(&root, &profile)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(profile)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkIdentityResult {
+    pub directory: String,
+    pub status: String,
+    pub message: Option<String>,
+}
+
+/// Match a directory against a simple `*` wildcard rule (e.g. `~/work/*`)
+fn directory_matches_rule(rule: &str, directory: &str) -> bool {
+    let expanded = expand_tilde_path(rule).to_string_lossy().replace('\\', "/");
+    let candidate = directory.replace('\\', "/");
+    let pattern = format!(
+        "^{}$",
+        expanded
+            .split('*')
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(".*")
+    );
+    Regex::new(&pattern)
+        .map(|re| re.is_match(&candidate))
+        .unwrap_or(false)
+}
+
+async fn approved_directories(settings: &SettingsStore) -> Vec<String> {
+    let Ok(value) = settings.load().await else {
+        return Vec::new();
+    };
+
+    let mut seen = HashSet::new();
+    let mut directories = Vec::new();
+    for key in ["approvedDirectories", "pinnedDirectories"] {
+        if let Some(items) = value.get(key).and_then(|v| v.as_array()) {
+            for item in items.iter().filter_map(|v| v.as_str()) {
+                let trimmed = item.trim();
+                if !trimmed.is_empty() && seen.insert(trimmed.to_string()) {
+                    directories.push(trimmed.to_string());
+                }
+            }
+        }
+    }
+    directories
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitIdentitySuggestion {
+    pub remote: Option<String>,
+    /// `host/owner/repo` the rules were matched against
+    pub remote_path: Option<String>,
+    pub profile: Option<GitIdentityProfile>,
+    pub matched_rule: Option<String>,
+    pub current_email: Option<String>,
+    /// The suggested identity is already the repository's local identity
+    pub already_applied: bool,
+    pub applied: bool,
+}
+
+/// Match `host/owner/repo` against a `*` wildcard rule, ignoring case, any URL scheme
+/// and a trailing `.git` on the rule
+fn remote_matches_rule(rule: &str, remote_path: &str) -> bool {
+    let rule = rule.trim().to_ascii_lowercase();
+    let rule = rule
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(&rule);
+    let rule = rule.trim_end_matches('/').trim_end_matches(".git");
+    if rule.is_empty() {
+        return false;
+    }
+    let pattern = format!(
+        "^{}$",
+        rule.split('*')
+            .map(regex::escape)
+            .collect::<Vec<_>>()
+            .join(".*")
+    );
+    Regex::new(&pattern)
+        .map(|re| re.is_match(&remote_path.to_ascii_lowercase()))
+        .unwrap_or(false)
+}
+
+/// The profile with the most specific remote rule (most literal characters) matching
+/// `host/owner/repo`, with that rule
+fn best_remote_rule_match(
+    profiles: Vec<GitIdentityProfile>,
+    remote_path: &str,
+) -> Option<(String, GitIdentityProfile)> {
+    profiles
+        .into_iter()
+        .filter_map(|profile| {
+            let rule = profile
+                .remote_rules
+                .iter()
+                .filter(|rule| remote_matches_rule(rule, remote_path))
+                .max_by_key(|rule| rule.chars().filter(|c| *c != '*').count())?
+                .clone();
+            Some((rule, profile))
+        })
+        .max_by_key(|(rule, _)| rule.chars().filter(|c| *c != '*').count())
+}
+
+/// Suggest the identity whose remote rules match the repository's remote. The most
+/// specific rule (most literal characters) wins. With `apply`, the suggestion is also
+/// written to the repository's local config unless it is already in place.
+#[tauri::command]
+pub async fn suggest_git_identity(
+    directory: String,
+    apply: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitIdentitySuggestion, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let current_email =
+        run_git_with_allowed_exit(&["config", "--local", "user.email"], &root, &[1])
+            .await
+            .ok()
+            .map(|email| email.trim().to_string())
+            .filter(|email| !email.is_empty());
+    let mut suggestion = GitIdentitySuggestion {
+        remote: None,
+        remote_path: None,
+        profile: None,
+        matched_rule: None,
+        current_email,
+        already_applied: false,
+        applied: false,
+    };
+
+    let Ok(remote_name) = resolve_default_remote(&root).await else {
+        return Ok(suggestion);
+    };
+    let remote_url = run_git(&["remote", "get-url", &remote_name], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    suggestion.remote = Some(remote_name);
+    let Ok(parsed) = git_remote::parse_remote_url(&remote_url) else {
+        return Ok(suggestion);
+    };
+    let remote_path = format!("{}/{}/{}", parsed.host, parsed.owner, parsed.repo);
+
+    let profiles = identity_store::load_profiles()
+        .await
+        .map_err(|e| e.to_string())?;
+    let best = best_remote_rule_match(profiles, &remote_path);
+    suggestion.remote_path = Some(remote_path);
+
+    let Some((rule, profile)) = best else {
+        return Ok(suggestion);
+    };
+    suggestion.already_applied = suggestion.current_email.as_deref() == Some(&profile.user_email);
+
+    if apply.unwrap_or(false) && !suggestion.already_applied {
+        if is_read_only_workspace(&root, state.settings()).await {
+            return Err(format!(
+                "{}: {} is opened in read-only mode",
+                READ_ONLY_WORKSPACE_ERROR,
+                root.display()
+            )
+            .into());
+        }
+        apply_identity_to_repo(&root, &profile)
+            .await
+            .map_err(|e| e.to_string())?;
+        info!(
+            "[git] Applied identity {} to {:?} via rule {}",
+            profile.name, root, rule
+        );
+        suggestion.applied = true;
+    }
+
+    suggestion.matched_rule = Some(rule);
+    suggestion.profile = Some(profile);
+    Ok(suggestion)
+}
+
+/// Apply one identity to many repositories. Repositories that already carry a
+/// different local identity are treated as per-repo overrides and skipped unless
+/// `overwrite` is set.
+#[tauri::command]
+pub async fn apply_identity_bulk(
+    profile_id: String,
+    directories: Option<Vec<String>>,
+    rule: Option<String>,
+    overwrite: Option<bool>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<BulkIdentityResult>, GitError> {
+    let profiles = identity_store::load_profiles()
+        .await
+        .map_err(|e| e.to_string())?;
+    let profile = profiles
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("Profile {} not found", profile_id))?;
+
+    let mut targets: Vec<String> = directories.unwrap_or_default();
+    if let Some(rule) = rule.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+        for directory in approved_directories(state.settings()).await {
+            if directory_matches_rule(rule, &directory) && !targets.contains(&directory) {
+                targets.push(directory);
+            }
+        }
+    }
+
+    if targets.is_empty() {
+        return Err("No repositories matched".into());
+    }
+
+    let overwrite = overwrite.unwrap_or(false);
+    let mut results = Vec::with_capacity(targets.len());
+
+    for directory in targets {
+        let result = |status: &str, message: Option<String>| BulkIdentityResult {
+            directory: directory.clone(),
+            status: status.to_string(),
+            message,
+        };
+
+        let root = match validate_git_path(&directory, state.settings()).await {
+            Ok(root) => root,
+            Err(err) => {
+                results.push(result("failed", Some(err.to_string())));
+                continue;
+            }
+        };
+
+        if !is_git_work_tree(&root).await {
+            results.push(result("skipped", Some("Not a git repository".to_string())));
+            continue;
+        }
+
+        if is_read_only_workspace(&root, state.settings()).await {
+            results.push(result(
+                "skipped",
+                Some("Workspace is read-only".to_string()),
+            ));
+            continue;
+        }
+
+        let local_email =
+            run_git_with_allowed_exit(&["config", "--local", "user.email"], &root, &[1])
+                .await
+                .unwrap_or_default();
+        let local_email = local_email.trim();
+
+        if local_email == profile.user_email {
+            let local_name =
+                run_git_with_allowed_exit(&["config", "--local", "user.name"], &root, &[1])
+                    .await
+                    .unwrap_or_default();
+            if local_name.trim() == profile.user_name {
+                results.push(result("unchanged", None));
+                continue;
+            }
+        } else if !local_email.is_empty() && !overwrite {
+            results.push(result(
+                "skipped",
+                Some(format!(
+                    "Repository overrides identity with {}",
+                    local_email
+                )),
+            ));
+            continue;
+        }
+
+        match apply_identity_to_repo(&root, &profile).await {
+            Ok(()) => results.push(result("applied", None)),
+            Err(err) => results.push(result("failed", Some(err.to_string()))),
+        }
+    }
+
+    info!(
+        "[git] Applied identity {} to {} repositories",
+        profile.id,
+        results.iter().filter(|r| r.status == "applied").count()
+    );
+
+    Ok(results)
+}
+
+/// Local config key naming the directory rule that set the repository's identity, so a
+/// later change of mapping can replace it without clobbering hand-set identities
+const MAPPED_IDENTITY_CONFIG_KEY: &str = "openchamber.identityRule";
+
+/// The most specific directory rule (most literal characters) matching `directory` or
+/// one of its parents
+fn match_directory_rule(
+    rules: &[identity_store::DirectoryIdentityRule],
+    directory: &Path,
+) -> Option<identity_store::DirectoryIdentityRule> {
+    rules
+        .iter()
+        .filter(|rule| {
+            let pattern = rule.pattern.trim().trim_end_matches(['/', '\\']);
+            !pattern.is_empty()
+                && directory
+                    .ancestors()
+                    .any(|dir| directory_matches_rule(pattern, &dir.to_string_lossy()))
+        })
+        .max_by_key(|rule| rule.pattern.chars().filter(|c| *c != '*').count())
+        .cloned()
+}
+
+/// Apply the identity mapped to `root`'s directory, if any. Repositories whose local
+/// identity was set by hand are left alone; ones set by an earlier rule are updated.
+/// Returns the profile when it was written.
+pub(crate) async fn apply_mapped_identity(
+    root: &Path,
+    settings: &SettingsStore,
+) -> Result<Option<GitIdentityProfile>> {
+    if !is_git_work_tree(root).await || is_read_only_workspace(root, settings).await {
+        return Ok(None);
+    }
+    let rules = identity_store::load_directory_rules().await?;
+    let Some(rule) = match_directory_rule(&rules, root) else {
+        return Ok(None);
+    };
+    let Some(profile) = identity_store::find_profile(&rule.profile_id).await? else {
+        warn!(
+            "[git] Directory rule {} names missing identity {}",
+            rule.pattern, rule.profile_id
+        );
+        return Ok(None);
+    };
+
+    let local = |key: &'static str| async move {
+        run_git_with_allowed_exit(&["config", "--local", key], root, &[1])
+            .await
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    let (email, name, mapped_by) = (
+        local("user.email").await,
+        local("user.name").await,
+        local(MAPPED_IDENTITY_CONFIG_KEY).await,
+    );
+    if email == profile.user_email && name == profile.user_name {
+        return Ok(None);
+    }
+    if !email.is_empty() && mapped_by.is_empty() {
+        info!(
+            "[git] {:?} overrides its identity with {}; not applying {}",
+            root, email, rule.pattern
+        );
+        return Ok(None);
+    }
+
+    apply_identity_to_repo(root, &profile).await?;
+    run_git(
+        &[
+            "config",
+            "--local",
+            MAPPED_IDENTITY_CONFIG_KEY,
+            &rule.pattern,
+        ],
+        root,
+    )
+    .await?;
+    info!(
+        "[git] Applied identity {} to {:?} via directory rule {}",
+        profile.name, root, rule.pattern
+    );
+    Ok(Some(profile))
+}
+
+#[tauri::command]
+pub async fn get_directory_identity_rules(
+) -> Result<Vec<identity_store::DirectoryIdentityRule>, GitError> {
+    identity_store::load_directory_rules()
+        .await
+        .map_err(GitError::from)
+}
+
+/// Map repositories under `pattern` to an identity, replacing any rule with the same
+/// pattern. Without `profile_id` the rule is removed. Returns the updated rules.
+#[tauri::command]
+pub async fn set_directory_identity_rule(
+    pattern: String,
+    profile_id: Option<String>,
+) -> Result<Vec<identity_store::DirectoryIdentityRule>, GitError> {
+    let pattern = pattern.trim().to_string();
+    if pattern.is_empty() {
+        return Err("Directory pattern is required".into());
+    }
+    let profile_id = profile_id
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty());
+    if let Some(id) = profile_id.as_deref() {
+        if identity_store::find_profile(id).await?.is_none() {
+            return Err(format!("Profile {} not found", id).into());
+        }
+    }
+
+    identity_store::update_directory_rules(|rules| {
+        rules.retain(|rule| rule.pattern != pattern);
+        if let Some(profile_id) = profile_id {
+            rules.push(identity_store::DirectoryIdentityRule {
+                pattern,
+                profile_id,
+            });
+        }
+        rules.sort_by(|a, b| a.pattern.cmp(&b.pattern));
+        Ok(rules.clone())
+    })
+    .await
+    .map_err(GitError::from)
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveIdentity {
+    /// What git commits with right now
+    pub user_name: Option<String>,
+    pub user_email: Option<String>,
+    /// Config scope `user.email` comes from: local, worktree, global, system or command
+    pub scope: Option<String>,
+    /// How the identity was chosen: `directory` or `remote` rule, `config` when only the
+    /// git config names a known profile, `none` otherwise
+    pub source: String,
+    pub matched_rule: Option<String>,
+    pub profile: Option<GitIdentityProfile>,
+    /// The matched profile is the one git uses
+    pub in_effect: bool,
+}
+
+/// Explain which identity applies to `directory`: a directory rule wins over a remote
+/// rule, and either over whatever the git config says
+#[tauri::command]
+pub async fn get_effective_identity(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<EffectiveIdentity, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let root = fs::canonicalize(&root).await.unwrap_or(root);
+
+    let config = |key: &'static str| {
+        let root = root.clone();
+        async move {
+            run_git_with_allowed_exit(&["config", "--show-scope", "--get", key], &root, &[1])
+                .await
+                .ok()
+                .and_then(|line| {
+                    let (scope, value) = line.trim_end().split_once('\t')?;
+                    Some((scope.to_string(), value.to_string()))
+                })
+                .filter(|(_, value)| !value.is_empty())
+        }
+    };
+    let email = config("user.email").await;
+    let user_name = config("user.name").await.map(|(_, name)| name);
+    let profiles = identity_store::load_profiles().await?;
+
+    let rules = identity_store::load_directory_rules().await?;
+    let mut matched = match_directory_rule(&rules, &root).and_then(|rule| {
+        let profile = profiles.iter().find(|p| p.id == rule.profile_id)?.clone();
+        Some(("directory", Some(rule.pattern), profile))
+    });
+    if matched.is_none() {
+        if let Ok(remote) = resolve_default_remote(&root).await {
+            let url = run_git(&["remote", "get-url", &remote], &root)
+                .await
+                .unwrap_or_default();
+            if let Ok(parsed) = git_remote::parse_remote_url(&url) {
+                let remote_path = format!("{}/{}/{}", parsed.host, parsed.owner, parsed.repo);
+                matched = best_remote_rule_match(profiles.clone(), &remote_path)
+                    .map(|(rule, profile)| ("remote", Some(rule), profile));
+            }
+        }
+    }
+    if matched.is_none() {
+        matched = email.as_ref().and_then(|(_, email)| {
+            let profile = profiles.iter().find(|p| &p.user_email == email)?.clone();
+            Some(("config", None, profile))
+        });
+    }
+
+    let (source, matched_rule, profile) = match matched {
+        Some((source, rule, profile)) => (source, rule, Some(profile)),
+        None => ("none", None, None),
+    };
+    let (scope, user_email) = email.unzip();
+    Ok(EffectiveIdentity {
+        in_effect: profile
+            .as_ref()
+            .is_some_and(|p| user_email.as_deref() == Some(p.user_email.as_str())),
+        user_name,
+        user_email,
+        scope,
+        source: source.to_string(),
+        matched_rule,
+        profile,
+    })
+}
+
+/// Commit convention enforced by `validate_commit_message`; keep in sync with the
+/// rules spelled out in the `generate_commit_message` prompt
+const COMMIT_TYPES: &[&str] = &[
+    "feat", "fix", "chore", "style", "refactor", "perf", "docs", "test", "build", "ci",
+];
+const COMMIT_SUMMARY_MAX_CHARS: usize = 70;
+
+static COMMIT_HEADER_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([^\s(:!]+)(?:\(([^)]*)\))?(!)?: (.*)$").unwrap());
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitMessageViolation {
+    pub rule: String,
+    pub severity: String,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitMessageValidation {
+    pub valid: bool,
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub summary: Option<String>,
+    pub breaking: bool,
+    pub violations: Vec<CommitMessageViolation>,
+}
+
+#[tauri::command]
+pub async fn validate_commit_message(message: String) -> Result<CommitMessageValidation, GitError> {
+    let mut violations = Vec::new();
+    let mut violation = |rule: &str, severity: &str, message: String| {
+        violations.push(CommitMessageViolation {
+            rule: rule.to_string(),
+            severity: severity.to_string(),
+            message,
+        });
+    };
+
+    let trimmed = message.trim();
+    let mut lines = trimmed.lines();
+    let header = lines.next().unwrap_or("").trim_end();
+
+    let mut commit_type = None;
+    let mut scope = None;
+    let mut summary = None;
+    let mut breaking = false;
+
+    if header.is_empty() {
+        violation("empty", "error", "Commit message is empty".to_string());
+    } else if let Some(caps) = COMMIT_HEADER_REGEX.captures(header) {
+        let kind = caps[1].to_string();
+        if !COMMIT_TYPES.contains(&kind.as_str()) {
+            violation(
+                "type",
+                "error",
+                format!(
+                    "Unknown type \"{}\"; use one of: {}",
+                    kind,
+                    COMMIT_TYPES.join(", ")
+                ),
+            );
+        }
+        if let Some(raw_scope) = caps.get(2) {
+            let value = raw_scope.as_str().trim();
+            if value.is_empty() {
+                violation("scope", "error", "Scope parentheses are empty".to_string());
+            } else if value.contains(char::is_whitespace) {
+                violation(
+                    "scope",
+                    "warning",
+                    "Scope should not contain spaces".to_string(),
+                );
+            }
+            scope = Some(value.to_string()).filter(|v| !v.is_empty());
+        }
+        breaking = caps.get(3).is_some();
+
+        let text = caps[4].trim().to_string();
+        let length = text.chars().count();
+        if text.is_empty() {
+            violation("summary", "error", "Summary is empty".to_string());
+        } else if length > COMMIT_SUMMARY_MAX_CHARS {
+            violation(
+                "summaryLength",
+                "error",
+                format!(
+                    "Summary is {} characters; keep it at or under {}",
+                    length, COMMIT_SUMMARY_MAX_CHARS
+                ),
+            );
+        }
+        if text.ends_with(['.', '!', '?', ',', ';', ':']) {
+            violation(
+                "trailingPunctuation",
+                "warning",
+                "Summary should not end with punctuation".to_string(),
+            );
+        }
+
+        commit_type = Some(kind);
+        summary = Some(text).filter(|t| !t.is_empty());
+    } else {
+        violation(
+            "format",
+            "error",
+            "Subject must follow type(scope): summary, e.g. \"fix(chat): restore enter key handling\""
+                .to_string(),
+        );
+    }
+
+    if let Some(second) = lines.next() {
+        if !second.trim().is_empty() {
+            violation(
+                "bodySeparator",
+                "warning",
+                "Separate the subject from the body with a blank line".to_string(),
+            );
+        }
+    }
+
+    let valid = !violations.iter().any(|v| v.severity == "error");
+    Ok(CommitMessageValidation {
+        valid,
+        commit_type,
+        scope,
+        summary,
+        breaking,
+        violations,
+    })
+}
+
+#[tauri::command]
+pub async fn generate_commit_message(
+    directory: String,
+    files: Vec<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<CommitMessageResponse, GitError> {
+    let _root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // 1. Collect diffs
+    let mut diff_summaries = String::new();
+    let mut file_changes = Vec::new();
+    for file in files {
+        if let Ok(diff) =
+            get_git_diff(directory.clone(), file.clone(), None, None, state.clone()).await
+        {
+            file_changes.push(HeuristicFileChange::from_diff(&file, &diff));
+            diff_summaries.push_str(&format!(
+                "FILE: {}\n{}\n\n",
+                file,
+                truncate_for_prompt(diff)
+            ));
+        }
+    }
+
+    if diff_summaries.is_empty() {
+        return Err("No diffs available for selected files".into());
+    }
+
+    // 2. Construct prompt (matching server/index.js)
+    let prompt = format!(
+        r#"You are drafting git commit notes for this codebase. Respond in JSON of the shape {{"subject": string, "highlights": string[]}} (ONLY the JSON in response, no markdown wrappers or anything except JSON) with these rules:
+- subject follows our convention: type[optional-scope]: summary (examples: "feat: add diff virtualization", "fix(chat): restore enter key handling")
+- allowed types: feat, fix, chore, style, refactor, perf, docs, test, build, ci (choose the best match or fallback to chore)
+- summary must be imperative, concise, <= 70 characters, no trailing punctuation
+- scope is optional; include only when obvious from filenames/folders; do not invent scopes
+- focus on the most impactful user-facing change; if multiple capabilities ship together, align the subject with the dominant theme and use highlights to cover the other major outcomes
+- highlights array should contain 2-3 plain sentences (<= 90 chars each) that describe distinct features or UI changes users will notice (e.g. "Add per-file revert action in Changes list"). Avoid subjective benefit statements, marketing tone, repeating the subject, or referencing helper function names. Highlight additions such as new controls/buttons, new actions (e.g. revert), or stored state changes explicitly. Skip highlights if fewer than two meaningful points exist.
+- text must be plain (no markdown bullets); each highlight should start with an uppercase verb
+
+Diff summary:
+{}"#,
+        diff_summaries
+    );
+
+    // 3. Ask the model through the OpenCode server (or the endpoint set in settings)
+    let reply = ai_completion::complete(state.inner(), &prompt, 3000)
+        .await
+        .map_err(|e| e.to_string());
+
+    // 4. Parse JSON, falling back to a local message when the model is unavailable
+    let parsed = reply.and_then(|raw_content| {
+        serde_json::from_str::<GeneratedCommitMessage>(strip_code_fence(&raw_content))
+            .map_err(|e| format!("Failed to parse AI response: {}", e))
+    });
+    match parsed {
+        Ok(message) => Ok(CommitMessageResponse {
+            message,
+            fallback: false,
+            fallback_reason: None,
+        }),
+        Err(reason) => {
+            warn!("[git] Using local commit message: {}", reason);
+            Ok(CommitMessageResponse {
+                message: heuristic_commit_message(&file_changes),
+                fallback: true,
+                fallback_reason: Some(reason),
+            })
+        }
+    }
+}
+
+/// Directory names too generic to use as a commit scope
+const GENERIC_SCOPE_DIRS: &[&str] = &["src", "lib", "app", "packages", "crates", "source", "."];
+
+struct HeuristicFileChange {
+    path: String,
+    added: bool,
+    deleted: bool,
+    insertions: usize,
+    deletions: usize,
+}
+
+impl HeuristicFileChange {
+    fn from_diff(path: &str, diff: &str) -> Self {
+        let mut insertions = 0;
+        let mut deletions = 0;
+        for line in diff.lines() {
+            if line.starts_with('+') && !line.starts_with("+++") {
+                insertions += 1;
+            } else if line.starts_with('-') && !line.starts_with("---") {
+                deletions += 1;
+            }
+        }
+        Self {
+            path: path.to_string(),
+            added: diff.contains("\nnew file mode") || diff.contains("--- /dev/null"),
+            deleted: diff.contains("\ndeleted file mode") || diff.contains("+++ /dev/null"),
+            insertions,
+            deletions,
+        }
+    }
+}
+
+fn infer_commit_type(changes: &[HeuristicFileChange]) -> &'static str {
+    let all = |check: fn(&str) -> bool| changes.iter().all(|c| check(&c.path.to_lowercase()));
+    let file_name = |path: &str| path.rsplit('/').next().unwrap_or(path).to_string();
+
+    if all(|p| {
+        p.ends_with(".md") || p.ends_with(".mdx") || p.ends_with(".txt") || p.starts_with("docs/")
+    }) {
+        "docs"
+    } else if all(|p| {
+        p.contains("/test/")
+            || p.contains("/tests/")
+            || p.starts_with("test")
+            || p.contains(".test.")
+            || p.contains(".spec.")
+            || p.contains("_test.")
+    }) {
+        "test"
+    } else if all(|p| {
+        p.starts_with(".github/") || p.starts_with(".circleci/") || p.contains(".gitlab-ci")
+    }) {
+        "ci"
+    } else if changes.iter().all(|c| {
+        matches!(
+            file_name(&c.path.to_lowercase()).as_str(),
+            "cargo.toml"
+                | "cargo.lock"
+                | "package.json"
+                | "package-lock.json"
+                | "pnpm-lock.yaml"
+                | "yarn.lock"
+                | "bun.lockb"
+                | "makefile"
+                | "dockerfile"
+                | "build.rs"
+                | "go.mod"
+                | "go.sum"
+        )
+    }) {
+        "build"
+    } else if all(|p| p.ends_with(".css") || p.ends_with(".scss") || p.ends_with(".less")) {
+        "style"
+    } else if changes.iter().any(|c| c.added) {
+        "feat"
+    } else {
+        "chore"
+    }
+}
+
+/// Last component of the directory shared by every path, unless it is too generic
+fn infer_commit_scope(changes: &[HeuristicFileChange]) -> Option<String> {
+    let mut common: Vec<&str> = changes.first()?.path.split('/').collect();
+    common.pop();
+    for change in &changes[1..] {
+        let parts: Vec<&str> = change.path.split('/').collect();
+        let shared = common
+            .iter()
+            .zip(parts.iter().take(parts.len().saturating_sub(1)))
+            .take_while(|(a, b)| a == b)
+            .count();
+        common.truncate(shared);
+    }
+    common
+        .iter()
+        .rev()
+        .find(|part| !GENERIC_SCOPE_DIRS.contains(part))
+        .map(|part| part.to_lowercase())
+}
+
+fn describe_files(changes: &[&HeuristicFileChange]) -> String {
+    let names: Vec<&str> = changes
+        .iter()
+        .map(|c| c.path.rsplit('/').next().unwrap_or(&c.path))
+        .collect();
+    match names.as_slice() {
+        [one] => one.to_string(),
+        [a, b] => format!("{} and {}", a, b),
+        _ => format!("{} files", names.len()),
+    }
+}
+
+/// Deterministic commit message from paths and line counts, used when no model is
+/// reachable. Follows the same convention as the model prompt.
+fn heuristic_commit_message(changes: &[HeuristicFileChange]) -> GeneratedCommitMessage {
+    let commit_type = infer_commit_type(changes);
+    let scope = infer_commit_scope(changes);
+
+    let added: Vec<&HeuristicFileChange> = changes.iter().filter(|c| c.added).collect();
+    let deleted: Vec<&HeuristicFileChange> = changes.iter().filter(|c| c.deleted).collect();
+    let modified: Vec<&HeuristicFileChange> =
+        changes.iter().filter(|c| !c.added && !c.deleted).collect();
+
+    let summary = if added.len() == changes.len() {
+        format!("add {}", describe_files(&added))
+    } else if deleted.len() == changes.len() {
+        format!("remove {}", describe_files(&deleted))
+    } else {
+        let all: Vec<&HeuristicFileChange> = changes.iter().collect();
+        format!("update {}", describe_files(&all))
+    };
+    let prefix = match &scope {
+        Some(scope) => format!("{}({}): ", commit_type, scope),
+        None => format!("{}: ", commit_type),
+    };
+    let summary: String = summary.chars().take(COMMIT_SUMMARY_MAX_CHARS).collect();
+
+    let mut highlights = Vec::new();
+    for (verb, group) in [("Add", &added), ("Remove", &deleted), ("Update", &modified)] {
+        if !group.is_empty() && group.len() < changes.len() {
+            highlights.push(format!("{} {}", verb, describe_files(group)));
+        }
+    }
+    let insertions: usize = changes.iter().map(|c| c.insertions).sum();
+    let deletions: usize = changes.iter().map(|c| c.deletions).sum();
+    if changes.len() > 1 || highlights.is_empty() {
+        highlights.push(format!(
+            "Change {} lines across {} file{} (+{} -{})",
+            insertions + deletions,
+            changes.len(),
+            if changes.len() == 1 { "" } else { "s" },
+            insertions,
+            deletions
+        ));
+    }
+
+    GeneratedCommitMessage {
+        subject: format!("{}{}", prefix, summary.trim_end()),
+        highlights,
+    }
+}
+
+/// Per-file cap on diff text sent to the model
+const AI_DIFF_FILE_MAX_BYTES: usize = 4000;
+/// Cap on the combined diff text of one prompt
+const AI_DIFF_TOTAL_MAX_BYTES: usize = 60_000;
+
+/// Cut a diff to `AI_DIFF_FILE_MAX_BYTES` on a character boundary
+fn truncate_for_prompt(diff: String) -> String {
+    if diff.len() <= AI_DIFF_FILE_MAX_BYTES {
+        return diff;
+    }
+    let mut end = AI_DIFF_FILE_MAX_BYTES;
+    while !diff.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n...", &diff[..end])
+}
+
+/// Per-file diffs of `range_args` (e.g. `[base, "HEAD"]`) formatted for a prompt,
+/// each truncated, stopping once the combined size reaches `AI_DIFF_TOTAL_MAX_BYTES`
+async fn collect_prompt_diffs(root: &Path, range_args: &[&str]) -> Result<String> {
+    let mut args = vec!["diff", "--name-only"];
+    args.extend_from_slice(range_args);
+    let files = run_git(&args, root).await?;
+
+    let mut summary = String::new();
+    let mut omitted = 0;
+    for file in files.lines().filter(|line| !line.is_empty()) {
+        if summary.len() >= AI_DIFF_TOTAL_MAX_BYTES {
+            omitted += 1;
+            continue;
+        }
+        let mut args = vec!["diff"];
+        args.extend_from_slice(range_args);
+        args.extend(["--", file]);
+        if let Ok(diff) = run_git(&args, root).await {
+            summary.push_str(&format!(
+                "FILE: {}\n{}\n\n",
+                file,
+                truncate_for_prompt(diff)
+            ));
+        }
+    }
+    if omitted > 0 {
+        summary.push_str(&format!("({} more files not shown)\n", omitted));
+    }
+    Ok(summary)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedPrDescription {
+    pub title: String,
+    pub summary: String,
+    #[serde(default)]
+    pub changes: Vec<String>,
+    #[serde(default)]
+    pub checklist: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PrDescriptionResponse {
+    pub description: GeneratedPrDescription,
+    pub base: String,
+    pub merge_base: String,
+    pub commits: Vec<String>,
+}
+
+/// Draft a pull request title and description for the current branch against
+/// `base_branch` (the remote's default branch when omitted) from the commit subjects and
+/// the merge-base diff
+#[tauri::command]
+pub async fn generate_pr_description(
+    directory: String,
+    base_branch: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<PrDescriptionResponse, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let base = match base_branch
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+    {
+        Some(base) => base.to_string(),
+        None => {
+            let remote = resolve_default_remote(&root)
+                .await
+                .map_err(|e| e.to_string())?;
+            let branch = resolve_remote_default_branch(&root, &remote)
+                .await
+                .ok_or_else(|| format!("Could not determine the default branch of {}", remote))?;
+            format!("{}/{}", remote, branch)
+        }
+    };
+    if base.starts_with('-') {
+        return Err(format!("Invalid base branch: {}", base).into());
+    }
+    let merge_base = run_git(&["merge-base", &base, "HEAD"], &root)
+        .await
+        .map_err(|_| format!("No common history with {}", base))?
+        .trim()
+        .to_string();
+
+    let range = format!("{}..HEAD", merge_base);
+    let commits: Vec<String> = run_git(&["log", "--reverse", "--format=%s", &range], &root)
+        .await
+        .map_err(|e| e.to_string())?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.to_string())
+        .collect();
+    if commits.is_empty() {
+        return Err(format!("The current branch has no commits that are not in {}", base).into());
+    }
+    let diffs = collect_prompt_diffs(&root, &[&merge_base, "HEAD"])
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let prompt = format!(
+        r#"You are drafting a pull request description for this codebase. Respond in JSON of the shape {{"title": string, "summary": string, "changes": string[], "checklist": string[]}} (ONLY the JSON in response, no markdown wrappers or anything except JSON) with these rules:
+- title follows our commit convention: type[optional-scope]: summary, imperative, <= 70 characters, no trailing punctuation
+- summary is 1-3 plain sentences on what the change does and why
+- changes lists 2-6 distinct user-visible or structural changes, one plain sentence each, starting with an uppercase verb
+- checklist lists concrete things a reviewer should verify or test (e.g. "Push to a repository over HTTPS with a stored token"); skip generic items like "code compiles"
+- text must be plain (no markdown bullets)
+
+Commits:
+{}
+
+Diff summary:
+{}"#,
+        commits.join("\n"),
+        diffs
+    );
+
+    let raw_content = ai_completion::complete(state.inner(), &prompt, 3000)
+        .await
+        .map_err(|e| e.to_string())?;
+    let description: GeneratedPrDescription = serde_json::from_str(strip_code_fence(&raw_content))
+        .map_err(|e| format!("Failed to parse AI response: {}", e))?;
+
+    Ok(PrDescriptionResponse {
+        description,
+        base,
+        merge_base,
+        commits,
+    })
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GitReviewTarget {
+    /// Review the staged changes
+    pub staged: Option<bool>,
+    /// Review a commit range such as `main..HEAD`
+    pub range: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitReviewFinding {
+    pub file: String,
+    /// Line range in the new version of the file
+    pub start_line: Option<u32>,
+    pub end_line: Option<u32>,
+    /// info, warning or error
+    pub severity: String,
+    pub comment: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitDiffReview {
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub findings: Vec<GitReviewFinding>,
+}
+
+/// Ask the model to review a diff: staged changes, a range, or by default all
+/// uncommitted changes to tracked files. Findings point at new-file line numbers so
+/// they can be shown on the diff view.
+#[tauri::command]
+pub async fn review_git_diff(
+    directory: String,
+    target: Option<GitReviewTarget>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<GitDiffReview, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+    let target = target.unwrap_or_default();
+
+    let range = target
+        .range
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(str::to_string);
+    let range_args: Vec<&str> = match (&range, target.staged.unwrap_or(false)) {
+        (Some(range), _) if range.starts_with('-') => {
+            return Err(format!("Invalid range: {}", range).into())
+        }
+        (Some(range), _) => vec![range.as_str()],
+        (None, true) => vec!["--cached"],
+        (None, false) => vec!["HEAD"],
+    };
+    let diffs = collect_prompt_diffs(&root, &range_args)
+        .await
+        .map_err(|e| e.to_string())?;
+    if diffs.trim().is_empty() {
+        return Err("No changes to review".into());
+    }
+
+    let prompt = format!(
+        r#"You are reviewing a change to this codebase before it is committed. Respond in JSON of the shape {{"summary": string, "findings": [{{"file": string, "startLine": number, "endLine": number, "severity": "info" | "warning" | "error", "comment": string}}]}} (ONLY the JSON in response, no markdown wrappers or anything except JSON) with these rules:
+- report bugs, security problems, missing error handling, and clear readability or consistency issues; do not praise or restate the change
+- file is the path exactly as given after FILE:
+- startLine/endLine are line numbers in the new version of the file, taken from the hunk headers
+- severity is error for likely bugs or security issues, warning for risky or questionable code, info for minor suggestions
+- comment is 1-2 plain sentences saying what is wrong and how to fix it
+- summary is one plain sentence with the overall assessment; return an empty findings array when there is nothing to report
+
+Diff:
+{}"#,
+        diffs
+    );
+
+    let raw_content = ai_completion::complete(state.inner(), &prompt, 4000)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut review: GitDiffReview = serde_json::from_str(strip_code_fence(&raw_content))
+        .map_err(|e| format!("Failed to parse AI response: {}", e))?;
+
+    for finding in &mut review.findings {
+        finding.severity = match finding.severity.to_lowercase().as_str() {
+            "error" => "error",
+            "warning" => "warning",
+            _ => "info",
+        }
+        .to_string();
+        if let (Some(start), Some(end)) = (finding.start_line, finding.end_line) {
+            if end < start {
+                finding.end_line = Some(start);
+            }
+        }
+    }
+    review
+        .findings
+        .retain(|f| !f.file.trim().is_empty() && !f.comment.trim().is_empty());
+
+    Ok(review)
+}
+
+/// Strip a markdown code fence a model may wrap its JSON reply in
+fn strip_code_fence(raw: &str) -> &str {
+    raw.trim()
         .trim_start_matches("```json")
         .trim_start_matches("```")
         .trim_end_matches("```")
-        .trim();
+        .trim()
+}
+
+static COMMIT_PERSON_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(author|committer) (.*) <(.*)> (\d+ [+-]\d{4})$").unwrap());
+static IDENTITY_SPEC_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.*?)\s*<([^<>]+)>$").unwrap());
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RewriteAuthorsResult {
+    pub total: usize,
+    pub rewritten: usize,
+    pub old_head: String,
+    pub new_head: String,
+    pub backup_ref: String,
+}
+
+/// Resolve an identity given as a profile id, `Name <email>` or a bare email
+async fn resolve_identity_spec(spec: &str) -> Result<(Option<String>, String), String> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return Err("Identity is required".to_string());
+    }
+    if let Some(profile) = identity_store::find_profile(spec)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        return Ok((Some(profile.user_name), profile.user_email));
+    }
+    if let Some(caps) = IDENTITY_SPEC_REGEX.captures(spec) {
+        let name = caps[1].trim().to_string();
+        return Ok((
+            Some(name).filter(|n| !n.is_empty()),
+            caps[2].trim().to_string(),
+        ));
+    }
+    if spec.contains('@') {
+        return Ok((None, spec.to_string()));
+    }
+    Err(format!("Unknown identity: {}", spec))
+}
+
+/// Re-create the commits in `range` (ending at HEAD) with `from_identity` replaced by
+/// `to_identity` as author/committer. Only unpushed, linear history is touched; the old
+/// head is kept under `refs/openchamber/backup/` so the rewrite can be undone.
+#[tauri::command]
+pub async fn rewrite_recent_authors(
+    directory: String,
+    range: String,
+    from_identity: String,
+    to_identity: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<RewriteAuthorsResult, GitError> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let (_, from_email) = resolve_identity_spec(&from_identity).await?;
+    let (to_name, to_email) = resolve_identity_spec(&to_identity).await?;
+    let to_name = to_name.ok_or_else(|| "Target identity needs a name".to_string())?;
+
+    let branch = get_current_branch_name(&root)
+        .await
+        .map_err(|e| e.to_string())?;
+    if branch == "HEAD" {
+        return Err("Cannot rewrite authors on a detached HEAD".into());
+    }
+    if is_bisecting(&root).await {
+        return Err("Finish the bisect session before rewriting history".into());
+    }
+
+    let range = range.trim();
+    let base = match range.split_once("..") {
+        Some((base, end)) => {
+            let end = end.trim();
+            if !end.is_empty() && end != "HEAD" && end != branch {
+                return Err("The range must end at the current branch".into());
+            }
+            base.trim().to_string()
+        }
+        None => range.to_string(),
+    };
+    let base_hash = run_git(
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", base),
+        ],
+        &root,
+    )
+    .await
+    .map_err(|_| format!("Unknown revision: {}", base))?;
+    let old_head = get_head_hash(&root).await.map_err(|e| e.to_string())?;
+    let rev_range = format!("{}..{}", base_hash, old_head);
+
+    let commits: Vec<String> = run_git(&["rev-list", "--reverse", &rev_range], &root)
+        .await
+        .map_err(|e| e.to_string())?
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+    if commits.is_empty() {
+        return Err("No commits in range".into());
+    }
+
+    let merges = run_git(&["rev-list", "--merges", &rev_range], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !merges.trim().is_empty() {
+        return Err("The range contains merge commits; rewrite a linear range instead".into());
+    }
+
+    let unpushed = run_git(&["rev-list", &rev_range, "--not", "--remotes"], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    if unpushed.lines().filter(|l| !l.is_empty()).count() != commits.len() {
+        return Err(
+            "Some commits in the range are already on a remote; refusing to rewrite them".into(),
+        );
+    }
+
+    let mut parent = base_hash.trim().to_string();
+    let mut rewritten = 0;
+
+    for commit in &commits {
+        let raw = run_git(&["cat-file", "commit", commit], &root)
+            .await
+            .map_err(|e| e.to_string())?;
+        let (headers, message) = raw.split_once("\n\n").unwrap_or((raw.as_str(), ""));
+
+        let mut tree = String::new();
+        let mut envs: Vec<(&str, String)> = Vec::new();
+        let mut changed = false;
+        for line in headers.lines() {
+            if let Some(value) = line.strip_prefix("tree ") {
+                tree = value.to_string();
+            } else if let Some(caps) = COMMIT_PERSON_REGEX.captures(line) {
+                let (name_key, email_key, date_key) = if &caps[1] == "author" {
+                    ("GIT_AUTHOR_NAME", "GIT_AUTHOR_EMAIL", "GIT_AUTHOR_DATE")
+                } else {
+                    (
+                        "GIT_COMMITTER_NAME",
+                        "GIT_COMMITTER_EMAIL",
+                        "GIT_COMMITTER_DATE",
+                    )
+                };
+                let matches = caps[3].eq_ignore_ascii_case(&from_email);
+                changed |= matches;
+                let (name, email) = if matches {
+                    (to_name.clone(), to_email.clone())
+                } else {
+                    (caps[2].to_string(), caps[3].to_string())
+                };
+                envs.push((name_key, name));
+                envs.push((email_key, email));
+                envs.push((date_key, format!("@{}", &caps[4])));
+            }
+        }
+        rewritten += usize::from(changed);
+
+        let env_refs: Vec<(&str, &str)> = envs.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let new_commit = run_git_with_env_input(
+            &["commit-tree", &tree, "-p", &parent],
+            &root,
+            &env_refs,
+            format!("{}\n", message).as_bytes(),
+        )
+        .await
+        .map_err(|e| format!("Failed to rewrite {}: {}", commit, e))?;
+        parent = new_commit.trim().to_string();
+    }
+
+    if rewritten == 0 {
+        return Err(format!("No commits in range were authored by {}", from_email).into());
+    }
+
+    let backup_ref = format!(
+        "refs/openchamber/backup/rewrite-authors-{}",
+        chrono::Utc::now().timestamp()
+    );
+    run_git(&["update-ref", &backup_ref, &old_head], &root)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let branch_ref = format!("refs/heads/{}", branch);
+    let reflog_message = format!("openchamber: rewrite authors {}", rev_range);
+    run_git(
+        &[
+            "update-ref",
+            "-m",
+            &reflog_message,
+            &branch_ref,
+            &parent,
+            &old_head,
+        ],
+        &root,
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    info!(
+        "[git] Rewrote {} of {} commits on {} (backup at {})",
+        rewritten,
+        commits.len(),
+        branch,
+        backup_ref
+    );
+
+    Ok(RewriteAuthorsResult {
+        total: commits.len(),
+        rewritten,
+        old_head,
+        new_head: parent,
+        backup_ref,
+    })
+}
+
+/// Manifest file names whose dependency lists are compared in change summaries
+const DEPENDENCY_MANIFESTS: &[&str] = &["package.json", "Cargo.toml", "go.mod", "requirements.txt"];
+
+static CARGO_SECTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\[(?:target\.[^\]]+\.)?((?:dev-|build-)?dependencies)(?:\.([A-Za-z0-9_-]+))?\]$")
+        .unwrap()
+});
+static CARGO_DEPENDENCY_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Za-z0-9_-]+)\s*=").unwrap());
+static REQUIREMENT_NAME_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Za-z0-9][A-Za-z0-9._-]*)").unwrap());
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyChange {
+    pub manifest: String,
+    pub name: String,
+    pub section: String,
+}
 
-    let message: GeneratedCommitMessage =
-        serde_json::from_str(cleaned).map_err(|e| format!("Failed to parse AI response: {}", e))?;
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeSummary {
+    pub base: Option<String>,
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+    pub renamed: Vec<WorktreeFileDiff>,
+    pub insertions: u32,
+    pub deletions: u32,
+    pub new_dependencies: Vec<DependencyChange>,
+    pub removed_dependencies: Vec<DependencyChange>,
+}
+
+/// (section, name) pairs declared in a dependency manifest
+fn parse_manifest_dependencies(file_name: &str, content: &str) -> HashSet<(String, String)> {
+    let mut deps = HashSet::new();
+    match file_name {
+        "package.json" => {
+            let Ok(json) = serde_json::from_str::<Value>(content) else {
+                return deps;
+            };
+            for section in [
+                "dependencies",
+                "devDependencies",
+                "peerDependencies",
+                "optionalDependencies",
+            ] {
+                if let Some(map) = json.get(section).and_then(|v| v.as_object()) {
+                    deps.extend(map.keys().map(|name| (section.to_string(), name.clone())));
+                }
+            }
+        }
+        "Cargo.toml" => {
+            let mut section: Option<String> = None;
+            for line in content.lines().map(str::trim) {
+                if line.starts_with('[') {
+                    section = None;
+                    if let Some(caps) = CARGO_SECTION_REGEX.captures(line) {
+                        // `[dependencies.foo]` declares `foo` itself
+                        match caps.get(2) {
+                            Some(name) => {
+                                deps.insert((caps[1].to_string(), name.as_str().to_string()));
+                            }
+                            None => section = Some(caps[1].to_string()),
+                        }
+                    }
+                } else if let (Some(section), Some(caps)) =
+                    (&section, CARGO_DEPENDENCY_REGEX.captures(line))
+                {
+                    deps.insert((section.clone(), caps[1].to_string()));
+                }
+            }
+        }
+        "go.mod" => {
+            let mut in_block = false;
+            for line in content.lines().map(str::trim) {
+                let spec = if in_block {
+                    if line.starts_with(')') {
+                        in_block = false;
+                        continue;
+                    }
+                    line
+                } else if line == "require (" {
+                    in_block = true;
+                    continue;
+                } else if let Some(rest) = line.strip_prefix("require ") {
+                    rest
+                } else {
+                    continue;
+                };
+                let module = spec.split_whitespace().next();
+                if let Some(module) = module.filter(|m| !m.starts_with("//")) {
+                    deps.insert(("require".to_string(), module.to_string()));
+                }
+            }
+        }
+        _ => {
+            for line in content.lines().map(str::trim) {
+                if line.starts_with('#') || line.starts_with('-') {
+                    continue;
+                }
+                if let Some(caps) = REQUIREMENT_NAME_REGEX.captures(line) {
+                    deps.insert(("requirements".to_string(), caps[1].to_ascii_lowercase()));
+                }
+            }
+        }
+    }
+    deps
+}
+
+/// Resolve a checkpoint (any commit-ish) or a timestamp (RFC 3339 or unix seconds) to
+/// the commit the summary is measured from. `None` means "before the first commit".
+async fn resolve_summary_base(root: &Path, checkpoint: &str) -> Result<Option<String>> {
+    let spec = format!("{}^{{commit}}", checkpoint);
+    if let Ok(hash) = run_git(&["rev-parse", "--verify", "--quiet", &spec], root).await {
+        return Ok(Some(hash.trim().to_string()));
+    }
+
+    let timestamp = chrono::DateTime::parse_from_rfc3339(checkpoint)
+        .map(|dt| dt.timestamp())
+        .ok()
+        .or_else(|| checkpoint.parse::<i64>().ok())
+        .ok_or_else(|| anyhow!("Unknown checkpoint or timestamp: {}", checkpoint))?;
+    let before = format!("--before=@{}", timestamp);
+    let hash = run_git(&["rev-list", "-1", &before, "HEAD"], root)
+        .await
+        .unwrap_or_default();
+    Ok(Some(hash.trim().to_string()).filter(|h| !h.is_empty()))
+}
+
+/// Aggregate everything that changed since a checkpoint or point in time, including
+/// uncommitted and untracked files, for post-agent-run reports
+#[tauri::command]
+pub async fn summarize_changes_since(
+    directory: String,
+    checkpoint_or_timestamp: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<ChangeSummary, GitError> {
+    let root = validate_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let checkpoint = checkpoint_or_timestamp.trim();
+    let base = resolve_summary_base(&root, checkpoint)
+        .await
+        .map_err(|e| e.to_string())?;
+    let base_tree = base.clone().unwrap_or_else(|| EMPTY_TREE_HASH.to_string());
+    let current_tree = snapshot_worktree_tree(&root)
+        .await
+        .map_err(|e| format!("Failed to snapshot working tree: {}", e))?;
+
+    let rename_arg = DiffPreferences::load(state.settings()).await.rename_arg();
+    let diff_args = |mode: &'static str| {
+        [
+            "-c",
+            "core.quotePath=false",
+            "diff-tree",
+            "-r",
+            rename_arg.as_str(),
+            mode,
+            base_tree.as_str(),
+            current_tree.as_str(),
+        ]
+    };
+    let name_status = run_git(&diff_args("--name-status"), &root)
+        .await
+        .map_err(|e| e.to_string())?;
+    let numstat = run_git(&diff_args("--numstat"), &root)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut summary = ChangeSummary {
+        base: base.clone(),
+        added: Vec::new(),
+        modified: Vec::new(),
+        deleted: Vec::new(),
+        renamed: Vec::new(),
+        insertions: 0,
+        deletions: 0,
+        new_dependencies: Vec::new(),
+        removed_dependencies: Vec::new(),
+    };
+    let mut manifests = Vec::new();
+
+    for file in pair_file_diffs(&name_status, &numstat) {
+        summary.insertions += file.insertions.unwrap_or(0);
+        summary.deletions += file.deletions.unwrap_or(0);
+
+        let file_name = file.path.rsplit('/').next().unwrap_or_default().to_string();
+        let is_manifest = DEPENDENCY_MANIFESTS.contains(&file_name.as_str())
+            || (file_name.starts_with("requirements") && file_name.ends_with(".txt"));
+        if is_manifest && file.status != "D" {
+            let old_path = file.old_path.clone().unwrap_or_else(|| file.path.clone());
+            manifests.push((old_path, file.path.clone(), file_name));
+        }
+
+        match file.status.as_str() {
+            "A" => summary.added.push(file.path),
+            "D" => summary.deleted.push(file.path),
+            "R" | "C" => summary.renamed.push(file),
+            _ => summary.modified.push(file.path),
+        }
+    }
+
+    for (old_path, path, file_name) in manifests {
+        let read = |tree: &str, path: &str| {
+            let root = root.clone();
+            let spec = format!("{}:{}", tree, path);
+            async move { run_git(&["show", &spec], &root).await.unwrap_or_default() }
+        };
+        let before = parse_manifest_dependencies(&file_name, &read(&base_tree, &old_path).await);
+        let after = parse_manifest_dependencies(&file_name, &read(&current_tree, &path).await);
+
+        let to_change = |(section, name): &(String, String)| DependencyChange {
+            manifest: path.clone(),
+            name: name.clone(),
+            section: section.clone(),
+        };
+        let mut added: Vec<_> = after.difference(&before).map(to_change).collect();
+        let mut removed: Vec<_> = before.difference(&after).map(to_change).collect();
+        added.sort_by(|a, b| a.name.cmp(&b.name));
+        removed.sort_by(|a, b| a.name.cmp(&b.name));
+        summary.new_dependencies.extend(added);
+        summary.removed_dependencies.extend(removed);
+    }
 
-    Ok(CommitMessageResponse { message })
+    Ok(summary)
 }
@@ -1,7 +1,14 @@
+pub mod attachments;
+pub mod config_integrity;
+pub mod editors;
 pub mod files;
 pub mod git;
 pub mod logs;
 pub mod permissions;
+pub mod project;
+pub mod providers;
 pub mod settings;
+pub mod skills;
 pub mod terminal;
+pub mod trash;
 pub mod notifications;
@@ -82,6 +82,14 @@ pub async fn process_directory_selection(
         normalized_path
     );
 
+    if let Err(err) = crate::commands::git::apply_mapped_identity(&path_buf, state.settings()).await
+    {
+        warn!(
+            "[permissions] Applying mapped identity to {}: {}",
+            normalized_path, err
+        );
+    }
+
     Ok(DirectoryPermissionResult {
         success: true,
         path: Some(normalized_path),
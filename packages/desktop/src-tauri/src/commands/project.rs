@@ -0,0 +1,123 @@
+use log::info;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use tauri::State;
+use tokio::fs;
+
+use crate::commands::git::{
+    ensure_openchamber_excluded, is_git_work_tree, validate_writable_git_path,
+};
+use crate::DesktopRuntime;
+
+const PROJECT_CONFIG_DIR: &str = ".openchamber";
+const PROJECT_SETTINGS_FILE: &str = "project.json";
+const PROJECT_COMMANDS_FILE: &str = "commands.json";
+const PROJECT_CHECKPOINTS_FILE: &str = "checkpoints.json";
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InitProjectResult {
+    pub root: String,
+    pub created: Vec<String>,
+    pub existing: Vec<String>,
+    pub excluded: bool,
+}
+
+fn starter_files(root: &Path) -> Vec<(&'static str, Value)> {
+    let project_name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    vec![
+        (
+            PROJECT_SETTINGS_FILE,
+            json!({
+                "version": 1,
+                "name": project_name,
+                "defaultAgent": null,
+                "defaultModel": null,
+            }),
+        ),
+        (
+            PROJECT_COMMANDS_FILE,
+            json!({
+                "version": 1,
+                "commands": [],
+            }),
+        ),
+        (
+            PROJECT_CHECKPOINTS_FILE,
+            json!({
+                "version": 1,
+                "enabled": true,
+                "checkpointBeforeAgentRun": true,
+                "maxCheckpoints": 20,
+            }),
+        ),
+    ]
+}
+
+/// Scaffold the `.openchamber/` project directory without touching existing files
+#[tauri::command]
+pub async fn init_openchamber_project(
+    directory: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<InitProjectResult, String> {
+    let root = validate_writable_git_path(&directory, state.settings())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let config_dir = root.join(PROJECT_CONFIG_DIR);
+    let mut created = Vec::new();
+    let mut existing = Vec::new();
+
+    if config_dir.is_dir() {
+        existing.push(format!("{}/", PROJECT_CONFIG_DIR));
+    } else {
+        fs::create_dir_all(&config_dir)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", PROJECT_CONFIG_DIR, e))?;
+        created.push(format!("{}/", PROJECT_CONFIG_DIR));
+    }
+
+    for (file_name, content) in starter_files(&root) {
+        let relative = format!("{}/{}", PROJECT_CONFIG_DIR, file_name);
+        let path = config_dir.join(file_name);
+        if fs::metadata(&path).await.is_ok() {
+            existing.push(relative);
+            continue;
+        }
+
+        let bytes = serde_json::to_vec_pretty(&content).map_err(|e| e.to_string())?;
+        fs::write(&path, bytes)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", relative, e))?;
+        created.push(relative);
+    }
+
+    // Only git repositories have an exclude file to update
+    let excluded = if is_git_work_tree(&root).await {
+        ensure_openchamber_excluded(&root)
+            .await
+            .map_err(|e| e.to_string())?;
+        true
+    } else {
+        false
+    };
+
+    info!(
+        "[project] Initialized {} in {:?} ({} created)",
+        PROJECT_CONFIG_DIR,
+        root,
+        created.len()
+    );
+
+    Ok(InitProjectResult {
+        root: root.to_string_lossy().to_string(),
+        created,
+        existing,
+        excluded,
+    })
+}
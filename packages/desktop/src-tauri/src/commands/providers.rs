@@ -0,0 +1,407 @@
+use log::{info, warn};
+use reqwest::{Client, RequestBuilder};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::opencode_auth;
+use crate::DesktopRuntime;
+
+const PROVIDER_TEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How a provider's key is checked: a cheap authenticated request that does not
+/// spend tokens (listing models or key info)
+#[derive(Clone, Copy)]
+enum KeyCheck {
+    /// `x-api-key` header plus `anthropic-version`
+    Anthropic,
+    /// `Authorization: Bearer <key>`
+    Bearer(&'static str),
+    /// `?key=<key>` query parameter
+    QueryKey(&'static str),
+}
+
+const KNOWN_KEY_CHECKS: &[(&str, KeyCheck)] = &[
+    ("anthropic", KeyCheck::Anthropic),
+    (
+        "openai",
+        KeyCheck::Bearer("https://api.openai.com/v1/models"),
+    ),
+    (
+        "openrouter",
+        KeyCheck::Bearer("https://openrouter.ai/api/v1/key"),
+    ),
+    (
+        "opencode",
+        KeyCheck::Bearer("https://opencode.ai/zen/v1/models"),
+    ),
+    (
+        "groq",
+        KeyCheck::Bearer("https://api.groq.com/openai/v1/models"),
+    ),
+    (
+        "mistral",
+        KeyCheck::Bearer("https://api.mistral.ai/v1/models"),
+    ),
+    (
+        "deepseek",
+        KeyCheck::Bearer("https://api.deepseek.com/models"),
+    ),
+    ("xai", KeyCheck::Bearer("https://api.x.ai/v1/models")),
+    (
+        "together",
+        KeyCheck::Bearer("https://api.together.xyz/v1/models"),
+    ),
+    (
+        "fireworks",
+        KeyCheck::Bearer("https://api.fireworks.ai/inference/v1/models"),
+    ),
+    (
+        "google",
+        KeyCheck::QueryKey("https://generativelanguage.googleapis.com/v1beta/models"),
+    ),
+];
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCredential {
+    pub id: String,
+    pub name: String,
+    pub connected: bool,
+    /// Credential type stored in OpenCode's auth store (`api`, `oauth`, `wellknown`)
+    pub auth_type: Option<String>,
+    /// Where the credential comes from: `auth` (auth store), `env` or `config`
+    pub source: Option<String>,
+    /// Environment variables OpenCode reads for this provider
+    pub env: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderKeyTestResult {
+    pub provider_id: String,
+    pub valid: bool,
+    pub status: Option<u16>,
+    pub message: String,
+    pub latency_ms: u64,
+}
+
+fn opencode_base_url(runtime: &DesktopRuntime) -> Option<String> {
+    let opencode = runtime.opencode_manager();
+    let port = opencode.current_port()?;
+    Some(format!("http://127.0.0.1:{port}{}", opencode.api_prefix()))
+}
+
+/// `GET /provider` from the running OpenCode server: `{ all, connected, default }`
+async fn fetch_opencode_providers(client: &Client, base: &str) -> Result<Value, String> {
+    let response = client
+        .get(format!("{base}/provider"))
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    if !response.status().is_success() {
+        return Err(format!("OpenCode returned {}", response.status()));
+    }
+    response.json::<Value>().await.map_err(|e| e.to_string())
+}
+
+fn stored_key(auth: &Value, provider_id: &str) -> Option<String> {
+    let entry = auth.get(provider_id)?;
+    if entry.get("type").and_then(Value::as_str) != Some("api") {
+        return None;
+    }
+    entry.get("key").and_then(Value::as_str).map(str::to_string)
+}
+
+async fn restart_opencode(runtime: &DesktopRuntime, reason: &str) -> Result<(), String> {
+    let opencode = runtime.opencode_manager();
+    if !opencode.is_cli_available() {
+        return Ok(());
+    }
+    info!("[desktop:providers] Restarting OpenCode after {}", reason);
+    opencode
+        .restart()
+        .await
+        .map_err(|e| format!("Failed to restart OpenCode: {}", e))
+}
+
+fn validate_provider_id(provider_id: &str) -> Result<&str, String> {
+    let trimmed = provider_id.trim();
+    if trimmed.is_empty() {
+        return Err("Provider ID is required".to_string());
+    }
+    if trimmed.contains(['/', '\\']) || trimmed.contains("..") {
+        return Err("Invalid provider ID".to_string());
+    }
+    Ok(trimmed)
+}
+
+/// Providers known to the local OpenCode installation and whether it has credentials
+/// for them. Falls back to the auth store alone while OpenCode is not running.
+#[tauri::command]
+pub async fn list_provider_credentials(
+    state: State<'_, DesktopRuntime>,
+) -> Result<Vec<ProviderCredential>, String> {
+    let auth = opencode_auth::read_auth()
+        .await
+        .map_err(|e| e.to_string())?;
+    let auth_entries = auth.as_object().cloned().unwrap_or_default();
+    let auth_type_of = |id: &str| {
+        auth_entries
+            .get(id)
+            .and_then(|entry| entry.get("type"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    let providers = match opencode_base_url(&state) {
+        Some(base) => match fetch_opencode_providers(&Client::new(), &base).await {
+            Ok(providers) => Some(providers),
+            Err(err) => {
+                warn!(
+                    "[desktop:providers] Failed to list OpenCode providers: {}",
+                    err
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    let mut credentials = Vec::new();
+    if let Some(providers) = providers {
+        let connected: Vec<&str> = providers
+            .get("connected")
+            .and_then(Value::as_array)
+            .map(|ids| ids.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        for provider in providers
+            .get("all")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let Some(id) = provider.get("id").and_then(Value::as_str) else {
+                continue;
+            };
+            let auth_type = auth_type_of(id);
+            let is_connected = connected.contains(&id) || auth_type.is_some();
+            let source = if auth_type.is_some() {
+                Some("auth".to_string())
+            } else if is_connected {
+                provider
+                    .get("source")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .or_else(|| Some("env".to_string()))
+            } else {
+                None
+            };
+            credentials.push(ProviderCredential {
+                id: id.to_string(),
+                name: provider
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or(id)
+                    .to_string(),
+                connected: is_connected,
+                auth_type,
+                source,
+                env: provider
+                    .get("env")
+                    .and_then(Value::as_array)
+                    .map(|vars| {
+                        vars.iter()
+                            .filter_map(Value::as_str)
+                            .map(str::to_string)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            });
+        }
+    }
+
+    // Credentials for providers OpenCode did not report (or OpenCode is down)
+    for (id, entry) in &auth_entries {
+        if credentials.iter().any(|c| &c.id == id) {
+            continue;
+        }
+        credentials.push(ProviderCredential {
+            id: id.clone(),
+            name: id.clone(),
+            connected: true,
+            auth_type: entry
+                .get("type")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            source: Some("auth".to_string()),
+            env: Vec::new(),
+        });
+    }
+
+    credentials.sort_by(|a, b| {
+        b.connected
+            .cmp(&a.connected)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(credentials)
+}
+
+/// Store an API key in OpenCode's auth store, then restart OpenCode so it is picked up
+#[tauri::command]
+pub async fn set_provider_api_key(
+    provider_id: String,
+    api_key: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<(), String> {
+    let provider_id = validate_provider_id(&provider_id)?;
+    let api_key = api_key.trim();
+    if api_key.is_empty() {
+        return Err("API key is required".to_string());
+    }
+    let credential = json!({ "type": "api", "key": api_key });
+
+    // Prefer OpenCode's own auth endpoint so it owns the file format
+    let mut stored = false;
+    if let Some(base) = opencode_base_url(&state) {
+        match Client::new()
+            .put(format!("{base}/auth/{provider_id}"))
+            .json(&credential)
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => stored = true,
+            Ok(response) => warn!(
+                "[desktop:providers] OpenCode rejected auth update for {}: {}",
+                provider_id,
+                response.status()
+            ),
+            Err(err) => warn!("[desktop:providers] OpenCode auth update failed: {}", err),
+        }
+    }
+
+    if !stored {
+        let mut auth = opencode_auth::read_auth()
+            .await
+            .map_err(|e| e.to_string())?;
+        let entries = auth
+            .as_object_mut()
+            .ok_or_else(|| "Auth file is not a valid JSON object".to_string())?;
+        entries.insert(provider_id.to_string(), credential);
+        opencode_auth::write_auth(&auth)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    info!("[desktop:providers] Stored API key for {}", provider_id);
+    restart_opencode(&state, &format!("provider {} connected", provider_id)).await
+}
+
+/// Remove a provider's stored credential. Returns false when none was stored.
+#[tauri::command]
+pub async fn remove_provider_api_key(
+    provider_id: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<bool, String> {
+    let provider_id = validate_provider_id(&provider_id)?;
+    let removed = opencode_auth::remove_provider_auth(provider_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    if removed {
+        restart_opencode(&state, &format!("provider {} disconnected", provider_id)).await?;
+    }
+    Ok(removed)
+}
+
+/// Check a key with a minimal authenticated request. Without `api_key` the key stored
+/// in the auth store is tested.
+#[tauri::command]
+pub async fn test_provider_api_key(
+    provider_id: String,
+    api_key: Option<String>,
+    state: State<'_, DesktopRuntime>,
+) -> Result<ProviderKeyTestResult, String> {
+    let provider_id = validate_provider_id(&provider_id)?.to_string();
+    let key = match api_key
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+    {
+        Some(key) => key,
+        None => {
+            let auth = opencode_auth::read_auth()
+                .await
+                .map_err(|e| e.to_string())?;
+            stored_key(&auth, &provider_id)
+                .ok_or_else(|| format!("No API key stored for {}", provider_id))?
+        }
+    };
+
+    let client = Client::builder()
+        .timeout(PROVIDER_TEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let check = KNOWN_KEY_CHECKS
+        .iter()
+        .find(|(id, _)| *id == provider_id)
+        .map(|(_, check)| *check);
+    let request: RequestBuilder = match check {
+        Some(KeyCheck::Anthropic) => client
+            .get("https://api.anthropic.com/v1/models")
+            .header("x-api-key", &key)
+            .header("anthropic-version", "2023-06-01"),
+        Some(KeyCheck::Bearer(url)) => client.get(url).bearer_auth(&key),
+        Some(KeyCheck::QueryKey(url)) => client.get(url).query(&[("key", key.as_str())]),
+        None => {
+            // OpenAI-compatible providers advertise their base URL through OpenCode
+            let base = opencode_base_url(&state).ok_or_else(|| {
+                format!("Cannot test {} while OpenCode is not running", provider_id)
+            })?;
+            let providers = fetch_opencode_providers(&client, &base).await?;
+            let api_url = providers
+                .get("all")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .find(|p| p.get("id").and_then(Value::as_str) == Some(provider_id.as_str()))
+                .and_then(|p| p.get("api"))
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("No API endpoint known for {}", provider_id))?;
+            client
+                .get(format!("{}/models", api_url.trim_end_matches('/')))
+                .bearer_auth(&key)
+        }
+    };
+
+    let started = Instant::now();
+    let result = request.send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(match result {
+        Ok(response) => {
+            let status = response.status();
+            let message = if status.is_success() {
+                "Key accepted".to_string()
+            } else if status.as_u16() == 401 || status.as_u16() == 403 {
+                "Key rejected by provider".to_string()
+            } else {
+                format!("Provider responded with {}", status)
+            };
+            ProviderKeyTestResult {
+                provider_id,
+                valid: status.is_success(),
+                status: Some(status.as_u16()),
+                message,
+                latency_ms,
+            }
+        }
+        Err(err) => ProviderKeyTestResult {
+            provider_id,
+            valid: false,
+            status: None,
+            message: format!("Request failed: {}", err),
+            latency_ms,
+        },
+    })
+}
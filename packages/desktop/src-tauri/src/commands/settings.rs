@@ -3,6 +3,7 @@ use serde_json::{json, Value};
 use std::collections::HashSet;
 use tauri::State;
 
+use crate::ai_completion;
 use crate::DesktopRuntime;
 use crate::path_utils::expand_tilde_path;
 
@@ -136,11 +137,34 @@ fn sanitize_settings_update(payload: &Value) -> Value {
                 result_obj.insert("defaultModel".to_string(), json!(s));
             }
         }
+        for key in [ai_completion::AI_ENDPOINT_KEY, ai_completion::AI_MODEL_KEY] {
+            if let Some(Value::String(s)) = obj.get(key) {
+                result_obj.insert(key.to_string(), json!(s.trim()));
+            }
+        }
         if let Some(Value::String(s)) = obj.get("defaultAgent") {
             if !s.is_empty() {
                 result_obj.insert("defaultAgent".to_string(), json!(s));
             }
         }
+        // An empty string or null clears the choice; merge drops null keys
+        match obj.get("preferredEditor") {
+            Some(Value::String(s)) if !s.trim().is_empty() => {
+                result_obj.insert("preferredEditor".to_string(), json!(s));
+            }
+            Some(Value::String(_)) | Some(Value::Null) => {
+                result_obj.insert("preferredEditor".to_string(), Value::Null);
+            }
+            _ => {}
+        }
+        if let Some(Value::String(s)) = obj.get("diffWhitespace") {
+            if matches!(
+                s.as_str(),
+                "show" | "ignore-all" | "ignore-change" | "ignore-eol"
+            ) {
+                result_obj.insert("diffWhitespace".to_string(), json!(s));
+            }
+        }
 
         // Boolean fields
         if let Some(Value::Bool(b)) = obj.get("useSystemTheme") {
@@ -155,6 +179,9 @@ fn sanitize_settings_update(payload: &Value) -> Value {
         if let Some(Value::Bool(b)) = obj.get("queueModeEnabled") {
             result_obj.insert("queueModeEnabled".to_string(), json!(b));
         }
+        if let Some(Value::Bool(b)) = obj.get("backgroundFetchEnabled") {
+            result_obj.insert("backgroundFetchEnabled".to_string(), json!(b));
+        }
 
         // Number fields
         if let Some(Value::Number(n)) = obj.get("autoDeleteAfterDays") {
@@ -168,6 +195,42 @@ fn sanitize_settings_update(payload: &Value) -> Value {
             }
         }
 
+        if let Some(Value::Number(n)) = obj.get("backgroundFetchIntervalMinutes") {
+            let parsed = n
+                .as_u64()
+                .or_else(|| n.as_f64().map(|value| value.round().max(0.0) as u64));
+            if let Some(value) = parsed {
+                let clamped = value.clamp(1, 24 * 60);
+                result_obj.insert("backgroundFetchIntervalMinutes".to_string(), json!(clamped));
+            }
+        }
+        if let Some(Value::Number(n)) = obj.get("diffContextLines") {
+            let parsed = n
+                .as_u64()
+                .or_else(|| n.as_f64().map(|value| value.round().max(0.0) as u64));
+            if let Some(value) = parsed {
+                result_obj.insert("diffContextLines".to_string(), json!(value.min(100)));
+            }
+        }
+        if let Some(Value::Number(n)) = obj.get("diffRenameSimilarity") {
+            let parsed = n
+                .as_u64()
+                .or_else(|| n.as_f64().map(|value| value.round().max(0.0) as u64));
+            if let Some(value) = parsed {
+                let clamped = value.clamp(1, 100);
+                result_obj.insert("diffRenameSimilarity".to_string(), json!(clamped));
+            }
+        }
+        if let Some(Value::Number(n)) = obj.get("attachmentsQuotaMb") {
+            let parsed = n
+                .as_u64()
+                .or_else(|| n.as_f64().map(|value| value.round().max(0.0) as u64));
+            if let Some(value) = parsed {
+                let clamped = value.clamp(1, 102_400);
+                result_obj.insert("attachmentsQuotaMb".to_string(), json!(clamped));
+            }
+        }
+
         // Array fields
         if let Some(arr) = obj.get("approvedDirectories") {
             result_obj.insert(
@@ -184,6 +247,12 @@ fn sanitize_settings_update(payload: &Value) -> Value {
         if let Some(arr) = obj.get("pinnedDirectories") {
             result_obj.insert("pinnedDirectories".to_string(), normalize_string_array(arr));
         }
+        if let Some(arr) = obj.get("readOnlyDirectories") {
+            result_obj.insert(
+                "readOnlyDirectories".to_string(),
+                normalize_string_array(arr),
+            );
+        }
 
         // Typography sizes object (partial)
         if let Some(typo) = obj.get("typographySizes") {
@@ -191,48 +260,6 @@ fn sanitize_settings_update(payload: &Value) -> Value {
                 result_obj.insert("typographySizes".to_string(), sanitized);
             }
         }
-
-        // Skill catalogs (array of objects)
-        if let Some(Value::Array(arr)) = obj.get("skillCatalogs") {
-            let mut seen: HashSet<String> = HashSet::new();
-            let mut catalogs: Vec<Value> = vec![];
-
-            for entry in arr {
-                let Some(obj) = entry.as_object() else { continue };
-
-                let id = obj.get("id").and_then(|v| v.as_str()).unwrap_or("").trim();
-                let label = obj.get("label").and_then(|v| v.as_str()).unwrap_or("").trim();
-                let source = obj.get("source").and_then(|v| v.as_str()).unwrap_or("").trim();
-                let subpath = obj.get("subpath").and_then(|v| v.as_str()).unwrap_or("").trim();
-                let git_identity_id = obj.get("gitIdentityId").and_then(|v| v.as_str()).unwrap_or("").trim();
-
-                if id.is_empty() || label.is_empty() || source.is_empty() {
-                    continue;
-                }
-
-                if seen.contains(id) {
-                    continue;
-                }
-                seen.insert(id.to_string());
-
-                let mut catalog = serde_json::Map::new();
-                catalog.insert("id".to_string(), json!(id));
-                catalog.insert("label".to_string(), json!(label));
-                catalog.insert("source".to_string(), json!(source));
-                if !subpath.is_empty() {
-                    catalog.insert("subpath".to_string(), json!(subpath));
-                }
-                if !git_identity_id.is_empty() {
-                    catalog.insert("gitIdentityId".to_string(), json!(git_identity_id));
-                }
-
-                catalogs.push(Value::Object(catalog));
-            }
-
-            if !catalogs.is_empty() {
-                result_obj.insert("skillCatalogs".to_string(), Value::Array(catalogs));
-            }
-        }
     }
 
     result
@@ -243,9 +270,13 @@ fn merge_persisted_settings(current: &Value, changes: &Value) -> Value {
     let mut result = current.clone();
 
     if let (Some(result_obj), Some(changes_obj)) = (result.as_object_mut(), changes.as_object()) {
-        // First apply all changes
+        // First apply all changes; a null value removes the key
         for (key, value) in changes_obj {
-            result_obj.insert(key.clone(), value.clone());
+            if value.is_null() {
+                result_obj.remove(key);
+            } else {
+                result_obj.insert(key.clone(), value.clone());
+            }
         }
 
         // Build approvedDirectories from base + additional
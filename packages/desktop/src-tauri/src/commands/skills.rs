@@ -0,0 +1,39 @@
+use tauri::State;
+
+use crate::skills_catalog::{
+    self, SkillCatalogSourceInput, SkillCatalogSourceResponse, SkillValidationResponse,
+};
+use crate::DesktopRuntime;
+
+/// Add a custom skills catalog after a test scan finds skills in it
+#[tauri::command]
+pub async fn add_skill_catalog_source(
+    source: SkillCatalogSourceInput,
+    state: State<'_, DesktopRuntime>,
+) -> Result<SkillCatalogSourceResponse, String> {
+    Ok(skills_catalog::add_catalog_source(state.settings(), source).await)
+}
+
+/// Replace a custom catalog's settings; the new source is test-scanned first
+#[tauri::command]
+pub async fn update_skill_catalog_source(
+    id: String,
+    source: SkillCatalogSourceInput,
+    state: State<'_, DesktopRuntime>,
+) -> Result<SkillCatalogSourceResponse, String> {
+    Ok(skills_catalog::update_catalog_source(state.settings(), &id, source).await)
+}
+
+#[tauri::command]
+pub async fn remove_skill_catalog_source(
+    id: String,
+    state: State<'_, DesktopRuntime>,
+) -> Result<SkillCatalogSourceResponse, String> {
+    Ok(skills_catalog::remove_catalog_source(state.settings(), &id).await)
+}
+
+/// Lint a local skill directory (or its SKILL.md) before sharing or installing it
+#[tauri::command]
+pub async fn validate_skill(path: String) -> Result<SkillValidationResponse, String> {
+    Ok(skills_catalog::validate_skill(&path).await)
+}
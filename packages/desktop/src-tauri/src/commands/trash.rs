@@ -0,0 +1,151 @@
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Mutex;
+use tokio::fs;
+
+/// Items moved to the OS trash by this app during the current session.
+static SESSION_TRASH: Lazy<Mutex<Vec<TrashedEntry>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashedEntry {
+    pub id: String,
+    pub original_path: String,
+    pub is_directory: bool,
+    pub deleted_at: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreFromTrashResult {
+    pub success: bool,
+    pub path: String,
+}
+
+/// Remove a file or directory, moving it to the OS trash unless `permanent` is set.
+/// Returns `true` when the item went to the trash (and can be restored this session).
+pub(crate) async fn remove_path(path: &Path, permanent: bool) -> Result<bool> {
+    let metadata = fs::symlink_metadata(path).await?;
+    let is_directory = metadata.is_dir();
+
+    if permanent {
+        if is_directory {
+            fs::remove_dir_all(path).await?;
+        } else {
+            fs::remove_file(path).await?;
+        }
+        return Ok(false);
+    }
+
+    let target = path.to_path_buf();
+    tokio::task::spawn_blocking(move || trash::delete(&target))
+        .await
+        .map_err(|e| anyhow!("Trash task failed: {}", e))?
+        .map_err(|e| anyhow!("Failed to move {} to trash: {}", path.display(), e))?;
+
+    let entry = TrashedEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        original_path: path.to_string_lossy().to_string(),
+        is_directory,
+        deleted_at: chrono::Utc::now().timestamp(),
+    };
+    info!("[trash] Moved {} to trash", entry.original_path);
+    SESSION_TRASH.lock().unwrap().push(entry);
+
+    Ok(true)
+}
+
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn restore_trashed_entry(entry: &TrashedEntry) -> Result<()> {
+    let original = std::path::PathBuf::from(&entry.original_path);
+    let mut candidates: Vec<trash::TrashItem> = trash::os_limited::list()
+        .map_err(|e| anyhow!("Failed to list trash: {}", e))?
+        .into_iter()
+        .filter(|item| {
+            item.original_path() == original && item.time_deleted >= entry.deleted_at - 5
+        })
+        .collect();
+
+    // Several deletions of the same path may exist; restore the most recent one.
+    candidates.sort_by_key(|item| item.time_deleted);
+    let item = candidates
+        .pop()
+        .ok_or_else(|| anyhow!("Item is no longer in the trash"))?;
+
+    trash::os_limited::restore_all([item]).map_err(|e| anyhow!("Failed to restore item: {}", e))
+}
+
+// macOS has no public API to put a trashed item back, and the trash crate doesn't report
+// where the item ended up (another volume's .Trashes, or a renamed duplicate).
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+fn restore_trashed_entry(_entry: &TrashedEntry) -> Result<()> {
+    Err(anyhow!(
+        "Restoring from trash is not supported on this platform"
+    ))
+}
+
+#[tauri::command]
+pub async fn list_trashed_items() -> Result<Vec<TrashedEntry>, String> {
+    let entries = SESSION_TRASH.lock().unwrap().clone();
+    Ok(entries.into_iter().rev().collect())
+}
+
+#[tauri::command]
+pub async fn restore_from_trash(id: String) -> Result<RestoreFromTrashResult, String> {
+    let entry = SESSION_TRASH
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|entry| entry.id == id)
+        .cloned()
+        .ok_or_else(|| format!("Trashed item {} not found", id))?;
+
+    if Path::new(&entry.original_path).exists() {
+        return Err(format!(
+            "Cannot restore: {} already exists",
+            entry.original_path
+        ));
+    }
+
+    if let Some(parent) = Path::new(&entry.original_path).parent() {
+        fs::create_dir_all(parent)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    let restore_entry = entry.clone();
+    tokio::task::spawn_blocking(move || restore_trashed_entry(&restore_entry))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| {
+            warn!("[trash] Failed to restore {}: {}", entry.original_path, e);
+            e.to_string()
+        })?;
+
+    SESSION_TRASH.lock().unwrap().retain(|item| item.id != id);
+    info!("[trash] Restored {}", entry.original_path);
+
+    Ok(RestoreFromTrashResult {
+        success: true,
+        path: entry.original_path,
+    })
+}
@@ -0,0 +1,188 @@
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Mutex};
+
+pub const ASKPASS_REQUEST_EVENT: &str = "git:askpass-request";
+/// Header the helper script sends so only processes we spawned can prompt the UI
+pub const ASKPASS_TOKEN_HEADER: &str = "x-openchamber-askpass";
+/// How long a prompt waits for an answer before git is told it was cancelled
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+#[cfg(unix)]
+const SCRIPT_NAME: &str = "openchamber-askpass.sh";
+#[cfg(unix)]
+const SCRIPT_BODY: &str = r#"#!/bin/sh
+exec curl -sf --max-time 330 -X POST \
+  -H "X-OpenChamber-Askpass: $OPENCHAMBER_ASKPASS_TOKEN" \
+  --data-urlencode "prompt=$1" \
+  "http://127.0.0.1:$OPENCHAMBER_ASKPASS_PORT/askpass"
+"#;
+#[cfg(windows)]
+const SCRIPT_NAME: &str = "openchamber-askpass.cmd";
+#[cfg(windows)]
+const SCRIPT_BODY: &str = "@curl.exe -sf --max-time 330 -X POST \
+-H \"X-OpenChamber-Askpass: %OPENCHAMBER_ASKPASS_TOKEN%\" \
+--data-urlencode \"prompt=%~1\" \
+\"http://127.0.0.1:%OPENCHAMBER_ASKPASS_PORT%/askpass\"\r\n";
+
+struct Bridge {
+    port: u16,
+    token: String,
+    script: PathBuf,
+}
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AskpassRequest {
+    pub id: String,
+    pub prompt: String,
+    /// passphrase, password, username or confirm (host key verification)
+    pub kind: String,
+    /// Whether the UI should mask the input
+    pub secret: bool,
+}
+
+static BRIDGE: OnceCell<Bridge> = OnceCell::new();
+/// Set while a window listens for `ASKPASS_REQUEST_EVENT`; without one, prompts fail at
+/// once instead of stalling git until `PROMPT_TIMEOUT`
+static LISTENER_ACTIVE: AtomicBool = AtomicBool::new(false);
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+static PENDING: Lazy<Mutex<HashMap<String, oneshot::Sender<Option<String>>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Write the helper to `~/.config/openchamber`. A fixed name in the shared temp dir
+/// could be pre-created or symlinked by another local user, so the file is always
+/// created fresh (never followed through a link) inside a directory only we own.
+fn write_script() -> Result<PathBuf> {
+    let mut dir = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
+    dir.push(".config");
+    dir.push("openchamber");
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(SCRIPT_NAME);
+    // Left over from a previous run; removing unlinks a symlink rather than its target
+    if let Err(err) = std::fs::remove_file(&path) {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            return Err(err.into());
+        }
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o700);
+    }
+    let mut file = options.open(&path)?;
+    file.write_all(SCRIPT_BODY.as_bytes())?;
+    Ok(path)
+}
+
+/// Install the helper script used as `SSH_ASKPASS`/`GIT_ASKPASS`. It posts each prompt
+/// to the local HTTP server on `port`, which forwards it to the UI.
+pub fn init(app: &AppHandle, port: u16) {
+    let _ = APP_HANDLE.set(app.clone());
+    match write_script() {
+        Ok(script) => {
+            info!("[git-askpass] Helper installed at {:?}", script);
+            let _ = BRIDGE.set(Bridge {
+                port,
+                token: uuid::Uuid::new_v4().to_string(),
+                script,
+            });
+        }
+        Err(err) => warn!("[git-askpass] Failed to install helper: {}", err),
+    }
+}
+
+/// Environment for git processes that may need a passphrase or password. Empty when
+/// the bridge is not installed, in which case git fails as before.
+pub fn env() -> Vec<(&'static str, String)> {
+    let Some(bridge) = BRIDGE.get() else {
+        return Vec::new();
+    };
+    let script = bridge.script.to_string_lossy().to_string();
+    vec![
+        ("GIT_ASKPASS", script.clone()),
+        ("SSH_ASKPASS", script),
+        // OpenSSH 8.4+ uses SSH_ASKPASS without a DISPLAY when this is set; older
+        // versions without a display fail the prompt, as they did before
+        ("SSH_ASKPASS_REQUIRE", "force".to_string()),
+        ("OPENCHAMBER_ASKPASS_PORT", bridge.port.to_string()),
+        ("OPENCHAMBER_ASKPASS_TOKEN", bridge.token.clone()),
+    ]
+}
+
+pub fn is_valid_token(token: &str) -> bool {
+    BRIDGE.get().is_some_and(|bridge| bridge.token == token)
+}
+
+fn classify_prompt(prompt: &str) -> (&'static str, bool) {
+    let lower = prompt.to_ascii_lowercase();
+    if lower.contains("passphrase") {
+        ("passphrase", true)
+    } else if lower.contains("(yes/no") {
+        ("confirm", false)
+    } else if lower.starts_with("username") {
+        ("username", false)
+    } else {
+        ("password", true)
+    }
+}
+
+pub fn set_listener_active(active: bool) {
+    LISTENER_ACTIVE.store(active, Ordering::SeqCst);
+}
+
+/// Ask the UI to answer a prompt from git or ssh. `None` means cancelled, timed out, or
+/// that no window is listening.
+pub async fn prompt(prompt: String) -> Option<String> {
+    let app = APP_HANDLE.get()?;
+    if !LISTENER_ACTIVE.load(Ordering::SeqCst) {
+        warn!("[git-askpass] No prompt listener; cancelling {:?}", prompt);
+        return None;
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    let (tx, rx) = oneshot::channel();
+    PENDING.lock().await.insert(id.clone(), tx);
+
+    let (kind, secret) = classify_prompt(&prompt);
+    let request = AskpassRequest {
+        id: id.clone(),
+        prompt,
+        kind: kind.to_string(),
+        secret,
+    };
+    if let Err(err) = app.emit(ASKPASS_REQUEST_EVENT, &request) {
+        warn!("[git-askpass] Failed to forward prompt: {}", err);
+        PENDING.lock().await.remove(&id);
+        return None;
+    }
+
+    let answer = tokio::time::timeout(PROMPT_TIMEOUT, rx).await;
+    PENDING.lock().await.remove(&id);
+    match answer {
+        Ok(Ok(answer)) => answer,
+        _ => None,
+    }
+}
+
+/// Deliver the UI's answer to a pending prompt. Returns false for unknown ids.
+pub async fn respond(id: &str, response: Option<String>) -> bool {
+    match PENDING.lock().await.remove(id) {
+        Some(tx) => tx.send(response).is_ok(),
+        None => false,
+    }
+}
@@ -0,0 +1,276 @@
+use anyhow::{anyhow, Result};
+use log::warn;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+const GIT_CREDENTIALS_STORAGE_FILE: &str = "git-credentials.json";
+/// Keychain service name; each entry's account is the host
+const KEYRING_SERVICE: &str = "openchamber-git";
+/// Header the helper script sends with its grant
+pub const CREDENTIAL_GRANT_HEADER: &str = "x-openchamber-credential";
+/// Answers `get` by redeeming the grant in its environment with the local HTTP server.
+/// The token never enters the environment, which hooks and other children inherit.
+const CREDENTIAL_HELPER_SCRIPT: &str =
+    "!f() { test \"$1\" = get || exit 0; exec curl -sf --max-time 30 \
+     -H \"X-OpenChamber-Credential: $OPENCHAMBER_GIT_CREDENTIAL_{n}\" \
+     \"http://127.0.0.1:$OPENCHAMBER_GIT_CREDENTIAL_PORT/git-credential\"; }; f";
+
+/// A stored HTTPS token. The token itself lives in the OS keychain; only the host and
+/// username are written to `git-credentials.json`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitHostCredential {
+    pub host: String,
+    pub username: String,
+    pub updated_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct GitCredentialsFile {
+    credentials: Vec<GitHostCredential>,
+}
+
+struct CredentialGrant {
+    username: String,
+    token: String,
+}
+
+/// Credential helper environment for one git process and everything it spawns (per-remote
+/// fetches, git-lfs, follow-up commands on the same clone). The grants stay redeemable
+/// until this is dropped, so hold it until the last of those processes has exited.
+#[derive(Default)]
+pub struct CredentialEnv {
+    vars: Vec<(String, String)>,
+    grants: Vec<String>,
+}
+
+impl CredentialEnv {
+    pub fn vars(&self) -> &[(String, String)] {
+        &self.vars
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+}
+
+impl Drop for CredentialEnv {
+    fn drop(&mut self) {
+        if self.grants.is_empty() {
+            return;
+        }
+        let mut grants = GRANTS.lock().unwrap();
+        for grant in &self.grants {
+            grants.remove(grant);
+        }
+    }
+}
+
+/// Serializes read/modify/write of the index file
+static STORE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static SERVER_PORT: OnceCell<u16> = OnceCell::new();
+/// std mutex: grants are revoked from `CredentialEnv::drop`
+static GRANTS: Lazy<std::sync::Mutex<HashMap<String, CredentialGrant>>> =
+    Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Record the port of the local HTTP server the helper script redeems grants with
+pub fn init(port: u16) {
+    let _ = SERVER_PORT.set(port);
+}
+
+async fn storage_path() -> Result<PathBuf> {
+    let mut path = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
+    path.push(".config");
+    path.push("openchamber");
+    fs::create_dir_all(&path).await?;
+    path.push(GIT_CREDENTIALS_STORAGE_FILE);
+    Ok(path)
+}
+
+async fn read_index(path: &PathBuf) -> Result<GitCredentialsFile> {
+    if !path.exists() {
+        return Ok(GitCredentialsFile::default());
+    }
+    let content = fs::read_to_string(path).await?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+async fn write_index(path: &PathBuf, index: &GitCredentialsFile) -> Result<()> {
+    let temp_path = path.with_file_name(format!(
+        "{}.tmp-{}",
+        GIT_CREDENTIALS_STORAGE_FILE,
+        uuid::Uuid::new_v4()
+    ));
+    fs::write(&temp_path, serde_json::to_string_pretty(index)?).await?;
+    if let Err(err) = fs::rename(&temp_path, path).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// Lowercase host, keeping an explicit port
+pub fn normalize_host(host: &str) -> Option<String> {
+    let host = host
+        .trim()
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .split('/')
+        .next()?;
+    let host = host.rsplit_once('@').map(|(_, h)| h).unwrap_or(host);
+    if host.is_empty() || host.starts_with('-') || host.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(host.to_ascii_lowercase())
+}
+
+/// Keychain calls block (and may show an OS prompt), so keep them off the runtime
+async fn with_entry<T: Send + 'static>(
+    host: &str,
+    action: impl FnOnce(keyring::Entry) -> keyring::Result<T> + Send + 'static,
+) -> Result<T> {
+    let host = host.to_string();
+    tokio::task::spawn_blocking(move || {
+        let entry = keyring::Entry::new(KEYRING_SERVICE, &host)?;
+        action(entry)
+    })
+    .await?
+    .map_err(|e| anyhow!("Keychain error: {}", e))
+}
+
+pub async fn list() -> Result<Vec<GitHostCredential>> {
+    let _guard = STORE_LOCK.lock().await;
+    Ok(read_index(&storage_path().await?).await?.credentials)
+}
+
+pub async fn save(host: &str, username: &str, token: &str) -> Result<GitHostCredential> {
+    let host = normalize_host(host).ok_or_else(|| anyhow!("A valid host is required"))?;
+    let token = token.trim().to_string();
+    if token.is_empty() {
+        return Err(anyhow!("Token is required"));
+    }
+    // Hosts accept any non-empty username with a PAT; GitLab documents `oauth2`
+    let username = match username.trim() {
+        "" => "oauth2".to_string(),
+        name => name.to_string(),
+    };
+
+    let _guard = STORE_LOCK.lock().await;
+    with_entry(&host, move |entry| entry.set_password(&token)).await?;
+
+    let path = storage_path().await?;
+    let mut index = read_index(&path).await?;
+    let credential = GitHostCredential {
+        host: host.clone(),
+        username,
+        updated_at: chrono::Utc::now().timestamp_millis(),
+    };
+    index.credentials.retain(|c| c.host != host);
+    index.credentials.push(credential.clone());
+    index.credentials.sort_by(|a, b| a.host.cmp(&b.host));
+    write_index(&path, &index).await?;
+    Ok(credential)
+}
+
+pub async fn remove(host: &str) -> Result<bool> {
+    let host = normalize_host(host).ok_or_else(|| anyhow!("A valid host is required"))?;
+    let _guard = STORE_LOCK.lock().await;
+    let existed = match with_entry(&host, |entry| entry.delete_credential()).await {
+        Ok(()) => true,
+        Err(err) => {
+            warn!("[git-credentials] Removing {} from keychain: {}", host, err);
+            false
+        }
+    };
+
+    let path = storage_path().await?;
+    let mut index = read_index(&path).await?;
+    let before = index.credentials.len();
+    index.credentials.retain(|c| c.host != host);
+    if index.credentials.len() != before {
+        write_index(&path, &index).await?;
+        return Ok(true);
+    }
+    Ok(existed)
+}
+
+/// Username and token stored for `host`, if any
+pub async fn lookup(host: &str) -> Option<(String, String)> {
+    let host = normalize_host(host)?;
+    let credential = list().await.ok()?.into_iter().find(|c| c.host == host)?;
+    match with_entry(&host, |entry| entry.get_password()).await {
+        Ok(token) => Some((credential.username, token)),
+        Err(err) => {
+            warn!("[git-credentials] Reading token for {}: {}", host, err);
+            None
+        }
+    }
+}
+
+/// Environment that installs a per-host credential helper for a single git process,
+/// answering with the stored tokens for `urls` that use HTTPS. Helpers from the user's
+/// config are cleared for those hosts only, so they can't shadow the stored token.
+/// The environment only carries grants; the token is handed out by `redeem` while the
+/// returned `CredentialEnv` is alive.
+pub async fn helper_env(urls: &[String]) -> CredentialEnv {
+    let mut env = CredentialEnv::default();
+    let Some(port) = SERVER_PORT.get() else {
+        return env;
+    };
+    let mut hosts: Vec<String> = urls
+        .iter()
+        .filter(|url| url.trim().starts_with("https://"))
+        .filter_map(|url| normalize_host(url))
+        .collect();
+    hosts.sort();
+    hosts.dedup();
+
+    let mut count = 0;
+    for (n, host) in hosts.iter().enumerate() {
+        let Some((username, token)) = lookup(host).await else {
+            continue;
+        };
+        let key = format!("credential.https://{}.helper", host);
+        for value in [
+            "".to_string(),
+            CREDENTIAL_HELPER_SCRIPT.replace("{n}", &n.to_string()),
+        ] {
+            env.vars
+                .push((format!("GIT_CONFIG_KEY_{}", count), key.clone()));
+            env.vars
+                .push((format!("GIT_CONFIG_VALUE_{}", count), value));
+            count += 1;
+        }
+        let grant = uuid::Uuid::new_v4().to_string();
+        GRANTS
+            .lock()
+            .unwrap()
+            .insert(grant.clone(), CredentialGrant { username, token });
+        env.vars
+            .push((format!("OPENCHAMBER_GIT_CREDENTIAL_{}", n), grant.clone()));
+        env.grants.push(grant);
+    }
+    if count > 0 {
+        env.vars
+            .push(("GIT_CONFIG_COUNT".to_string(), count.to_string()));
+        env.vars.push((
+            "OPENCHAMBER_GIT_CREDENTIAL_PORT".to_string(),
+            port.to_string(),
+        ));
+    }
+    env
+}
+
+/// Credential helper output for a live grant; `None` once its `CredentialEnv` is dropped
+pub fn redeem(grant: &str) -> Option<String> {
+    let grants = GRANTS.lock().unwrap();
+    let grant = grants.get(grant)?;
+    Some(format!(
+        "username={}\npassword={}\n",
+        grant.username, grant.token
+    ))
+}
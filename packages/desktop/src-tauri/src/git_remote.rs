@@ -0,0 +1,204 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::sync::LazyLock;
+
+// user@host:owner/repo(.git)
+static SCP_REMOTE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:[^@/\s]+@)?([^:/\s]+):(?:/)?([^\s]+?)/?$").unwrap());
+// scheme://[user[:password]@]host[:port]/path
+static URL_REMOTE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([a-zA-Z][a-zA-Z0-9+.-]*)://(?:[^@/\s]+@)?([^:/\s]+)(?::(\d+))?/([^\s]+?)/?$")
+        .unwrap()
+});
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RemoteProvider {
+    Github,
+    Gitlab,
+    Bitbucket,
+    Azure,
+    Unknown,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteUrl {
+    pub host: String,
+    pub owner: String,
+    pub repo: String,
+    pub protocol: String,
+    pub provider: RemoteProvider,
+    pub web_url: String,
+}
+
+fn detect_provider(host: &str) -> RemoteProvider {
+    let host = host.to_ascii_lowercase();
+    if host.contains("github") {
+        RemoteProvider::Github
+    } else if host.contains("gitlab") {
+        RemoteProvider::Gitlab
+    } else if host.contains("bitbucket") {
+        RemoteProvider::Bitbucket
+    } else if host.contains("dev.azure.com") || host.contains("visualstudio.com") {
+        RemoteProvider::Azure
+    } else {
+        RemoteProvider::Unknown
+    }
+}
+
+/// Parse any of the remote URL forms git accepts (scp-like SSH, ssh://, https://, git://)
+pub fn parse_remote_url(url: &str) -> Result<RemoteUrl> {
+    let raw = url.trim();
+    if raw.is_empty() {
+        return Err(anyhow!("Remote URL is required"));
+    }
+
+    let (protocol, host, path) = if let Some(caps) = URL_REMOTE_REGEX.captures(raw) {
+        let scheme = caps[1].to_ascii_lowercase();
+        if scheme == "file" {
+            return Err(anyhow!("Local remotes have no web URL"));
+        }
+        let protocol = match scheme.as_str() {
+            "http" | "https" => "https".to_string(),
+            "ssh" | "git+ssh" | "ssh+git" => "ssh".to_string(),
+            other => other.to_string(),
+        };
+        (protocol, caps[2].to_string(), caps[4].to_string())
+    } else if let Some(caps) = SCP_REMOTE_REGEX.captures(raw) {
+        ("ssh".to_string(), caps[1].to_string(), caps[2].to_string())
+    } else {
+        return Err(anyhow!("Unrecognized remote URL: {}", raw));
+    };
+
+    let provider = detect_provider(&host);
+    let mut segments: Vec<&str> = path
+        .trim_end_matches(".git")
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    // Azure DevOps paths look like org/project/_git/repo (ssh: v3/org/project/repo)
+    if provider == RemoteProvider::Azure {
+        segments.retain(|s| *s != "_git" && *s != "v3");
+    }
+
+    if segments.len() < 2 {
+        return Err(anyhow!(
+            "Remote URL is missing owner or repository: {}",
+            raw
+        ));
+    }
+
+    let repo = segments.pop().unwrap().to_string();
+    let owner = segments.join("/");
+    let web_host = if provider == RemoteProvider::Azure && host.starts_with("ssh.") {
+        host.trim_start_matches("ssh.").to_string()
+    } else {
+        host.clone()
+    };
+    let web_url = if provider == RemoteProvider::Azure {
+        format!("https://{}/{}/_git/{}", web_host, owner, repo)
+    } else {
+        format!("https://{}/{}/{}", web_host, owner, repo)
+    };
+
+    Ok(RemoteUrl {
+        host,
+        owner,
+        repo,
+        protocol,
+        provider,
+        web_url,
+    })
+}
+
+impl RemoteUrl {
+    pub fn commit_url(&self, commit: &str) -> String {
+        match self.provider {
+            RemoteProvider::Gitlab => format!("{}/-/commit/{}", self.web_url, commit),
+            RemoteProvider::Bitbucket => format!("{}/commits/{}", self.web_url, commit),
+            _ => format!("{}/commit/{}", self.web_url, commit),
+        }
+    }
+
+    pub fn branch_url(&self, branch: &str) -> String {
+        let branch = urlencoding::encode(branch).replace("%2F", "/");
+        match self.provider {
+            RemoteProvider::Gitlab => format!("{}/-/tree/{}", self.web_url, branch),
+            RemoteProvider::Bitbucket => format!("{}/branch/{}", self.web_url, branch),
+            RemoteProvider::Azure => format!("{}?version=GB{}", self.web_url, branch),
+            _ => format!("{}/tree/{}", self.web_url, branch),
+        }
+    }
+
+    /// Compare view showing what `head` adds on top of `base`
+    pub fn compare_url(&self, base: &str, head: &str) -> String {
+        let encode = |reference: &str| urlencoding::encode(reference).replace("%2F", "/");
+        let (base, head) = (encode(base), encode(head));
+        match self.provider {
+            RemoteProvider::Gitlab => format!("{}/-/compare/{}...{}", self.web_url, base, head),
+            RemoteProvider::Bitbucket => {
+                format!("{}/branches/compare/{}%0D{}", self.web_url, head, base)
+            }
+            RemoteProvider::Azure => format!(
+                "{}/branchCompare?baseVersion=GB{}&targetVersion=GB{}",
+                self.web_url, base, head
+            ),
+            _ => format!("{}/compare/{}...{}", self.web_url, base, head),
+        }
+    }
+
+    /// Link to a file at a given ref, optionally highlighting a line range
+    pub fn file_url(&self, reference: &str, path: &str, lines: Option<(u32, u32)>) -> String {
+        let path = path
+            .trim_start_matches('/')
+            .split('/')
+            .map(|segment| urlencoding::encode(segment).into_owned())
+            .collect::<Vec<_>>()
+            .join("/");
+        let lines = lines.map(|(start, end)| (start.min(end), start.max(end)));
+
+        match self.provider {
+            RemoteProvider::Gitlab => {
+                let anchor = match lines {
+                    Some((start, end)) if start != end => format!("#L{}-{}", start, end),
+                    Some((start, _)) => format!("#L{}", start),
+                    None => String::new(),
+                };
+                format!("{}/-/blob/{}/{}{}", self.web_url, reference, path, anchor)
+            }
+            RemoteProvider::Bitbucket => {
+                let anchor = match lines {
+                    Some((start, end)) if start != end => format!("#lines-{}:{}", start, end),
+                    Some((start, _)) => format!("#lines-{}", start),
+                    None => String::new(),
+                };
+                format!("{}/src/{}/{}{}", self.web_url, reference, path, anchor)
+            }
+            RemoteProvider::Azure => {
+                let range = match lines {
+                    Some((start, end)) => format!(
+                        "&line={}&lineEnd={}&lineStartColumn=1&lineEndColumn=1",
+                        start,
+                        end + 1
+                    ),
+                    None => String::new(),
+                };
+                format!(
+                    "{}?path=/{}&version=GC{}{}",
+                    self.web_url, path, reference, range
+                )
+            }
+            _ => {
+                let anchor = match lines {
+                    Some((start, end)) if start != end => format!("#L{}-L{}", start, end),
+                    Some((start, _)) => format!("#L{}", start),
+                    None => String::new(),
+                };
+                format!("{}/blob/{}/{}{}", self.web_url, reference, path, anchor)
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use std::{
+    collections::HashSet,
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use log::{debug, info, warn};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::{mpsc, Mutex};
+
+pub const GIT_STATUS_CHANGED_EVENT: &str = "git:status-changed";
+/// Quiet period before emitting; a checkout or an agent edit touches many files at once
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// Worktree directories whose churn never affects what the git panel shows
+const IGNORED_WORKTREE_DIRS: &[&str] = &["node_modules", "target", "dist", ".next", ".turbo"];
+/// Git directory entries that reflect status-relevant state
+const WATCHED_GIT_ENTRIES: &[&str] = &[
+    "HEAD",
+    "index",
+    "refs",
+    "packed-refs",
+    "MERGE_HEAD",
+    "REBASE_HEAD",
+    "CHERRY_PICK_HEAD",
+    "REVERT_HEAD",
+    "FETCH_HEAD",
+    "rebase-merge",
+    "rebase-apply",
+];
+
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct GitStatusChangedPayload {
+    pub directory: String,
+    pub worktree_changed: bool,
+    pub git_state_changed: bool,
+}
+
+/// Dropping the watcher stops notify, which closes the channel and ends the debounce task
+struct ActiveWatch {
+    directory: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+static ACTIVE_WATCH: Lazy<Mutex<Option<ActiveWatch>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Clone, Copy)]
+enum ChangeKind {
+    Worktree,
+    GitState,
+}
+
+fn classify_path(path: &Path, root: &Path, git_dirs: &[PathBuf]) -> Option<ChangeKind> {
+    for git_dir in git_dirs {
+        if let Ok(relative) = path.strip_prefix(git_dir) {
+            let first = relative.components().next()?.as_os_str().to_string_lossy();
+            // Lock files come and go during every git command; the real file follows
+            if path.extension().is_some_and(|ext| ext == "lock") {
+                return None;
+            }
+            return WATCHED_GIT_ENTRIES
+                .contains(&first.as_ref())
+                .then_some(ChangeKind::GitState);
+        }
+    }
+
+    let relative = path.strip_prefix(root).ok()?;
+    let ignored = relative.components().any(|component| match component {
+        Component::Normal(name) => {
+            let name = name.to_string_lossy();
+            name == ".git" || IGNORED_WORKTREE_DIRS.contains(&name.as_ref())
+        }
+        _ => false,
+    });
+    (!ignored).then_some(ChangeKind::Worktree)
+}
+
+/// The worktree's own git dir plus the common dir (they differ for linked worktrees)
+async fn resolve_git_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for flag in ["--absolute-git-dir", "--git-common-dir"] {
+        let Ok(output) = Command::new("git")
+            .args(["rev-parse", "--path-format=absolute", flag])
+            .current_dir(root)
+            .stdin(Stdio::null())
+            .output()
+            .await
+        else {
+            continue;
+        };
+        if !output.status.success() {
+            continue;
+        }
+        let dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Above this many changed worktree paths in one batch, skip asking git which are ignored
+const IGNORE_CHECK_LIMIT: usize = 1000;
+
+/// Whether any of `paths` is not ignored by git. Build output churns constantly and the
+/// recursive watch reports it, so ignored paths are dropped here on the event side.
+async fn any_not_ignored(root: &Path, paths: &HashSet<PathBuf>) -> bool {
+    if paths.len() > IGNORE_CHECK_LIMIT {
+        return true;
+    }
+    let mut input = Vec::new();
+    for path in paths {
+        input.extend_from_slice(path.to_string_lossy().as_bytes());
+        input.push(0);
+    }
+    let child = Command::new("git")
+        .args(["check-ignore", "-z", "--stdin"])
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return true;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        if stdin.write_all(&input).await.is_err() {
+            return true;
+        }
+    }
+    let Ok(output) = child.wait_with_output().await else {
+        return true;
+    };
+    // Exit code 1 means none of the paths are ignored
+    if !output.status.success() {
+        return true;
+    }
+    let ignored = String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .count();
+    ignored < paths.len()
+}
+
+async fn debounce_events(
+    app: AppHandle,
+    root: PathBuf,
+    git_dirs: Vec<PathBuf>,
+    mut rx: mpsc::UnboundedReceiver<Event>,
+) {
+    let directory = root.to_string_lossy().to_string();
+
+    while let Some(event) = rx.recv().await {
+        let mut worktree_paths = HashSet::new();
+        let mut git_state_changed = false;
+        let mut absorb = |event: &Event| {
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+            for path in &event.paths {
+                match classify_path(path, &root, &git_dirs) {
+                    Some(ChangeKind::Worktree) => {
+                        worktree_paths.insert(path.clone());
+                    }
+                    Some(ChangeKind::GitState) => git_state_changed = true,
+                    None => {}
+                }
+            }
+        };
+        absorb(&event);
+
+        // Keep collecting until the stream has been quiet for the debounce window
+        loop {
+            match tokio::time::timeout(DEBOUNCE, rx.recv()).await {
+                Ok(Some(event)) => absorb(&event),
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+
+        let worktree_changed =
+            !worktree_paths.is_empty() && any_not_ignored(&root, &worktree_paths).await;
+        if !worktree_changed && !git_state_changed {
+            continue;
+        }
+        debug!(
+            "[git-watch] {} changed (worktree: {}, git: {})",
+            directory, worktree_changed, git_state_changed
+        );
+        let _ = app.emit(
+            GIT_STATUS_CHANGED_EVENT,
+            GitStatusChangedPayload {
+                directory: directory.clone(),
+                worktree_changed,
+                git_state_changed,
+            },
+        );
+    }
+}
+
+/// Start watching `root` (and its git directories), replacing any previous watch.
+/// Only one directory is watched at a time: the one active in the UI.
+pub async fn watch_directory(app: AppHandle, root: PathBuf) -> Result<()> {
+    let mut active = ACTIVE_WATCH.lock().await;
+    if active.as_ref().is_some_and(|watch| watch.directory == root) {
+        return Ok(());
+    }
+    *active = None;
+
+    let git_dirs = resolve_git_dirs(&root).await;
+    let (tx, rx) = mpsc::unbounded_channel();
+    let mut watcher =
+        notify::recommended_watcher(move |result: notify::Result<Event>| match result {
+            Ok(event) => {
+                let _ = tx.send(event);
+            }
+            Err(err) => warn!("[git-watch] Watcher error: {}", err),
+        })
+        .context("Failed to create file watcher")?;
+
+    // One recursive watch: FSEvents and ReadDirectoryChangesW cover a tree natively, and
+    // ignored directories are filtered out as events arrive
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", root.display()))?;
+    // Linked worktrees keep their git dirs elsewhere. HEAD, index and the in-progress
+    // markers live directly in the git dir; only refs needs to be followed down.
+    for git_dir in git_dirs.iter().filter(|dir| !dir.starts_with(&root)) {
+        if let Err(err) = watcher.watch(git_dir, RecursiveMode::NonRecursive) {
+            warn!("[git-watch] Failed to watch {:?}: {}", git_dir, err);
+        }
+        let refs = git_dir.join("refs");
+        if refs.is_dir() {
+            if let Err(err) = watcher.watch(&refs, RecursiveMode::Recursive) {
+                warn!("[git-watch] Failed to watch {:?}: {}", refs, err);
+            }
+        }
+    }
+
+    tauri::async_runtime::spawn(debounce_events(app, root.clone(), git_dirs, rx));
+    info!("[git-watch] Watching {:?}", root);
+
+    *active = Some(ActiveWatch {
+        directory: root,
+        _watcher: watcher,
+    });
+    Ok(())
+}
+
+/// Stop watching `root`; a watch that has since moved to another directory is kept
+pub async fn unwatch(root: &Path) {
+    let mut active = ACTIVE_WATCH.lock().await;
+    if active.as_ref().is_some_and(|watch| watch.directory == root) {
+        if let Some(watch) = active.take() {
+            info!("[git-watch] Stopped watching {:?}", watch.directory);
+        }
+    }
+}
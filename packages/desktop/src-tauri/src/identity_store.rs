@@ -0,0 +1,242 @@
+use anyhow::{anyhow, Result};
+use log::{error, info, warn};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::SystemTime;
+use tauri::{AppHandle, Emitter};
+use tokio::fs;
+use tokio::sync::Mutex;
+
+pub const IDENTITIES_CHANGED_EVENT: &str = "git-identities://changed";
+const GIT_IDENTITY_STORAGE_FILE: &str = "git-identities.json";
+const DIRECTORY_RULES_STORAGE_FILE: &str = "git-identity-directories.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitIdentityProfile {
+    pub id: String,
+    pub name: String,
+    pub user_name: String,
+    pub user_email: String,
+    pub ssh_key: Option<String>,
+    /// The key is held by ssh-agent; `ssh_key` may then be omitted or point to the
+    /// public key, which selects the matching agent key
+    #[serde(default)]
+    pub ssh_agent: bool,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    /// Remote patterns this identity is suggested for, e.g. `github.com/acme/*`
+    #[serde(default)]
+    pub remote_rules: Vec<String>,
+    /// Add a `Signed-off-by` trailer to commits in repositories using this identity
+    #[serde(default)]
+    pub signoff: bool,
+}
+
+/// Default identity for repositories under a directory. `pattern` is a path (matching
+/// itself and everything below it) with optional `*` wildcards, e.g. `~/work/*`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryIdentityRule {
+    pub pattern: String,
+    pub profile_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct DirectoryRulesFile {
+    rules: Vec<DirectoryIdentityRule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GitIdentityProfilesWrapper {
+    pub profiles: Vec<GitIdentityProfile>,
+}
+
+struct CachedProfiles {
+    modified: Option<SystemTime>,
+    profiles: Vec<GitIdentityProfile>,
+}
+
+/// Single lock around every read/modify/write of `git-identities.json`. The cache is
+/// keyed on the file mtime so edits made outside the app are still picked up.
+static IDENTITY_CACHE: Lazy<Mutex<Option<CachedProfiles>>> = Lazy::new(|| Mutex::new(None));
+/// Serializes read/modify/write of `git-identity-directories.json`
+static DIRECTORY_RULES_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+/// Register the app handle used to broadcast identity changes to the webview
+pub fn register_app_handle(app: &AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+}
+
+async fn storage_path() -> Result<PathBuf> {
+    storage_path_for(GIT_IDENTITY_STORAGE_FILE).await
+}
+
+async fn storage_path_for(file_name: &str) -> Result<PathBuf> {
+    let mut path = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
+    path.push(".config");
+    path.push("openchamber");
+    fs::create_dir_all(&path).await?;
+    path.push(file_name);
+    Ok(path)
+}
+
+async fn file_modified(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).await.ok()?.modified().ok()
+}
+
+async fn read_from_disk(path: &PathBuf) -> Result<Vec<GitIdentityProfile>> {
+    if !path.exists() {
+        info!("Identities file does not exist at {:?}", path);
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path).await?;
+    let wrapper: serde_json::Value = match serde_json::from_str(&content) {
+        Ok(w) => w,
+        Err(e) => {
+            error!("Failed to parse identities JSON: {}", e);
+            return Err(e.into());
+        }
+    };
+
+    if let Some(profiles) = wrapper.get("profiles") {
+        match serde_json::from_value::<Vec<GitIdentityProfile>>(profiles.clone()) {
+            Ok(p) => {
+                info!("Loaded {} identity profiles", p.len());
+                Ok(p)
+            }
+            Err(e) => {
+                error!("Failed to deserialize profiles array: {}", e);
+                warn!("Profiles JSON: {}", profiles);
+                Err(e.into())
+            }
+        }
+    } else {
+        warn!("No 'profiles' key found in identities JSON");
+        Ok(Vec::new())
+    }
+}
+
+async fn load_locked(
+    cache: &mut Option<CachedProfiles>,
+    path: &PathBuf,
+) -> Result<Vec<GitIdentityProfile>> {
+    let modified = file_modified(path).await;
+    if let Some(cached) = cache.as_ref() {
+        if cached.modified == modified {
+            return Ok(cached.profiles.clone());
+        }
+    }
+
+    let profiles = read_from_disk(path).await?;
+    *cache = Some(CachedProfiles {
+        modified,
+        profiles: profiles.clone(),
+    });
+    Ok(profiles)
+}
+
+/// Write via a temp file + rename so readers never observe a half-written file
+async fn write_to_disk(path: &PathBuf, profiles: &[GitIdentityProfile]) -> Result<()> {
+    let wrapper = GitIdentityProfilesWrapper {
+        profiles: profiles.to_vec(),
+    };
+    let content = serde_json::to_string_pretty(&wrapper)?;
+    let temp_path = path.with_file_name(format!(
+        "{}.tmp-{}",
+        GIT_IDENTITY_STORAGE_FILE,
+        uuid::Uuid::new_v4()
+    ));
+    fs::write(&temp_path, content).await?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&temp_path, std::fs::Permissions::from_mode(0o600)).await;
+    }
+
+    if let Err(err) = fs::rename(&temp_path, path).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+pub async fn load_profiles() -> Result<Vec<GitIdentityProfile>> {
+    let path = storage_path().await?;
+    let mut cache = IDENTITY_CACHE.lock().await;
+    load_locked(&mut cache, &path).await
+}
+
+pub async fn find_profile(id: &str) -> Result<Option<GitIdentityProfile>> {
+    Ok(load_profiles().await?.into_iter().find(|p| p.id == id))
+}
+
+/// Apply `update` to the stored profiles atomically, persist the result and notify listeners.
+/// Nothing is written when `update` returns an error.
+pub async fn update_profiles<R>(
+    update: impl FnOnce(&mut Vec<GitIdentityProfile>) -> Result<R>,
+) -> Result<R> {
+    let path = storage_path().await?;
+    let mut cache = IDENTITY_CACHE.lock().await;
+    let mut profiles = load_locked(&mut cache, &path).await?;
+
+    let result = update(&mut profiles)?;
+    write_to_disk(&path, &profiles).await?;
+    *cache = Some(CachedProfiles {
+        modified: file_modified(&path).await,
+        profiles: profiles.clone(),
+    });
+    drop(cache);
+
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit(
+            IDENTITIES_CHANGED_EVENT,
+            GitIdentityProfilesWrapper { profiles },
+        );
+    }
+
+    Ok(result)
+}
+
+async fn read_directory_rules(path: &PathBuf) -> Result<Vec<DirectoryIdentityRule>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path).await?;
+    Ok(serde_json::from_str::<DirectoryRulesFile>(&content)?.rules)
+}
+
+pub async fn load_directory_rules() -> Result<Vec<DirectoryIdentityRule>> {
+    let path = storage_path_for(DIRECTORY_RULES_STORAGE_FILE).await?;
+    let _guard = DIRECTORY_RULES_LOCK.lock().await;
+    read_directory_rules(&path).await
+}
+
+/// Apply `update` to the stored directory rules and persist the result. Nothing is
+/// written when `update` returns an error.
+pub async fn update_directory_rules<R>(
+    update: impl FnOnce(&mut Vec<DirectoryIdentityRule>) -> Result<R>,
+) -> Result<R> {
+    let path = storage_path_for(DIRECTORY_RULES_STORAGE_FILE).await?;
+    let _guard = DIRECTORY_RULES_LOCK.lock().await;
+    let mut rules = read_directory_rules(&path).await?;
+
+    let result = update(&mut rules)?;
+    let content = serde_json::to_string_pretty(&DirectoryRulesFile { rules })?;
+    let temp_path = path.with_file_name(format!(
+        "{}.tmp-{}",
+        DIRECTORY_RULES_STORAGE_FILE,
+        uuid::Uuid::new_v4()
+    ));
+    fs::write(&temp_path, content).await?;
+    if let Err(err) = fs::rename(&temp_path, &path).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(err.into());
+    }
+    Ok(result)
+}
@@ -1,5 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod ai_completion;
+mod background_fetch;
 mod commands;
 mod logging;
 mod assistant_notifications;
@@ -9,7 +11,13 @@ mod opencode_config;
 mod opencode_manager;
 mod window_state;
 mod path_utils;
+mod git_askpass;
+mod git_credentials;
+mod git_remote;
+mod git_watcher;
+mod identity_store;
 mod skills_catalog;
+mod skills_usage;
 
 use std::{collections::HashMap, path::PathBuf, sync::Arc, time::{Duration, Instant}};
 
@@ -17,35 +25,74 @@ use anyhow::{anyhow, Result};
 use axum::{
     body::{to_bytes, Body},
     extract::{OriginalUri, State},
-    http::{Method, Request, Response, StatusCode},
+    http::{HeaderMap, Method, Request, Response, StatusCode},
     response::IntoResponse,
     routing::{any, get, post},
-    Json, Router,
+    Form, Json, Router,
 };
 use assistant_notifications::spawn_assistant_notifications;
 use session_activity::spawn_session_activity_tracker;
-use commands::files::{create_directory, list_directory, search_files};
+use commands::attachments::{gc_attachments, list_attachments, store_attachment};
+use commands::config_integrity::{check_config_integrity, check_config_integrity_on_startup};
+use commands::editors::{detect_editors, open_in_editor};
+use commands::files::{
+    create_directory, delete_path, list_directory, replace_in_workspace, search_files,
+};
 use commands::git::{
-    add_git_worktree, check_is_git_repository, checkout_branch, create_branch, create_git_commit,
-    create_git_identity, delete_git_branch, delete_git_identity, delete_remote_branch,
-    ensure_openchamber_ignored, generate_commit_message, get_commit_files, get_current_git_identity,
-    get_git_branches, get_git_diff, get_git_file_diff, get_git_identities, get_git_log, get_git_status,
-    git_fetch, git_pull, git_push, is_linked_worktree, list_git_worktrees, remove_git_worktree,
-    revert_git_file, set_git_identity, update_git_identity,
+    add_commit_note, add_git_worktree, add_sparse_checkout_patterns, apply_identity_bulk,
+    apply_patch, can_fast_forward, cancel_git_operation, check_is_git_repository, checkout_branch,
+    clear_stale_git_lock, compare_worktrees, create_branch, create_git_commit, create_git_identity,
+    create_git_tag, create_release, delete_branches, delete_git_branch, delete_git_identity,
+    delete_remote_branch, disable_sparse_checkout, discard_all_changes, ensure_openchamber_ignored,
+    export_git_archive, export_git_history, export_patches, forget_rerere_resolution,
+    generate_commit_message, generate_pr_description, generate_ssh_key_for_identity,
+    get_branch_divergence, get_branch_relationship, get_commit_checks, get_commit_files,
+    get_commit_graph, get_commit_notes, get_current_git_identity, get_directory_identity_rules,
+    get_effective_identity, get_file_at_revision, get_file_permalink, get_git_branches,
+    get_git_diff, get_git_file_diff, get_git_identities, get_git_locks, get_git_log,
+    get_git_reflog, get_git_status, get_git_status_batch, get_gitignore_templates, get_index_flags,
+    get_range_diffstat, get_recent_collaborators, get_recent_commit_subjects,
+    get_recent_file_activity, get_repo_size_stats, get_repo_statistics, get_rerere_status,
+    get_sparse_checkout, get_stale_branches, get_workspace_read_only, git_bisect_bad,
+    git_bisect_good, git_bisect_reset, git_bisect_skip, git_bisect_start, git_bisect_status,
+    git_clean, git_clean_preview, git_clone, git_fetch, git_grep, git_pull, git_push,
+    git_squash_merge, init_git_repository, init_sparse_checkout, is_linked_worktree,
+    list_git_credentials, list_git_tags, list_git_worktrees, list_safety_snapshots,
+    lock_git_worktree, move_git_worktree, parse_remote_url, prune_git_worktrees, push_notes,
+    remove_git_credential, remove_git_worktree, repair_branch_tracking, repair_git_worktrees,
+    resolve_remote_web_url, respond_git_askpass, restore_from_reflog, restore_safety_snapshot,
+    revert_git_file, review_git_diff, rewrite_recent_authors, run_git_maintenance,
+    save_git_credential, search_git_commits, set_branch_upstream, set_commit_signoff_default,
+    set_directory_identity_rule, set_git_askpass_listener, set_git_identity, set_index_flags,
+    set_rerere_enabled, set_sparse_checkout, set_workspace_read_only, start_background_fetch,
+    stop_background_fetch, suggest_git_identity, summarize_changes_since, test_git_identity,
+    unlock_git_worktree, unset_branch_upstream, unwatch_git_status, update_git_identity,
+    validate_commit_message, verify_git_tag, watch_git_status,
 };
 use commands::logs::fetch_desktop_logs;
+use commands::notifications::desktop_notify;
 use commands::permissions::{
     pick_directory, process_directory_selection, request_directory_access,
     restore_bookmarks_on_startup, start_accessing_directory, stop_accessing_directory,
 };
 use commands::notifications::desktop_notify;
+use commands::project::init_openchamber_project;
+use commands::providers::{
+    list_provider_credentials, remove_provider_api_key, set_provider_api_key,
+    test_provider_api_key,
+};
 use commands::settings::{load_settings, restart_opencode, save_settings};
+use commands::skills::{
+    add_skill_catalog_source, remove_skill_catalog_source, update_skill_catalog_source,
+    validate_skill,
+};
 use commands::terminal::{
     close_terminal, create_terminal_session, force_kill_terminal, resize_terminal,
     restart_terminal_session, send_terminal_input, TerminalState,
 };
+use commands::trash::{list_trashed_items, restore_from_trash};
 use futures_util::StreamExt as FuturesStreamExt;
-use log::{error, info, warn};
+use log::{debug, error, info, warn};
 use opencode_manager::OpenCodeManager;
 use portpicker::pick_unused_port;
 use reqwest::{header, Body as ReqwestBody, Client};
@@ -62,7 +109,7 @@ use tauri_plugin_shell::init as shell_plugin;
 use tokio::{
     fs,
     net::TcpListener,
-    sync::{broadcast, Mutex},
+    sync::{broadcast, Mutex, Semaphore},
 };
 use tower_http::cors::CorsLayer;
 use window_state::{load_window_state, persist_window_state, WindowStateManager};
@@ -78,6 +125,10 @@ use window_vibrancy::{apply_vibrancy, NSVisualEffectMaterial};
 static NEEDS_TRAFFIC_LIGHT_FIX: AtomicBool = AtomicBool::new(false);
 
 const PROXY_BODY_LIMIT: usize = 32 * 1024 * 1024; // 32MB
+/// Requests held while OpenCode restarts; further requests fail immediately
+const RESTART_QUEUE_CAPACITY: usize = 64;
+const RESTART_QUEUE_TIMEOUT: Duration = Duration::from_secs(20);
+const OPENCODE_UNAVAILABLE_CODE: &str = "OpenCodeUnavailable";
 const CLIENT_RELOAD_DELAY_MS: u64 = 800;
 const MODELS_DEV_API_URL: &str = "https://models.dev/api.json";
 const MODELS_METADATA_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
@@ -160,9 +211,11 @@ impl DesktopRuntime {
         let server_state = ServerState {
             client,
             opencode: opencode.clone(),
+            settings: settings.clone(),
             server_port,
             directory_change_lock: Arc::new(Mutex::new(())),
             models_metadata_cache: Arc::new(Mutex::new(ModelsMetadataCache::default())),
+            restart_queue: Arc::new(Semaphore::new(RESTART_QUEUE_CAPACITY)),
         };
 
         spawn_http_server(server_port, server_state, shutdown_rx);
@@ -207,9 +260,11 @@ impl DesktopRuntime {
 struct ServerState {
     client: Client,
     opencode: Arc<OpenCodeManager>,
+    settings: Arc<SettingsStore>,
     server_port: u16,
     directory_change_lock: Arc<Mutex<()>>,
     models_metadata_cache: Arc<Mutex<ModelsMetadataCache>>,
+    restart_queue: Arc<Semaphore>,
 }
 
 #[derive(Default)]
@@ -232,6 +287,14 @@ struct ConfigErrorResponse {
     error: String,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProxyUnavailableResponse {
+    error: String,
+    code: &'static str,
+    retryable: bool,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ConfigMetadataResponse {
@@ -651,6 +714,9 @@ fn main() {
             #[cfg(target_os = "macos")]
             prevent_app_nap();
 
+            tauri::async_runtime::block_on(check_config_integrity_on_startup());
+
+            identity_store::register_app_handle(app.app_handle());
             app.manage(TerminalState::new());
 
             let stored_state = tauri::async_runtime::block_on(load_window_state()).unwrap_or(None);
@@ -688,6 +754,8 @@ fn main() {
 
             let runtime = DesktopRuntime::initialize_sync()?;
             app.manage(runtime.clone());
+            git_askpass::init(app.app_handle(), runtime.server_port);
+            git_credentials::init(runtime.server_port);
 
             let app_handle = app.app_handle().clone();
             let runtime_clone = runtime.clone();
@@ -813,9 +881,20 @@ fn main() {
             load_settings,
             save_settings,
             restart_opencode,
+            add_skill_catalog_source,
+            update_skill_catalog_source,
+            remove_skill_catalog_source,
+            validate_skill,
             list_directory,
             search_files,
+            replace_in_workspace,
+            store_attachment,
+            list_attachments,
+            gc_attachments,
             create_directory,
+            delete_path,
+            list_trashed_items,
+            restore_from_trash,
             request_directory_access,
             start_accessing_directory,
             stop_accessing_directory,
@@ -823,10 +902,17 @@ fn main() {
             restore_bookmarks_on_startup,
             process_directory_selection,
             check_is_git_repository,
+            get_workspace_read_only,
+            set_workspace_read_only,
             get_git_status,
+            get_git_status_batch,
+            watch_git_status,
+            unwatch_git_status,
             get_git_diff,
             get_git_file_diff,
             revert_git_file,
+            git_clean_preview,
+            git_clean,
             is_linked_worktree,
             get_git_branches,
             delete_git_branch,
@@ -834,14 +920,96 @@ fn main() {
             list_git_worktrees,
             add_git_worktree,
             remove_git_worktree,
+            prune_git_worktrees,
+            lock_git_worktree,
+            unlock_git_worktree,
+            move_git_worktree,
+            repair_git_worktrees,
+            compare_worktrees,
+            summarize_changes_since,
             ensure_openchamber_ignored,
+            init_openchamber_project,
+            list_provider_credentials,
+            set_provider_api_key,
+            remove_provider_api_key,
+            test_provider_api_key,
+            check_config_integrity,
+            detect_editors,
+            open_in_editor,
             create_git_commit,
+            get_recent_collaborators,
             git_push,
             git_pull,
             git_fetch,
+            start_background_fetch,
+            stop_background_fetch,
+            git_clone,
+            parse_remote_url,
+            get_file_permalink,
+            resolve_remote_web_url,
+            get_commit_checks,
+            export_git_history,
+            export_git_archive,
+            get_recent_commit_subjects,
+            get_recent_file_activity,
+            get_git_reflog,
+            restore_from_reflog,
+            git_bisect_start,
+            git_bisect_good,
+            git_bisect_bad,
+            git_bisect_skip,
+            git_bisect_reset,
+            git_bisect_status,
+            get_branch_relationship,
+            can_fast_forward,
+            get_branch_divergence,
+            rewrite_recent_authors,
+            repair_branch_tracking,
+            list_git_tags,
+            create_git_tag,
+            verify_git_tag,
             checkout_branch,
+            get_git_locks,
+            clear_stale_git_lock,
+            set_branch_upstream,
+            unset_branch_upstream,
+            git_squash_merge,
+            export_patches,
+            apply_patch,
             create_branch,
             get_git_log,
+            get_index_flags,
+            set_index_flags,
+            cancel_git_operation,
+            get_directory_identity_rules,
+            set_directory_identity_rule,
+            get_effective_identity,
+            discard_all_changes,
+            list_safety_snapshots,
+            restore_safety_snapshot,
+            get_rerere_status,
+            set_rerere_enabled,
+            forget_rerere_resolution,
+            get_file_at_revision,
+            get_range_diffstat,
+            get_commit_graph,
+            get_stale_branches,
+            delete_branches,
+            set_commit_signoff_default,
+            create_release,
+            get_sparse_checkout,
+            init_sparse_checkout,
+            set_sparse_checkout,
+            add_sparse_checkout_patterns,
+            disable_sparse_checkout,
+            get_repo_statistics,
+            run_git_maintenance,
+            get_repo_size_stats,
+            search_git_commits,
+            git_grep,
+            get_commit_notes,
+            add_commit_note,
+            push_notes,
             get_commit_files,
             get_git_identities,
             create_git_identity,
@@ -849,7 +1017,21 @@ fn main() {
             delete_git_identity,
             get_current_git_identity,
             set_git_identity,
+            apply_identity_bulk,
+            suggest_git_identity,
+            generate_ssh_key_for_identity,
+            test_git_identity,
+            respond_git_askpass,
+            set_git_askpass_listener,
+            list_git_credentials,
+            save_git_credential,
+            remove_git_credential,
+            init_git_repository,
+            get_gitignore_templates,
             generate_commit_message,
+            generate_pr_description,
+            review_git_diff,
+            validate_commit_message,
             create_terminal_session,
             send_terminal_input,
             resize_terminal,
@@ -1055,6 +1237,8 @@ async fn run_http_server(
         .route("/health", get(health_handler))
         .route("/api/openchamber/models-metadata", get(models_metadata_handler))
         .route("/api/opencode/directory", post(change_directory_handler))
+        .route("/askpass", post(askpass_handler))
+        .route("/git-credential", get(git_credential_handler))
         .route("/api", any(proxy_to_opencode))
         .route("/api/{*rest}", any(proxy_to_opencode))
         .with_state(state)
@@ -1073,6 +1257,39 @@ async fn run_http_server(
     Ok(())
 }
 
+#[derive(Deserialize)]
+struct AskpassForm {
+    prompt: String,
+}
+
+/// Prompts from the askpass helper script git and ssh run for passphrases and passwords
+async fn askpass_handler(
+    headers: HeaderMap,
+    Form(form): Form<AskpassForm>,
+) -> Result<String, StatusCode> {
+    let token = headers
+        .get(git_askpass::ASKPASS_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !git_askpass::is_valid_token(token) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    // A non-2xx status makes the helper exit non-zero, which git treats as cancelled
+    git_askpass::prompt(form.prompt)
+        .await
+        .ok_or(StatusCode::GONE)
+}
+
+/// Credential grants redeemed by the helper script installed for HTTPS remotes
+async fn git_credential_handler(headers: HeaderMap) -> Result<String, StatusCode> {
+    let grant = headers
+        .get(git_credentials::CREDENTIAL_GRANT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    // A non-2xx status makes the helper exit non-zero, which git treats as no answer
+    git_credentials::redeem(grant).ok_or(StatusCode::FORBIDDEN)
+}
+
 async fn health_handler(State(state): State<ServerState>) -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "ok",
@@ -1149,6 +1366,52 @@ fn json_response<T: Serialize>(status: StatusCode, payload: T) -> Response<Body>
     (status, Json(payload)).into_response()
 }
 
+fn opencode_unavailable_response(message: impl Into<String>) -> Response<Body> {
+    let mut response = json_response(
+        StatusCode::SERVICE_UNAVAILABLE,
+        ProxyUnavailableResponse {
+            error: message.into(),
+            code: OPENCODE_UNAVAILABLE_CODE,
+            retryable: true,
+        },
+    );
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, header::HeaderValue::from_static("2"));
+    response
+}
+
+/// Hold a request while OpenCode is restarting instead of failing it straight away.
+/// At most `RESTART_QUEUE_CAPACITY` requests wait; each waits up to `RESTART_QUEUE_TIMEOUT`.
+async fn hold_until_opencode_ready(state: &ServerState, path: &str) -> Result<(), Response<Body>> {
+    if state.opencode.is_ready() || !state.opencode.is_cli_available() {
+        return Ok(());
+    }
+    // Only a start in progress is worth waiting for; a crashed or stopped OpenCode
+    // won't come back on its own
+    if !state.opencode.is_starting() {
+        return Err(opencode_unavailable_response("OpenCode is not running"));
+    }
+
+    let Ok(_permit) = state.restart_queue.try_acquire() else {
+        warn!("[desktop:http] Restart queue full; rejecting {}", path);
+        return Err(opencode_unavailable_response(
+            "OpenCode is restarting and too many requests are waiting",
+        ));
+    };
+
+    debug!("[desktop:http] Holding {} until OpenCode is ready", path);
+    if state.opencode.wait_until_ready(RESTART_QUEUE_TIMEOUT).await {
+        Ok(())
+    } else {
+        warn!("[desktop:http] OpenCode not ready in time for {}", path);
+        Err(opencode_unavailable_response(format!(
+            "OpenCode did not become ready within {} seconds",
+            RESTART_QUEUE_TIMEOUT.as_secs()
+        )))
+    }
+}
+
 fn config_error_response(status: StatusCode, message: impl Into<String>) -> Response<Body> {
     json_response(status, ConfigErrorResponse {
         error: message.into(),
@@ -1357,6 +1620,8 @@ struct SkillListItem {
     scope: opencode_config::Scope,
     source: opencode_config::SkillSource,
     sources: opencode_config::SkillConfigSources,
+    use_count: u64,
+    last_used_at: Option<String>,
 }
 
 /// Response type for skill file content
@@ -1371,17 +1636,21 @@ async fn handle_skill_list_route(
 ) -> Result<Response<Body>, StatusCode> {
     let working_directory = state.opencode.get_working_directory();
     let discovered = opencode_config::discover_skills(Some(&working_directory));
+    let usage = skills_usage::load_usage().await;
     
     let mut skills = Vec::new();
     for skill in discovered {
         match opencode_config::get_skill_sources(&skill.name, Some(&working_directory)).await {
             Ok(sources) => {
+                let skill_usage = usage.get(&skill.name).cloned().unwrap_or_default();
                 skills.push(SkillListItem {
                     name: skill.name,
                     path: skill.path,
                     scope: skill.scope,
                     source: skill.source,
                     sources,
+                    use_count: skill_usage.use_count,
+                    last_used_at: skill_usage.last_used_at,
                 });
             }
             Err(err) => {
@@ -1770,6 +2039,60 @@ async fn handle_command_route(
     }
 }
 
+/// Add (`POST` without an id), replace (`PUT`) or remove (`DELETE`) a custom skills catalog
+async fn handle_catalog_source_route(
+    state: &ServerState,
+    id: Option<String>,
+    method: Method,
+    req: Request<Body>,
+) -> Result<Response<Body>, StatusCode> {
+    if method == Method::DELETE {
+        let Some(id) = id else {
+            return Ok(StatusCode::METHOD_NOT_ALLOWED.into_response());
+        };
+        let response = skills_catalog::remove_catalog_source(&state.settings, &id).await;
+        return Ok(catalog_source_response(response));
+    }
+    let expects_id = match method {
+        Method::POST => false,
+        Method::PUT => true,
+        _ => return Ok(StatusCode::METHOD_NOT_ALLOWED.into_response()),
+    };
+    if expects_id != id.is_some() {
+        return Ok(StatusCode::METHOD_NOT_ALLOWED.into_response());
+    }
+
+    let payload_map = match parse_request_payload(req).await {
+        Ok(data) => data,
+        Err(resp) => return Ok(resp),
+    };
+    let payload_value = Value::Object(payload_map.into_iter().collect());
+    let Ok(input) =
+        serde_json::from_value::<skills_catalog::SkillCatalogSourceInput>(payload_value)
+    else {
+        return Ok(config_error_response(
+            StatusCode::BAD_REQUEST,
+            "Malformed catalog source",
+        ));
+    };
+    let response = match id {
+        Some(id) => skills_catalog::update_catalog_source(&state.settings, &id, input).await,
+        None => skills_catalog::add_catalog_source(&state.settings, input).await,
+    };
+    Ok(catalog_source_response(response))
+}
+
+fn catalog_source_response(response: skills_catalog::SkillCatalogSourceResponse) -> Response<Body> {
+    let status = match response.error.as_ref().map(|e| e.kind.as_str()) {
+        None => StatusCode::OK,
+        Some("duplicate") => StatusCode::CONFLICT,
+        Some("notFound") => StatusCode::NOT_FOUND,
+        Some("authRequired") => StatusCode::UNAUTHORIZED,
+        Some(_) => StatusCode::BAD_REQUEST,
+    };
+    json_response(status, response)
+}
+
 async fn handle_config_routes(
     state: ServerState,
     path: &str,
@@ -1811,6 +2134,40 @@ async fn handle_config_routes(
         return Ok(json_response(StatusCode::OK, payload));
     }
 
+    if path == "/api/config/skills/catalog/sources" && method == Method::POST {
+        return handle_catalog_source_route(&state, None, method, req).await;
+    }
+
+    if let Some(id) = path.strip_prefix("/api/config/skills/catalog/sources/") {
+        let id = urlencoding::decode(id)
+            .map(|id| id.trim().to_string())
+            .unwrap_or_default();
+        if id.is_empty() {
+            return Ok(config_error_response(
+                StatusCode::BAD_REQUEST,
+                "Catalog id is required",
+            ));
+        }
+        return handle_catalog_source_route(&state, Some(id), method, req).await;
+    }
+
+    if path == "/api/config/skills/search" && method == Method::GET {
+        let query = req
+            .uri()
+            .query()
+            .and_then(|q| q.split('&').find_map(|pair| pair.strip_prefix("q=")))
+            .and_then(|q| {
+                urlencoding::decode(&q.replace('+', " "))
+                    .ok()
+                    .map(|q| q.into_owned())
+            })
+            .unwrap_or_default();
+
+        let working_directory = state.opencode.get_working_directory();
+        let payload = skills_catalog::search_skills(&working_directory, &query).await;
+        return Ok(json_response(StatusCode::OK, payload));
+    }
+
     if path == "/api/config/skills/scan" && method == Method::POST {
         let payload_map = match parse_request_payload(req).await {
             Ok(data) => data,
@@ -1850,6 +2207,38 @@ async fn handle_config_routes(
         return Ok(json_response(status, response));
     }
 
+    if path == "/api/config/skills/details" && method == Method::POST {
+        let payload_map = match parse_request_payload(req).await {
+            Ok(data) => data,
+            Err(resp) => return Ok(resp),
+        };
+
+        let payload_value = serde_json::Value::Object(payload_map.into_iter().collect());
+        let details_request =
+            match serde_json::from_value::<skills_catalog::SkillDetailsRequest>(payload_value) {
+                Ok(v) => v,
+                Err(_) => {
+                    return Ok(config_error_response(
+                        StatusCode::BAD_REQUEST,
+                        "Malformed skill details request",
+                    ));
+                }
+            };
+
+        let response = skills_catalog::get_skill_details(details_request).await;
+        let status = if response.ok {
+            StatusCode::OK
+        } else if response.error.as_ref().map(|e| e.kind.as_str()) == Some("notFound") {
+            StatusCode::NOT_FOUND
+        } else if response.error.as_ref().map(|e| e.kind.as_str()) == Some("authRequired") {
+            StatusCode::UNAUTHORIZED
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+
+        return Ok(json_response(status, response));
+    }
+
     if path == "/api/config/skills/install" && method == Method::POST {
         let payload_map = match parse_request_payload(req).await {
             Ok(data) => data,
@@ -1894,6 +2283,60 @@ async fn handle_config_routes(
         return Ok(json_response(status, response));
     }
 
+    if path == "/api/config/skills/installed" && method == Method::GET {
+        let working_directory = state.opencode.get_working_directory();
+        let skills =
+            skills_catalog::list_installed_skills_with_provenance(&working_directory).await;
+        return Ok(json_response(
+            StatusCode::OK,
+            serde_json::json!({ "skills": skills }),
+        ));
+    }
+
+    if path == "/api/config/skills/updates" && method == Method::GET {
+        let working_directory = state.opencode.get_working_directory();
+        let payload = skills_catalog::check_skill_updates(&working_directory).await;
+        return Ok(json_response(StatusCode::OK, payload));
+    }
+
+    if path == "/api/config/skills/update" && method == Method::POST {
+        let payload_map = match parse_request_payload(req).await {
+            Ok(data) => data,
+            Err(resp) => return Ok(resp),
+        };
+        let skill_name = payload_map
+            .get("skillName")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .unwrap_or("");
+        if skill_name.is_empty() {
+            return Ok(config_error_response(
+                StatusCode::BAD_REQUEST,
+                "Skill name is required",
+            ));
+        }
+        let scope = payload_map.get("scope").and_then(|v| v.as_str());
+
+        let working_directory = state.opencode.get_working_directory();
+        let response = skills_catalog::update_skill(&working_directory, skill_name, scope).await;
+        let status = if response.ok {
+            StatusCode::OK
+        } else if response.error.as_ref().map(|e| e.kind.as_str()) == Some("notFound") {
+            StatusCode::NOT_FOUND
+        } else if response.error.as_ref().map(|e| e.kind.as_str()) == Some("authRequired") {
+            StatusCode::UNAUTHORIZED
+        } else {
+            StatusCode::BAD_REQUEST
+        };
+        return Ok(json_response(status, response));
+    }
+
+    if path == "/api/config/skills/update-all" && method == Method::POST {
+        let working_directory = state.opencode.get_working_directory();
+        let response = skills_catalog::update_all_skills(&working_directory).await;
+        return Ok(json_response(StatusCode::OK, response));
+    }
+
     // Handle skill routes: /api/config/skills and /api/config/skills/:name
     if path == "/api/config/skills" && method == Method::GET {
         return handle_skill_list_route(&state).await;
@@ -2106,6 +2549,10 @@ async fn proxy_to_opencode(
         return handle_config_routes(state, &origin_path, method, req).await;
     }
 
+    if let Err(response) = hold_until_opencode_ready(&state, &origin_path).await {
+        return Ok(response);
+    }
+
     let port = state.opencode.current_port().ok_or_else(|| {
         error!("[desktop:http] PROXY FAILED: OpenCode not running (no port)");
         StatusCode::SERVICE_UNAVAILABLE
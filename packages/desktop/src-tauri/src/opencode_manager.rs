@@ -27,6 +27,7 @@ static URL_REGEX: Lazy<Regex> = Lazy::new(|| {
 const FIRST_SIGNAL_TIMEOUT_MS: u64 = 750;
 const READY_CHECK_TIMEOUT_MS: u64 = 20000;
 const READY_CHECK_INTERVAL_MS: u64 = 400;
+const READY_WAIT_POLL_MS: u64 = 100;
 
 #[derive(Clone)]
 pub struct OpenCodeManager {
@@ -39,10 +40,21 @@ pub struct OpenCodeManager {
     port: Arc<RwLock<Option<u16>>>,
     api_prefix: Arc<RwLock<String>>,
     is_ready: Arc<AtomicBool>,
+    /// Set from the moment a (re)start begins until it succeeds or fails
+    starting: Arc<AtomicBool>,
     shutting_down: Arc<AtomicBool>,
     http_client: Client,
 }
 
+/// Clears `OpenCodeManager::starting` however the start attempt ends
+struct StartingGuard(Arc<AtomicBool>);
+
+impl Drop for StartingGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
 fn normalize_api_prefix(prefix: &str) -> String {
     let trimmed = prefix.trim();
     if trimmed.is_empty() || trimmed == "/" {
@@ -106,6 +118,7 @@ impl OpenCodeManager {
             port: Arc::new(RwLock::new(None)),
             api_prefix: Arc::new(RwLock::new(String::new())),
             is_ready: Arc::new(AtomicBool::new(false)),
+            starting: Arc::new(AtomicBool::new(false)),
             shutting_down: Arc::new(AtomicBool::new(false)),
             http_client: Client::builder()
                 .timeout(Duration::from_secs(2))
@@ -130,6 +143,7 @@ impl OpenCodeManager {
             }
         }
 
+        let _starting = self.mark_starting();
         self.is_ready.store(false, Ordering::SeqCst);
         let child = self.spawn_process().await?;
         *guard = Some(child);
@@ -155,6 +169,7 @@ impl OpenCodeManager {
 
     pub async fn restart(&self) -> Result<()> {
         info!("[desktop:opencode] restarting...");
+        let _starting = self.mark_starting();
         self.is_ready.store(false, Ordering::SeqCst);
 
         self.graceful_stop().await?;
@@ -237,6 +252,34 @@ impl OpenCodeManager {
         self.shutting_down.load(Ordering::SeqCst)
     }
 
+    fn mark_starting(&self) -> StartingGuard {
+        self.starting.store(true, Ordering::SeqCst);
+        StartingGuard(self.starting.clone())
+    }
+
+    /// Whether a start or restart is underway, as opposed to OpenCode being down
+    pub fn is_starting(&self) -> bool {
+        self.starting.load(Ordering::SeqCst)
+    }
+
+    /// Wait for a (re)start in progress to finish. Returns false when it fails, on
+    /// timeout, or on shutdown.
+    pub async fn wait_until_ready(&self, max_wait: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + max_wait;
+        loop {
+            if self.is_ready() {
+                return true;
+            }
+            if !self.is_starting()
+                || self.is_shutting_down()
+                || tokio::time::Instant::now() >= deadline
+            {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(READY_WAIT_POLL_MS)).await;
+        }
+    }
+
     pub async fn is_child_running(&self) -> Result<bool> {
         let mut guard = self.child.lock().await;
         if let Some(child) = guard.as_mut() {
@@ -556,7 +599,7 @@ fn resolve_opencode_binary() -> Option<String> {
     None
 }
 
-fn build_augmented_env() -> HashMap<String, String> {
+pub(crate) fn build_augmented_env() -> HashMap<String, String> {
     let mut env: HashMap<String, String> = std::env::vars().collect();
     if let Ok(login_path) = detect_login_shell_path() {
         let current = env.get("PATH").cloned().unwrap_or_default();
@@ -14,6 +14,7 @@ use tauri::{AppHandle, Emitter};
 use tokio::sync::Mutex;
 use tokio_util::io::StreamReader;
 
+use crate::skills_usage;
 use crate::DesktopRuntime;
 
 #[derive(Deserialize)]
@@ -306,6 +307,8 @@ async fn handle_event(
             }
         }
         "message.part.updated" => {
+            skills_usage::record_from_part_event(&event.properties).await;
+
             let Some(info) = event.properties.get("info") else {
                 return;
             };
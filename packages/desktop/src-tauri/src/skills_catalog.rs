@@ -1,26 +1,56 @@
 use anyhow::{anyhow, Context, Result};
+use futures_util::StreamExt;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use tokio::process::Command;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
+use crate::git_credentials::{self, CredentialEnv};
+use crate::identity_store;
 use crate::opencode_config;
+use crate::path_utils::expand_tilde_path;
+use crate::SettingsStore;
 
 static SKILL_NAME_RE: Lazy<Regex> = Lazy::new(|| {
     Regex::new(r"^[a-z0-9]([a-z0-9-]*[a-z0-9])?$").expect("valid skill name regex")
 });
 
 static AUTH_ERROR_RE: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)(permission denied|publickey|could not read from remote repository|authentication failed)")
+    Regex::new(r"(?i)(permission denied|publickey|could not read from remote repository|could not read username|authentication failed)")
         .expect("valid auth error regex")
 });
 
+static COMMIT_SHA_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[0-9a-fA-F]{7,40}$").expect("valid commit sha regex"));
+
+// Repository source forms accepted by parse_repo_source, tried in this order
+static SSH_URL_SOURCE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^ssh://(?:([^@/\s]+)@)?([^/\s]+)/([^\s#]+)$").expect("valid ssh url regex")
+});
+
+static SCP_SOURCE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([^@/\s]+)@([^:/\s]+):([^\s#]+)$").expect("valid scp source regex"));
+
+static HTTPS_SOURCE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(https?)://(?:[^@/\s]+@)?([^/\s]+)/([^\s#]+)$").expect("valid https source regex")
+});
+
+static HOST_PATH_SOURCE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([^/\s]+\.[^/\s]+)/([^\s#]+)$").expect("valid host path regex"));
+
+static SHORTHAND_SOURCE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([^/\s]+)/([^/\s]+)(?:/(.+))?$").expect("valid shorthand regex"));
+
 const CACHE_TTL: Duration = Duration::from_secs(30 * 60);
+const ARCHIVE_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+const ARCHIVE_MAX_DOWNLOAD_BYTES: u64 = 100 * 1024 * 1024;
+const ARCHIVE_MAX_EXTRACTED_BYTES: u64 = 500 * 1024 * 1024;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -32,12 +62,15 @@ pub struct SkillsCatalogSource {
     pub source: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_subpath: Option<String>,
+    /// Branch, tag or commit to read instead of the default branch
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
 
     #[serde(skip_serializing)]
     pub git_identity_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SkillsCatalogInstalledBadge {
     pub is_installed: bool,
@@ -45,7 +78,7 @@ pub struct SkillsCatalogInstalledBadge {
     pub scope: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SkillsCatalogItem {
     pub source_id: String,
@@ -54,6 +87,8 @@ pub struct SkillsCatalogItem {
     pub repo_subpath: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub git_identity_id: Option<String>,
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
     pub skill_dir: String,
     pub skill_name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -66,6 +101,22 @@ pub struct SkillsCatalogItem {
     pub installed: SkillsCatalogInstalledBadge,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillSearchResult {
+    #[serde(flatten)]
+    pub item: SkillsCatalogItem,
+    pub score: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillsSearchResponse {
+    pub ok: bool,
+    /// Best match first
+    pub results: Vec<SkillSearchResult>,
+}
+
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SkillsCatalogResponse {
@@ -74,6 +125,9 @@ pub struct SkillsCatalogResponse {
     pub sources: Option<Vec<SkillsCatalogSource>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub items_by_source: Option<HashMap<String, Vec<SkillsCatalogItem>>>,
+    /// Sources that could not be scanned; their item lists are empty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors_by_source: Option<HashMap<String, SkillsRepoError>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<SkillsRepoError>,
 }
@@ -147,70 +201,102 @@ struct RepoParsed {
     clone_https: String,
     clone_ssh: String,
     effective_subpath: Option<String>,
+    /// Set for local directory sources, which are read in place instead of cloned
+    local_path: Option<PathBuf>,
+    /// Set for zip/tar.gz URLs, which are downloaded and extracted instead of cloned
+    archive_url: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct GitIdentityWrapper {
-    profiles: Vec<GitIdentityProfile>,
-}
-
-#[derive(Debug, Clone, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct GitIdentityProfile {
-    id: String,
-    name: String,
-    #[serde(default)]
-    ssh_key: Option<String>,
-}
-
-fn identities_storage_path() -> Result<PathBuf> {
-    let mut path = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
-    path.push(".config");
-    path.push("openchamber");
-    path.push("git-identities.json");
-    Ok(path)
-}
-
-fn list_identities() -> Vec<IdentitySummary> {
-    let Ok(path) = identities_storage_path() else {
-        return vec![];
-    };
-
-    let Ok(content) = std::fs::read_to_string(path) else {
-        return vec![];
-    };
-
-    let Ok(wrapper) = serde_json::from_str::<GitIdentityWrapper>(&content) else {
-        return vec![];
-    };
-
-    wrapper
-        .profiles
+async fn list_identities() -> Vec<IdentitySummary> {
+    identity_store::load_profiles()
+        .await
+        .unwrap_or_default()
         .into_iter()
         .map(|p| IdentitySummary { id: p.id, name: p.name })
         .collect()
 }
 
-fn resolve_identity_ssh_key(identity_id: Option<&str>) -> Option<String> {
+/// `core.sshCommand` for cloning with an identity; `None` means the identity can't do SSH
+/// and the source is cloned over HTTPS. Agent identities skip `-i` (or, with a public key
+/// set, use it only to pick the agent key), so hardware and forwarded keys work.
+async fn resolve_identity_ssh_command(identity_id: Option<&str>) -> Option<String> {
     let id = identity_id?.trim();
     if id.is_empty() {
         return None;
     }
 
-    let path = identities_storage_path().ok()?;
-    let content = std::fs::read_to_string(path).ok()?;
-    let wrapper = serde_json::from_str::<GitIdentityWrapper>(&content).ok()?;
+    let profile = identity_store::find_profile(id).await.ok().flatten()?;
+    let key = profile
+        .ssh_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|k| !k.is_empty());
+    let key_args = match (key, profile.ssh_agent) {
+        (Some(key), true) => format!(" -o IdentitiesOnly=yes -i {}", key),
+        (Some(key), false) => format!(" -i {}", key),
+        (None, true) => String::new(),
+        (None, false) => return None,
+    };
+    Some(format!(
+        "ssh{} -o BatchMode=yes -o StrictHostKeyChecking=accept-new",
+        key_args
+    ))
+}
 
-    wrapper
-        .profiles
-        .into_iter()
-        .find(|p| p.id == id)
-        .and_then(|p| p.ssh_key)
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
+/// Clone URLs for a repository at `path` on `host`. GitHub keeps the short `owner/repo`
+/// form as its normalized name; other hosts are normalized as `host/path`.
+fn repo_on_host(
+    host: &str,
+    path: &str,
+    scheme: &str,
+    ssh_user: &str,
+    explicit_subpath: Option<String>,
+) -> Result<RepoParsed> {
+    let host_name = host.split(':').next().unwrap_or(host).to_ascii_lowercase();
+    let path = path.trim_matches('/');
+    let has_git_suffix = path.ends_with(".git");
+    let bare_path = path.trim_end_matches(".git").trim_matches('/');
+
+    if host_name == "github.com" {
+        let segments: Vec<&str> = bare_path.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.len() < 2 {
+            return Err(anyhow!("Unsupported repository source format"));
+        }
+        let (owner, repo) = (segments[0], segments[1]);
+        return Ok(RepoParsed {
+            normalized_repo: format!("{}/{}", owner, repo),
+            clone_https: format!("https://github.com/{}/{}.git", owner, repo),
+            clone_ssh: format!("git@github.com:{}/{}.git", owner, repo),
+            effective_subpath: explicit_subpath,
+            local_path: None,
+            archive_url: None,
+        });
+    }
+
+    if bare_path.is_empty() || bare_path.split('/').any(|s| s.is_empty() || s == "..") {
+        return Err(anyhow!("Unsupported repository source format"));
+    }
+    // Plain git servers may only answer to the exact path, so keep `.git` as given
+    let clone_path = if has_git_suffix {
+        format!("{}.git", bare_path)
+    } else {
+        bare_path.to_string()
+    };
+
+    Ok(RepoParsed {
+        normalized_repo: format!("{}/{}", host.to_ascii_lowercase(), bare_path),
+        clone_https: format!("{}://{}/{}", scheme, host, clone_path),
+        clone_ssh: format!("{}@{}:{}", ssh_user, host_name, clone_path),
+        effective_subpath: explicit_subpath,
+        local_path: None,
+        archive_url: None,
+    })
 }
 
+/// Accepts HTTPS and SSH URLs for any git host (`https://gitlab.com/group/repo`,
+/// `git@git.example.com:team/skills.git`, `ssh://git@host:2222/repo.git`), a
+/// scheme-less `host/path`, GitHub `owner/repo[/subpath]` shorthand, local
+/// directories (`file:///path`, `/path` or `~/path`), and zip/tar.gz archive URLs.
 fn parse_repo_source(source: &str, subpath: Option<&str>) -> Result<RepoParsed> {
     let raw = source.trim();
     if raw.is_empty() {
@@ -221,35 +307,83 @@ fn parse_repo_source(source: &str, subpath: Option<&str>) -> Result<RepoParsed>
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty());
 
-    // SSH URL
-    let ssh_re = Regex::new(r"^git@github\.com:([^/\s]+)/([^\s#]+)$").unwrap();
-    if let Some(caps) = ssh_re.captures(raw) {
-        let owner = caps.get(1).unwrap().as_str();
-        let repo = caps.get(2).unwrap().as_str().trim_end_matches(".git");
+    // Local directory, e.g. a skills repo under development
+    let local = raw.strip_prefix("file://");
+    if let Some(path) =
+        local.or_else(|| (raw.starts_with('~') || Path::new(raw).is_absolute()).then_some(raw))
+    {
+        let path = expand_tilde_path(path);
+        if !path.is_absolute() {
+            return Err(anyhow!("Local skill sources must be absolute paths"));
+        }
         return Ok(RepoParsed {
-            normalized_repo: format!("{}/{}", owner, repo),
-            clone_https: format!("https://github.com/{}/{}.git", owner, repo),
-            clone_ssh: format!("git@github.com:{}/{}.git", owner, repo),
+            normalized_repo: path.display().to_string(),
+            clone_https: String::new(),
+            clone_ssh: String::new(),
             effective_subpath: explicit_subpath,
+            local_path: Some(path),
+            archive_url: None,
         });
     }
 
-    // HTTPS URL
-    let https_re = Regex::new(r"^https?://github\.com/([^/\s]+)/([^\s#]+)$").unwrap();
-    if let Some(caps) = https_re.captures(raw) {
-        let owner = caps.get(1).unwrap().as_str();
-        let repo = caps.get(2).unwrap().as_str().trim_end_matches(".git");
+    // Archive download, e.g. a GitHub release asset
+    if let Some(rest) = raw.strip_prefix("http://") {
+        if archive_format(&format!("https://{}", rest)).is_some() {
+            return Err(anyhow!("Archive URLs must use https://"));
+        }
+    }
+    if archive_format(raw).is_some() {
         return Ok(RepoParsed {
-            normalized_repo: format!("{}/{}", owner, repo),
-            clone_https: format!("https://github.com/{}/{}.git", owner, repo),
-            clone_ssh: format!("git@github.com:{}/{}.git", owner, repo),
+            normalized_repo: raw.to_string(),
+            clone_https: String::new(),
+            clone_ssh: String::new(),
             effective_subpath: explicit_subpath,
+            local_path: None,
+            archive_url: Some(raw.to_string()),
         });
     }
 
+    // ssh:// URL; a custom port only works through the URL form
+    if let Some(caps) = SSH_URL_SOURCE_RE.captures(raw) {
+        let user = caps.get(1).map(|m| m.as_str()).unwrap_or("git");
+        let host = caps.get(2).unwrap().as_str();
+        let path = caps.get(3).unwrap().as_str();
+        let mut parsed = repo_on_host(host, path, "https", user, explicit_subpath)?;
+        if host.contains(':') {
+            // scp-style URLs can't carry a port, and HTTPS runs on its own port
+            parsed.clone_ssh = raw.to_string();
+            let host_name = host.split(':').next().unwrap_or(host);
+            parsed.clone_https = parsed.clone_https.replacen(host, host_name, 1);
+        }
+        return Ok(parsed);
+    }
+
+    // scp-style SSH, e.g. git@gitlab.com:group/repo.git
+    if let Some(caps) = SCP_SOURCE_RE.captures(raw) {
+        let user = caps.get(1).unwrap().as_str();
+        let host = caps.get(2).unwrap().as_str();
+        let path = caps.get(3).unwrap().as_str();
+        return repo_on_host(host, path, "https", user, explicit_subpath);
+    }
+
+    // HTTP(S) URL
+    if let Some(caps) = HTTPS_SOURCE_RE.captures(raw) {
+        let scheme = caps.get(1).unwrap().as_str();
+        let host = caps.get(2).unwrap().as_str();
+        let path = caps.get(3).unwrap().as_str();
+        return repo_on_host(host, path, scheme, "git", explicit_subpath);
+    }
+
+    // Scheme-less host/path; GitHub owners can't contain dots, so a dotted first
+    // segment is a host
+    if let Some(caps) = HOST_PATH_SOURCE_RE.captures(raw) {
+        let host = caps.get(1).unwrap().as_str();
+        let path = caps.get(2).unwrap().as_str();
+        return repo_on_host(host, path, "https", "git", explicit_subpath);
+    }
+
     // Shorthand owner/repo[/subpath]
-    let shorthand_re = Regex::new(r"^([^/\s]+)/([^/\s]+)(?:/(.+))?$").unwrap();
-    if let Some(caps) = shorthand_re.captures(raw) {
+    if let Some(caps) = SHORTHAND_SOURCE_RE.captures(raw) {
         let owner = caps.get(1).unwrap().as_str();
         let repo = caps.get(2).unwrap().as_str().trim_end_matches(".git");
         let shorthand_subpath = caps
@@ -262,6 +396,8 @@ fn parse_repo_source(source: &str, subpath: Option<&str>) -> Result<RepoParsed>
             clone_https: format!("https://github.com/{}/{}.git", owner, repo),
             clone_ssh: format!("git@github.com:{}/{}.git", owner, repo),
             effective_subpath: explicit_subpath.or(shorthand_subpath),
+            local_path: None,
+            archive_url: None,
         });
     }
 
@@ -314,18 +450,27 @@ fn parse_skill_md_frontmatter(contents: &str) -> (Option<String>, Option<String>
     (name, description, warnings)
 }
 
-async fn run_git(args: &[String], cwd: &Path, ssh_key: Option<&str>, timeout: Duration) -> Result<(String, String)> {
+async fn run_git(
+    args: &[String],
+    cwd: &Path,
+    ssh_command: Option<&str>,
+    timeout: Duration,
+) -> Result<(String, String)> {
+    run_git_with_env(args, cwd, ssh_command, timeout, &[]).await
+}
+
+async fn run_git_with_env(
+    args: &[String],
+    cwd: &Path,
+    ssh_command: Option<&str>,
+    timeout: Duration,
+    envs: &[(String, String)],
+) -> Result<(String, String)> {
     let mut cmd = Command::new("git");
 
-    if let Some(key) = ssh_key {
-        let key = key.trim();
-        if !key.is_empty() {
-            let ssh_command = format!(
-                "ssh -i {} -o BatchMode=yes -o StrictHostKeyChecking=accept-new",
-                key
-            );
-            cmd.arg("-c").arg(format!("core.sshCommand={}", ssh_command));
-        }
+    if let Some(ssh_command) = ssh_command {
+        cmd.arg("-c")
+            .arg(format!("core.sshCommand={}", ssh_command));
     }
 
     cmd.args(args)
@@ -334,7 +479,8 @@ async fn run_git(args: &[String], cwd: &Path, ssh_key: Option<&str>, timeout: Du
         .kill_on_drop(true)
         .env("GIT_TERMINAL_PROMPT", "0")
         .env("GCM_INTERACTIVE", "Never")
-        .env("LC_ALL", "C");
+        .env("LC_ALL", "C")
+        .envs(envs.iter().map(|(k, v)| (k, v)));
 
     let output = tokio::time::timeout(timeout, cmd.output())
         .await
@@ -352,12 +498,12 @@ async fn run_git(args: &[String], cwd: &Path, ssh_key: Option<&str>, timeout: Du
     Ok((stdout, stderr))
 }
 
-fn auth_required_error(message: &str) -> SkillsRepoError {
+async fn auth_required_error(message: &str) -> SkillsRepoError {
     SkillsRepoError {
         kind: "authRequired".to_string(),
         message: message.to_string(),
         ssh_only: Some(true),
-        identities: Some(list_identities()),
+        identities: Some(list_identities().await),
         conflicts: None,
     }
 }
@@ -372,6 +518,14 @@ fn simple_error(kind: &str, message: &str) -> SkillsRepoError {
     }
 }
 
+/// Error for a failed `scan_repo_items`
+async fn scan_error(err: &anyhow::Error) -> SkillsRepoError {
+    if err.to_string().contains("AUTH_REQUIRED") {
+        return auth_required_error("Authentication required to access this repository. Use a Git identity with an SSH key, or save an HTTPS token for this host.").await;
+    }
+    simple_error("networkError", &err.to_string())
+}
+
 fn conflicts_error(conflicts: Vec<SkillConflict>) -> SkillsRepoError {
     SkillsRepoError {
         kind: "conflicts".to_string(),
@@ -382,46 +536,174 @@ fn conflicts_error(conflicts: Vec<SkillConflict>) -> SkillsRepoError {
     }
 }
 
-async fn clone_repo(clone_url: &str, target_dir: &Path, ssh_key: Option<&str>) -> Result<()> {
-    let preferred = vec![
-        "clone".to_string(),
-        "--depth".to_string(),
-        "1".to_string(),
-        "--filter=blob:none".to_string(),
-        "--no-checkout".to_string(),
-        clone_url.to_string(),
-        target_dir.display().to_string(),
-    ];
+/// Environment that answers HTTPS auth for `clone_url` with the token stored for its host
+/// (see `git_credentials`); empty for SSH clones. Keep it alive for every command that
+/// talks to the remote.
+async fn https_token_env(clone_url: &str, ssh_command: Option<&str>) -> CredentialEnv {
+    if ssh_command.is_some() {
+        return CredentialEnv::default();
+    }
+    git_credentials::helper_env(&[clone_url.to_string()]).await
+}
 
-    let fallback = vec![
-        "clone".to_string(),
-        "--depth".to_string(),
-        "1".to_string(),
-        "--no-checkout".to_string(),
-        clone_url.to_string(),
-        target_dir.display().to_string(),
-    ];
+/// Shallow clone without checkout. `git_ref` may be a branch or tag (`--branch`) or a
+/// commit, which is fetched on its own and detached onto HEAD.
+async fn clone_repo(
+    clone_url: &str,
+    target_dir: &Path,
+    ssh_command: Option<&str>,
+    git_ref: Option<&str>,
+) -> Result<()> {
+    let git_ref = git_ref.map(str::trim).filter(|r| !r.is_empty());
+    if git_ref.is_some_and(|r| r.starts_with('-')) {
+        return Err(anyhow!("Invalid ref"));
+    }
+    let commit = git_ref.filter(|r| COMMIT_SHA_RE.is_match(r));
+    let branch = git_ref.filter(|_| commit.is_none());
+
+    let clone_args = |filter: bool| {
+        let mut args = vec!["clone".to_string(), "--depth".to_string(), "1".to_string()];
+        if filter {
+            args.push("--filter=blob:none".to_string());
+        }
+        args.push("--no-checkout".to_string());
+        if let Some(branch) = branch {
+            args.push("--branch".to_string());
+            args.push(branch.to_string());
+        }
+        args.push(clone_url.to_string());
+        args.push(target_dir.display().to_string());
+        args
+    };
 
     let cwd = std::env::temp_dir();
+    let token_env = https_token_env(clone_url, ssh_command).await;
+
+    // A partial clone fetches blobs lazily at checkout, which would need the token again
+    let filtered = token_env.is_empty()
+        && run_git(
+            &clone_args(true),
+            &cwd,
+            ssh_command,
+            Duration::from_secs(60),
+        )
+        .await
+        .is_ok();
+    if !filtered {
+        let _ = tokio::fs::remove_dir_all(target_dir).await;
+        run_git_with_env(
+            &clone_args(false),
+            &cwd,
+            ssh_command,
+            Duration::from_secs(60),
+            token_env.vars(),
+        )
+        .await?;
+    }
 
-    if run_git(&preferred, &cwd, ssh_key, Duration::from_secs(60)).await.is_ok() {
-        return Ok(());
+    if let Some(commit) = commit {
+        checkout_commit_ref(target_dir, commit, ssh_command, token_env.vars()).await?;
+    }
+    Ok(())
+}
+
+/// Point HEAD of a fresh shallow clone at `commit`. Servers usually allow fetching a
+/// full hash directly; abbreviated ones need the whole history.
+async fn checkout_commit_ref(
+    repo_dir: &Path,
+    commit: &str,
+    ssh_command: Option<&str>,
+    envs: &[(String, String)],
+) -> Result<()> {
+    let cwd = std::env::temp_dir();
+    let git = |args: &[&str]| {
+        let mut full = vec!["-C".to_string(), repo_dir.display().to_string()];
+        full.extend(args.iter().map(|a| a.to_string()));
+        full
+    };
+
+    let fetched = commit.len() == 40
+        && run_git_with_env(
+            &git(&["fetch", "--depth", "1", "origin", commit]),
+            &cwd,
+            ssh_command,
+            Duration::from_secs(60),
+            envs,
+        )
+        .await
+        .is_ok();
+    if !fetched {
+        run_git_with_env(
+            &git(&["fetch", "--unshallow", "origin"]),
+            &cwd,
+            ssh_command,
+            Duration::from_secs(120),
+            envs,
+        )
+        .await?;
     }
 
-    run_git(&fallback, &cwd, ssh_key, Duration::from_secs(60)).await?;
+    let target = format!("{}^{{commit}}", commit);
+    let (resolved, _) = run_git(
+        &git(&["rev-parse", "--verify", "--quiet", &target]),
+        &cwd,
+        ssh_command,
+        Duration::from_secs(15),
+    )
+    .await
+    .map_err(|_| anyhow!("Commit {} not found in repository", commit))?;
+    run_git(
+        &git(&["update-ref", "--no-deref", "HEAD", resolved.trim()]),
+        &cwd,
+        ssh_command,
+        Duration::from_secs(15),
+    )
+    .await?;
     Ok(())
 }
 
+async fn head_commit(repo_dir: &Path) -> Option<String> {
+    let args = vec![
+        "-C".to_string(),
+        repo_dir.display().to_string(),
+        "rev-parse".to_string(),
+        "HEAD".to_string(),
+    ];
+    run_git(&args, &std::env::temp_dir(), None, Duration::from_secs(15))
+        .await
+        .ok()
+        .map(|(out, _)| out.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+}
+
 async fn safe_rm(dir: &Path) {
     let _ = tokio::fs::remove_dir_all(dir).await;
 }
 
+/// (repo source, skill dir, frontmatter name, description, warnings, installable)
+type RawSkillItem = (
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Vec<String>,
+    bool,
+);
+
+struct RepoScan {
+    effective_subpath: Option<String>,
+    /// Commit that was scanned; absent for local directory and archive sources
+    commit: Option<String>,
+    items: Vec<RawSkillItem>,
+}
+
 async fn scan_repo_items(
     source: &str,
     subpath: Option<&str>,
     default_subpath: Option<&str>,
-    ssh_key: Option<&str>,
-) -> Result<(String, Option<String>, Vec<(String, String, Option<String>, Option<String>, Vec<String>, bool)>)> {
+    git_ref: Option<&str>,
+    ssh_command: Option<&str>,
+) -> Result<RepoScan> {
     let parsed = parse_repo_source(source, subpath)?;
     let effective_subpath = parsed
         .effective_subpath
@@ -429,7 +711,43 @@ async fn scan_repo_items(
         .or_else(|| default_subpath.map(|s| s.to_string()))
         .filter(|s| !s.trim().is_empty());
 
-    let clone_url = if ssh_key.is_some() {
+    if let Some(local_path) = parsed.local_path.as_deref() {
+        let skill_dirs = find_local_skill_dirs(local_path, effective_subpath.as_deref()).await?;
+        let items = read_skill_items(source, local_path, skill_dirs, None).await;
+        return Ok(RepoScan {
+            effective_subpath,
+            commit: None,
+            items,
+        });
+    }
+
+    if let Some(archive_url) = parsed.archive_url.as_deref() {
+        let temp_base = std::env::temp_dir().join(format!(
+            "openchamber-desktop-skills-scan-{}",
+            Uuid::new_v4()
+        ));
+        let found = match download_archive(archive_url, &temp_base).await {
+            Ok(root) => find_local_skill_dirs(&root, effective_subpath.as_deref())
+                .await
+                .map(|skill_dirs| (root, skill_dirs)),
+            Err(err) => Err(err),
+        };
+        let items = match found {
+            Ok((root, skill_dirs)) => read_skill_items(source, &root, skill_dirs, None).await,
+            Err(err) => {
+                safe_rm(&temp_base).await;
+                return Err(err);
+            }
+        };
+        safe_rm(&temp_base).await;
+        return Ok(RepoScan {
+            effective_subpath,
+            commit: None,
+            items,
+        });
+    }
+
+    let clone_url = if ssh_command.is_some() {
         parsed.clone_ssh.clone()
     } else {
         parsed.clone_https.clone()
@@ -440,7 +758,7 @@ async fn scan_repo_items(
     // Clone into temp_base (directory must not exist for git clone target)
     let _ = tokio::fs::remove_dir_all(&temp_base).await;
 
-    let clone_res = clone_repo(&clone_url, &temp_base, ssh_key).await;
+    let clone_res = clone_repo(&clone_url, &temp_base, ssh_command, git_ref).await;
     if let Err(err) = clone_res {
         let msg = err.to_string();
         if AUTH_ERROR_RE.is_match(&msg) {
@@ -466,7 +784,7 @@ async fn scan_repo_items(
             "--no-cone".to_string(),
         ],
         &std::env::temp_dir(),
-        ssh_key,
+        ssh_command,
         Duration::from_secs(15),
     )
     .await;
@@ -482,7 +800,13 @@ async fn scan_repo_items(
         ];
         set_args.extend(patterns.clone());
 
-        let sparse_set = run_git(&set_args, &std::env::temp_dir(), ssh_key, Duration::from_secs(30)).await;
+        let sparse_set = run_git(
+            &set_args,
+            &std::env::temp_dir(),
+            ssh_command,
+            Duration::from_secs(30),
+        )
+        .await;
         if sparse_set.is_ok() {
             let checkout = run_git(
                 &vec![
@@ -493,7 +817,7 @@ async fn scan_repo_items(
                     "HEAD".to_string(),
                 ],
                 &std::env::temp_dir(),
-                ssh_key,
+                ssh_command,
                 Duration::from_secs(60),
             )
             .await;
@@ -506,7 +830,7 @@ async fn scan_repo_items(
                         "ls-files".to_string(),
                     ],
                     &std::env::temp_dir(),
-                    ssh_key,
+                    ssh_command,
                     Duration::from_secs(15),
                 )
                 .await;
@@ -539,12 +863,22 @@ async fn scan_repo_items(
             list_args.push(sp.clone());
         }
 
-        let list_out = run_git(&list_args, &std::env::temp_dir(), ssh_key, Duration::from_secs(30)).await;
+        let list_out = run_git(
+            &list_args,
+            &std::env::temp_dir(),
+            ssh_command,
+            Duration::from_secs(30),
+        )
+        .await;
         let stdout = match list_out {
             Ok((out, _)) => out,
             Err(_) => {
                 safe_rm(&temp_base).await;
-                return Ok((parsed.normalized_repo, effective_subpath, vec![]));
+                return Ok(RepoScan {
+                    effective_subpath,
+                    commit: None,
+                    items: vec![],
+                });
             }
         };
 
@@ -571,6 +905,226 @@ async fn scan_repo_items(
     skill_dirs.sort();
     skill_dirs.dedup();
 
+    let items = read_skill_items(source, &temp_base, skill_dirs, Some(ssh_command)).await;
+    let commit = head_commit(&temp_base).await;
+
+    safe_rm(&temp_base).await;
+
+    Ok(RepoScan {
+        effective_subpath,
+        commit,
+        items,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+/// Archives are only fetched over HTTPS; their contents end up as installed skills
+fn archive_format(source: &str) -> Option<ArchiveFormat> {
+    if !source.starts_with("https://") {
+        return None;
+    }
+    let path = source
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(source)
+        .to_ascii_lowercase();
+    if path.ends_with(".zip") {
+        Some(ArchiveFormat::Zip)
+    } else if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+        Some(ArchiveFormat::TarGz)
+    } else {
+        None
+    }
+}
+
+/// Download and extract an archive into `target_dir`. Returns the directory to scan:
+/// a lone top-level folder (as in GitHub source archives) is treated as the root.
+async fn download_archive(url: &str, target_dir: &Path) -> Result<PathBuf> {
+    let format = archive_format(url).ok_or_else(|| anyhow!("Unsupported archive format"))?;
+
+    // Don't let a redirect downgrade the download to plain HTTP
+    let redirect = reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.url().scheme() != "https" {
+            attempt.error("Archive redirected to a non-HTTPS URL")
+        } else if attempt.previous().len() >= 10 {
+            attempt.stop()
+        } else {
+            attempt.follow()
+        }
+    });
+    let client = reqwest::Client::builder()
+        .timeout(ARCHIVE_DOWNLOAD_TIMEOUT)
+        .redirect(redirect)
+        .build()?;
+    let mut response = client.get(url).send().await?;
+    if !response.status().is_success() {
+        return Err(anyhow!(
+            "Archive download failed: HTTP {}",
+            response.status()
+        ));
+    }
+    if response
+        .content_length()
+        .is_some_and(|len| len > ARCHIVE_MAX_DOWNLOAD_BYTES)
+    {
+        return Err(anyhow!("Archive is too large"));
+    }
+
+    let mut bytes = vec![];
+    while let Some(chunk) = response.chunk().await? {
+        if (bytes.len() + chunk.len()) as u64 > ARCHIVE_MAX_DOWNLOAD_BYTES {
+            return Err(anyhow!("Archive is too large"));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let target = target_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || extract_archive(&bytes, format, &target)).await??;
+
+    let mut entries = tokio::fs::read_dir(target_dir).await?;
+    let mut top_level = vec![];
+    while let Some(entry) = entries.next_entry().await? {
+        top_level.push(entry.path());
+    }
+    match top_level.as_slice() {
+        [only] if only.is_dir() && !only.join("SKILL.md").exists() => Ok(only.clone()),
+        _ => Ok(target_dir.to_path_buf()),
+    }
+}
+
+/// Relative path of an archive entry, or None if it could escape the extraction dir
+fn archive_entry_path(path: &Path) -> Option<PathBuf> {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(part) => result.push(part),
+            std::path::Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(result)
+}
+
+/// Blocking. Only regular files and directories are extracted; symlinks and other entry
+/// types are skipped, and an entry pointing outside `target_dir` fails the whole archive.
+fn extract_archive(bytes: &[u8], format: ArchiveFormat, target_dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(target_dir)?;
+    let mut extracted: u64 = 0;
+    let mut write_entry =
+        |name: &Path, is_dir: bool, reader: &mut dyn std::io::Read| -> Result<()> {
+            let rel = archive_entry_path(name)
+                .ok_or_else(|| anyhow!("Archive entry {} escapes the archive", name.display()))?;
+            if rel.as_os_str().is_empty() {
+                return Ok(());
+            }
+            let dest = target_dir.join(rel);
+            if is_dir {
+                std::fs::create_dir_all(&dest)?;
+                return Ok(());
+            }
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let mut file = std::fs::File::create(&dest)?;
+            let remaining = ARCHIVE_MAX_EXTRACTED_BYTES - extracted;
+            let written =
+                std::io::copy(&mut std::io::Read::take(reader, remaining + 1), &mut file)?;
+            if written > remaining {
+                return Err(anyhow!("Archive is too large when extracted"));
+            }
+            extracted += written;
+            Ok(())
+        };
+
+    match format {
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
+            for index in 0..archive.len() {
+                let mut file = archive.by_index(index)?;
+                if file.is_symlink() {
+                    continue;
+                }
+                let name = PathBuf::from(file.name());
+                let is_dir = file.is_dir();
+                write_entry(&name, is_dir, &mut file)?;
+            }
+        }
+        ArchiveFormat::TarGz => {
+            let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes));
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let entry_type = entry.header().entry_type();
+                if !entry_type.is_file() && !entry_type.is_dir() {
+                    continue;
+                }
+                let name = entry.path()?.into_owned();
+                write_entry(&name, entry_type.is_dir(), &mut entry)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Directories under `base` (or `base/subpath`) holding a SKILL.md, as repo-relative
+/// paths. Hidden directories and `node_modules` are skipped, as are symlinks.
+async fn find_local_skill_dirs(base: &Path, subpath: Option<&str>) -> Result<Vec<String>> {
+    if !base.is_dir() {
+        return Err(anyhow!("Local directory not found: {}", base.display()));
+    }
+    let subpath = subpath.map(|s| s.trim().trim_matches('/')).unwrap_or("");
+    if subpath.split('/').any(|part| part.trim() == "..") {
+        return Err(anyhow!("Invalid subpath"));
+    }
+    let root = repo_path_to_fs(base, subpath);
+    if !root.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut skill_dirs = vec![];
+    let mut stack = vec![(root, subpath.to_string())];
+    while let Some((dir, rel)) = stack.pop() {
+        if !rel.is_empty() && dir.join("SKILL.md").is_file() {
+            skill_dirs.push(rel.clone());
+        }
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name == "node_modules" {
+                continue;
+            }
+            if entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false) {
+                let child_rel = if rel.is_empty() {
+                    name
+                } else {
+                    format!("{}/{}", rel, name)
+                };
+                stack.push((entry.path(), child_rel));
+            }
+        }
+    }
+
+    skill_dirs.sort();
+    Ok(skill_dirs)
+}
+
+/// Read SKILL.md of each skill dir under `base` and validate the skill with the rules the
+/// install applies. `git_fallback` carries the ssh command when `base` is a sparse clone,
+/// whose files may need `git show` instead.
+async fn read_skill_items(
+    source: &str,
+    base: &Path,
+    skill_dirs: Vec<String>,
+    git_fallback: Option<Option<&str>>,
+) -> Vec<RawSkillItem> {
+    let symlinks = match git_fallback {
+        Some(ssh_command) => list_tree_symlinks(base, ssh_command).await,
+        None => vec![],
+    };
     let mut items = vec![];
 
     for skill_dir in skill_dirs {
@@ -585,43 +1139,75 @@ async fn scan_repo_items(
             continue;
         }
 
-        let mut warnings = vec![];
+        let skill_md_repo_path = format!("{}/SKILL.md", skill_dir);
 
-        let skill_md_repo_path = if skill_dir.is_empty() {
-            "SKILL.md".to_string()
-        } else {
-            format!("{}/SKILL.md", skill_dir)
-        };
-
-        let skill_md_fs_path = repo_path_to_fs(&temp_base, &skill_md_repo_path);
-        let contents = match tokio::fs::read_to_string(&skill_md_fs_path).await {
-            Ok(text) => text,
-            Err(_) => {
+        let skill_md_fs_path = repo_path_to_fs(base, &skill_md_repo_path);
+        let contents = match (
+            tokio::fs::read_to_string(&skill_md_fs_path).await,
+            git_fallback,
+        ) {
+            (Ok(text), _) => Some(text),
+            (Err(_), Some(ssh_command)) => {
                 // Fallback to git show if the file is not present in working tree.
                 let show_args = vec![
                     "-C".to_string(),
-                    temp_base.display().to_string(),
+                    base.display().to_string(),
                     "show".to_string(),
                     format!("HEAD:{}", skill_md_repo_path),
                 ];
 
-                match run_git(&show_args, &std::env::temp_dir(), ssh_key, Duration::from_secs(15)).await {
-                    Ok((out, _)) => out,
-                    Err(_) => {
-                        warnings.push("Failed to read SKILL.md".to_string());
-                        String::new()
-                    }
-                }
+                run_git(
+                    &show_args,
+                    &std::env::temp_dir(),
+                    ssh_command,
+                    Duration::from_secs(15),
+                )
+                .await
+                .ok()
+                .map(|(out, _)| out)
             }
+            (Err(_), None) => None,
         };
 
-        let (frontmatter_name, description, mut fm_warnings) = parse_skill_md_frontmatter(&contents);
-        warnings.append(&mut fm_warnings);
+        let (frontmatter_name, description, _) =
+            parse_skill_md_frontmatter(contents.as_deref().unwrap_or(""));
+
+        let diagnostics = match git_fallback {
+            // Local directories and extracted archives are on disk in full
+            None => validate_skill_dir(&repo_path_to_fs(base, &skill_dir)).await,
+            // A scan clone only checks out SKILL.md files; sizes are left to the install
+            Some(_) => {
+                let mut diagnostics = vec![];
+                validate_skill_dir_name(&skill_name, &mut diagnostics);
+                match contents.as_deref() {
+                    Some(contents) => validate_skill_md(contents, &skill_name, &mut diagnostics),
+                    None => diagnostics.push(SkillDiagnostic::error(
+                        "unreadable",
+                        "Failed to read SKILL.md".to_string(),
+                        Some("SKILL.md".to_string()),
+                    )),
+                }
+                let prefix = format!("{}/", skill_dir);
+                diagnostics.extend(symlinks.iter().filter_map(|path| {
+                    let rel = path.strip_prefix(&prefix)?;
+                    Some(SkillDiagnostic::error(
+                        "symlink",
+                        "Symlinks are not supported in skills".to_string(),
+                        Some(rel.to_string()),
+                    ))
+                }));
+                diagnostics
+            }
+        };
 
-        let installable = validate_skill_name(&skill_name);
-        if !installable {
-            warnings.push("Skill directory name is not a valid OpenCode skill name".to_string());
-        }
+        let installable = !diagnostics.iter().any(SkillDiagnostic::is_error);
+        let warnings = diagnostics
+            .into_iter()
+            .map(|diagnostic| match diagnostic.path {
+                Some(path) if path != "SKILL.md" => format!("{}: {}", path, diagnostic.message),
+                _ => diagnostic.message,
+            })
+            .collect();
 
         items.push((
             source.to_string(),
@@ -633,24 +1219,129 @@ async fn scan_repo_items(
         ));
     }
 
-    safe_rm(&temp_base).await;
+    items
+}
 
-    Ok((parsed.normalized_repo, effective_subpath, items))
+/// Symlinked paths in a clone's HEAD tree. The modes come from the tree objects, so this
+/// works on a blobless clone without fetching file contents.
+async fn list_tree_symlinks(base: &Path, ssh_command: Option<&str>) -> Vec<String> {
+    let args = vec![
+        "-C".to_string(),
+        base.display().to_string(),
+        "ls-tree".to_string(),
+        "-r".to_string(),
+        "-z".to_string(),
+        "HEAD".to_string(),
+    ];
+    let Ok((out, _)) = run_git(
+        &args,
+        &std::env::temp_dir(),
+        ssh_command,
+        Duration::from_secs(30),
+    )
+    .await
+    else {
+        return vec![];
+    };
+    out.split('\0')
+        .filter_map(|entry| {
+            let (meta, path) = entry.split_once('\t')?;
+            meta.starts_with("120000 ").then(|| path.to_string())
+        })
+        .collect()
 }
 
-#[derive(Debug, Clone)]
+/// A scanned catalog source. Kept in memory and mirrored to
+/// `~/.config/openchamber/cache/skills/` so a fresh launch can serve it right away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct CacheEntry {
-    created_at: Instant,
+    key: String,
+    /// Unix milliseconds
+    fetched_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    commit: Option<String>,
     items: Vec<SkillsCatalogItem>,
 }
 
-static CATALOG_CACHE: Lazy<Mutex<HashMap<String, CacheEntry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+impl CacheEntry {
+    fn is_fresh(&self) -> bool {
+        let age = chrono::Utc::now().timestamp_millis() - self.fetched_at;
+        (0..CACHE_TTL.as_millis() as i64).contains(&age)
+    }
+}
+
+static CATALOG_CACHE: Lazy<Mutex<HashMap<String, CacheEntry>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Keys with a background refresh in flight
+static CATALOG_REFRESHING: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+fn cache_file_path(key: &str) -> Option<PathBuf> {
+    let name = format!("{:x}.json", Sha256::digest(key.as_bytes()));
+    dirs::home_dir().map(|home| {
+        home.join(".config")
+            .join("openchamber")
+            .join("cache")
+            .join("skills")
+            .join(name)
+    })
+}
+
+/// Memory first, then disk (which also warms the memory cache). Any age is returned.
+async fn load_cache_entry(key: &str) -> Option<CacheEntry> {
+    if let Some(entry) = CATALOG_CACHE.lock().await.get(key) {
+        return Some(entry.clone());
+    }
+
+    let content = tokio::fs::read(cache_file_path(key)?).await.ok()?;
+    let entry = serde_json::from_slice::<CacheEntry>(&content)
+        .ok()
+        .filter(|entry| entry.key == key)?;
+    CATALOG_CACHE
+        .lock()
+        .await
+        .insert(key.to_string(), entry.clone());
+    Some(entry)
+}
+
+async fn store_cache_entry(entry: CacheEntry) {
+    CATALOG_CACHE
+        .lock()
+        .await
+        .insert(entry.key.clone(), entry.clone());
+
+    let Some(path) = cache_file_path(&entry.key) else {
+        return;
+    };
+    let write = async {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let temp_path = path.with_extension(format!("json.tmp-{}", Uuid::new_v4()));
+        tokio::fs::write(&temp_path, serde_json::to_vec(&entry)?).await?;
+        if let Err(err) = tokio::fs::rename(&temp_path, &path).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(anyhow::Error::from(err));
+        }
+        Ok::<(), anyhow::Error>(())
+    };
+    if let Err(err) = write.await {
+        log::warn!("[skills] Failed to write catalog cache: {}", err);
+    }
+}
 
-fn cache_key(normalized_repo: &str, subpath: Option<&str>, identity_id: Option<&str>) -> String {
+fn cache_key(
+    normalized_repo: &str,
+    subpath: Option<&str>,
+    git_ref: Option<&str>,
+    identity_id: Option<&str>,
+) -> String {
     format!(
-        "{}::{}::{}",
+        "{}::{}::{}::{}",
         normalized_repo,
         subpath.unwrap_or(""),
+        git_ref.unwrap_or(""),
         identity_id.unwrap_or("")
     )
 }
@@ -676,6 +1367,12 @@ fn load_custom_catalog_sources() -> Vec<SkillsCatalogSource> {
         return vec![];
     };
 
+    catalog_sources_from_settings(&value)
+}
+
+/// Custom sources from the `skillCatalogs` settings entry, skipping malformed entries and
+/// repeated ids
+fn catalog_sources_from_settings(value: &serde_json::Value) -> Vec<SkillsCatalogSource> {
     let Some(arr) = value.get("skillCatalogs").and_then(|v| v.as_array()) else {
         return vec![];
     };
@@ -690,6 +1387,7 @@ fn load_custom_catalog_sources() -> Vec<SkillsCatalogSource> {
         let label = obj.get("label").and_then(|v| v.as_str()).unwrap_or("").trim();
         let source = obj.get("source").and_then(|v| v.as_str()).unwrap_or("").trim();
         let subpath = obj.get("subpath").and_then(|v| v.as_str()).unwrap_or("").trim();
+        let git_ref = obj.get("ref").and_then(|v| v.as_str()).unwrap_or("").trim();
         let git_identity_id = obj.get("gitIdentityId").and_then(|v| v.as_str()).unwrap_or("").trim();
 
         if id.is_empty() || label.is_empty() || source.is_empty() {
@@ -706,21 +1404,52 @@ fn load_custom_catalog_sources() -> Vec<SkillsCatalogSource> {
             label: label.to_string(),
             description: Some(source.to_string()),
             source: source.to_string(),
-            default_subpath: if subpath.is_empty() { None } else { Some(subpath.to_string()) },
-            git_identity_id: if git_identity_id.is_empty() { None } else { Some(git_identity_id.to_string()) },
+            default_subpath: if subpath.is_empty() {
+                None
+            } else {
+                Some(subpath.to_string())
+            },
+            git_ref: if git_ref.is_empty() {
+                None
+            } else {
+                Some(git_ref.to_string())
+            },
+            git_identity_id: if git_identity_id.is_empty() {
+                None
+            } else {
+                Some(git_identity_id.to_string())
+            },
         });
     }
 
     result
 }
 
-pub async fn get_curated_sources() -> Vec<SkillsCatalogSource> {
+fn catalog_source_to_settings(src: &SkillsCatalogSource) -> serde_json::Value {
+    let mut entry = serde_json::Map::new();
+    entry.insert("id".to_string(), serde_json::json!(src.id));
+    entry.insert("label".to_string(), serde_json::json!(src.label));
+    entry.insert("source".to_string(), serde_json::json!(src.source));
+    if let Some(subpath) = &src.default_subpath {
+        entry.insert("subpath".to_string(), serde_json::json!(subpath));
+    }
+    if let Some(git_ref) = &src.git_ref {
+        entry.insert("ref".to_string(), serde_json::json!(git_ref));
+    }
+    if let Some(identity_id) = &src.git_identity_id {
+        entry.insert("gitIdentityId".to_string(), serde_json::json!(identity_id));
+    }
+    serde_json::Value::Object(entry)
+}
+
+pub async fn get_curated_sources() -> Vec<SkillsCatalogSource> {
     let mut sources = vec![SkillsCatalogSource {
         id: "anthropic".to_string(),
         label: "Anthropic".to_string(),
         description: Some("Anthropic’s public skills repository".to_string()),
         source: "anthropics/skills".to_string(),
         default_subpath: Some("skills".to_string()),
+        git_ref: None,
         git_identity_id: None,
     }];
 
@@ -728,6 +1457,9 @@ pub async fn get_curated_sources() -> Vec<SkillsCatalogSource> {
     sources
 }
 
+/// Sources are scanned this many at a time; each scan is a clone or download
+const CATALOG_SCAN_CONCURRENCY: usize = 3;
+
 pub async fn get_catalog(working_directory: &Path, refresh: bool) -> SkillsCatalogResponse {
     let sources = get_curated_sources().await;
 
@@ -735,123 +1467,340 @@ pub async fn get_catalog(working_directory: &Path, refresh: bool) -> SkillsCatal
     let installed_by_name: HashMap<String, opencode_config::DiscoveredSkill> =
         discovered.into_iter().map(|s| (s.name.clone(), s)).collect();
 
-    let mut items_by_source: HashMap<String, Vec<SkillsCatalogItem>> = HashMap::new();
+    let results: Vec<(
+        String,
+        std::result::Result<Vec<SkillsCatalogItem>, SkillsRepoError>,
+    )> = futures_util::stream::iter(sources.clone())
+        .map(|src| async move {
+            (
+                src.id.clone(),
+                catalog_items_for_source(&src, refresh).await,
+            )
+        })
+        .buffered(CATALOG_SCAN_CONCURRENCY)
+        .collect()
+        .await;
 
-    for src in &sources {
-        let parsed = match parse_repo_source(&src.source, None) {
-            Ok(p) => p,
-            Err(_) => {
-                items_by_source.insert(src.id.clone(), vec![]);
+    let mut items_by_source: HashMap<String, Vec<SkillsCatalogItem>> = HashMap::new();
+    let mut errors_by_source: HashMap<String, SkillsRepoError> = HashMap::new();
+
+    for (source_id, result) in results {
+        let scanned_items = match result {
+            Ok(items) => items,
+            Err(error) => {
+                errors_by_source.insert(source_id.clone(), error);
+                items_by_source.insert(source_id, vec![]);
                 continue;
             }
         };
 
-        let effective_subpath = src
-            .default_subpath
-            .as_deref()
-            .or(parsed.effective_subpath.as_deref())
-            .unwrap_or("");
+        // Update installed badges at request time (cache may be stale for installs)
+        let mut enriched = vec![];
+        for mut item in scanned_items {
+            item.installed = installed_badge(&installed_by_name, &item.skill_name);
+            enriched.push(item);
+        }
 
-        let key = cache_key(&parsed.normalized_repo, Some(effective_subpath), src.git_identity_id.as_deref());
+        items_by_source.insert(source_id, enriched);
+    }
 
-        let maybe_cached = if refresh {
+    SkillsCatalogResponse {
+        ok: true,
+        sources: Some(sources),
+        items_by_source: Some(items_by_source),
+        errors_by_source: if errors_by_source.is_empty() {
             None
         } else {
-            let cache = CATALOG_CACHE.lock().await;
-            cache.get(&key).cloned()
+            Some(errors_by_source)
+        },
+        error: None,
+    }
+}
+
+/// Items of one catalog source. A cached scan is served even when stale, with a refresh
+/// started in the background; only uncached sources, local directories and explicit
+/// refreshes wait for a scan. Installed badges are left for the caller to fill in.
+async fn catalog_items_for_source(
+    src: &SkillsCatalogSource,
+    refresh: bool,
+) -> std::result::Result<Vec<SkillsCatalogItem>, SkillsRepoError> {
+    let parsed = parse_repo_source(&src.source, None)
+        .map_err(|err| simple_error("invalidSource", &err.to_string()))?;
+    let key = catalog_cache_key(src, &parsed);
+
+    // Local directories are read fresh every time; they change while being developed
+    if refresh || parsed.local_path.is_some() {
+        return scan_catalog_source(src, &parsed, key, None)
+            .await
+            .map(|entry| entry.items);
+    }
+
+    match load_cache_entry(&key).await {
+        Some(entry) if entry.is_fresh() => Ok(entry.items),
+        Some(entry) => {
+            let items = entry.items.clone();
+            refresh_in_background(src.clone(), entry);
+            Ok(items)
+        }
+        None => scan_catalog_source(src, &parsed, key, None)
+            .await
+            .map(|entry| entry.items),
+    }
+}
+
+fn installed_badge(
+    installed_by_name: &HashMap<String, opencode_config::DiscoveredSkill>,
+    skill_name: &str,
+) -> SkillsCatalogInstalledBadge {
+    let installed = installed_by_name.get(skill_name);
+    SkillsCatalogInstalledBadge {
+        is_installed: installed.is_some(),
+        scope: installed.map(|s| match s.scope {
+            opencode_config::Scope::User => "user".to_string(),
+            opencode_config::Scope::Project => "project".to_string(),
+        }),
+    }
+}
+
+/// Search the catalog across all sources. Only cached scans are searched (stale ones are
+/// refreshed in the background), so results come back without touching the network;
+/// local directory sources are read directly.
+pub async fn search_skills(working_directory: &Path, query: &str) -> SkillsSearchResponse {
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    if terms.is_empty() {
+        return SkillsSearchResponse {
+            ok: true,
+            results: vec![],
         };
+    }
 
-        let cached_items = maybe_cached.and_then(|entry| {
-            if entry.created_at.elapsed() < CACHE_TTL {
-                Some(entry.items)
-            } else {
-                None
-            }
-        });
+    let discovered = opencode_config::discover_skills(Some(working_directory));
+    let installed_by_name: HashMap<String, opencode_config::DiscoveredSkill> = discovered
+        .into_iter()
+        .map(|s| (s.name.clone(), s))
+        .collect();
 
-        let scanned_items = if let Some(items) = cached_items {
-            items
-        } else {
-            let ssh_key = resolve_identity_ssh_key(src.git_identity_id.as_deref());
-            let scan = scan_repo_items(&src.source, None, src.default_subpath.as_deref(), ssh_key.as_deref()).await;
+    let mut results = vec![];
+    for src in get_curated_sources().await {
+        let Ok(parsed) = parse_repo_source(&src.source, None) else {
+            continue;
+        };
+        let key = catalog_cache_key(&src, &parsed);
 
-            let (_, _, raw_items) = match scan {
-                Ok(v) => v,
-                Err(_) => {
-                    items_by_source.insert(src.id.clone(), vec![]);
-                    continue;
+        let items = if parsed.local_path.is_some() {
+            match scan_catalog_source(&src, &parsed, key, None).await {
+                Ok(entry) => entry.items,
+                Err(_) => continue,
+            }
+        } else {
+            match load_cache_entry(&key).await {
+                Some(entry) if entry.is_fresh() => entry.items,
+                Some(entry) => {
+                    let items = entry.items.clone();
+                    refresh_in_background(src.clone(), entry);
+                    items
                 }
-            };
+                None => continue,
+            }
+        };
 
-            let mut items: Vec<SkillsCatalogItem> = vec![];
-            for (repo_source, skill_dir, fm_name, desc, warnings, installable) in raw_items {
-                let skill_name = skill_dir
-                    .split('/')
-                    .filter(|s| !s.is_empty())
-                    .last()
-                    .unwrap_or("")
-                    .to_string();
+        for mut item in items {
+            let Some(score) = search_score(&item, &terms) else {
+                continue;
+            };
+            item.installed = installed_badge(&installed_by_name, &item.skill_name);
+            results.push(SkillSearchResult { item, score });
+        }
+    }
 
-                let installed = installed_by_name.get(&skill_name);
+    results.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.item.skill_name.cmp(&b.item.skill_name))
+            .then_with(|| a.item.source_id.cmp(&b.item.source_id))
+    });
+    SkillsSearchResponse { ok: true, results }
+}
 
-                items.push(SkillsCatalogItem {
-                    source_id: src.id.clone(),
-                    repo_source,
-                    repo_subpath: src.default_subpath.clone(),
-                    git_identity_id: src.git_identity_id.clone(),
-                    skill_dir,
-                    skill_name,
-                    frontmatter_name: fm_name,
-                    description: desc,
-                    installable,
-                    warnings: if warnings.is_empty() { None } else { Some(warnings) },
-                    installed: SkillsCatalogInstalledBadge {
-                        is_installed: installed.is_some(),
-                        scope: installed.map(|s| match s.scope {
-                            opencode_config::Scope::User => "user".to_string(),
-                            opencode_config::Scope::Project => "project".to_string(),
-                        }),
-                    },
-                });
+/// Every term has to match somewhere. Matches on the skill name outrank the frontmatter
+/// name, which outranks the description; exact beats prefix beats substring.
+fn search_score(item: &SkillsCatalogItem, terms: &[String]) -> Option<u32> {
+    let name = item.skill_name.to_lowercase();
+    let frontmatter_name = item
+        .frontmatter_name
+        .as_deref()
+        .unwrap_or("")
+        .to_lowercase();
+    let description = item.description.as_deref().unwrap_or("").to_lowercase();
+
+    let mut total = 0;
+    for term in terms {
+        let field_score = |field: &str, exact: u32, prefix: u32, contains: u32| {
+            if field == term {
+                exact
+            } else if field.starts_with(term.as_str()) {
+                prefix
+            } else if field.contains(term.as_str()) {
+                contains
+            } else {
+                0
             }
+        };
+        let description_score = if description
+            .split(|c: char| !c.is_alphanumeric())
+            .any(|word| word.starts_with(term.as_str()))
+        {
+            15
+        } else if description.contains(term.as_str()) {
+            10
+        } else {
+            0
+        };
 
-            items.sort_by(|a, b| a.skill_name.cmp(&b.skill_name));
+        let score = field_score(&name, 100, 60, 40)
+            .max(field_score(&frontmatter_name, 90, 50, 30))
+            .max(description_score);
+        if score == 0 {
+            return None;
+        }
+        total += score;
+    }
+    Some(total)
+}
 
-            let mut cache = CATALOG_CACHE.lock().await;
-            cache.insert(
-                key,
-                CacheEntry {
-                    created_at: Instant::now(),
-                    items: items.clone(),
-                },
-            );
+fn catalog_cache_key(src: &SkillsCatalogSource, parsed: &RepoParsed) -> String {
+    let effective_subpath = src
+        .default_subpath
+        .as_deref()
+        .or(parsed.effective_subpath.as_deref())
+        .unwrap_or("");
+
+    cache_key(
+        &parsed.normalized_repo,
+        Some(effective_subpath),
+        src.git_ref.as_deref(),
+        src.git_identity_id.as_deref(),
+    )
+}
 
-            items
-        };
+fn refresh_in_background(src: SkillsCatalogSource, previous: CacheEntry) {
+    tokio::spawn(async move {
+        if !CATALOG_REFRESHING.lock().await.insert(previous.key.clone()) {
+            return;
+        }
+        if let Ok(parsed) = parse_repo_source(&src.source, None) {
+            if let Err(err) =
+                scan_catalog_source(&src, &parsed, previous.key.clone(), Some(&previous)).await
+            {
+                log::warn!(
+                    "[skills] Background refresh of catalog source {} failed: {}",
+                    src.id,
+                    err.message
+                );
+            }
+        }
+        CATALOG_REFRESHING.lock().await.remove(&previous.key);
+    });
+}
 
-        // Update installed badges at request time (cache may be stale for installs)
-        let mut enriched = vec![];
-        for mut item in scanned_items {
-            let installed = installed_by_name.get(&item.skill_name);
-            item.installed = SkillsCatalogInstalledBadge {
-                is_installed: installed.is_some(),
-                scope: installed.map(|s| match s.scope {
-                    opencode_config::Scope::User => "user".to_string(),
-                    opencode_config::Scope::Project => "project".to_string(),
-                }),
+/// Scan a catalog source and cache the result. With a `previous` scan of a git source, the
+/// clone is skipped when the source still points at the scanned commit.
+async fn scan_catalog_source(
+    src: &SkillsCatalogSource,
+    parsed: &RepoParsed,
+    key: String,
+    previous: Option<&CacheEntry>,
+) -> std::result::Result<CacheEntry, SkillsRepoError> {
+    let ssh_command = resolve_identity_ssh_command(src.git_identity_id.as_deref()).await;
+
+    let is_git = parsed.local_path.is_none() && parsed.archive_url.is_none();
+    let cached_commit = previous
+        .filter(|_| is_git)
+        .and_then(|p| p.commit.as_deref());
+    if let (Some(previous), Some(commit)) = (previous, cached_commit) {
+        let unchanged = match src.git_ref.as_deref() {
+            Some(pinned) if COMMIT_SHA_RE.is_match(pinned) => {
+                commit.starts_with(&pinned.to_ascii_lowercase())
+            }
+            git_ref => {
+                let clone_url = if ssh_command.is_some() {
+                    &parsed.clone_ssh
+                } else {
+                    &parsed.clone_https
+                };
+                resolve_remote_commit(clone_url, git_ref, ssh_command.as_deref())
+                    .await
+                    .is_ok_and(|latest| latest == commit)
+            }
+        };
+        if unchanged {
+            let entry = CacheEntry {
+                fetched_at: chrono::Utc::now().timestamp_millis(),
+                ..previous.clone()
             };
-            enriched.push(item);
+            store_cache_entry(entry.clone()).await;
+            return Ok(entry);
         }
+    }
+
+    let scan = scan_repo_items(
+        &src.source,
+        None,
+        src.default_subpath.as_deref(),
+        src.git_ref.as_deref(),
+        ssh_command.as_deref(),
+    )
+    .await;
+
+    let scan = match scan {
+        Ok(v) => v,
+        Err(err) => return Err(scan_error(&err).await),
+    };
+
+    let mut items: Vec<SkillsCatalogItem> = vec![];
+    for (repo_source, skill_dir, fm_name, desc, warnings, installable) in scan.items {
+        let skill_name = skill_dir
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .last()
+            .unwrap_or("")
+            .to_string();
 
-        items_by_source.insert(src.id.clone(), enriched);
+        items.push(SkillsCatalogItem {
+            source_id: src.id.clone(),
+            repo_source,
+            repo_subpath: src.default_subpath.clone(),
+            git_identity_id: src.git_identity_id.clone(),
+            git_ref: src.git_ref.clone(),
+            skill_dir,
+            skill_name,
+            frontmatter_name: fm_name,
+            description: desc,
+            installable,
+            warnings: if warnings.is_empty() {
+                None
+            } else {
+                Some(warnings)
+            },
+            installed: SkillsCatalogInstalledBadge {
+                is_installed: false,
+                scope: None,
+            },
+        });
     }
 
-    SkillsCatalogResponse {
-        ok: true,
-        sources: Some(sources),
-        items_by_source: Some(items_by_source),
-        error: None,
+    items.sort_by(|a, b| a.skill_name.cmp(&b.skill_name));
+
+    let entry = CacheEntry {
+        key,
+        fetched_at: chrono::Utc::now().timestamp_millis(),
+        commit: scan.commit,
+        items,
+    };
+    if parsed.local_path.is_none() {
+        store_cache_entry(entry.clone()).await;
     }
+    Ok(entry)
 }
 
 #[derive(Debug, Deserialize)]
@@ -859,16 +1808,27 @@ pub async fn get_catalog(working_directory: &Path, refresh: bool) -> SkillsCatal
 pub struct SkillsScanRequest {
     pub source: String,
     pub subpath: Option<String>,
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
     pub git_identity_id: Option<String>,
 }
 
 pub async fn scan_repository(req: SkillsScanRequest) -> SkillsRepoScanResponse {
-    let ssh_key = resolve_identity_ssh_key(req.git_identity_id.as_deref());
-
-    match scan_repo_items(&req.source, req.subpath.as_deref(), None, ssh_key.as_deref()).await {
-        Ok((_normalized, effective_subpath, raw_items)) => {
+    let ssh_command = resolve_identity_ssh_command(req.git_identity_id.as_deref()).await;
+
+    match scan_repo_items(
+        &req.source,
+        req.subpath.as_deref(),
+        None,
+        req.git_ref.as_deref(),
+        ssh_command.as_deref(),
+    )
+    .await
+    {
+        Ok(scan) => {
+            let effective_subpath = scan.effective_subpath;
             let mut items = vec![];
-            for (repo_source, skill_dir, fm_name, desc, warnings, installable) in raw_items {
+            for (repo_source, skill_dir, fm_name, desc, warnings, installable) in scan.items {
                 let skill_name = skill_dir
                     .split('/')
                     .filter(|s| !s.is_empty())
@@ -881,6 +1841,7 @@ pub async fn scan_repository(req: SkillsScanRequest) -> SkillsRepoScanResponse {
                     repo_source,
                     repo_subpath: effective_subpath.clone(),
                     git_identity_id: req.git_identity_id.clone(),
+                    git_ref: req.git_ref.clone(),
                     skill_dir,
                     skill_name,
                     frontmatter_name: fm_name,
@@ -898,98 +1859,598 @@ pub async fn scan_repository(req: SkillsScanRequest) -> SkillsRepoScanResponse {
                 error: None,
             }
         }
-        Err(err) => {
-            if err.to_string().contains("AUTH_REQUIRED") {
-                return SkillsRepoScanResponse {
-                    ok: false,
-                    items: None,
-                    error: Some(auth_required_error("Authentication required to access this repository")),
-                };
-            }
-
-            SkillsRepoScanResponse {
-                ok: false,
-                items: None,
-                error: Some(simple_error("networkError", &err.to_string())),
-            }
-        }
+        Err(err) => SkillsRepoScanResponse {
+            ok: false,
+            items: None,
+            error: Some(scan_error(&err).await),
+        },
     }
 }
 
+const SKILL_DETAILS_MAX_MD_BYTES: u64 = 1024 * 1024;
+const SKILL_DETAILS_MAX_FILES: usize = 1000;
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SkillsInstallSelection {
+pub struct SkillDetailsRequest {
+    pub source: String,
+    /// Repo-relative, as in catalog items
     pub skill_dir: String,
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+    pub git_identity_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct SkillsInstallRequest {
-    pub source: String,
-    pub subpath: Option<String>,
-    pub git_identity_id: Option<String>,
-    pub scope: String,
-    pub selections: Vec<SkillsInstallSelection>,
-    pub conflict_policy: Option<String>,
-    pub conflict_decisions: Option<HashMap<String, String>>,
+pub struct SkillFileEntry {
+    /// Relative to the skill directory
+    pub path: String,
+    pub size: u64,
 }
 
-fn user_skill_dir() -> Result<PathBuf> {
-    Ok(dirs::home_dir()
-        .ok_or_else(|| anyhow!("Could not find home directory"))?
-        .join(".config")
-        .join("opencode")
-        .join("skill"))
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDetails {
+    pub skill_dir: String,
+    pub skill_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frontmatter_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Full SKILL.md, frontmatter included
+    pub content: String,
+    pub files: Vec<SkillFileEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<Vec<String>>,
 }
 
-fn target_skill_dir(scope: &str, working_directory: &Path, skill_name: &str) -> Result<PathBuf> {
-    if scope == "user" {
-        return Ok(user_skill_dir()?.join(skill_name));
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDetailsResponse {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill: Option<SkillDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<SkillsRepoError>,
+}
+
+/// SKILL.md and file listing of one skill, read without installing it. Git sources get a
+/// sparse checkout of just that directory.
+pub async fn get_skill_details(req: SkillDetailsRequest) -> SkillDetailsResponse {
+    let failed = |error: SkillsRepoError| SkillDetailsResponse {
+        ok: false,
+        skill: None,
+        error: Some(error),
+    };
+
+    let skill_dir = req.skill_dir.trim().trim_matches('/').to_string();
+    if skill_dir.split('/').any(|part| part.trim() == "..") {
+        return failed(simple_error("invalidSource", "Invalid skill directory"));
     }
 
-    if scope == "project" {
-        return Ok(working_directory.join(".opencode").join("skill").join(skill_name));
+    let parsed = match parse_repo_source(&req.source, None) {
+        Ok(p) => p,
+        Err(err) => return failed(simple_error("invalidSource", &err.to_string())),
+    };
+    let ssh_command = resolve_identity_ssh_command(req.git_identity_id.as_deref()).await;
+
+    let temp_base = std::env::temp_dir().join(format!(
+        "openchamber-desktop-skills-details-{}",
+        Uuid::new_v4()
+    ));
+    let result = async {
+        let (base, commit) = match (parsed.local_path.clone(), parsed.archive_url.as_deref()) {
+            (Some(path), _) => (path, None),
+            (None, Some(url)) => match download_archive(url, &temp_base).await {
+                Ok(root) => (root, None),
+                Err(err) => return Err(simple_error("networkError", &err.to_string())),
+            },
+            (None, None) => {
+                // Cone mode always checks out top-level files, which covers a root skill
+                let selections: Vec<String> = if skill_dir.is_empty() {
+                    vec![]
+                } else {
+                    vec![skill_dir.clone()]
+                };
+                let commit = checkout_selected_dirs(
+                    &parsed,
+                    &temp_base,
+                    ssh_command.as_deref(),
+                    req.git_ref.as_deref(),
+                    &selections,
+                )
+                .await?;
+                (temp_base.clone(), commit)
+            }
+        };
+        read_skill_details(&base, &skill_dir, commit).await
     }
+    .await;
+    safe_rm(&temp_base).await;
 
-    Err(anyhow!("Invalid scope"))
+    match result {
+        Ok(skill) => SkillDetailsResponse {
+            ok: true,
+            skill: Some(skill),
+            error: None,
+        },
+        Err(error) => failed(error),
+    }
 }
 
-fn repo_path_to_fs(base: &Path, repo_rel_posix: &str) -> PathBuf {
-    let mut current = base.to_path_buf();
-    for part in repo_rel_posix.split('/') {
-        let trimmed = part.trim();
-        if trimmed.is_empty() {
+async fn read_skill_details(
+    base: &Path,
+    skill_dir: &str,
+    commit: Option<String>,
+) -> std::result::Result<SkillDetails, SkillsRepoError> {
+    let not_found = || simple_error("notFound", "SKILL.md not found in selected directory");
+
+    let dir = repo_path_to_fs(base, skill_dir);
+    let (Ok(base_real), Ok(dir_real)) = (
+        tokio::fs::canonicalize(base).await,
+        tokio::fs::canonicalize(&dir).await,
+    ) else {
+        return Err(not_found());
+    };
+    if !dir_real.starts_with(&base_real) {
+        return Err(simple_error("invalidSource", "Invalid skill directory"));
+    }
+
+    let skill_md = dir.join("SKILL.md");
+    let meta = match tokio::fs::symlink_metadata(&skill_md).await {
+        Ok(meta) if meta.is_file() => meta,
+        _ => return Err(not_found()),
+    };
+    if meta.len() > SKILL_DETAILS_MAX_MD_BYTES {
+        return Err(simple_error(
+            "invalidSource",
+            "SKILL.md is too large to preview",
+        ));
+    }
+    let content = match tokio::fs::read(&skill_md).await {
+        Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        Err(err) => return Err(simple_error("unknown", &err.to_string())),
+    };
+
+    let (frontmatter_name, description, mut warnings) = parse_skill_md_frontmatter(&content);
+
+    let mut files = vec![];
+    let mut has_symlinks = false;
+    let mut truncated = false;
+    let mut stack = vec![(dir.clone(), String::new())];
+    'walk: while let Some((current, rel)) = stack.pop() {
+        let Ok(mut entries) = tokio::fs::read_dir(&current).await else {
             continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" {
+                continue;
+            }
+            let child_rel = if rel.is_empty() {
+                name
+            } else {
+                format!("{}/{}", rel, name)
+            };
+            let Ok(meta) = tokio::fs::symlink_metadata(entry.path()).await else {
+                continue;
+            };
+            if meta.file_type().is_symlink() {
+                has_symlinks = true;
+            } else if meta.is_dir() {
+                stack.push((entry.path(), child_rel));
+            } else if meta.is_file() {
+                if files.len() >= SKILL_DETAILS_MAX_FILES {
+                    truncated = true;
+                    break 'walk;
+                }
+                files.push(SkillFileEntry {
+                    path: child_rel,
+                    size: meta.len(),
+                });
+            }
         }
-        current.push(trimmed);
     }
-    current
-}
+    files.sort_by(|a, b| a.path.cmp(&b.path));
 
-async fn copy_dir_no_symlinks(src: &Path, dst: &Path) -> Result<()> {
-    let src_real = tokio::fs::canonicalize(src).await?;
+    if has_symlinks {
+        warnings.push("Contains symlinks, which are not supported in skills".to_string());
+    }
+    if truncated {
+        warnings.push(format!(
+            "File listing truncated to {} files",
+            SKILL_DETAILS_MAX_FILES
+        ));
+    }
 
-    tokio::fs::create_dir_all(dst).await?;
+    let skill_name = skill_dir
+        .split('/')
+        .rfind(|s| !s.is_empty())
+        .unwrap_or("")
+        .to_string();
+    if !validate_skill_name(&skill_name) {
+        warnings.push("Skill directory name is not a valid OpenCode skill name".to_string());
+    }
 
-    let mut stack: Vec<(PathBuf, PathBuf)> = vec![(src.to_path_buf(), dst.to_path_buf())];
+    Ok(SkillDetails {
+        skill_dir: skill_dir.to_string(),
+        skill_name,
+        frontmatter_name,
+        description,
+        content,
+        files,
+        commit,
+        warnings: if warnings.is_empty() {
+            None
+        } else {
+            Some(warnings)
+        },
+    })
+}
 
-    while let Some((current_src, current_dst)) = stack.pop() {
-        tokio::fs::create_dir_all(&current_dst).await?;
+const SKILL_MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+const SKILL_MAX_TOTAL_BYTES: u64 = 50 * 1024 * 1024;
+const SKILL_MAX_DESCRIPTION_CHARS: usize = 1024;
 
-        let current_src_real = tokio::fs::canonicalize(&current_src).await?;
-        if !current_src_real.starts_with(&src_real) {
-            return Err(anyhow!("Invalid source path traversal detected"));
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDiagnostic {
+    /// `error` blocks installation, `warning` does not
+    pub severity: String,
+    pub code: String,
+    pub message: String,
+    /// Relative to the skill directory
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl SkillDiagnostic {
+    fn error(code: &str, message: String, path: Option<String>) -> Self {
+        Self {
+            severity: "error".to_string(),
+            code: code.to_string(),
+            message,
+            path,
         }
+    }
 
-        let mut dir = tokio::fs::read_dir(&current_src).await?;
-        while let Some(entry) = dir.next_entry().await? {
-            let next_src = entry.path();
-            let next_dst = current_dst.join(entry.file_name());
+    fn warning(code: &str, message: String, path: Option<String>) -> Self {
+        Self {
+            severity: "warning".to_string(),
+            code: code.to_string(),
+            message,
+            path,
+        }
+    }
 
-            let meta = tokio::fs::symlink_metadata(&next_src).await?;
-            if meta.file_type().is_symlink() {
-                return Err(anyhow!("Symlinks are not supported in skills"));
-            }
+    fn is_error(&self) -> bool {
+        self.severity == "error"
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillValidationResponse {
+    pub ok: bool,
+    /// No error diagnostics
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill_dir: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<Vec<SkillDiagnostic>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<SkillsRepoError>,
+}
+
+/// Lint a skill directory, or the directory of a SKILL.md path
+pub async fn validate_skill(path_or_dir: &str) -> SkillValidationResponse {
+    let failed = |error: SkillsRepoError| SkillValidationResponse {
+        ok: false,
+        valid: None,
+        skill_dir: None,
+        skill_name: None,
+        diagnostics: None,
+        error: Some(error),
+    };
+
+    let path = expand_tilde_path(path_or_dir);
+    if !path.is_absolute() {
+        return failed(simple_error(
+            "invalidSource",
+            "An absolute path is required",
+        ));
+    }
+    let dir = if path.file_name().is_some_and(|name| name == "SKILL.md") {
+        path.parent().map(Path::to_path_buf).unwrap_or(path)
+    } else {
+        path
+    };
+    if !dir.is_dir() {
+        return failed(simple_error(
+            "notFound",
+            &format!("Skill directory not found: {}", dir.display()),
+        ));
+    }
+
+    let diagnostics = validate_skill_dir(&dir).await;
+    SkillValidationResponse {
+        ok: true,
+        valid: Some(!diagnostics.iter().any(SkillDiagnostic::is_error)),
+        skill_dir: Some(dir.display().to_string()),
+        skill_name: dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string()),
+        diagnostics: Some(diagnostics),
+        error: None,
+    }
+}
+
+/// Name rules, SKILL.md frontmatter, file sizes, symlinks and executables. Any `error`
+/// diagnostic means the skill should not be installed.
+async fn validate_skill_dir(dir: &Path) -> Vec<SkillDiagnostic> {
+    let mut diagnostics = vec![];
+
+    let dir_name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    validate_skill_dir_name(&dir_name, &mut diagnostics);
+
+    let skill_md = dir.join("SKILL.md");
+    match tokio::fs::symlink_metadata(&skill_md).await {
+        Ok(meta) if meta.is_file() && meta.len() > SKILL_DETAILS_MAX_MD_BYTES => {
+            diagnostics.push(SkillDiagnostic::error(
+                "fileTooLarge",
+                format!(
+                    "SKILL.md is larger than {} bytes",
+                    SKILL_DETAILS_MAX_MD_BYTES
+                ),
+                Some("SKILL.md".to_string()),
+            ));
+        }
+        Ok(meta) if meta.is_file() => match tokio::fs::read(&skill_md).await {
+            Ok(bytes) => validate_skill_md(
+                &String::from_utf8_lossy(&bytes),
+                &dir_name,
+                &mut diagnostics,
+            ),
+            Err(err) => diagnostics.push(SkillDiagnostic::error(
+                "unreadable",
+                format!("Failed to read SKILL.md: {}", err),
+                Some("SKILL.md".to_string()),
+            )),
+        },
+        // Symlinks are reported by the walk below
+        Ok(meta) if meta.file_type().is_symlink() => {}
+        _ => diagnostics.push(SkillDiagnostic::error(
+            "missingSkillMd",
+            "SKILL.md not found".to_string(),
+            Some("SKILL.md".to_string()),
+        )),
+    }
+
+    let mut total_bytes = 0u64;
+    let mut stack = vec![(dir.to_path_buf(), String::new())];
+    while let Some((current, rel)) = stack.pop() {
+        let Ok(mut entries) = tokio::fs::read_dir(&current).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" {
+                continue;
+            }
+            let child_rel = if rel.is_empty() {
+                name
+            } else {
+                format!("{}/{}", rel, name)
+            };
+            let Ok(meta) = tokio::fs::symlink_metadata(entry.path()).await else {
+                continue;
+            };
+
+            if meta.file_type().is_symlink() {
+                diagnostics.push(SkillDiagnostic::error(
+                    "symlink",
+                    "Symlinks are not supported in skills".to_string(),
+                    Some(child_rel),
+                ));
+            } else if meta.is_dir() {
+                stack.push((entry.path(), child_rel));
+            } else if meta.is_file() {
+                total_bytes += meta.len();
+                if meta.len() > SKILL_MAX_FILE_BYTES {
+                    diagnostics.push(SkillDiagnostic::warning(
+                        "fileTooLarge",
+                        format!(
+                            "File is larger than {} MB",
+                            SKILL_MAX_FILE_BYTES / (1024 * 1024)
+                        ),
+                        Some(child_rel.clone()),
+                    ));
+                }
+
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    if meta.permissions().mode() & 0o111 != 0 {
+                        diagnostics.push(SkillDiagnostic::warning(
+                            "executable",
+                            "File is executable; review it before installing".to_string(),
+                            Some(child_rel),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    if total_bytes > SKILL_MAX_TOTAL_BYTES {
+        diagnostics.push(SkillDiagnostic::error(
+            "skillTooLarge",
+            format!(
+                "Skill is larger than {} MB in total",
+                SKILL_MAX_TOTAL_BYTES / (1024 * 1024)
+            ),
+            None,
+        ));
+    }
+
+    diagnostics.sort_by(|a, b| {
+        b.is_error()
+            .cmp(&a.is_error())
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    diagnostics
+}
+
+fn validate_skill_dir_name(dir_name: &str, diagnostics: &mut Vec<SkillDiagnostic>) {
+    if !validate_skill_name(dir_name) {
+        diagnostics.push(SkillDiagnostic::error(
+            "invalidName",
+            format!(
+                "Directory name \"{}\" is not a valid skill name: use 1-64 lowercase letters, digits and hyphens, not starting or ending with a hyphen",
+                dir_name
+            ),
+            None,
+        ));
+    }
+}
+
+fn validate_skill_md(contents: &str, dir_name: &str, diagnostics: &mut Vec<SkillDiagnostic>) {
+    let skill_md = || Some("SKILL.md".to_string());
+
+    let (name, description, warnings) = parse_skill_md_frontmatter(contents);
+    if !warnings.is_empty() {
+        for warning in warnings {
+            diagnostics.push(SkillDiagnostic::error(
+                "invalidFrontmatter",
+                warning,
+                skill_md(),
+            ));
+        }
+        return;
+    }
+
+    match name.as_deref().map(str::trim) {
+        None | Some("") => diagnostics.push(SkillDiagnostic::warning(
+            "missingName",
+            "Frontmatter has no name; the directory name is used".to_string(),
+            skill_md(),
+        )),
+        Some(name) if name != dir_name => diagnostics.push(SkillDiagnostic::warning(
+            "nameMismatch",
+            format!(
+                "Frontmatter name \"{}\" does not match the directory name \"{}\"",
+                name, dir_name
+            ),
+            skill_md(),
+        )),
+        Some(_) => {}
+    }
+
+    match description.as_deref().map(str::trim) {
+        None | Some("") => diagnostics.push(SkillDiagnostic::error(
+            "missingDescription",
+            "Frontmatter must include a description".to_string(),
+            skill_md(),
+        )),
+        Some(description) if description.chars().count() > SKILL_MAX_DESCRIPTION_CHARS => {
+            diagnostics.push(SkillDiagnostic::warning(
+                "descriptionTooLong",
+                format!(
+                    "Description is longer than {} characters",
+                    SKILL_MAX_DESCRIPTION_CHARS
+                ),
+                skill_md(),
+            ))
+        }
+        Some(_) => {}
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillsInstallSelection {
+    pub skill_dir: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillsInstallRequest {
+    pub source: String,
+    pub subpath: Option<String>,
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+    pub git_identity_id: Option<String>,
+    pub scope: String,
+    pub selections: Vec<SkillsInstallSelection>,
+    pub conflict_policy: Option<String>,
+    pub conflict_decisions: Option<HashMap<String, String>>,
+}
+
+fn user_skill_dir() -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .ok_or_else(|| anyhow!("Could not find home directory"))?
+        .join(".config")
+        .join("opencode")
+        .join("skill"))
+}
+
+fn target_skill_dir(scope: &str, working_directory: &Path, skill_name: &str) -> Result<PathBuf> {
+    if scope == "user" {
+        return Ok(user_skill_dir()?.join(skill_name));
+    }
+
+    if scope == "project" {
+        return Ok(working_directory.join(".opencode").join("skill").join(skill_name));
+    }
+
+    Err(anyhow!("Invalid scope"))
+}
+
+fn repo_path_to_fs(base: &Path, repo_rel_posix: &str) -> PathBuf {
+    let mut current = base.to_path_buf();
+    for part in repo_rel_posix.split('/') {
+        let trimmed = part.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        current.push(trimmed);
+    }
+    current
+}
+
+async fn copy_dir_no_symlinks(src: &Path, dst: &Path) -> Result<()> {
+    let src_real = tokio::fs::canonicalize(src).await?;
+
+    tokio::fs::create_dir_all(dst).await?;
+
+    let mut stack: Vec<(PathBuf, PathBuf)> = vec![(src.to_path_buf(), dst.to_path_buf())];
+
+    while let Some((current_src, current_dst)) = stack.pop() {
+        tokio::fs::create_dir_all(&current_dst).await?;
+
+        let current_src_real = tokio::fs::canonicalize(&current_src).await?;
+        if !current_src_real.starts_with(&src_real) {
+            return Err(anyhow!("Invalid source path traversal detected"));
+        }
+
+        let mut dir = tokio::fs::read_dir(&current_src).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let next_src = entry.path();
+            let next_dst = current_dst.join(entry.file_name());
+
+            let meta = tokio::fs::symlink_metadata(&next_src).await?;
+            if meta.file_type().is_symlink() {
+                return Err(anyhow!("Symlinks are not supported in skills"));
+            }
 
             if meta.is_dir() {
                 stack.push((next_src, next_dst));
@@ -1017,8 +2478,96 @@ async fn copy_dir_no_symlinks(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Clone the source into `temp_base` with only `selections` checked out. Returns the
+/// checked-out commit.
+async fn checkout_selected_dirs(
+    parsed: &RepoParsed,
+    temp_base: &Path,
+    ssh_command: Option<&str>,
+    git_ref: Option<&str>,
+    selections: &[String],
+) -> std::result::Result<Option<String>, SkillsRepoError> {
+    let clone_url = if ssh_command.is_some() {
+        parsed.clone_ssh.clone()
+    } else {
+        parsed.clone_https.clone()
+    };
+
+    let _ = tokio::fs::remove_dir_all(temp_base).await;
+
+    let clone_res = clone_repo(&clone_url, temp_base, ssh_command, git_ref).await;
+    if let Err(err) = clone_res {
+        let msg = err.to_string();
+        if AUTH_ERROR_RE.is_match(&msg) {
+            return Err(auth_required_error("Authentication required to access this repository. Use a Git identity with an SSH key, or save an HTTPS token for this host.").await);
+        }
+
+        return Err(simple_error("networkError", &msg));
+    }
+
+    // sparse-checkout selected dirs
+    let init_args = vec![
+        "-C".to_string(),
+        temp_base.display().to_string(),
+        "sparse-checkout".to_string(),
+        "init".to_string(),
+        "--cone".to_string(),
+    ];
+    let _ = run_git(
+        &init_args,
+        &std::env::temp_dir(),
+        ssh_command,
+        Duration::from_secs(15),
+    )
+    .await;
+
+    let mut set_args = vec![
+        "-C".to_string(),
+        temp_base.display().to_string(),
+        "sparse-checkout".to_string(),
+        "set".to_string(),
+    ];
+    for dir in selections {
+        set_args.push(dir.clone());
+    }
+
+    if let Err(err) = run_git(
+        &set_args,
+        &std::env::temp_dir(),
+        ssh_command,
+        Duration::from_secs(30),
+    )
+    .await
+    {
+        safe_rm(temp_base).await;
+        return Err(simple_error("unknown", &err.to_string()));
+    }
+
+    let checkout_args = vec![
+        "-C".to_string(),
+        temp_base.display().to_string(),
+        "checkout".to_string(),
+        "--force".to_string(),
+        "HEAD".to_string(),
+    ];
+
+    if let Err(err) = run_git(
+        &checkout_args,
+        &std::env::temp_dir(),
+        ssh_command,
+        Duration::from_secs(60),
+    )
+    .await
+    {
+        safe_rm(temp_base).await;
+        return Err(simple_error("unknown", &err.to_string()));
+    }
+
+    Ok(head_commit(temp_base).await)
+}
+
 pub async fn install_skills(working_directory: &Path, req: SkillsInstallRequest) -> SkillsInstallResponse {
-    let ssh_key = resolve_identity_ssh_key(req.git_identity_id.as_deref());
+    let ssh_command = resolve_identity_ssh_command(req.git_identity_id.as_deref()).await;
 
     let selections: Vec<String> = req
         .selections
@@ -1086,7 +2635,6 @@ pub async fn install_skills(working_directory: &Path, req: SkillsInstallRequest)
         };
     }
 
-    // Clone
     let parsed = match parse_repo_source(&req.source, req.subpath.as_deref()) {
         Ok(p) => p,
         Err(err) => {
@@ -1099,106 +2647,101 @@ pub async fn install_skills(working_directory: &Path, req: SkillsInstallRequest)
         }
     };
 
-    let clone_url = if ssh_key.is_some() {
-        parsed.clone_ssh.clone()
-    } else {
-        parsed.clone_https.clone()
-    };
-
     let temp_base = std::env::temp_dir().join(format!("openchamber-desktop-skills-install-{}", Uuid::new_v4()));
-    let _ = tokio::fs::remove_dir_all(&temp_base).await;
-
-    let clone_res = clone_repo(&clone_url, &temp_base, ssh_key.as_deref()).await;
-    if let Err(err) = clone_res {
-        let msg = err.to_string();
-        if AUTH_ERROR_RE.is_match(&msg) {
+    let archive = match parsed.archive_url.as_deref() {
+        Some(url) => match download_archive(url, &temp_base).await {
+            Ok(root) => Some(root),
+            Err(err) => {
+                safe_rm(&temp_base).await;
+                return SkillsInstallResponse {
+                    ok: false,
+                    installed: None,
+                    skipped: None,
+                    error: Some(simple_error("networkError", &err.to_string())),
+                };
+            }
+        },
+        None => None,
+    };
+    let (source_base, source_commit) = match parsed.local_path.clone().or(archive) {
+        Some(base) if base.is_dir() => (base, None),
+        Some(local_path) => {
             return SkillsInstallResponse {
                 ok: false,
                 installed: None,
                 skipped: None,
-                error: Some(auth_required_error("Authentication required to access this repository")),
+                error: Some(simple_error(
+                    "invalidSource",
+                    &format!("Local directory not found: {}", local_path.display()),
+                )),
             };
         }
+        None => match checkout_selected_dirs(
+            &parsed,
+            &temp_base,
+            ssh_command.as_deref(),
+            req.git_ref.as_deref(),
+            &selections,
+        )
+        .await
+        {
+            Ok(commit) => (temp_base.clone(), commit),
+            Err(error) => {
+                return SkillsInstallResponse {
+                    ok: false,
+                    installed: None,
+                    skipped: None,
+                    error: Some(error),
+                };
+            }
+        },
+    };
+    let mut records = vec![];
 
-        return SkillsInstallResponse {
-            ok: false,
-            installed: None,
-            skipped: None,
-            error: Some(simple_error("networkError", &msg)),
-        };
-    }
-
-    // sparse-checkout selected dirs
-    let init_args = vec![
-        "-C".to_string(),
-        temp_base.display().to_string(),
-        "sparse-checkout".to_string(),
-        "init".to_string(),
-        "--cone".to_string(),
-    ];
-    let _ = run_git(&init_args, &std::env::temp_dir(), ssh_key.as_deref(), Duration::from_secs(15)).await;
+    let mut installed = vec![];
+    let mut skipped = vec![];
 
-    let mut set_args = vec![
-        "-C".to_string(),
-        temp_base.display().to_string(),
-        "sparse-checkout".to_string(),
-        "set".to_string(),
-    ];
-    for dir in &selections {
-        set_args.push(dir.clone());
-    }
-
-    if let Err(err) = run_git(&set_args, &std::env::temp_dir(), ssh_key.as_deref(), Duration::from_secs(30)).await {
-        safe_rm(&temp_base).await;
-        return SkillsInstallResponse {
-            ok: false,
-            installed: None,
-            skipped: None,
-            error: Some(simple_error("unknown", &err.to_string())),
-        };
-    }
-
-    let checkout_args = vec![
-        "-C".to_string(),
-        temp_base.display().to_string(),
-        "checkout".to_string(),
-        "--force".to_string(),
-        "HEAD".to_string(),
-    ];
-
-    if let Err(err) = run_git(&checkout_args, &std::env::temp_dir(), ssh_key.as_deref(), Duration::from_secs(60)).await {
-        safe_rm(&temp_base).await;
-        return SkillsInstallResponse {
-            ok: false,
-            installed: None,
-            skipped: None,
-            error: Some(simple_error("unknown", &err.to_string())),
-        };
-    }
-
-    let mut installed = vec![];
-    let mut skipped = vec![];
-
-    for skill_dir in selections {
-        let skill_name = skill_dir
-            .split('/')
-            .filter(|s| !s.is_empty())
-            .last()
-            .unwrap_or("")
-            .to_string();
+    for skill_dir in selections {
+        let skill_name = skill_dir
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .last()
+            .unwrap_or("")
+            .to_string();
 
         if !validate_skill_name(&skill_name) {
             skipped.push(SkippedSkill { skill_name, reason: "Invalid skill name (directory basename)".to_string() });
             continue;
         }
 
-        let src_dir = repo_path_to_fs(&temp_base, &skill_dir);
+        if skill_dir.split('/').any(|part| part.trim() == "..") {
+            skipped.push(SkippedSkill {
+                skill_name,
+                reason: "Invalid skill directory".to_string(),
+            });
+            continue;
+        }
+
+        let src_dir = repo_path_to_fs(&source_base, &skill_dir);
         let skill_md = src_dir.join("SKILL.md");
         if !skill_md.exists() {
             skipped.push(SkippedSkill { skill_name, reason: "SKILL.md not found in selected directory".to_string() });
             continue;
         }
 
+        if let Some(problem) = validate_skill_dir(&src_dir)
+            .await
+            .into_iter()
+            .find(SkillDiagnostic::is_error)
+        {
+            let reason = match problem.path {
+                Some(path) => format!("{} ({})", problem.message, path),
+                None => problem.message,
+            };
+            skipped.push(SkippedSkill { skill_name, reason });
+            continue;
+        }
+
         let target_dir = match target_skill_dir(&req.scope, working_directory, &skill_name) {
             Ok(p) => p,
             Err(err) => {
@@ -1252,11 +2795,481 @@ pub async fn install_skills(working_directory: &Path, req: SkillsInstallRequest)
             continue;
         }
 
+        let tree = match source_commit {
+            Some(_) => skill_dir_tree(&source_base, "HEAD", &skill_dir).await,
+            None => None,
+        };
+        records.push(SkillManifestEntry {
+            skill_name: skill_name.clone(),
+            scope: req.scope.clone(),
+            path: target_dir.display().to_string(),
+            source: req.source.clone(),
+            subpath: req.subpath.clone(),
+            git_ref: req.git_ref.clone(),
+            git_identity_id: req.git_identity_id.clone(),
+            skill_dir,
+            commit: source_commit.clone(),
+            tree,
+            installed_at: chrono::Utc::now().to_rfc3339(),
+        });
         installed.push(InstalledSkill { skill_name, scope: req.scope.clone() });
     }
 
     safe_rm(&temp_base).await;
 
+    if let Err(err) = save_manifest_entries(records).await {
+        log::warn!("[skills] Failed to update skills manifest: {}", err);
+    }
+
+    SkillsInstallResponse {
+        ok: true,
+        installed: Some(installed),
+        skipped: Some(skipped),
+        error: None,
+    }
+}
+
+const SKILLS_MANIFEST_FILE: &str = "skills-manifest.json";
+const SKILLS_MANIFEST_VERSION: u32 = 1;
+
+/// Provenance of an installed skill, written to the skills manifest at install time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillManifestEntry {
+    pub skill_name: String,
+    pub scope: String,
+    /// Installed skill directory
+    pub path: String,
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<String>,
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_identity_id: Option<String>,
+    /// Skill directory inside the source repository
+    pub skill_dir: String,
+    /// Source commit the skill was installed from; absent for local directory and archive sources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Tree of `skill_dir` at `commit`; unrelated commits leave it unchanged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree: Option<String>,
+    pub installed_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SkillsManifest {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    skills: Vec<SkillManifestEntry>,
+}
+
+/// Serializes read/modify/write of `skills-manifest.json`
+static MANIFEST_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn manifest_path() -> Result<PathBuf> {
+    Ok(dirs::home_dir()
+        .ok_or_else(|| anyhow!("Could not find home directory"))?
+        .join(".config")
+        .join("openchamber")
+        .join(SKILLS_MANIFEST_FILE))
+}
+
+async fn read_manifest() -> Vec<SkillManifestEntry> {
+    let Ok(path) = manifest_path() else {
+        return vec![];
+    };
+    let Ok(content) = tokio::fs::read(&path).await else {
+        return vec![];
+    };
+    serde_json::from_slice::<SkillsManifest>(&content)
+        .map(|manifest| manifest.skills)
+        .unwrap_or_default()
+}
+
+/// Add or replace the entries for the installed directories in `entries`. Entries whose
+/// skill has since been removed are dropped along the way.
+async fn save_manifest_entries(entries: Vec<SkillManifestEntry>) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let _guard = MANIFEST_LOCK.lock().await;
+    let mut skills = read_manifest().await;
+    skills.retain(|existing| {
+        !entries.iter().any(|e| e.path == existing.path)
+            && Path::new(&existing.path).join("SKILL.md").exists()
+    });
+    skills.extend(entries);
+    skills.sort_by(|a, b| {
+        a.skill_name
+            .cmp(&b.skill_name)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let path = manifest_path()?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let temp_path = path.with_file_name(format!("{}.tmp-{}", SKILLS_MANIFEST_FILE, Uuid::new_v4()));
+    let manifest = SkillsManifest {
+        version: SKILLS_MANIFEST_VERSION,
+        skills,
+    };
+    tokio::fs::write(&temp_path, serde_json::to_vec_pretty(&manifest)?).await?;
+    if let Err(err) = tokio::fs::rename(&temp_path, &path).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// Manifest entries of skills that are still installed and visible from `working_directory`:
+/// user skills, and project skills of this project
+async fn relevant_manifest_entries(working_directory: &Path) -> Vec<SkillManifestEntry> {
+    read_manifest()
+        .await
+        .into_iter()
+        .filter(|record| {
+            target_skill_dir(&record.scope, working_directory, &record.skill_name).is_ok_and(
+                |target| target == Path::new(&record.path) && target.join("SKILL.md").exists(),
+            )
+        })
+        .collect()
+}
+
+async fn skill_dir_tree(repo_dir: &Path, rev: &str, skill_dir: &str) -> Option<String> {
+    let args = vec![
+        "-C".to_string(),
+        repo_dir.display().to_string(),
+        "rev-parse".to_string(),
+        "--verify".to_string(),
+        "--quiet".to_string(),
+        format!("{}:{}", rev, skill_dir),
+    ];
+    run_git(&args, &std::env::temp_dir(), None, Duration::from_secs(15))
+        .await
+        .ok()
+        .map(|(out, _)| out.trim().to_string())
+        .filter(|tree| !tree.is_empty())
+}
+
+/// Commit `git_ref` (or the default branch) points to upstream, without cloning
+async fn resolve_remote_commit(
+    clone_url: &str,
+    git_ref: Option<&str>,
+    ssh_command: Option<&str>,
+) -> Result<String> {
+    // ls-remote patterns match any ref ending in the name, so ask for the exact refs
+    // `clone --branch` would pick, in its order: branch, then tag. Annotated tags are
+    // listed twice; the peeled `^{}` entry is the commit.
+    let candidates = match git_ref {
+        None => vec!["HEAD".to_string()],
+        Some(r) if r.starts_with('-') => return Err(anyhow!("Invalid ref")),
+        Some(r) => vec![
+            format!("refs/heads/{}", r),
+            format!("refs/tags/{}^{{}}", r),
+            format!("refs/tags/{}", r),
+        ],
+    };
+    let mut args = vec!["ls-remote".to_string(), clone_url.to_string()];
+    args.extend(candidates.iter().cloned());
+    let token_env = https_token_env(clone_url, ssh_command).await;
+    let (out, _) = run_git_with_env(
+        &args,
+        &std::env::temp_dir(),
+        ssh_command,
+        Duration::from_secs(30),
+        token_env.vars(),
+    )
+    .await?;
+    let refs: Vec<(&str, &str)> = out
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .collect();
+    candidates
+        .iter()
+        .find_map(|candidate| refs.iter().find(|(_, name)| name == candidate))
+        .map(|(sha, _)| sha.to_string())
+        .ok_or_else(|| anyhow!("Ref {} not found upstream", git_ref.unwrap_or("HEAD")))
+}
+
+type SourceKey = (String, Option<String>, Option<String>);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdateStatus {
+    pub skill_name: String,
+    pub scope: String,
+    pub source: String,
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installed_commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_commit: Option<String>,
+    pub update_available: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUpdatesResponse {
+    pub ok: bool,
+    pub updates: Vec<SkillUpdateStatus>,
+}
+
+/// Compare installed skills with their source. A skill has an update when its directory
+/// changed upstream; commits elsewhere in the repository don't count. Skills pinned to a
+/// commit never have one.
+pub async fn check_skill_updates(working_directory: &Path) -> SkillUpdatesResponse {
+    let records = relevant_manifest_entries(working_directory).await;
+
+    // Keyed by (source, ref, git identity): one ls-remote/clone per group
+    let mut groups: Vec<(SourceKey, Vec<SkillManifestEntry>)> = vec![];
+    for record in records {
+        let key = (
+            record.source.clone(),
+            record.git_ref.clone(),
+            record.git_identity_id.clone(),
+        );
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, members)) => members.push(record),
+            None => groups.push((key, vec![record])),
+        }
+    }
+
+    let mut updates = vec![];
+    for ((source, git_ref, identity_id), members) in groups {
+        let status = |record: &SkillManifestEntry,
+                      latest: Option<String>,
+                      update_available: bool,
+                      error: Option<String>| {
+            SkillUpdateStatus {
+                skill_name: record.skill_name.clone(),
+                scope: record.scope.clone(),
+                source: record.source.clone(),
+                git_ref: record.git_ref.clone(),
+                installed_commit: record.commit.clone(),
+                latest_commit: latest,
+                update_available,
+                error,
+            }
+        };
+
+        if git_ref
+            .as_deref()
+            .is_some_and(|r| COMMIT_SHA_RE.is_match(r))
+        {
+            updates.extend(
+                members
+                    .iter()
+                    .map(|r| status(r, r.commit.clone(), false, None)),
+            );
+            continue;
+        }
+
+        let parsed = match parse_repo_source(&source, None) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                let message = err.to_string();
+                updates.extend(
+                    members
+                        .iter()
+                        .map(|r| status(r, None, false, Some(message.clone()))),
+                );
+                continue;
+            }
+        };
+        // Local directories and archives have no upstream commit to compare with; they are
+        // reinstalled on request
+        if parsed.local_path.is_some() || parsed.archive_url.is_some() {
+            updates.extend(members.iter().map(|r| status(r, None, false, None)));
+            continue;
+        }
+
+        let ssh_command = resolve_identity_ssh_command(identity_id.as_deref()).await;
+        let clone_url = if ssh_command.is_some() {
+            parsed.clone_ssh
+        } else {
+            parsed.clone_https
+        };
+        let latest =
+            resolve_remote_commit(&clone_url, git_ref.as_deref(), ssh_command.as_deref()).await;
+        let latest = match latest {
+            Ok(v) => v,
+            Err(err) => {
+                let message = err.to_string();
+                updates.extend(
+                    members
+                        .iter()
+                        .map(|r| status(r, None, false, Some(message.clone()))),
+                );
+                continue;
+            }
+        };
+
+        if members
+            .iter()
+            .all(|r| r.commit.as_deref() == Some(latest.as_str()))
+        {
+            updates.extend(
+                members
+                    .iter()
+                    .map(|r| status(r, Some(latest.clone()), false, None)),
+            );
+            continue;
+        }
+
+        // The repository moved on; fetch trees only to see whether the skills changed
+        let temp_base = std::env::temp_dir().join(format!(
+            "openchamber-desktop-skills-update-{}",
+            Uuid::new_v4()
+        ));
+        if let Err(err) = clone_repo(
+            &clone_url,
+            &temp_base,
+            ssh_command.as_deref(),
+            git_ref.as_deref(),
+        )
+        .await
+        {
+            safe_rm(&temp_base).await;
+            let message = err.to_string();
+            updates.extend(
+                members
+                    .iter()
+                    .map(|r| status(r, Some(latest.clone()), false, Some(message.clone()))),
+            );
+            continue;
+        }
+        for record in &members {
+            let upstream = skill_dir_tree(&temp_base, "HEAD", &record.skill_dir).await;
+            let entry = match (&upstream, &record.tree) {
+                (None, _) => status(
+                    record,
+                    Some(latest.clone()),
+                    false,
+                    Some("Skill no longer exists upstream".to_string()),
+                ),
+                (Some(upstream), Some(tree)) => {
+                    status(record, Some(latest.clone()), upstream != tree, None)
+                }
+                (Some(_), None) => status(
+                    record,
+                    Some(latest.clone()),
+                    record.commit.as_deref() != Some(latest.as_str()),
+                    None,
+                ),
+            };
+            updates.push(entry);
+        }
+        safe_rm(&temp_base).await;
+    }
+
+    updates.sort_by(|a, b| a.skill_name.cmp(&b.skill_name));
+    SkillUpdatesResponse { ok: true, updates }
+}
+
+fn install_request_for(
+    record: &SkillManifestEntry,
+    skill_dirs: Vec<String>,
+) -> SkillsInstallRequest {
+    SkillsInstallRequest {
+        source: record.source.clone(),
+        subpath: record.subpath.clone(),
+        git_ref: record.git_ref.clone(),
+        git_identity_id: record.git_identity_id.clone(),
+        scope: record.scope.clone(),
+        selections: skill_dirs
+            .into_iter()
+            .map(|skill_dir| SkillsInstallSelection { skill_dir })
+            .collect(),
+        conflict_policy: Some("overwriteAll".to_string()),
+        conflict_decisions: None,
+    }
+}
+
+/// Reinstall one skill from the source it was installed from
+pub async fn update_skill(
+    working_directory: &Path,
+    skill_name: &str,
+    scope: Option<&str>,
+) -> SkillsInstallResponse {
+    let record = relevant_manifest_entries(working_directory)
+        .await
+        .into_iter()
+        .filter(|r| r.skill_name == skill_name && scope.is_none_or(|s| r.scope == s))
+        // A project skill shadows the user one, as in discovery
+        .max_by_key(|r| r.scope == "project");
+    let Some(record) = record else {
+        return SkillsInstallResponse {
+            ok: false,
+            installed: None,
+            skipped: None,
+            error: Some(simple_error(
+                "notFound",
+                "No recorded source for this skill",
+            )),
+        };
+    };
+
+    let request = install_request_for(&record, vec![record.skill_dir.clone()]);
+    install_skills(working_directory, request).await
+}
+
+/// Reinstall every skill `check_skill_updates` reports an update for, one clone per source
+pub async fn update_all_skills(working_directory: &Path) -> SkillsInstallResponse {
+    let outdated: Vec<(String, String)> = check_skill_updates(working_directory)
+        .await
+        .updates
+        .into_iter()
+        .filter(|u| u.update_available)
+        .map(|u| (u.skill_name, u.scope))
+        .collect();
+    let records: Vec<SkillManifestEntry> = relevant_manifest_entries(working_directory)
+        .await
+        .into_iter()
+        .filter(|r| outdated.contains(&(r.skill_name.clone(), r.scope.clone())))
+        .collect();
+
+    let mut batches: Vec<(SkillManifestEntry, Vec<String>)> = vec![];
+    for record in records {
+        let same_batch = |other: &SkillManifestEntry| {
+            other.source == record.source
+                && other.git_ref == record.git_ref
+                && other.git_identity_id == record.git_identity_id
+                && other.scope == record.scope
+        };
+        match batches.iter_mut().find(|(first, _)| same_batch(first)) {
+            Some((_, dirs)) => dirs.push(record.skill_dir.clone()),
+            None => {
+                let dirs = vec![record.skill_dir.clone()];
+                batches.push((record, dirs));
+            }
+        }
+    }
+
+    let mut installed = vec![];
+    let mut skipped = vec![];
+    for (record, dirs) in batches {
+        let names: Vec<String> = dirs
+            .iter()
+            .map(|d| d.rsplit('/').next().unwrap_or(d).to_string())
+            .collect();
+        let response = install_skills(working_directory, install_request_for(&record, dirs)).await;
+        installed.extend(response.installed.unwrap_or_default());
+        skipped.extend(response.skipped.unwrap_or_default());
+        if let Some(error) = response.error {
+            skipped.extend(names.into_iter().map(|skill_name| SkippedSkill {
+                skill_name,
+                reason: error.message.clone(),
+            }));
+        }
+    }
+
     SkillsInstallResponse {
         ok: true,
         installed: Some(installed),
@@ -1264,3 +3277,286 @@ pub async fn install_skills(working_directory: &Path, req: SkillsInstallRequest)
         error: None,
     }
 }
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledSkillProvenance {
+    pub name: String,
+    /// Path to the skill's SKILL.md
+    pub path: String,
+    pub scope: opencode_config::Scope,
+    pub source: opencode_config::SkillSource,
+    /// Where the skill was installed from; absent for skills created or copied in by hand
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<SkillManifestEntry>,
+}
+
+/// Installed skills visible from `working_directory`, each with its manifest entry if it has one
+pub async fn list_installed_skills_with_provenance(
+    working_directory: &Path,
+) -> Vec<InstalledSkillProvenance> {
+    let manifest = read_manifest().await;
+    let mut skills: Vec<InstalledSkillProvenance> =
+        opencode_config::discover_skills(Some(working_directory))
+            .into_iter()
+            .map(|skill| {
+                let skill_dir = Path::new(&skill.path).parent().map(Path::to_path_buf);
+                let provenance = manifest
+                    .iter()
+                    .find(|entry| skill_dir.as_deref() == Some(Path::new(&entry.path)))
+                    .cloned();
+                InstalledSkillProvenance {
+                    name: skill.name,
+                    path: skill.path,
+                    scope: skill.scope,
+                    source: skill.source,
+                    provenance,
+                }
+            })
+            .collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    skills
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillCatalogSourceInput {
+    pub label: String,
+    pub source: String,
+    pub subpath: Option<String>,
+    #[serde(rename = "ref")]
+    pub git_ref: Option<String>,
+    pub git_identity_id: Option<String>,
+}
+
+impl SkillCatalogSourceInput {
+    fn into_source(self, id: String) -> SkillsCatalogSource {
+        let non_empty = |value: Option<String>| {
+            value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let source = self.source.trim().to_string();
+        SkillsCatalogSource {
+            id,
+            label: self.label.trim().to_string(),
+            description: Some(source.clone()),
+            source,
+            default_subpath: non_empty(self.subpath),
+            git_ref: non_empty(self.git_ref),
+            git_identity_id: non_empty(self.git_identity_id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillCatalogSourceResponse {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<SkillsCatalogSource>,
+    /// Skills found by the test scan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skill_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<SkillsRepoError>,
+}
+
+impl SkillCatalogSourceResponse {
+    fn failed(error: SkillsRepoError) -> Self {
+        Self {
+            ok: false,
+            source: None,
+            skill_count: None,
+            error: Some(error),
+        }
+    }
+}
+
+async fn read_catalog_sources(
+    settings: &SettingsStore,
+) -> std::result::Result<Vec<SkillsCatalogSource>, SkillsRepoError> {
+    settings
+        .load()
+        .await
+        .map(|value| catalog_sources_from_settings(&value))
+        .map_err(|err| simple_error("unknown", &format!("Failed to load settings: {}", err)))
+}
+
+/// Re-read the saved catalogs and apply one change to them. Catalog edits run a slow test
+/// scan first, so the list read before the scan may be stale by the time it is saved.
+async fn modify_catalog_sources<T>(
+    settings: &SettingsStore,
+    change: impl FnOnce(&mut Vec<SkillsCatalogSource>) -> std::result::Result<T, SkillsRepoError>,
+) -> std::result::Result<T, SkillsRepoError> {
+    let mut current = settings
+        .load()
+        .await
+        .map_err(|err| simple_error("unknown", &format!("Failed to load settings: {}", err)))?;
+    if !current.is_object() {
+        current = serde_json::json!({});
+    }
+    let mut sources = catalog_sources_from_settings(&current);
+    let result = change(&mut sources)?;
+    if let Some(obj) = current.as_object_mut() {
+        let entries = sources.iter().map(catalog_source_to_settings).collect();
+        obj.insert(
+            "skillCatalogs".to_string(),
+            serde_json::Value::Array(entries),
+        );
+    }
+    settings
+        .save(current)
+        .await
+        .map_err(|err| simple_error("unknown", &format!("Failed to save settings: {}", err)))?;
+    Ok(result)
+}
+
+fn same_catalog_source(a: &SkillsCatalogSource, b: &SkillsCatalogSource) -> bool {
+    a.source == b.source && a.default_subpath == b.default_subpath && a.git_ref == b.git_ref
+}
+
+/// Scan a source the way the catalog will, so a source that can't be read is never saved.
+/// The scan also warms the catalog cache for it.
+async fn test_scan_catalog_source(
+    src: &SkillsCatalogSource,
+) -> std::result::Result<usize, SkillsRepoError> {
+    if src.label.is_empty() {
+        return Err(simple_error("invalidSource", "Catalog name is required"));
+    }
+    let parsed = parse_repo_source(&src.source, None)
+        .map_err(|err| simple_error("invalidSource", &err.to_string()))?;
+    let entry = scan_catalog_source(src, &parsed, catalog_cache_key(src, &parsed), None).await?;
+    if entry.items.is_empty() {
+        return Err(simple_error(
+            "invalidSource",
+            "No skills found in this repository",
+        ));
+    }
+    Ok(entry.items.len())
+}
+
+fn duplicate_catalog_error() -> SkillsRepoError {
+    simple_error("duplicate", "This catalog already exists")
+}
+
+fn catalog_not_found_error() -> SkillsRepoError {
+    simple_error("notFound", "Catalog not found")
+}
+
+pub async fn add_catalog_source(
+    settings: &SettingsStore,
+    input: SkillCatalogSourceInput,
+) -> SkillCatalogSourceResponse {
+    let src = input.into_source(format!("custom:{}", Uuid::new_v4()));
+    let sources = match read_catalog_sources(settings).await {
+        Ok(sources) => sources,
+        Err(error) => return SkillCatalogSourceResponse::failed(error),
+    };
+    if sources
+        .iter()
+        .any(|existing| same_catalog_source(existing, &src))
+    {
+        return SkillCatalogSourceResponse::failed(duplicate_catalog_error());
+    }
+
+    let skill_count = match test_scan_catalog_source(&src).await {
+        Ok(count) => count,
+        Err(error) => return SkillCatalogSourceResponse::failed(error),
+    };
+
+    let saved = modify_catalog_sources(settings, |sources| {
+        if sources
+            .iter()
+            .any(|existing| same_catalog_source(existing, &src))
+        {
+            return Err(duplicate_catalog_error());
+        }
+        sources.push(src.clone());
+        Ok(())
+    })
+    .await;
+    match saved {
+        Ok(()) => SkillCatalogSourceResponse {
+            ok: true,
+            source: Some(src),
+            skill_count: Some(skill_count),
+            error: None,
+        },
+        Err(error) => SkillCatalogSourceResponse::failed(error),
+    }
+}
+
+pub async fn update_catalog_source(
+    settings: &SettingsStore,
+    id: &str,
+    input: SkillCatalogSourceInput,
+) -> SkillCatalogSourceResponse {
+    let sources = match read_catalog_sources(settings).await {
+        Ok(sources) => sources,
+        Err(error) => return SkillCatalogSourceResponse::failed(error),
+    };
+    if !sources.iter().any(|existing| existing.id == id) {
+        return SkillCatalogSourceResponse::failed(catalog_not_found_error());
+    }
+
+    let src = input.into_source(id.to_string());
+    let is_duplicate = |sources: &[SkillsCatalogSource]| {
+        sources
+            .iter()
+            .any(|existing| existing.id != id && same_catalog_source(existing, &src))
+    };
+    if is_duplicate(&sources) {
+        return SkillCatalogSourceResponse::failed(duplicate_catalog_error());
+    }
+
+    let skill_count = match test_scan_catalog_source(&src).await {
+        Ok(count) => count,
+        Err(error) => return SkillCatalogSourceResponse::failed(error),
+    };
+
+    let saved = modify_catalog_sources(settings, |sources| {
+        if is_duplicate(sources.as_slice()) {
+            return Err(duplicate_catalog_error());
+        }
+        let existing = sources
+            .iter_mut()
+            .find(|existing| existing.id == id)
+            .ok_or_else(catalog_not_found_error)?;
+        *existing = src.clone();
+        Ok(())
+    })
+    .await;
+    match saved {
+        Ok(()) => SkillCatalogSourceResponse {
+            ok: true,
+            source: Some(src),
+            skill_count: Some(skill_count),
+            error: None,
+        },
+        Err(error) => SkillCatalogSourceResponse::failed(error),
+    }
+}
+
+pub async fn remove_catalog_source(
+    settings: &SettingsStore,
+    id: &str,
+) -> SkillCatalogSourceResponse {
+    let removed = modify_catalog_sources(settings, |sources| {
+        let index = sources
+            .iter()
+            .position(|existing| existing.id == id)
+            .ok_or_else(catalog_not_found_error)?;
+        Ok(sources.remove(index))
+    })
+    .await;
+    match removed {
+        Ok(removed) => SkillCatalogSourceResponse {
+            ok: true,
+            source: Some(removed),
+            skill_count: None,
+            error: None,
+        },
+        Err(error) => SkillCatalogSourceResponse::failed(error),
+    }
+}
@@ -0,0 +1,161 @@
+use anyhow::{anyhow, Result};
+use log::{debug, warn};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use tokio::fs;
+use tokio::sync::Mutex;
+
+const SKILLS_USAGE_FILE: &str = "skills-usage.json";
+/// Tool name OpenCode uses when an agent loads a skill
+const SKILL_TOOL_NAME: &str = "skill";
+/// Tool call ids already counted; part updates for one call arrive several times
+const RECENT_CALLS_CAPACITY: usize = 256;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillUsage {
+    pub use_count: u64,
+    pub last_used_at: Option<String>,
+    pub last_session_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct SkillsUsageFile {
+    #[serde(default)]
+    skills: HashMap<String, SkillUsage>,
+}
+
+struct UsageState {
+    loaded: Option<HashMap<String, SkillUsage>>,
+    recent_calls: VecDeque<String>,
+}
+
+static USAGE_STATE: Lazy<Mutex<UsageState>> = Lazy::new(|| {
+    Mutex::new(UsageState {
+        loaded: None,
+        recent_calls: VecDeque::new(),
+    })
+});
+
+fn storage_path() -> Result<PathBuf> {
+    let mut path = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
+    path.push(".config");
+    path.push("openchamber");
+    path.push(SKILLS_USAGE_FILE);
+    Ok(path)
+}
+
+async fn read_from_disk() -> HashMap<String, SkillUsage> {
+    let Ok(path) = storage_path() else {
+        return HashMap::new();
+    };
+    let Ok(content) = fs::read(&path).await else {
+        return HashMap::new();
+    };
+    match serde_json::from_slice::<SkillsUsageFile>(&content) {
+        Ok(file) => file.skills,
+        Err(err) => {
+            warn!("[skills-usage] Ignoring unreadable {:?}: {}", path, err);
+            HashMap::new()
+        }
+    }
+}
+
+async fn write_to_disk(skills: &HashMap<String, SkillUsage>) -> Result<()> {
+    let path = storage_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    let content = serde_json::to_vec_pretty(&SkillsUsageFile {
+        skills: skills.clone(),
+    })?;
+    let temp_path = path.with_file_name(format!(
+        "{}.tmp-{}",
+        SKILLS_USAGE_FILE,
+        uuid::Uuid::new_v4()
+    ));
+    fs::write(&temp_path, content).await?;
+    if let Err(err) = fs::rename(&temp_path, &path).await {
+        let _ = fs::remove_file(&temp_path).await;
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// Per-skill usage recorded so far, keyed by skill name
+pub async fn load_usage() -> HashMap<String, SkillUsage> {
+    let mut state = USAGE_STATE.lock().await;
+    if state.loaded.is_none() {
+        state.loaded = Some(read_from_disk().await);
+    }
+    state.loaded.clone().unwrap_or_default()
+}
+
+/// Inspect a `message.part.updated` payload and record a use when it is a completed
+/// call of the skill tool
+pub async fn record_from_part_event(properties: &Value) {
+    let Some(part) = properties.get("part") else {
+        return;
+    };
+    if part.get("type").and_then(Value::as_str) != Some("tool")
+        || part.get("tool").and_then(Value::as_str) != Some(SKILL_TOOL_NAME)
+    {
+        return;
+    }
+    let Some(tool_state) = part.get("state") else {
+        return;
+    };
+    if tool_state.get("status").and_then(Value::as_str) != Some("completed") {
+        return;
+    }
+    let Some(name) = tool_state
+        .get("input")
+        .and_then(|input| input.get("name"))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    else {
+        return;
+    };
+
+    let call_id = part
+        .get("callID")
+        .or_else(|| part.get("id"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let session_id = part
+        .get("sessionID")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let mut state = USAGE_STATE.lock().await;
+    if !call_id.is_empty() {
+        if state.recent_calls.contains(&call_id) {
+            return;
+        }
+        if state.recent_calls.len() >= RECENT_CALLS_CAPACITY {
+            state.recent_calls.pop_front();
+        }
+        state.recent_calls.push_back(call_id);
+    }
+
+    if state.loaded.is_none() {
+        state.loaded = Some(read_from_disk().await);
+    }
+    let skills = state.loaded.get_or_insert_with(HashMap::new);
+    let usage = skills.entry(name.to_string()).or_default();
+    usage.use_count += 1;
+    usage.last_used_at = Some(chrono::Utc::now().to_rfc3339());
+    usage.last_session_id = session_id;
+    debug!("[skills-usage] {} used ({} total)", name, usage.use_count);
+
+    let snapshot = skills.clone();
+    drop(state);
+    if let Err(err) = write_to_disk(&snapshot).await {
+        warn!("[skills-usage] Failed to persist usage: {}", err);
+    }
+}
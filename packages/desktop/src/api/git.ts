@@ -7,6 +7,7 @@ import type {
   GetGitDiffOptions,
   GitFileDiffResponse,
   GitBranch,
+  GetGitBranchesOptions,
   GitDeleteBranchPayload,
   GitDeleteRemoteBranchPayload,
   GeneratedCommitMessage,
@@ -34,7 +35,18 @@ async function safeGitInvoke<T>(command: string, args?: Record<string, unknown>)
     });
   } catch (error) {
     const message = typeof error === 'string' ? error : (error as Error).message || 'Unknown error';
-    throw new Error(message);
+    // Git commands reject with a GitError object; keep its kind and detail for callers
+    const gitError =
+      typeof error === 'object' && error !== null ? (error as Record<string, unknown>) : {};
+    const text = (value: unknown) => (typeof value === 'string' ? value : undefined);
+    throw Object.assign(new Error(message), {
+      kind: text(gitError.kind) ?? 'unknown',
+      detail: text(gitError.detail),
+      // Set for hookFailed: which hook failed and what it printed
+      hook: text(gitError.hook),
+      hookOutput: text(gitError.hookOutput),
+      gitOutput: text(gitError.gitOutput)
+    });
   }
 }
 
@@ -77,8 +89,12 @@ export const createDesktopGitAPI = (): GitAPI => ({
     return safeGitInvoke<boolean>('is_linked_worktree', { directory });
   },
 
-  async getGitBranches(directory: string): Promise<GitBranch> {
-    return safeGitInvoke<GitBranch>('get_git_branches', { directory });
+  async getGitBranches(directory: string, options?: GetGitBranchesOptions): Promise<GitBranch> {
+    return safeGitInvoke<GitBranch>('get_git_branches', {
+      directory,
+      sort: options?.sort,
+      includeRemotes: options?.includeRemotes
+    });
   },
 
   async deleteGitBranch(directory: string, payload: GitDeleteBranchPayload): Promise<{ success: boolean }> {
@@ -140,16 +156,24 @@ export const createDesktopGitAPI = (): GitAPI => ({
       directory,
       message,
       addAll: options?.addAll,
-      files: options?.files
+      files: options?.files,
+      signoff: options?.signoff,
+      noVerify: options?.noVerify,
+      allowEmpty: options?.allowEmpty,
+      authorDate: options?.authorDate,
+      commitDate: options?.commitDate
     });
   },
 
-  async gitPush(directory: string, options?: { remote?: string; branch?: string; options?: string[] | Record<string, unknown> }): Promise<GitPushResult> {
+  async gitPush(directory: string, options?: { remote?: string; branch?: string; options?: string[] | Record<string, unknown>; remotes?: string[]; pushToAll?: boolean; noVerify?: boolean }): Promise<GitPushResult> {
     return safeGitInvoke<GitPushResult>('git_push', {
       directory,
       remote: options?.remote,
       branch: options?.branch,
-      options: options?.options
+      options: options?.options,
+      remotes: options?.remotes,
+      pushToAll: options?.pushToAll,
+      noVerify: options?.noVerify
     });
   },
 
@@ -169,9 +193,10 @@ export const createDesktopGitAPI = (): GitAPI => ({
     return { success: true };
   },
 
-  async checkoutBranch(directory: string, branch: string): Promise<{ success: boolean; branch: string }> {
-    await safeGitInvoke<void>('checkout_branch', { directory, branch });
-    return { success: true, branch };
+  async checkoutBranch(directory: string, branch: string): Promise<{ success: boolean; branch: string; created?: boolean; tracking?: string | null }> {
+    // Remote branches come back as the local branch created to track them
+    const result = await safeGitInvoke<{ branch: string; created: boolean; tracking: string | null }>('checkout_branch', { directory, branch });
+    return { success: true, ...result };
   },
 
   async createBranch(directory: string, name: string, startPoint?: string): Promise<{ success: boolean; branch: string }> {
@@ -189,7 +214,15 @@ export const createDesktopGitAPI = (): GitAPI => ({
       maxCount: options?.maxCount,
       from: options?.from,
       to: options?.to,
-      file: options?.file
+      file: options?.file,
+      operationId: options?.operationId,
+      filters: {
+        author: options?.author,
+        since: options?.since,
+        until: options?.until,
+        grep: options?.grep,
+        paths: options?.paths
+      }
     });
   },
 
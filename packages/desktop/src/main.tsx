@@ -6,7 +6,7 @@ import { initializeDesktopBridge } from './lib/bridge';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import type { RuntimeAPIs } from '@openchamber/ui/lib/api/types';
-import type { DesktopApi, DesktopSettings } from '@openchamber/ui/lib/desktop';
+import type { DesktopApi, DesktopSettings, GitAskpassRequest, GitStatusChangedEvent } from '@openchamber/ui/lib/desktop';
 import '@openchamber/ui/index.css';
 import '@openchamber/ui/styles/fonts';
 
@@ -47,6 +47,8 @@ declare global {
 
 const CHECK_FOR_UPDATES_EVENT = 'openchamber:check-for-updates';
 const MENU_ACTION_EVENT = 'openchamber:menu-action';
+const GIT_ASKPASS_REQUEST_EVENT = 'git:askpass-request';
+const GIT_STATUS_CHANGED_EVENT = 'git:status-changed';
 
 const cleanupFunctions: Array<() => void | Promise<void>> = [];
 
@@ -249,6 +251,35 @@ window.opencodeDesktop = {
   },
   async restartToUpdate(): Promise<void> {
     return restartToUpdate();
+  },
+  async onGitAskpassRequest(handler: (request: GitAskpassRequest) => void) {
+    const unlisten = await listen<GitAskpassRequest>(GIT_ASKPASS_REQUEST_EVENT, (event) => {
+      handler(event.payload);
+    });
+    // The backend cancels prompts right away while nothing is listening
+    await invoke('set_git_askpass_listener', { active: true });
+    return () => {
+      unlisten();
+      invoke('set_git_askpass_listener', { active: false }).catch(() => {});
+    };
+  },
+  async respondGitAskpass(id: string, response: string | null) {
+    await invoke('respond_git_askpass', { id, response });
+  },
+  async watchGitStatus(directory: string, handler: (event: GitStatusChangedEvent) => void) {
+    const unlisten = await listen<GitStatusChangedEvent>(GIT_STATUS_CHANGED_EVENT, (event) => {
+      handler(event.payload);
+    });
+    try {
+      await invoke('watch_git_status', { directory });
+    } catch (error) {
+      unlisten();
+      throw error;
+    }
+    return () => {
+      unlisten();
+      invoke('unwatch_git_status', { directory }).catch(() => {});
+    };
   }
 };
 
@@ -20,6 +20,7 @@ import { useFontPreferences } from '@/hooks/useFontPreferences';
 import { CODE_FONT_OPTION_MAP, DEFAULT_MONO_FONT, DEFAULT_UI_FONT, UI_FONT_OPTION_MAP } from '@/lib/fontOptions';
 import { ConfigUpdateOverlay } from '@/components/ui/ConfigUpdateOverlay';
 import { AboutDialog } from '@/components/ui/AboutDialog';
+import { GitAskpassDialog } from '@/components/ui/GitAskpassDialog';
 import { RuntimeAPIProvider } from '@/contexts/RuntimeAPIProvider';
 import { registerRuntimeAPIs } from '@/contexts/runtimeAPIRegistry';
 import { OnboardingScreen } from '@/components/onboarding/OnboardingScreen';
@@ -214,6 +215,7 @@ function App({ apis }: AppProps) {
               <Toaster />
               <ConfigUpdateOverlay />
               <AboutDialogWrapper />
+              {isDesktopRuntime && <GitAskpassDialog />}
               {showMemoryDebug && (
                 <MemoryDebugPanel onClose={() => setShowMemoryDebug(false)} />
               )}
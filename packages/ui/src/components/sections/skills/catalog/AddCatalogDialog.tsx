@@ -20,14 +20,9 @@ import {
 
 import { RiGitRepositoryLine } from '@remixicon/react';
 
-import { getRegisteredRuntimeAPIs } from '@/contexts/runtimeAPIRegistry';
-import { getDesktopSettings, isDesktopRuntime, isVSCodeRuntime } from '@/lib/desktop';
-import { updateDesktopSettings } from '@/lib/persistence';
-import type { DesktopSettings, SkillCatalogConfig } from '@/lib/desktop';
+import { isVSCodeRuntime } from '@/lib/desktop';
 import { useSkillsCatalogStore } from '@/stores/useSkillsCatalogStore';
 
-const generateCatalogId = () => `custom:${Date.now()}-${Math.random().toString(16).slice(2)}`;
-
 const guessLabelFromSource = (value: string) => {
   const trimmed = value.trim();
   const ssh = trimmed.match(/^git@github\.com:([^/\s]+)\/([^\s#]+)$/i);
@@ -47,46 +42,19 @@ const guessLabelFromSource = (value: string) => {
 
 type IdentityOption = { id: string; name: string };
 
-const loadSettings = async (): Promise<DesktopSettings | null> => {
-  try {
-    if (isDesktopRuntime()) {
-      return await getDesktopSettings();
-    }
-
-    const runtimeSettings = getRegisteredRuntimeAPIs()?.settings;
-    if (runtimeSettings) {
-      const result = await runtimeSettings.load();
-      return (result?.settings || {}) as DesktopSettings;
-    }
-
-    const response = await fetch('/api/config/settings', {
-      method: 'GET',
-      headers: { Accept: 'application/json' },
-    });
-
-    if (!response.ok) {
-      return null;
-    }
-
-    return (await response.json().catch(() => null)) as DesktopSettings | null;
-  } catch {
-    return null;
-  }
-};
-
 interface AddCatalogDialogProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
 }
 
 export const AddCatalogDialog: React.FC<AddCatalogDialogProps> = ({ open, onOpenChange }) => {
-  const { scanRepo, loadCatalog, isScanning } = useSkillsCatalogStore();
+  const { sources, scanRepo, addCatalogSource, isScanning } = useSkillsCatalogStore();
 
   const [label, setLabel] = React.useState('');
   const [source, setSource] = React.useState('');
   const [subpath, setSubpath] = React.useState('');
 
-  const [existingCatalogs, setExistingCatalogs] = React.useState<SkillCatalogConfig[]>([]);
+  const [isSaving, setIsSaving] = React.useState(false);
 
   const [scanCount, setScanCount] = React.useState<number | null>(null);
   const [scanOk, setScanOk] = React.useState(false);
@@ -104,24 +72,18 @@ export const AddCatalogDialog: React.FC<AddCatalogDialogProps> = ({ open, onOpen
     setScanOk(false);
     setIdentityOptions([]);
     setGitIdentityId(null);
-
-    void (async () => {
-      const settings = await loadSettings();
-      const catalogs = Array.isArray(settings?.skillCatalogs) ? settings?.skillCatalogs : [];
-      setExistingCatalogs(catalogs || []);
-    })();
   }, [open]);
 
   const isDuplicate = React.useMemo(() => {
     const normalizedSource = source.trim();
     const normalizedSubpath = subpath.trim();
 
-    return existingCatalogs.some((c) => {
+    return sources.some((c) => {
       const s = (c.source || '').trim();
-      const sp = (c.subpath || '').trim();
+      const sp = (c.defaultSubpath || '').trim();
       return s === normalizedSource && sp === normalizedSubpath;
     });
-  }, [existingCatalogs, source, subpath]);
+  }, [sources, source, subpath]);
 
   const handleScan = async () => {
     const trimmedSource = source.trim();
@@ -201,24 +163,22 @@ export const AddCatalogDialog: React.FC<AddCatalogDialogProps> = ({ open, onOpen
       return;
     }
 
-    const next: SkillCatalogConfig = {
-      id: generateCatalogId(),
-      label: trimmedLabel,
-      source: trimmedSource,
-      ...(trimmedSubpath ? { subpath: trimmedSubpath } : {}),
-      ...(gitIdentityId ? { gitIdentityId } : {}),
-    };
-
-    const updated = [...existingCatalogs, next];
-
+    setIsSaving(true);
     try {
-      await updateDesktopSettings({ skillCatalogs: updated });
-      setExistingCatalogs(updated);
+      const result = await addCatalogSource({
+        label: trimmedLabel,
+        source: trimmedSource,
+        ...(trimmedSubpath ? { subpath: trimmedSubpath } : {}),
+        ...(gitIdentityId ? { gitIdentityId } : {}),
+      });
+      if (!result.ok) {
+        toast.error(result.error?.message || 'Failed to save catalog');
+        return;
+      }
       toast.success('Catalog added');
-      await loadCatalog({ refresh: true });
       onOpenChange(false);
-    } catch (error) {
-      toast.error(error instanceof Error ? error.message : 'Failed to save catalog');
+    } finally {
+      setIsSaving(false);
     }
   };
 
@@ -322,9 +282,9 @@ export const AddCatalogDialog: React.FC<AddCatalogDialogProps> = ({ open, onOpen
           <Button
             variant="default"
             onClick={() => void handleAdd()}
-            disabled={!scanOk || isDuplicate || !label.trim() || !source.trim()}
+            disabled={isSaving || !scanOk || isDuplicate || !label.trim() || !source.trim()}
           >
-            Add catalog
+            {isSaving ? 'Adding…' : 'Add catalog'}
           </Button>
         </DialogFooter>
       </DialogContent>
@@ -1,4 +1,5 @@
 import React from 'react';
+import { toast } from 'sonner';
 
 import { Button } from '@/components/ui/button';
 import { Input } from '@/components/ui/input';
@@ -16,11 +17,6 @@ import { RiAddLine, RiDeleteBinLine, RiRefreshLine } from '@remixicon/react';
 import { useSkillsCatalogStore } from '@/stores/useSkillsCatalogStore';
 import type { SkillsCatalogItem } from '@/lib/api/types';
 
-import { getRegisteredRuntimeAPIs } from '@/contexts/runtimeAPIRegistry';
-import { getDesktopSettings, isDesktopRuntime } from '@/lib/desktop';
-import { updateDesktopSettings } from '@/lib/persistence';
-import type { DesktopSettings, SkillCatalogConfig } from '@/lib/desktop';
-
 import { AddCatalogDialog } from './AddCatalogDialog';
 import { InstallSkillDialog } from './InstallSkillDialog';
 
@@ -31,33 +27,6 @@ interface SkillsCatalogPageProps {
   onModeChange: (mode: SkillsMode) => void;
 }
 
-const loadSettings = async (): Promise<DesktopSettings | null> => {
-  try {
-    if (isDesktopRuntime()) {
-      return await getDesktopSettings();
-    }
-
-    const runtimeSettings = getRegisteredRuntimeAPIs()?.settings;
-    if (runtimeSettings) {
-      const result = await runtimeSettings.load();
-      return (result?.settings || {}) as DesktopSettings;
-    }
-
-    const response = await fetch('/api/config/settings', {
-      method: 'GET',
-      headers: { Accept: 'application/json' },
-    });
-
-    if (!response.ok) {
-      return null;
-    }
-
-    return (await response.json().catch(() => null)) as DesktopSettings | null;
-  } catch {
-    return null;
-  }
-};
-
 export const SkillsCatalogPage: React.FC<SkillsCatalogPageProps> = ({ mode, onModeChange }) => {
   const {
     sources,
@@ -65,6 +34,7 @@ export const SkillsCatalogPage: React.FC<SkillsCatalogPageProps> = ({ mode, onMo
     selectedSourceId,
     setSelectedSource,
     loadCatalog,
+    removeCatalogSource,
     isLoadingCatalog,
     lastCatalogError,
   } = useSkillsCatalogStore();
@@ -110,11 +80,10 @@ export const SkillsCatalogPage: React.FC<SkillsCatalogPageProps> = ({ mode, onMo
 
     setIsRemovingCatalog(true);
     try {
-      const settings = await loadSettings();
-      const catalogs = (Array.isArray(settings?.skillCatalogs) ? settings?.skillCatalogs : []) as SkillCatalogConfig[];
-      const updated = catalogs.filter((c) => c.id !== selectedSourceId);
-      await updateDesktopSettings({ skillCatalogs: updated });
-      await loadCatalog({ refresh: true });
+      const result = await removeCatalogSource(selectedSourceId);
+      if (!result.ok) {
+        toast.error(result.error?.message || 'Failed to remove catalog');
+      }
     } finally {
       setIsRemovingCatalog(false);
     }
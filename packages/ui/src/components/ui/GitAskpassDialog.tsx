@@ -0,0 +1,118 @@
+import React from 'react';
+
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+} from '@/components/ui/dialog';
+import { Button } from '@/components/ui/button';
+import { Input } from '@/components/ui/input';
+import { getDesktopApi, type GitAskpassRequest } from '@/lib/desktop';
+
+const TITLES: Record<GitAskpassRequest['kind'], string> = {
+  passphrase: 'SSH key passphrase',
+  password: 'Git password',
+  username: 'Git username',
+  confirm: 'Confirm host',
+};
+
+/**
+ * Answers passphrase and password prompts from git and ssh on desktop. Prompts queue up
+ * while one is open; closing the dialog cancels the current one.
+ */
+export const GitAskpassDialog: React.FC = () => {
+  const [queue, setQueue] = React.useState<GitAskpassRequest[]>([]);
+  const [value, setValue] = React.useState('');
+  const current = queue[0];
+
+  React.useEffect(() => {
+    const api = getDesktopApi();
+    if (!api?.onGitAskpassRequest) {
+      return;
+    }
+    let unsubscribe: (() => void) | null = null;
+    let disposed = false;
+    api
+      .onGitAskpassRequest((request) => {
+        setQueue((prev) => [...prev, request]);
+      })
+      .then((fn) => {
+        if (disposed) {
+          fn();
+        } else {
+          unsubscribe = fn;
+        }
+      })
+      .catch((error) => {
+        console.warn('Failed to listen for git prompts', error);
+      });
+    return () => {
+      disposed = true;
+      unsubscribe?.();
+    };
+  }, []);
+
+  const answer = React.useCallback((response: string | null) => {
+    if (!current) {
+      return;
+    }
+    getDesktopApi()?.respondGitAskpass?.(current.id, response).catch((error) => {
+      console.warn('Failed to answer git prompt', error);
+    });
+    setValue('');
+    setQueue((prev) => prev.slice(1));
+  }, [current]);
+
+  if (!current) {
+    return null;
+  }
+
+  const isConfirm = current.kind === 'confirm';
+
+  return (
+    <Dialog open onOpenChange={(open) => { if (!open) answer(null); }}>
+      <DialogContent className="max-w-md">
+        <DialogHeader>
+          <DialogTitle>{TITLES[current.kind] ?? 'Git prompt'}</DialogTitle>
+          <DialogDescription className="whitespace-pre-wrap break-words">
+            {current.prompt}
+          </DialogDescription>
+        </DialogHeader>
+
+        {!isConfirm && (
+          <form
+            onSubmit={(e) => {
+              e.preventDefault();
+              answer(value);
+            }}
+          >
+            <Input
+              autoFocus
+              type={current.secret ? 'password' : 'text'}
+              value={value}
+              onChange={(e) => setValue(e.target.value)}
+            />
+          </form>
+        )}
+
+        <DialogFooter>
+          <Button variant="ghost" onClick={() => answer(null)}>
+            Cancel
+          </Button>
+          {isConfirm ? (
+            <Button variant="default" onClick={() => answer('yes')}>
+              Trust host
+            </Button>
+          ) : (
+            <Button variant="default" onClick={() => answer(value)}>
+              Continue
+            </Button>
+          )}
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+};
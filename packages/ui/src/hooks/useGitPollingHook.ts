@@ -3,16 +3,27 @@ import { useGitStore } from '@/stores/useGitStore';
 import { useDirectoryStore } from '@/stores/useDirectoryStore';
 import { useRuntimeAPIs } from '@/hooks/useRuntimeAPIs';
 import { useSessionStore } from '@/stores/useSessionStore';
+import { getDesktopApi } from '@/lib/desktop';
 
 /**
  * Background git polling hook - monitors git status regardless of which tab is open.
+ * On desktop, file-system change events from the backend replace the polling timer.
  * Must be used inside RuntimeAPIProvider.
  */
 export function useGitPolling() {
     const { git } = useRuntimeAPIs();
     const fallbackDirectory = useDirectoryStore((state) => state.currentDirectory);
     const { currentSessionId, sessions, worktreeMetadata: worktreeMap } = useSessionStore();
-    const { setActiveDirectory, startPolling, stopPolling, fetchAll } = useGitStore();
+    const {
+        setActiveDirectory,
+        startPolling,
+        stopPolling,
+        fetchAll,
+        fetchStatus,
+        fetchBranches,
+        fetchLog,
+        fetchAllDiffs,
+    } = useGitStore();
 
     const effectiveDirectory = React.useMemo(() => {
         const worktreeMetadata = currentSessionId
@@ -35,10 +46,55 @@ export function useGitPolling() {
 
         fetchAll(effectiveDirectory, git);
 
-        startPolling(git);
+        const watchGitStatus = getDesktopApi()?.watchGitStatus;
+        if (!watchGitStatus) {
+            startPolling(git);
+            return () => {
+                stopPolling();
+            };
+        }
+
+        let unwatch: (() => void) | null = null;
+        let disposed = false;
+        watchGitStatus(effectiveDirectory, async (event) => {
+            if (event.gitStateChanged) {
+                void fetchBranches(effectiveDirectory, git);
+            }
+            const statusChanged = await fetchStatus(effectiveDirectory, git, { silent: true });
+            if (statusChanged || event.gitStateChanged) {
+                await fetchLog(effectiveDirectory, git);
+                void fetchAllDiffs(effectiveDirectory, git);
+            }
+        })
+            .then((fn) => {
+                if (disposed) {
+                    fn();
+                } else {
+                    unwatch = fn;
+                }
+            })
+            .catch((error) => {
+                console.warn('Failed to watch git status, falling back to polling', error);
+                if (!disposed) {
+                    startPolling(git);
+                }
+            });
 
         return () => {
+            disposed = true;
+            unwatch?.();
             stopPolling();
         };
-    }, [effectiveDirectory, git, setActiveDirectory, startPolling, stopPolling, fetchAll]);
+    }, [
+        effectiveDirectory,
+        git,
+        setActiveDirectory,
+        startPolling,
+        stopPolling,
+        fetchAll,
+        fetchStatus,
+        fetchBranches,
+        fetchLog,
+        fetchAllDiffs,
+    ]);
 }
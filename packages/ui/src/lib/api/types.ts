@@ -85,6 +85,10 @@ export interface GitStatusFile {
   path: string;
   index: string;
   working_dir: string;
+  /** Changes are hidden by `update-index --assume-unchanged` */
+  assumeUnchanged?: boolean;
+  /** Changes are hidden by `update-index --skip-worktree` */
+  skipWorktree?: boolean;
 }
 
 export interface GitStatus {
@@ -95,6 +99,14 @@ export interface GitStatus {
   files: GitStatusFile[];
   isClean: boolean;
   diffStats?: Record<string, { insertions: number; deletions: number }>;
+  /** Assume-unchanged or skip-worktree files whose changes git hides, so absent from `files` */
+  hiddenFiles?: GitIndexFlags[];
+}
+
+export interface GitIndexFlags {
+  path: string;
+  assumeUnchanged: boolean;
+  skipWorktree: boolean;
 }
 
 export interface GitDiffResponse {
@@ -126,6 +138,14 @@ export interface GitBranchDetails {
   tracking?: string;
   ahead?: number;
   behind?: number;
+  lastCommitDate?: string | null;
+  lastCommitAuthor?: string | null;
+  lastCommitSubject?: string | null;
+}
+
+export interface GetGitBranchesOptions {
+  sort?: 'name' | 'recent';
+  includeRemotes?: boolean;
 }
 
 export interface GitBranch {
@@ -145,6 +165,7 @@ export interface GitCommitResult {
   commit: string;
   branch: string;
   summary: GitCommitSummary;
+  verificationSkipped?: boolean;
 }
 
 export interface GitPushResult {
@@ -155,6 +176,16 @@ export interface GitPushResult {
   }>;
   repo: string;
   ref: unknown;
+  remotes?: Array<{
+    remote: string;
+    success: boolean;
+    pushed: Array<{
+      local: string;
+      remote: string;
+    }>;
+    error?: { kind: string; message: string; detail?: string } | null;
+  }>;
+  verificationSkipped?: boolean;
 }
 
 export interface GitPullResult {
@@ -173,6 +204,7 @@ export interface GitIdentityProfile {
   sshKey?: string | null;
   color?: string | null;
   icon?: string | null;
+  signoff?: boolean;
 }
 
 export interface GitIdentitySummary {
@@ -243,6 +275,13 @@ export interface GitDeleteRemoteBranchPayload {
 export interface CreateGitCommitOptions {
   addAll?: boolean;
   files?: string[];
+  signoff?: boolean;
+  noVerify?: boolean;
+  /** Commit even when nothing is staged */
+  allowEmpty?: boolean;
+  /** Any date git accepts */
+  authorDate?: string;
+  commitDate?: string;
 }
 
 export interface GitLogOptions {
@@ -250,6 +289,16 @@ export interface GitLogOptions {
   from?: string;
   to?: string;
   file?: string;
+  author?: string;
+  /** Any date git understands, e.g. `2024-01-31` or `2 weeks ago` */
+  since?: string;
+  until?: string;
+  /** Matched against commit messages, ignoring case */
+  grep?: string;
+  /** Only commits touching any of these paths */
+  paths?: string[];
+  /** Lets a slow request be stopped with the `cancel_git_operation` command */
+  operationId?: string;
 }
 
 export interface GeneratedCommitMessage {
@@ -264,7 +313,7 @@ export interface GitAPI {
   getGitFileDiff(directory: string, options: GetGitFileDiffOptions): Promise<GitFileDiffResponse>;
   revertGitFile(directory: string, filePath: string): Promise<void>;
   isLinkedWorktree(directory: string): Promise<boolean>;
-  getGitBranches(directory: string): Promise<GitBranch>;
+  getGitBranches(directory: string, options?: GetGitBranchesOptions): Promise<GitBranch>;
   deleteGitBranch(directory: string, payload: GitDeleteBranchPayload): Promise<{ success: boolean }>;
   deleteRemoteBranch(directory: string, payload: GitDeleteRemoteBranchPayload): Promise<{ success: boolean }>;
   generateCommitMessage(directory: string, files: string[]): Promise<{ message: GeneratedCommitMessage }>;
@@ -273,10 +322,10 @@ export interface GitAPI {
   removeGitWorktree(directory: string, payload: GitRemoveWorktreePayload): Promise<{ success: boolean }>;
   ensureOpenChamberIgnored(directory: string): Promise<void>;
   createGitCommit(directory: string, message: string, options?: CreateGitCommitOptions): Promise<GitCommitResult>;
-  gitPush(directory: string, options?: { remote?: string; branch?: string; options?: string[] | Record<string, unknown> }): Promise<GitPushResult>;
+  gitPush(directory: string, options?: { remote?: string; branch?: string; options?: string[] | Record<string, unknown>; remotes?: string[]; pushToAll?: boolean; noVerify?: boolean }): Promise<GitPushResult>;
   gitPull(directory: string, options?: { remote?: string; branch?: string }): Promise<GitPullResult>;
   gitFetch(directory: string, options?: { remote?: string; branch?: string }): Promise<{ success: boolean }>;
-  checkoutBranch(directory: string, branch: string): Promise<{ success: boolean; branch: string }>;
+  checkoutBranch(directory: string, branch: string): Promise<{ success: boolean; branch: string; created?: boolean; tracking?: string | null }>;
   createBranch(directory: string, name: string, startPoint?: string): Promise<{ success: boolean; branch: string }>;
   getGitLog(directory: string, options?: GitLogOptions): Promise<GitLogResponse>;
   getCommitFiles(directory: string, hash: string): Promise<GitCommitFilesResponse>;
@@ -423,6 +472,8 @@ export interface SkillsCatalogSource {
   description?: string;
   source: string;
   defaultSubpath?: string;
+  /** Branch, tag or commit to read instead of the default branch */
+  ref?: string;
 }
 
 export interface SkillsCatalogItemInstalledBadge {
@@ -435,6 +486,7 @@ export interface SkillsCatalogItem {
   repoSource: string;
   repoSubpath?: string;
   gitIdentityId?: string;
+  ref?: string;
   skillDir: string;
   skillName: string;
   frontmatterName?: string;
@@ -448,12 +500,14 @@ export interface SkillsCatalogResponse {
   ok: boolean;
   sources?: SkillsCatalogSource[];
   itemsBySource?: Record<SkillsCatalogSourceId, SkillsCatalogItem[]>;
+  errorsBySource?: Record<SkillsCatalogSourceId, SkillsRepoScanError>;
   error?: { kind: string; message: string };
 }
 
 export interface SkillsRepoScanRequest {
   source: string;
   subpath?: string;
+  ref?: string;
   gitIdentityId?: string;
 }
 
@@ -462,6 +516,7 @@ export type SkillsRepoScanError =
   | { kind: 'invalidSource'; message: string }
   | { kind: 'gitUnavailable'; message: string }
   | { kind: 'networkError'; message: string }
+  | { kind: 'notFound'; message: string }
   | { kind: 'unknown'; message: string };
 
 export interface SkillsRepoScanResponse {
@@ -477,6 +532,7 @@ export interface SkillsInstallSelection {
 export interface SkillsInstallRequest {
   source: string;
   subpath?: string;
+  ref?: string;
   gitIdentityId?: string;
   scope: 'user' | 'project';
   selections: SkillsInstallSelection[];
@@ -497,3 +553,110 @@ export interface SkillsInstallResponse {
   error?: SkillsInstallError;
 }
 
+
+export interface SkillUpdateStatus {
+  skillName: string;
+  scope: 'user' | 'project';
+  source: string;
+  ref?: string;
+  installedCommit?: string;
+  latestCommit?: string;
+  updateAvailable: boolean;
+  error?: string;
+}
+
+export interface SkillUpdatesResponse {
+  ok: boolean;
+  updates: SkillUpdateStatus[];
+}
+
+export interface SkillManifestEntry {
+  skillName: string;
+  scope: 'user' | 'project';
+  path: string;
+  source: string;
+  subpath?: string;
+  ref?: string;
+  gitIdentityId?: string;
+  skillDir: string;
+  commit?: string;
+  tree?: string;
+  installedAt: string;
+}
+
+export interface InstalledSkillProvenance {
+  name: string;
+  path: string;
+  scope: 'user' | 'project';
+  source: 'opencode' | 'claude';
+  provenance?: SkillManifestEntry;
+}
+
+export interface SkillCatalogSourceInput {
+  label: string;
+  source: string;
+  subpath?: string;
+  ref?: string;
+  gitIdentityId?: string;
+}
+
+export interface SkillCatalogSourceResponse {
+  ok: boolean;
+  source?: SkillsCatalogSource;
+  skillCount?: number;
+  error?: SkillsRepoScanError | { kind: 'duplicate'; message: string };
+}
+
+export interface SkillSearchResult extends SkillsCatalogItem {
+  score: number;
+}
+
+export interface SkillsSearchResponse {
+  ok: boolean;
+  results: SkillSearchResult[];
+}
+
+export interface SkillDetailsRequest {
+  source: string;
+  skillDir: string;
+  ref?: string;
+  gitIdentityId?: string;
+}
+
+export interface SkillFileEntry {
+  path: string;
+  size: number;
+}
+
+export interface SkillDetails {
+  skillDir: string;
+  skillName: string;
+  frontmatterName?: string;
+  description?: string;
+  content: string;
+  files: SkillFileEntry[];
+  commit?: string;
+  warnings?: string[];
+}
+
+export interface SkillDetailsResponse {
+  ok: boolean;
+  skill?: SkillDetails;
+  error?: SkillsRepoScanError;
+}
+
+export interface SkillDiagnostic {
+  severity: 'error' | 'warning';
+  code: string;
+  message: string;
+  path?: string;
+}
+
+export interface SkillValidationResponse {
+  ok: boolean;
+  valid?: boolean;
+  skillDir?: string;
+  skillName?: string;
+  diagnostics?: SkillDiagnostic[];
+  error?: SkillsRepoScanError;
+}
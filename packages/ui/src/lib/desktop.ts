@@ -32,6 +32,7 @@ export type SkillCatalogConfig = {
   label: string;
   source: string;
   subpath?: string;
+  ref?: string;
   gitIdentityId?: string;
 };
 
@@ -57,6 +58,19 @@ export type DesktopSettings = {
   skillCatalogs?: SkillCatalogConfig[];
 };
 
+export type GitAskpassRequest = {
+  id: string;
+  prompt: string;
+  kind: 'passphrase' | 'password' | 'username' | 'confirm';
+  secret: boolean;
+};
+
+export type GitStatusChangedEvent = {
+  directory: string;
+  worktreeChanged: boolean;
+  gitStateChanged: boolean;
+};
+
 export type DesktopSettingsApi = {
   getSettings: () => Promise<DesktopSettings>;
   updateSettings: (changes: Partial<DesktopSettings>) => Promise<DesktopSettings>;
@@ -80,6 +94,11 @@ export type DesktopApi = {
   downloadUpdate?: (onProgress?: (progress: UpdateProgress) => void) => Promise<void>;
   restartToUpdate?: () => Promise<void>;
   openExternal?: (url: string) => Promise<{ success: boolean; error?: string }>;
+  // Passphrase/password prompts from git and ssh; returns an unsubscribe function
+  onGitAskpassRequest?: (handler: (request: GitAskpassRequest) => void) => Promise<() => void>;
+  respondGitAskpass?: (id: string, response: string | null) => Promise<void>;
+  // File-system driven git status updates for one directory; returns an unsubscribe function
+  watchGitStatus?: (directory: string, handler: (event: GitStatusChangedEvent) => void) => Promise<() => void>;
 };
 
 export const isDesktopRuntime = (): boolean =>
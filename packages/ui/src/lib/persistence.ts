@@ -60,6 +60,7 @@ const sanitizeSkillCatalogs = (value: unknown): DesktopSettings['skillCatalogs']
     const label = typeof candidate.label === 'string' ? candidate.label.trim() : '';
     const source = typeof candidate.source === 'string' ? candidate.source.trim() : '';
     const subpath = typeof candidate.subpath === 'string' ? candidate.subpath.trim() : '';
+    const ref = typeof candidate.ref === 'string' ? candidate.ref.trim() : '';
     const gitIdentityId = typeof candidate.gitIdentityId === 'string' ? candidate.gitIdentityId.trim() : '';
 
     if (!id || !label || !source) continue;
@@ -71,6 +72,7 @@ const sanitizeSkillCatalogs = (value: unknown): DesktopSettings['skillCatalogs']
       label,
       source,
       ...(subpath ? { subpath } : {}),
+      ...(ref ? { ref } : {}),
       ...(gitIdentityId ? { gitIdentityId } : {}),
     });
   }
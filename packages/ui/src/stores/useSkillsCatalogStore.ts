@@ -10,6 +10,8 @@ import type {
   SkillsInstallRequest,
   SkillsInstallResponse,
   SkillsInstallError,
+  SkillCatalogSourceInput,
+  SkillCatalogSourceResponse,
 } from '@/lib/api/types';
 
 import { useSkillsStore } from '@/stores/useSkillsStore';
@@ -47,8 +49,33 @@ export interface SkillsCatalogState {
   loadCatalog: (options?: { refresh?: boolean }) => Promise<boolean>;
   scanRepo: (request: SkillsRepoScanRequest) => Promise<SkillsRepoScanResponse>;
   installSkills: (request: SkillsInstallRequest) => Promise<SkillsInstallResponse>;
+  addCatalogSource: (input: SkillCatalogSourceInput) => Promise<SkillCatalogSourceResponse>;
+  removeCatalogSource: (id: string) => Promise<SkillCatalogSourceResponse>;
 }
 
+const requestCatalogSource = async (
+  path: string,
+  init: RequestInit,
+  fallbackMessage: string
+): Promise<SkillCatalogSourceResponse> => {
+  try {
+    const response = await fetch(`/api/config/skills/catalog/sources${path}`, {
+      ...init,
+      headers: { 'Content-Type': 'application/json', Accept: 'application/json' },
+    });
+    const payload = (await response.json().catch(() => null)) as SkillCatalogSourceResponse | null;
+    if (!payload) {
+      return { ok: false, error: { kind: 'unknown', message: fallbackMessage } };
+    }
+    if (!response.ok || !payload.ok) {
+      return { ok: false, error: payload.error || { kind: 'unknown', message: fallbackMessage } };
+    }
+    return payload;
+  } catch (error) {
+    return { ok: false, error: { kind: 'unknown', message: error instanceof Error ? error.message : String(error) } };
+  }
+};
+
 export const useSkillsCatalogStore = create<SkillsCatalogState>()(
   devtools(
     (set, get) => ({
@@ -200,6 +227,22 @@ export const useSkillsCatalogStore = create<SkillsCatalogState>()(
           set({ isInstalling: false });
         }
       },
+
+      addCatalogSource: async (input) => {
+        const result = await requestCatalogSource('', { method: 'POST', body: JSON.stringify(input) }, 'Failed to save catalog');
+        if (result.ok) {
+          await get().loadCatalog();
+        }
+        return result;
+      },
+
+      removeCatalogSource: async (id) => {
+        const result = await requestCatalogSource(`/${encodeURIComponent(id)}`, { method: 'DELETE' }, 'Failed to remove catalog');
+        if (result.ok) {
+          await get().loadCatalog();
+        }
+        return result;
+      },
     }),
     { name: 'skills-catalog-store' }
   )
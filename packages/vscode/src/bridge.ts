@@ -876,6 +876,72 @@ export async function handleBridgeMessage(message: BridgeRequest, ctx?: BridgeCo
         return { id, type, success: true, data };
       }
 
+      case 'api:config/skills:catalog-sources': {
+        const { method, id: catalogId, body } = (payload || {}) as {
+          method?: string;
+          id?: string;
+          body?: { label?: string; source?: string; subpath?: string };
+        };
+        const readCatalogs = () => {
+          const raw = (readSettings(ctx) as { skillCatalogs?: unknown }).skillCatalogs;
+          type StoredCatalog = { id: string; label: string; source: string; subpath?: string };
+          return (Array.isArray(raw) ? raw : []).filter(
+            (entry): entry is StoredCatalog =>
+              Boolean(entry && typeof entry === 'object')
+              && typeof (entry as StoredCatalog).id === 'string'
+              && typeof (entry as StoredCatalog).source === 'string'
+          );
+        };
+
+        if (method === 'DELETE') {
+          const catalogs = readCatalogs();
+          const removed = catalogs.find((c) => c.id === catalogId);
+          if (!removed) {
+            return { id, type, success: true, data: { ok: false, error: { kind: 'notFound', message: 'Catalog not found' } } };
+          }
+          await persistSettings({ skillCatalogs: catalogs.filter((c) => c.id !== removed.id) }, ctx);
+          return { id, type, success: true, data: { ok: true } };
+        }
+
+        const label = typeof body?.label === 'string' ? body.label.trim() : '';
+        const source = typeof body?.source === 'string' ? body.source.trim() : '';
+        const subpath = typeof body?.subpath === 'string' ? body.subpath.trim() : '';
+        if (!label || !source) {
+          return { id, type, success: true, data: { ok: false, error: { kind: 'invalidSource', message: 'Catalog name and repository are required' } } };
+        }
+        const sameSource = (c: { source: string; subpath?: string }) => c.source === source && (c.subpath || '') === subpath;
+        if (readCatalogs().some(sameSource)) {
+          return { id, type, success: true, data: { ok: false, error: { kind: 'duplicate', message: 'This catalog already exists' } } };
+        }
+
+        const scanned = await scanSkillsRepositoryFromGit({ source, subpath: subpath || undefined });
+        if (!scanned.ok) {
+          return { id, type, success: true, data: scanned };
+        }
+        const skillCount = (scanned.items || []).length;
+        if (skillCount === 0) {
+          return { id, type, success: true, data: { ok: false, error: { kind: 'invalidSource', message: 'No skills found in this repository' } } };
+        }
+
+        // Re-read after the scan so a catalog added meanwhile isn't dropped
+        const catalogs = readCatalogs();
+        if (catalogs.some(sameSource)) {
+          return { id, type, success: true, data: { ok: false, error: { kind: 'duplicate', message: 'This catalog already exists' } } };
+        }
+        const entry = { id: `custom:${Date.now()}-${Math.random().toString(16).slice(2)}`, label, source, ...(subpath ? { subpath } : {}) };
+        await persistSettings({ skillCatalogs: [...catalogs, entry] }, ctx);
+        return {
+          id,
+          type,
+          success: true,
+          data: {
+            ok: true,
+            source: { id: entry.id, label, description: source, source, ...(subpath ? { defaultSubpath: subpath } : {}) },
+            skillCount,
+          },
+        };
+      }
+
       case 'api:config/skills:scan': {
         const body = (payload || {}) as { source?: string; subpath?: string; gitIdentityId?: string };
         const data = await scanSkillsRepositoryFromGit({
@@ -432,6 +432,22 @@ const handleLocalApiRequest = async (url: URL, init?: RequestInit) => {
     }
   }
 
+  // Custom skills catalogs: /api/config/skills/catalog/sources[/:id]
+  if (pathname === '/api/config/skills/catalog/sources' || pathname.startsWith('/api/config/skills/catalog/sources/')) {
+    const catalogId = decodeURIComponent(pathname.slice('/api/config/skills/catalog/sources/'.length));
+    const body = init?.body ? JSON.parse(init.body as string) : {};
+    try {
+      const verb = ((init?.method || 'GET') as string).toUpperCase();
+      const data = await sendBridgeMessage('api:config/skills:catalog-sources', { method: verb, id: catalogId, body });
+      const kind = (data as { error?: { kind?: string } } | null)?.error?.kind;
+      const status = kind === 'duplicate' ? 409 : kind === 'notFound' ? 404 : skillsCatalogStatusFromPayload(data);
+      return new Response(JSON.stringify(data), { status, headers: { 'Content-Type': 'application/json' } });
+    } catch (error) {
+      const message = error instanceof Error ? error.message : String(error);
+      return new Response(JSON.stringify({ ok: false, error: { kind: 'unknown', message } }), { status: 500, headers: { 'Content-Type': 'application/json' } });
+    }
+  }
+
   // Skills scan: /api/config/skills/scan
   if (pathname === '/api/config/skills/scan') {
     const body = init?.body ? JSON.parse(init.body as string) : {};
@@ -6,6 +6,7 @@ import fs from 'fs';
 import http from 'http';
 import { fileURLToPath } from 'url';
 import os from 'os';
+import crypto from 'crypto';
 import { createUiAuth } from './lib/ui-auth.js';
 import { startCloudflareTunnel, printTunnelWarning, checkCloudflaredAvailable } from './lib/cloudflare-tunnel.js';
 
@@ -459,11 +460,6 @@ const sanitizeSettingsUpdate = (payload) => {
     result.queueModeEnabled = candidate.queueModeEnabled;
   }
 
-  const skillCatalogs = sanitizeSkillCatalogs(candidate.skillCatalogs);
-  if (skillCatalogs) {
-    result.skillCatalogs = skillCatalogs;
-  }
-
   return result;
 };
 
@@ -2567,6 +2563,71 @@ async function main(options = {}) {
     }
   });
 
+  // Custom catalogs are test-scanned before they are saved; settings are re-read right
+  // before the write so a concurrent change to the list isn't lost
+  app.post('/api/config/skills/catalog/sources', async (req, res) => {
+    try {
+      const body = req.body || {};
+      const [entry] = sanitizeSkillCatalogs([{ ...body, id: `custom:${crypto.randomUUID()}` }]) || [];
+      if (!entry) {
+        return res.status(400).json({ ok: false, error: { kind: 'invalidSource', message: 'Catalog name and repository are required' } });
+      }
+
+      const sameSource = (c) => c.source === entry.source && (c.subpath || '') === (entry.subpath || '');
+      const existing = sanitizeSkillCatalogs((await readSettingsFromDisk()).skillCatalogs) || [];
+      if (existing.some(sameSource)) {
+        return res.status(409).json({ ok: false, error: { kind: 'duplicate', message: 'This catalog already exists' } });
+      }
+
+      const scanned = await scanSkillsRepository({
+        source: entry.source,
+        subpath: entry.subpath,
+        defaultSubpath: entry.subpath,
+        identity: resolveGitIdentity(entry.gitIdentityId),
+      });
+      if (!scanned.ok) {
+        return res.status(scanned.error?.kind === 'authRequired' ? 401 : 400).json({ ok: false, error: scanned.error });
+      }
+      const skillCount = (scanned.items || []).length;
+      if (skillCount === 0) {
+        return res.status(400).json({ ok: false, error: { kind: 'invalidSource', message: 'No skills found in this repository' } });
+      }
+
+      const settings = await readSettingsFromDisk();
+      const catalogs = sanitizeSkillCatalogs(settings.skillCatalogs) || [];
+      if (catalogs.some(sameSource)) {
+        return res.status(409).json({ ok: false, error: { kind: 'duplicate', message: 'This catalog already exists' } });
+      }
+      await writeSettingsToDisk({ ...settings, skillCatalogs: [...catalogs, entry] });
+
+      const { subpath, ...rest } = entry;
+      res.json({
+        ok: true,
+        source: { ...rest, description: entry.source, ...(subpath ? { defaultSubpath: subpath } : {}) },
+        skillCount,
+      });
+    } catch (error) {
+      console.error('Failed to add skills catalog:', error);
+      res.status(500).json({ ok: false, error: { kind: 'unknown', message: error.message || 'Failed to save catalog' } });
+    }
+  });
+
+  app.delete('/api/config/skills/catalog/sources/:id', async (req, res) => {
+    try {
+      const settings = await readSettingsFromDisk();
+      const catalogs = sanitizeSkillCatalogs(settings.skillCatalogs) || [];
+      const removed = catalogs.find((c) => c.id === req.params.id);
+      if (!removed) {
+        return res.status(404).json({ ok: false, error: { kind: 'notFound', message: 'Catalog not found' } });
+      }
+      await writeSettingsToDisk({ ...settings, skillCatalogs: catalogs.filter((c) => c.id !== removed.id) });
+      res.json({ ok: true });
+    } catch (error) {
+      console.error('Failed to remove skills catalog:', error);
+      res.status(500).json({ ok: false, error: { kind: 'unknown', message: error.message || 'Failed to remove catalog' } });
+    }
+  });
+
   app.post('/api/config/skills/scan', async (req, res) => {
     try {
       const { source, subpath, gitIdentityId } = req.body || {};